@@ -0,0 +1,267 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::LogEntry;
+use crate::cache::{DiskCache, WHOIS_CACHE};
+use crate::net::source_key;
+use crate::offline;
+
+// Raw lines quoted in one report; abuse desks rarely read past this.
+const EXCERPT_LINES: usize = 50;
+// Whois excerpts from earlier reports; a few thousand networks.
+const WHOIS_CACHE_BYTES: u64 = 1024 * 1024;
+// Whois keys worth quoting: the network owner and where to send complaints.
+const WHOIS_KEYS: [&str; 10] = [
+    "orgname",
+    "org-name",
+    "netname",
+    "descr",
+    "country",
+    "cidr",
+    "inetnum",
+    "orgabuseemail",
+    "abuse-mailbox",
+    "abuse-c",
+];
+
+// Sources with the most blocked entries, busiest first, with IPv6 sources
+// grouped by `v6_prefix`. Entries are expected newest first, as the app
+// stores them; each list is returned oldest first.
+pub fn top_offenders<'a>(
+    entries: impl IntoIterator<Item = &'a LogEntry>,
+    limit: usize,
+    v6_prefix: u8,
+) -> Vec<(String, Vec<&'a LogEntry>)> {
+    let mut by_source: HashMap<Cow<str>, Vec<&LogEntry>> = HashMap::new();
+    for entry in entries {
+        if entry.action == "ALLOW" || entry.action == "AUDIT" {
+            continue;
+        }
+        if let Some(ip) = entry.src_ip.as_deref() {
+            by_source
+                .entry(source_key(ip, v6_prefix))
+                .or_default()
+                .push(entry);
+        }
+    }
+    let mut offenders: Vec<(String, Vec<&LogEntry>)> = by_source
+        .into_iter()
+        .map(|(ip, mut entries)| {
+            entries.reverse();
+            (ip.into_owned(), entries)
+        })
+        .collect();
+    offenders.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    offenders.truncate(limit);
+    offenders
+}
+
+// Plain-text report for an abuse@ mailbox: counts, first/last time, the
+// network owner from whois and the raw log lines.
+pub fn abuse_report(ip: &str, entries: &[&LogEntry], whois: Option<&str>) -> String {
+    let mut actions: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut ports: BTreeMap<u16, usize> = BTreeMap::new();
+    let mut protos: BTreeMap<&str, usize> = BTreeMap::new();
+    for entry in entries {
+        *actions.entry(entry.action.as_str()).or_insert(0) += 1;
+        if let Some(port) = entry.dst_port {
+            *ports.entry(port).or_insert(0) += 1;
+        }
+        *protos
+            .entry(entry.proto.as_deref().unwrap_or("-"))
+            .or_insert(0) += 1;
+    }
+    let first = entries
+        .first()
+        .map_or("-", |entry| entry.timestamp.as_str());
+    let last = entries.last().map_or("-", |entry| entry.timestamp.as_str());
+
+    let mut out = format!(
+        "Subject: Abuse report for {ip} ({} blocked connection attempts)\n\n\
+         Hello,\n\n\
+         Our firewall (UFW) blocked {} connection attempts from {ip} between\n\
+         {first} and {last}. Please investigate this host.\n\n\
+         Summary\n\
+         \x20 Source:      {ip}\n\
+         \x20 First seen:  {first}\n\
+         \x20 Last seen:   {last}\n\
+         \x20 Actions:     {}\n\
+         \x20 Dst ports:   {}\n\
+         \x20 Protocols:   {}\n",
+        entries.len(),
+        entries.len(),
+        joined_counts(actions),
+        joined_counts(ports),
+        joined_counts(protos),
+    );
+    let excerpt = whois.map(whois_excerpt).unwrap_or_default();
+    if !excerpt.is_empty() {
+        out.push_str("\nWhois\n");
+        for line in excerpt {
+            out.push_str(&format!("  {}\n", line));
+        }
+    }
+    out.push_str(&format!(
+        "\nLog excerpt ({} of {} lines, timestamps as logged)\n",
+        entries.len().min(EXCERPT_LINES),
+        entries.len()
+    ));
+    for entry in entries.iter().take(EXCERPT_LINES) {
+        out.push_str(&format!("  {}\n", entry.raw));
+    }
+    out
+}
+
+// "22 (30), 23 (12)" in key order.
+fn joined_counts<K: Display>(counts: BTreeMap<K, usize>) -> String {
+    counts
+        .into_iter()
+        .map(|(key, count)| format!("{} ({})", key, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// First line for each interesting whois key, as "Key: value".
+pub fn whois_excerpt(output: &str) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut lines = Vec::new();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let lower = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if value.is_empty() || !WHOIS_KEYS.contains(&lower.as_str()) || seen.contains(&lower) {
+            continue;
+        }
+        lines.push(format!("{}: {}", key.trim(), value));
+        seen.push(lower);
+    }
+    lines
+}
+
+// The address an offender key is looked up by: the IP itself, or the network
+// address of a `/len` prefix. None for anything else a log field carried.
+fn whois_target(key: &str) -> Option<IpAddr> {
+    let (addr, len) = match key.split_once('/') {
+        Some((addr, len)) => (addr, Some(len)),
+        None => (key, None),
+    };
+    let addr: IpAddr = addr.parse().ok()?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    match len.map(str::parse::<u8>) {
+        None => Some(addr),
+        Some(Ok(len)) if len <= max => Some(addr),
+        Some(_) => None,
+    }
+}
+
+// Runs the system `whois`; None offline or when it is missing or fails. The
+// `--` keeps the address from ever being read as an option.
+fn lookup_whois(addr: IpAddr) -> Option<String> {
+    if !offline::network_allowed() {
+        return None;
+    }
+    let output = Command::new("whois")
+        .arg("--")
+        .arg(addr.to_string())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// `lookup_whois` through the cache of earlier excerpts, which also serves
+// them offline.
+fn cached_whois(ip: &str, cache: &DiskCache, known: &HashMap<String, String>) -> Option<String> {
+    if let Some(excerpt) = known.get(ip) {
+        return Some(excerpt.clone());
+    }
+    let excerpt = whois_excerpt(&lookup_whois(whois_target(ip)?)?).join("\n");
+    cache.store(ip, &excerpt);
+    Some(excerpt)
+}
+
+// Writes one `abuse-<ip>.txt` per offender into `dir`, creating it first.
+// Offenders whose key is not an address or prefix get no report.
+pub fn write_reports(
+    dir: &Path,
+    offenders: &[(String, Vec<&LogEntry>)],
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let cache = DiskCache::open(WHOIS_CACHE, WHOIS_CACHE_BYTES);
+    let known: HashMap<String, String> = cache.load().into_iter().collect();
+    let mut written = Vec::with_capacity(offenders.len());
+    for (ip, entries) in offenders {
+        if whois_target(ip).is_none() {
+            continue;
+        }
+        let whois = cached_whois(ip, &cache, &known);
+        let path = dir.join(format!("abuse-{}.txt", ip.replace([':', '/'], "_")));
+        fs::write(&path, abuse_report(ip, entries, whois.as_deref()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn builds_per_offender_reports() {
+        let line = |time: &str, action: &str, src: &str, dpt: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 {time} h kernel: [UFW {action}] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        let entries = [
+            line("20:03:00", "BLOCK", "1.2.3.4", 23),
+            line("20:02:00", "ALLOW", "5.6.7.8", 22),
+            line("20:01:00", "BLOCK", "5.6.7.8", 22),
+            line("20:00:00", "BLOCK", "1.2.3.4", 22),
+        ];
+        let offenders = top_offenders(&entries, 1, 64);
+        assert_eq!(offenders.len(), 1);
+        assert_eq!(offenders[0].0, "1.2.3.4");
+        assert_eq!(offenders[0].1[0].timestamp, "Feb 11 20:00:00 h");
+
+        let whois = "% comment\nnetname:  EXAMPLE-NET\ndescr: Example Hosting\ndescr: second\nabuse-mailbox: abuse@example.net\n";
+        assert_eq!(
+            whois_excerpt(whois),
+            [
+                "netname: EXAMPLE-NET",
+                "descr: Example Hosting",
+                "abuse-mailbox: abuse@example.net"
+            ]
+        );
+        let report = abuse_report(&offenders[0].0, &offenders[0].1, Some(whois));
+        assert!(report.starts_with("Subject: Abuse report for 1.2.3.4 (2 blocked"));
+        assert!(report.contains("  First seen:  Feb 11 20:00:00 h\n"));
+        assert!(report.contains("  Dst ports:   22 (1), 23 (1)\n"));
+        assert!(report.contains("\nWhois\n  netname: EXAMPLE-NET\n"));
+        assert!(report.ends_with(
+            "[UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1 PROTO=TCP SPT=1 DPT=23\n"
+        ));
+    }
+
+    #[test]
+    fn looks_up_only_addresses_and_prefixes() {
+        assert_eq!(whois_target("1.2.3.4"), "1.2.3.4".parse().ok());
+        assert_eq!(whois_target("1.2.3.0/24"), "1.2.3.0".parse().ok());
+        assert_eq!(whois_target("2001:db8::/56"), "2001:db8::".parse().ok());
+        assert_eq!(whois_target("1.2.3.0/33"), None);
+        assert_eq!(whois_target("-h evil.example"), None);
+        assert_eq!(whois_target("--help"), None);
+    }
+}
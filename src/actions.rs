@@ -0,0 +1,140 @@
+use std::fs;
+use std::net::IpAddr;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleKind {
+    DenySource,
+    AllowOut,
+}
+
+// A firewall change offered from the table or the egress review, confirmed
+// before it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockAction {
+    pub ip: String,
+    pub argv: Vec<String>,
+    pub kind: RuleKind,
+}
+
+impl BlockAction {
+    // `ufw deny from IP`, through `sudo -n` when not running as root: the TUI
+    // owns the terminal, so sudo must not stop to ask for a password.
+    pub fn deny_source(ip: &str) -> Result<Self, String> {
+        let ip = ip
+            .parse::<IpAddr>()
+            .map_err(|_| format!("Not an IP address: {}", ip))?
+            .to_string();
+        let mut argv = sudo_prefix();
+        argv.extend(["ufw", "deny", "from"].map(str::to_string));
+        argv.push(ip.clone());
+        Ok(Self {
+            ip,
+            argv,
+            kind: RuleKind::DenySource,
+        })
+    }
+
+    // `ufw allow out to IP port N proto P`; ufw only takes ports with TCP or
+    // UDP, so other protocols allow the whole destination.
+    pub fn allow_out(ip: &str, port: Option<u16>, proto: &str) -> Result<Self, String> {
+        let ip = ip
+            .parse::<IpAddr>()
+            .map_err(|_| format!("Not an IP address: {}", ip))?
+            .to_string();
+        let mut argv = sudo_prefix();
+        argv.extend(["ufw", "allow", "out", "to"].map(str::to_string));
+        argv.push(ip.clone());
+        let proto = proto.to_ascii_lowercase();
+        if let Some(port) = port
+            && (proto == "tcp" || proto == "udp")
+        {
+            argv.extend([
+                "port".to_string(),
+                port.to_string(),
+                "proto".to_string(),
+                proto,
+            ]);
+        }
+        Ok(Self {
+            ip,
+            argv,
+            kind: RuleKind::AllowOut,
+        })
+    }
+
+    // "block 1.2.3.4" for status messages, with the IP as shown.
+    pub fn describe(&self, shown_ip: &str) -> String {
+        match self.kind {
+            RuleKind::DenySource => format!("block {}", shown_ip),
+            RuleKind::AllowOut => format!("allow outbound traffic to {}", shown_ip),
+        }
+    }
+
+    // Shell form for the dialog and the clipboard; every word is safe unquoted.
+    pub fn command_line(&self) -> String {
+        self.argv.join(" ")
+    }
+
+    // Runs the command and returns ufw's first output line ("Rule added").
+    pub fn run(&self) -> Result<String, String> {
+        let output = Command::new(&self.argv[0])
+            .args(&self.argv[1..])
+            .output()
+            .map_err(|err| format!("{}: {}", self.argv[0], err))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().chain(stdout.lines()).next().unwrap_or("");
+            return Err(format!("{} failed: {}", self.command_line(), reason.trim()));
+        }
+        Ok(stdout.lines().next().unwrap_or("done").trim().to_string())
+    }
+}
+
+// The TUI owns the terminal, so sudo must not stop to ask for a password.
+fn sudo_prefix() -> Vec<String> {
+    if is_root() {
+        Vec::new()
+    } else {
+        vec!["sudo".to_string(), "-n".to_string()]
+    }
+}
+
+// Effective UID 0, read from /proc to avoid a libc dependency.
+fn is_root() -> bool {
+    fs::read_to_string("/proc/self/status").is_ok_and(|status| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Uid:"))
+            .and_then(|ids| ids.split_whitespace().nth(1))
+            == Some("0")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_deny_commands_for_valid_ips_only() {
+        let action = BlockAction::deny_source("2001:DB8::1").unwrap();
+        assert_eq!(action.ip, "2001:db8::1");
+        assert!(action.command_line().ends_with("ufw deny from 2001:db8::1"));
+        assert_eq!(action.argv[0] == "sudo", !is_root());
+        assert!(BlockAction::deny_source("1.2.3.4; reboot").is_err());
+
+        let allow = BlockAction::allow_out("203.0.113.7", Some(443), "TCP").unwrap();
+        assert!(
+            allow
+                .command_line()
+                .ends_with("ufw allow out to 203.0.113.7 port 443 proto tcp")
+        );
+        let allow = BlockAction::allow_out("203.0.113.7", None, "ICMP").unwrap();
+        assert!(
+            allow
+                .command_line()
+                .ends_with("ufw allow out to 203.0.113.7")
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use crate::LogEntry;
+
+// Rows that collapse into one in the `=` aggregated view.
+type GroupKey<'a> = (
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<u16>,
+    Option<&'a str>,
+    &'a str,
+);
+
+// One aggregated row, keyed by its representative entry (the first in view
+// order). Entries are stored newest first, so the highest index is the oldest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub count: usize,
+    pub first_seen: usize,
+    pub last_seen: usize,
+    // Source ports differ within the group (shown as "*").
+    pub mixed_src_ports: bool,
+}
+
+pub fn same_group(a: &LogEntry, b: &LogEntry) -> bool {
+    group_key(a) == group_key(b)
+}
+
+fn group_key(entry: &LogEntry) -> GroupKey<'_> {
+    (
+        entry.src_ip.as_deref(),
+        entry.dst_ip.as_deref(),
+        entry.dst_port,
+        entry.proto.as_deref(),
+        entry.action.as_str(),
+    )
+}
+
+// Groups `indices` (in view order) by (src, dst, dpt, proto, action). Returns
+// the representatives in view order and each one's group.
+pub fn group(entries: &[LogEntry], indices: &[usize]) -> (Vec<usize>, HashMap<usize, Group>) {
+    let mut representatives = Vec::new();
+    let mut by_key: HashMap<GroupKey, usize> = HashMap::new();
+    let mut groups: HashMap<usize, Group> = HashMap::new();
+    for &idx in indices {
+        let entry = &entries[idx];
+        let rep = *by_key.entry(group_key(entry)).or_insert_with(|| {
+            representatives.push(idx);
+            idx
+        });
+        let group = groups.entry(rep).or_insert(Group {
+            count: 0,
+            first_seen: idx,
+            last_seen: idx,
+            mixed_src_ports: false,
+        });
+        group.count += 1;
+        group.first_seen = group.first_seen.max(idx);
+        group.last_seen = group.last_seen.min(idx);
+        group.mixed_src_ports |= entries[rep].src_port != entry.src_port;
+    }
+    (representatives, groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn groups_identical_tuples() {
+        let entry = |time: &str, src: &str, spt: u16, dpt: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 {time} h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT={spt} DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        // Newest first, as the app stores them.
+        let entries = [
+            entry("20:21:03", "1.1.1.1", 40002, 22),
+            entry("20:21:02", "2.2.2.2", 50000, 22),
+            entry("20:21:01", "1.1.1.1", 40001, 22),
+            entry("20:21:00", "1.1.1.1", 40000, 23),
+        ];
+        let (reps, groups) = group(&entries, &[0, 1, 2, 3]);
+        assert_eq!(reps, [0, 1, 3]);
+        assert_eq!(
+            groups[&0],
+            Group {
+                count: 2,
+                first_seen: 2,
+                last_seen: 0,
+                mixed_src_ports: true,
+            }
+        );
+        assert_eq!(groups[&1].count, 1);
+        assert!(!groups[&3].mixed_src_ports);
+    }
+}
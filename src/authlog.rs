@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::{FileFingerprint, file_fingerprint};
+
+// sshd outcomes per remote IP from an auth.log-style file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthStats {
+    pub failed: usize,
+    pub invalid_user: usize,
+    pub accepted: usize,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthEvent {
+    Failed,
+    InvalidUser,
+    Accepted,
+}
+
+// Secondary log correlated with firewall entries by source IP. Re-read when
+// its size or mtime changes.
+pub struct AuthLog {
+    pub path: PathBuf,
+    fingerprint: Option<FileFingerprint>,
+    by_ip: HashMap<String, AuthStats>,
+}
+
+impl AuthLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            fingerprint: None,
+            by_ip: HashMap::new(),
+        }
+    }
+
+    // Returns Ok(true) when the file changed and was re-read.
+    pub fn refresh(&mut self) -> io::Result<bool> {
+        let current = file_fingerprint(&self.path)?;
+        if self.fingerprint.as_ref() == Some(&current) {
+            return Ok(false);
+        }
+        let contents = fs::read_to_string(&self.path)?;
+        self.by_ip = summarize(&contents);
+        self.fingerprint = Some(current);
+        Ok(true)
+    }
+
+    pub fn stats(&self, ip: &str) -> Option<&AuthStats> {
+        self.by_ip.get(ip)
+    }
+}
+
+fn summarize(contents: &str) -> HashMap<String, AuthStats> {
+    let mut by_ip: HashMap<String, AuthStats> = HashMap::new();
+    for line in contents.lines() {
+        let Some((ip, event)) = parse_sshd_line(line) else {
+            continue;
+        };
+        let stats = by_ip.entry(ip.to_string()).or_default();
+        match event {
+            AuthEvent::Failed => stats.failed += 1,
+            AuthEvent::InvalidUser => stats.invalid_user += 1,
+            AuthEvent::Accepted => stats.accepted += 1,
+        }
+        stats.last_seen = line
+            .split(" sshd")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+    }
+    by_ip
+}
+
+// Recognizes the sshd messages worth correlating and returns the remote IP
+// ("... from IP port N ...").
+fn parse_sshd_line(line: &str) -> Option<(&str, AuthEvent)> {
+    let message = &line[line.find(" sshd")?..];
+    let event = if message.contains("Failed password") || message.contains("Failed publickey") {
+        AuthEvent::Failed
+    } else if message.contains("Invalid user") {
+        AuthEvent::InvalidUser
+    } else if message.contains("Accepted ") {
+        AuthEvent::Accepted
+    } else {
+        return None;
+    };
+    let mut tokens = message.split_whitespace();
+    tokens.find(|token| *token == "from")?;
+    Some((tokens.next()?, event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_sshd_events_by_ip() {
+        let log = "\
+Feb 11 20:21:00 host sshd[100]: Invalid user admin from 203.0.113.5 port 40000
+Feb 11 20:21:01 host sshd[100]: Failed password for invalid user admin from 203.0.113.5 port 40000 ssh2
+Feb 11 20:21:05 host sshd[101]: Failed password for root from 203.0.113.5 port 40002 ssh2
+Feb 11 20:22:00 host sshd[102]: Accepted publickey for me from 192.168.1.10 port 50000 ssh2: ED25519 SHA256:x
+Feb 11 20:22:01 host CRON[5]: pam_unix(cron:session): session opened for user root";
+        let by_ip = summarize(log);
+        assert_eq!(
+            by_ip.get("203.0.113.5"),
+            Some(&AuthStats {
+                failed: 2,
+                invalid_user: 1,
+                accepted: 0,
+                last_seen: "Feb 11 20:21:05 host".to_string(),
+            })
+        );
+        assert_eq!(by_ip.get("192.168.1.10").map(|s| s.accepted), Some(1));
+        assert_eq!(by_ip.len(), 2);
+    }
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use crate::net::{PrefixTrie, parse_network};
+
+// `--blocklist` files merged into one lookup. Each line holds an address or
+// CIDR network; anything after `;` or `#` is a comment, as in Spamhaus DROP
+// ("1.10.16.0/20 ; SBL256894") or FireHOL lists. Unparsable lines are skipped.
+#[derive(Default)]
+pub struct Blocklists {
+    names: Vec<String>,
+    trie: PrefixTrie,
+}
+
+impl Blocklists {
+    pub fn load(paths: &[PathBuf]) -> io::Result<Self> {
+        let mut lists = Self::default();
+        for path in paths {
+            let text = fs::read_to_string(path).map_err(|err| {
+                io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+            })?;
+            lists.add(list_name(path), &text);
+        }
+        Ok(lists)
+    }
+
+    fn add(&mut self, name: String, text: &str) {
+        let label = self.names.len() as u32;
+        self.names.push(name);
+        for line in text.lines() {
+            let entry = line.split([';', '#']).next().unwrap_or_default().trim();
+            if let Some((addr, len)) = parse_network(entry) {
+                self.trie.insert(addr, len, label);
+            }
+        }
+    }
+
+    // Name of the list that has `ip`, for the detail pane.
+    pub fn list_for(&self, ip: Option<&str>) -> Option<&str> {
+        let addr = ip?.trim().parse::<IpAddr>().ok()?;
+        let label = self.trie.lookup(addr)?;
+        self.names.get(label as usize).map(String::as_str)
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} networks from {}",
+            self.trie.networks(),
+            self.names.join(", ")
+        )
+    }
+}
+
+fn list_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_addresses_and_networks_from_every_list() {
+        let mut lists = Blocklists::default();
+        lists.add(
+            "drop.txt".to_string(),
+            "; Spamhaus DROP\n1.10.16.0/20 ; SBL256894\n2001:db8:dead::/48\nnot an ip\n",
+        );
+        lists.add("hosts.txt".to_string(), "# hosts\n203.0.113.7\n1.10.16.5\n");
+        assert_eq!(lists.trie.networks(), 4);
+        assert_eq!(lists.list_for(Some("1.10.31.255")), Some("drop.txt"));
+        assert_eq!(lists.list_for(Some("1.10.32.0")), None);
+        // The most specific network wins.
+        assert_eq!(lists.list_for(Some("1.10.16.5")), Some("hosts.txt"));
+        assert_eq!(lists.list_for(Some("203.0.113.7")), Some("hosts.txt"));
+        assert_eq!(lists.list_for(Some("203.0.113.8")), None);
+        assert_eq!(lists.list_for(Some("2001:db8:dead:1::9")), Some("drop.txt"));
+        assert_eq!(lists.list_for(Some("2001:db8:beef::1")), None);
+        assert_eq!(lists.list_for(None), None);
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use crate::LogEntry;
+use crate::aggregate::same_group;
+
+// Consecutive rows with the same (src, dst, dpt, proto, action) that fold
+// into one while the time-ordered table is flooded.
+pub const MIN_RUN: usize = 20;
+
+// A folded run, keyed by its first row in view order. Entries are stored
+// newest first, so the highest index is the oldest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Burst {
+    pub count: usize,
+    pub first_seen: usize,
+    pub last_seen: usize,
+}
+
+// Positions in `indices` of every run of at least MIN_RUN identical tuples.
+pub fn runs(entries: &[LogEntry], indices: &[usize]) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for pos in 1..=indices.len() {
+        let continues =
+            pos < indices.len() && same_group(&entries[indices[start]], &entries[indices[pos]]);
+        if !continues {
+            if pos - start >= MIN_RUN {
+                runs.push(start..pos);
+            }
+            start = pos;
+        }
+    }
+    runs
+}
+
+// A run's lasting identity: the ID of its oldest entry, which stays put while
+// the flood adds rows at the newest end.
+pub fn run_key(entries: &[LogEntry], indices: &[usize], run: &Range<usize>) -> u64 {
+    let oldest = indices[run.clone()].iter().max().copied().unwrap_or(0);
+    entries[oldest].id
+}
+
+// `indices` with every run not in `expanded` folded into its first row.
+// Returns the rows and the burst behind each folded one.
+pub fn compress(
+    entries: &[LogEntry],
+    indices: &[usize],
+    expanded: &HashSet<u64>,
+) -> (Vec<usize>, HashMap<usize, Burst>) {
+    let mut rows = Vec::with_capacity(indices.len());
+    let mut bursts = HashMap::new();
+    let mut next = 0;
+    for run in runs(entries, indices) {
+        if expanded.contains(&run_key(entries, indices, &run)) {
+            continue;
+        }
+        rows.extend_from_slice(&indices[next..run.start]);
+        let members = &indices[run.clone()];
+        let rep = members[0];
+        rows.push(rep);
+        bursts.insert(
+            rep,
+            Burst {
+                count: members.len(),
+                first_seen: members.iter().max().copied().unwrap_or(rep),
+                last_seen: members.iter().min().copied().unwrap_or(rep),
+            },
+        );
+        next = run.end;
+    }
+    rows.extend_from_slice(&indices[next..]);
+    (rows, bursts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn folds_long_runs_unless_expanded() {
+        let entry = |id: u64, src: &str| {
+            let mut entry = parse_ufw_line(&format!(
+                "Feb 11 20:00:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=40000 DPT=22"
+            ))
+            .unwrap();
+            entry.id = id;
+            entry
+        };
+        // A flood of 25 from one source between single rows, then a short
+        // run of 19 that stays as it is.
+        let mut entries = vec![entry(100, "9.9.9.9")];
+        entries.extend((0..25).map(|i| entry(99 - i, "1.1.1.1")));
+        entries.push(entry(50, "9.9.9.9"));
+        entries.extend((0..19).map(|i| entry(49 - i, "2.2.2.2")));
+        let indices: Vec<usize> = (0..entries.len()).collect();
+
+        let found = runs(&entries, &indices);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0], 1..26);
+        let (rows, bursts) = compress(&entries, &indices, &HashSet::new());
+        assert_eq!(rows.len(), entries.len() - 24);
+        assert_eq!(&rows[..3], [0, 1, 26]);
+        assert_eq!(
+            bursts[&1],
+            Burst {
+                count: 25,
+                first_seen: 25,
+                last_seen: 1,
+            }
+        );
+        assert_eq!(bursts.len(), 1);
+
+        let key = run_key(&entries, &indices, &(1..26));
+        assert_eq!(key, 75);
+        let (rows, bursts) = compress(&entries, &indices, &HashSet::from([key]));
+        assert_eq!(rows, indices);
+        assert!(bursts.is_empty());
+    }
+}
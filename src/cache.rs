@@ -0,0 +1,327 @@
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+const APP_DIR: &str = "ufw-log-viewer";
+
+// The `DiskCache` files the app keeps; `--clear-cache` removes only these, so
+// a `--cache-dir` shared with other files loses nothing else.
+pub const GEOIP_CACHE: &str = "geoip";
+pub const WHOIS_CACHE: &str = "whois";
+const CACHE_FILES: [&str; 2] = [GEOIP_CACHE, WHOIS_CACHE];
+
+// Paths from `--config`, `--state-dir` and `--cache-dir`, falling back to the
+// UFW_LOG_VIEWER_CONFIG, _STATE_DIR and _CACHE_DIR environment variables.
+// Set once at startup, before anything is read or written, so the app can run
+// self-contained from a USB stick or with a read-only home.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirOverrides {
+    // config.ini itself rather than its directory.
+    pub config: Option<PathBuf>,
+    // Presets, expected egress and pinned interfaces.
+    pub state_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl DirOverrides {
+    // Fills the paths not given on the command line from `var`; empty values
+    // count as unset.
+    pub fn or_env(self, var: impl Fn(&str) -> Option<OsString>) -> Self {
+        let env = |name: &str| {
+            var(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        Self {
+            config: self.config.or_else(|| env("UFW_LOG_VIEWER_CONFIG")),
+            state_dir: self.state_dir.or_else(|| env("UFW_LOG_VIEWER_STATE_DIR")),
+            cache_dir: self.cache_dir.or_else(|| env("UFW_LOG_VIEWER_CACHE_DIR")),
+        }
+    }
+}
+
+static OVERRIDES: OnceLock<DirOverrides> = OnceLock::new();
+
+pub fn set_overrides(overrides: DirOverrides) {
+    let _ = OVERRIDES.set(overrides);
+}
+
+pub fn overrides() -> &'static DirOverrides {
+    OVERRIDES.get_or_init(DirOverrides::default)
+}
+
+// Cache root shared by enrichers: the `--cache-dir` override, else
+// $XDG_CACHE_HOME/ufw-log-viewer, falling back to ~/.cache/ufw-log-viewer.
+// Relative XDG values are ignored per the spec.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = &overrides().cache_dir {
+        return Some(dir.clone());
+    }
+    let xdg = std::env::var_os("XDG_CACHE_HOME").map(PathBuf::from);
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    cache_dir_from(xdg, home)
+}
+
+// Files the app writes as it is used, such as filter presets: the
+// `--state-dir` override, else the config directory.
+pub fn state_dir() -> Option<PathBuf> {
+    match &overrides().state_dir {
+        Some(dir) => Some(dir.clone()),
+        None => config_dir(),
+    }
+}
+
+// Settings: $XDG_CONFIG_HOME/ufw-log-viewer, falling back to
+// ~/.config/ufw-log-viewer.
+pub fn config_dir() -> Option<PathBuf> {
+    let xdg = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from);
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    xdg_dir_from(xdg, home, ".config")
+}
+
+fn cache_dir_from(xdg_cache_home: Option<PathBuf>, home: Option<PathBuf>) -> Option<PathBuf> {
+    xdg_dir_from(xdg_cache_home, home, ".cache")
+}
+
+fn xdg_dir_from(xdg: Option<PathBuf>, home: Option<PathBuf>, fallback: &str) -> Option<PathBuf> {
+    if let Some(dir) = xdg.filter(|dir| dir.is_absolute()) {
+        return Some(dir.join(APP_DIR));
+    }
+    home.filter(|dir| dir.is_absolute())
+        .map(|dir| dir.join(fallback).join(APP_DIR))
+}
+
+// One enricher's lookups kept between runs in `<cache dir>/<name>`, a
+// `key<TAB>value` line each, oldest first. A file grown past `limit` bytes is
+// cut back to its newer half. Write errors are ignored: the cache only saves
+// repeating a lookup.
+pub struct DiskCache {
+    path: Option<PathBuf>,
+    limit: u64,
+}
+
+impl DiskCache {
+    pub fn open(name: &str, limit: u64) -> Self {
+        Self {
+            path: cache_dir().map(|dir| dir.join(name)),
+            limit,
+        }
+    }
+
+    // Neither reads nor writes anything.
+    pub fn none() -> Self {
+        Self {
+            path: None,
+            limit: 0,
+        }
+    }
+
+    // Forgets the file when it is older than `time`, e.g. results from a
+    // database that has since been updated.
+    pub fn expire_before(&self, time: SystemTime) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let written = fs::metadata(path).and_then(|meta| meta.modified());
+        if written.is_ok_and(|written| written < time) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    pub fn load(&self) -> Vec<(String, String)> {
+        let Some(contents) = self
+            .path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+        else {
+            return Vec::new();
+        };
+        contents
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(key, value)| (key.to_string(), unescape(value)))
+            .collect()
+    }
+
+    pub fn store(&self, key: &str, value: &str) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let line = format!("{}\t{}\n", key, escape(value));
+        let appended = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| {
+                file.write_all(line.as_bytes())?;
+                file.metadata()
+            });
+        if appended.is_ok_and(|meta| meta.len() > self.limit) {
+            let _ = self.shrink(path);
+        }
+    }
+
+    fn shrink(&self, path: &Path) -> io::Result<()> {
+        let contents = fs::read_to_string(path)?;
+        let mut kept = 0;
+        let start = contents
+            .lines()
+            .rev()
+            .take_while(|line| {
+                kept += line.len() + 1;
+                kept as u64 <= self.limit / 2
+            })
+            .count();
+        let lines: Vec<&str> = contents.lines().collect();
+        let mut rest = lines[lines.len() - start..].join("\n");
+        rest.push('\n');
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, rest)?;
+        fs::rename(tmp, path)
+    }
+}
+
+// Values may span lines (whois excerpts); keys are addresses.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+// Removes the app's cache files from `dir`, and `dir` itself once that left
+// it empty. Returns how many bytes were freed.
+pub fn clear_cache(dir: &Path) -> io::Result<u64> {
+    let mut freed = 0;
+    for name in CACHE_FILES {
+        let path = dir.join(name);
+        // Also a half-written shrink.
+        for path in [path.with_extension("tmp"), path] {
+            match fs::metadata(&path) {
+                Ok(meta) => {
+                    fs::remove_file(&path)?;
+                    freed += meta.len();
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+    let _ = fs::remove_dir(dir);
+    Ok(freed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_dir_prefers_absolute_xdg_cache_home() {
+        assert_eq!(
+            cache_dir_from(Some("/tmp/xdg".into()), Some("/home/u".into())),
+            Some(PathBuf::from("/tmp/xdg/ufw-log-viewer"))
+        );
+        assert_eq!(
+            cache_dir_from(Some("relative".into()), Some("/home/u".into())),
+            Some(PathBuf::from("/home/u/.cache/ufw-log-viewer"))
+        );
+        assert_eq!(cache_dir_from(None, None), None);
+    }
+
+    #[test]
+    fn disk_cache_round_trips_values_and_stays_under_its_limit() {
+        let dir = std::env::temp_dir().join(format!("ufw-disk-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = DiskCache {
+            path: Some(dir.join("whois")),
+            limit: 200,
+        };
+        cache.store("1.2.3.4", "netname: A\\B\ndescr: tab\there");
+        assert_eq!(
+            cache.load(),
+            [(
+                "1.2.3.4".to_string(),
+                "netname: A\\B\ndescr: tab\there".to_string()
+            )]
+        );
+        for n in 0..20 {
+            cache.store(&format!("10.0.0.{}", n), "US");
+        }
+        let loaded = cache.load();
+        assert!(fs::metadata(dir.join("whois")).unwrap().len() <= 200);
+        assert_eq!(loaded.last().unwrap().0, "10.0.0.19");
+        assert!(loaded.iter().all(|(key, _)| key != "1.2.3.4"));
+
+        cache.expire_before(SystemTime::now() + std::time::Duration::from_secs(60));
+        assert!(cache.load().is_empty());
+        assert!(DiskCache::none().load().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clearing_removes_only_the_cache_files() {
+        let dir = std::env::temp_dir().join(format!("ufw-clear-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(GEOIP_CACHE), "1.2.3.4\tLV\n").unwrap();
+        fs::write(dir.join(WHOIS_CACHE), "").unwrap();
+        fs::write(dir.join("notes.txt"), "keep me").unwrap();
+        assert_eq!(clear_cache(&dir).unwrap(), 11);
+        assert!(!dir.join(GEOIP_CACHE).exists() && !dir.join(WHOIS_CACHE).exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("notes.txt")).unwrap(),
+            "keep me"
+        );
+
+        fs::remove_file(dir.join("notes.txt")).unwrap();
+        assert_eq!(clear_cache(&dir).unwrap(), 0);
+        assert!(!dir.exists());
+        assert_eq!(clear_cache(&dir).unwrap(), 0);
+    }
+
+    #[test]
+    fn command_line_overrides_beat_environment_variables() {
+        let env = |name: &str| match name {
+            "UFW_LOG_VIEWER_CONFIG" => Some(OsString::from("/env/config.ini")),
+            "UFW_LOG_VIEWER_STATE_DIR" => Some(OsString::from("/env/state")),
+            "UFW_LOG_VIEWER_CACHE_DIR" => Some(OsString::new()),
+            _ => None,
+        };
+        let flags = DirOverrides {
+            state_dir: Some("/usb/state".into()),
+            ..DirOverrides::default()
+        };
+        assert_eq!(
+            flags.or_env(env),
+            DirOverrides {
+                config: Some("/env/config.ini".into()),
+                state_dir: Some("/usb/state".into()),
+                cache_dir: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::net::IpAddr;
+
+use crate::LogEntry;
+use crate::ports::PortSet;
+
+// tcpdump/BPF expression scoped to an entry's flow: both hosts, the protocol
+// and the destination port (the source port is usually ephemeral).
+pub fn entry_bpf(entry: &LogEntry) -> Option<String> {
+    let mut parts = Vec::new();
+    for ip in [entry.src_ip.as_deref(), entry.dst_ip.as_deref()]
+        .into_iter()
+        .flatten()
+    {
+        if ip.parse::<IpAddr>().is_ok() {
+            parts.push(format!("host {}", ip));
+        }
+    }
+    match (entry.proto.as_deref().and_then(bpf_proto), entry.dst_port) {
+        (Some(proto @ ("tcp" | "udp")), Some(port)) => {
+            parts.push(format!("{} port {}", proto, port))
+        }
+        (Some(proto), _) => parts.push(proto.to_string()),
+        (None, Some(port)) => parts.push(format!("port {}", port)),
+        (None, None) => {}
+    }
+    (!parts.is_empty()).then(|| parts.join(" and "))
+}
+
+// BPF expression for the IP, port and protocol filters. Substring filters
+// that do not name a full address, CIDR or protocol, and negated ports, cannot
+// be expressed and are reported back instead.
+pub fn filters_bpf(ip: &str, port: &str, proto: &str) -> Result<String, String> {
+    let mut parts = Vec::new();
+    let ip = ip.trim();
+    if !ip.is_empty() {
+        if ip.parse::<IpAddr>().is_ok() {
+            parts.push(format!("host {}", ip));
+        } else if is_cidr(ip) {
+            parts.push(format!("net {}", ip));
+        } else {
+            return Err(format!("IP filter '{}' is not a full address or CIDR", ip));
+        }
+    }
+    let proto = proto.trim();
+    let proto = if proto.is_empty() {
+        None
+    } else {
+        Some(
+            bpf_proto(proto)
+                .ok_or_else(|| format!("Protocol filter '{}' has no BPF form", proto))?,
+        )
+    };
+    let port = port.trim();
+    if !port.is_empty() {
+        let ports = PortSet::parse(port)?;
+        let prefix = match proto {
+            Some(proto @ ("tcp" | "udp")) => format!("{} ", proto),
+            _ => String::new(),
+        };
+        let terms: Vec<String> = ports
+            .ranges()
+            .iter()
+            .map(|&(low, high)| {
+                if low == high {
+                    format!("{}port {}", prefix, low)
+                } else {
+                    format!("{}portrange {}-{}", prefix, low, high)
+                }
+            })
+            .collect();
+        parts.push(match terms.len() {
+            1 => terms.concat(),
+            _ => format!("({})", terms.join(" or ")),
+        });
+    }
+    if let Some(proto) = proto
+        && port.is_empty()
+    {
+        parts.push(proto.to_string());
+    }
+    if parts.is_empty() {
+        return Err("No IP, port or protocol filter set".to_string());
+    }
+    Ok(parts.join(" and "))
+}
+
+fn bpf_proto(proto: &str) -> Option<&'static str> {
+    match proto.to_ascii_lowercase().as_str() {
+        "tcp" => Some("tcp"),
+        "udp" => Some("udp"),
+        "icmp" => Some("icmp"),
+        "icmpv6" | "ipv6-icmp" | "icmp6" => Some("icmp6"),
+        "sctp" => Some("sctp"),
+        _ => None,
+    }
+}
+
+fn is_cidr(value: &str) -> bool {
+    value.split_once('/').is_some_and(|(addr, prefix)| {
+        addr.parse::<IpAddr>().is_ok() && prefix.parse::<u8>().is_ok_and(|p| p <= 128)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn builds_expression_from_entry_and_filters() {
+        let line = "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1 PROTO=TCP SPT=51515 DPT=22";
+        let entry = parse_ufw_line(line).unwrap();
+        assert_eq!(
+            entry_bpf(&entry).as_deref(),
+            Some("host 1.2.3.4 and host 10.0.0.1 and tcp port 22")
+        );
+        assert_eq!(
+            filters_bpf("10.0.0.0/8", "443", "tcp").as_deref(),
+            Ok("net 10.0.0.0/8 and tcp port 443")
+        );
+        assert_eq!(filters_bpf("", "", "ICMP").as_deref(), Ok("icmp"));
+        assert_eq!(
+            filters_bpf("", "22,>1024", "udp").as_deref(),
+            Ok("(udp port 22 or udp portrange 1025-65535)")
+        );
+        assert!(filters_bpf("10.0.", "", "").is_err());
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+
+use crate::cache::DirOverrides;
+use crate::mqtt::MqttTarget;
+use crate::parser::LogFormat;
+use crate::render_cache::Breakpoints;
+use crate::siem::SiemFormat;
+use crate::sink::{LineFormat, SinkSpec};
+use crate::tags::TagRule;
+use crate::theme::Theme;
+use crate::timerange::now_seconds;
+use crate::trend::TrendFormat;
+use crate::{ExportTarget, FilterField, parse_expected_ports, parse_filter_arg, parse_since};
+
+const AFTER_HELP: &str = "\
+--print exits 0 when entries matched, 1 when none did and 2 on errors, like grep.
+Several LOG_PATHs, e.g. kern.log and ufw.log, are merged by timestamp with a File column.
+UFW_LOG_VIEWER_CONFIG, UFW_LOG_VIEWER_STATE_DIR and UFW_LOG_VIEWER_CACHE_DIR set the same paths.
+If LOG_PATH is not provided, the app checks:
+  1) /var/log/ufw-firewall.log
+  2) /var/log/ufw.log
+  3) /var/log/kern.log";
+
+// The command line as clap reads it. Modes that replace the TUI (`--print`,
+// `--trend`, `--export`, `--clear-cache`) conflict with each other, and the
+// options that only make sense within one require it.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "A terminal UI for browsing and filtering UFW logs",
+    after_help = AFTER_HELP,
+    args_conflicts_with_subcommands = true,
+    disable_help_subcommand = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Tool>,
+    // Positional paths and `--file` values in the order given; `parse` merges
+    // the latter in.
+    #[arg(value_name = "LOG_PATH", help = "Log files or quoted globs to read")]
+    pub logs: Vec<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Log file or quoted glob to read (same as LOG_PATH, repeatable)"
+    )]
+    file: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Read settings from FILE instead of config.ini in the config directory"
+    )]
+    pub config: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Keep presets, pinned interfaces and expected egress in DIR"
+    )]
+    pub state_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Keep cached enrichment data in DIR"
+    )]
+    pub cache_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with_all = ["logs", "file", "print", "summary", "print_format", "trend", "export", "journal", "listen"],
+        help = "Remove cached enrichment data and exit"
+    )]
+    pub clear_cache: bool,
+
+    #[arg(long, help = "Disable every network-touching feature")]
+    pub offline: bool,
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Also follow new logs appearing in DIR (repeatable)"
+    )]
+    pub watch_dir: Vec<PathBuf>,
+    #[arg(
+        long,
+        value_name = "FMT",
+        value_parser = log_format,
+        help = "Log format: auto (default), ufw, pfsense, windows"
+    )]
+    pub format: Option<LogFormat>,
+
+    #[arg(
+        long,
+        value_name = "FMT",
+        value_parser = siem_format,
+        conflicts_with = "trend",
+        help = "Write entries as cef or leef records and exit"
+    )]
+    pub export: Option<SiemFormat>,
+    #[arg(
+        long,
+        value_name = "TARGET",
+        value_parser = export_target,
+        requires = "export",
+        help = "Export target: file, - (stdout) or udp://host[:port]"
+    )]
+    pub output: Option<ExportTarget>,
+    #[arg(
+        long,
+        visible_alias = "query",
+        conflicts_with_all = ["trend", "export"],
+        help = "Print entries matching --filter/--since/--iface and exit"
+    )]
+    pub print: bool,
+    #[arg(
+        long,
+        value_name = "F",
+        value_parser = line_format,
+        conflicts_with_all = ["trend", "export"],
+        help = "Printed entries as raw lines (default) or json (JSONL)"
+    )]
+    pub print_format: Option<LineFormat>,
+    #[arg(
+        long,
+        conflicts_with_all = ["trend", "export"],
+        help = "Print, then end with matched counts by action"
+    )]
+    pub summary: bool,
+    #[arg(
+        long,
+        value_name = "WEEKS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Print per-week counts for the log and its rotations and exit"
+    )]
+    pub trend: Option<u32>,
+    #[arg(
+        long,
+        value_name = "F",
+        value_parser = trend_format,
+        requires = "trend",
+        help = "Trend output: csv (default) or json"
+    )]
+    pub trend_format: Option<TrendFormat>,
+
+    #[arg(
+        long,
+        value_name = "HOST",
+        help = "Relay new entries matching the view to udp://host[:port]"
+    )]
+    pub forward: Vec<String>,
+    #[arg(
+        long,
+        value_name = "URL",
+        value_parser = mqtt_url,
+        help = "Publish new matching entries as JSON to mqtt://[user:pass@]host[:port]/topic"
+    )]
+    pub mqtt: Vec<String>,
+    #[arg(
+        long,
+        value_name = "K:TARGET",
+        value_parser = SinkSpec::parse,
+        help = "Send new matching entries to a sink: syslog, mqtt, webhook:http://..., jsonl:PATH, csv:PATH (repeatable)"
+    )]
+    pub sink: Vec<SinkSpec>,
+    #[arg(
+        long,
+        value_name = "D",
+        help = "Save tcpdump output started with T into D"
+    )]
+    pub capture_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Write E abuse reports into DIR (default ./abuse-reports)"
+    )]
+    pub abuse_dir: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = abuse_top,
+        help = "Number of top blocked sources E reports on (default 10)"
+    )]
+    pub abuse_top: Option<usize>,
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = ipv6_prefix,
+        help = "Group IPv6 offenders in s and E by /N (default 64, 128 per address)"
+    )]
+    pub ipv6_prefix: Option<u8>,
+    #[arg(
+        long,
+        value_name = "L",
+        value_parser = parse_expected_ports,
+        help = "Flag inbound ALLOWs to ports not in L (e.g. 22,80,https)"
+    )]
+    pub expect_ports: Option<HashSet<u16>>,
+    #[arg(
+        long,
+        value_name = "RULE",
+        value_parser = TagRule::parse,
+        help = "Tag entries matching FIELD=VALUE with a technique, e.g. port=3389:T1021 (repeatable)"
+    )]
+    pub tag: Vec<TagRule>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Correlate sshd logins from PATH (e.g. /var/log/auth.log) by IP"
+    )]
+    pub auth_log: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Show source countries from a MaxMind/GeoLite2 .mmdb database"
+    )]
+    pub geoip: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Mark sources listed in PATH (IP or CIDR per line, e.g. Spamhaus DROP) with # (repeatable)"
+    )]
+    pub blocklist: Vec<PathBuf>,
+    #[arg(
+        long,
+        value_name = "GLOBS",
+        value_delimiter = ',',
+        help = "Only offer interfaces matching GLOBS as chips, e.g. 'eth*,ppp0'"
+    )]
+    pub iface: Vec<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Start on interface NAME's chip, or all (default: the busiest WAN-like one)"
+    )]
+    pub interface: Option<String>,
+    #[arg(
+        long,
+        help = "Treat 100.64.0.0/10 as local instead of flagging it as bogon"
+    )]
+    pub cgnat_local: bool,
+    #[arg(
+        long,
+        help = "Flag DNS query floods and UDP port spread (tunnels) in the Enter drill-down"
+    )]
+    pub dns_watch: bool,
+    #[arg(long, help = "Plain less-like view of raw lines for narrow terminals")]
+    pub pager: bool,
+    #[arg(
+        long,
+        help = "Also load rotated logs (LOG.1, LOG.2.gz, ...) in timestamp order"
+    )]
+    pub rotated: bool,
+    #[arg(
+        long,
+        conflicts_with = "listen",
+        help = "Read kernel messages from journald (journalctl -k) instead of a file"
+    )]
+    pub journal: bool,
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "Collect UFW lines sent over syslog (UDP and TCP) to ADDR, e.g. 0.0.0.0:5514"
+    )]
+    pub listen: Option<String>,
+    #[arg(
+        long,
+        value_name = "ADDR",
+        help = "Serve a read-only, auto-refreshing page of the view on ADDR, e.g. 127.0.0.1:8080"
+    )]
+    pub http: Option<String>,
+    #[arg(
+        long,
+        help = "Keep the newest 20000 entries, cut raw lines to 256 bytes, shrink caches"
+    )]
+    pub low_memory: bool,
+    #[arg(
+        long,
+        overrides_with = "no_follow",
+        help = "Follow the log live (default)"
+    )]
+    pub follow: bool,
+    #[arg(
+        long,
+        overrides_with = "follow",
+        help = "Start with live updates paused"
+    )]
+    pub no_follow: bool,
+    #[arg(
+        long,
+        value_name = "WHEN",
+        value_parser = since,
+        help = "Only load entries since WHEN: 2h, 7d, today, 20:00 (L loads older)"
+    )]
+    pub since: Option<i64>,
+    #[arg(
+        long,
+        value_name = "F=V",
+        value_parser = parse_filter_arg,
+        help = "Start with a filter, e.g. ip=10.0.0.5 or port=!443 (repeatable)"
+    )]
+    pub filter: Vec<(FilterField, String)>,
+    #[arg(long, help = "Leave the mouse to the terminal (native text selection)")]
+    pub no_mouse: bool,
+    #[arg(
+        long,
+        value_name = "NAME",
+        value_parser = theme,
+        help = "Colors: dark (default), light or mono"
+    )]
+    pub theme: Option<Theme>,
+    #[arg(long, help = "b copies the ufw deny command instead of running it")]
+    pub dry_run: bool,
+    #[arg(
+        long,
+        value_name = "W",
+        value_parser = Breakpoints::parse,
+        help = "Table widths that add dates and service descriptions (default 120,150)"
+    )]
+    pub breakpoints: Option<Breakpoints>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Tool {
+    #[command(about = "Check permissions, UFW logging and terminal support")]
+    Doctor {
+        #[arg(
+            value_name = "LOG_PATH",
+            help = "Log file to check instead of the default one"
+        )]
+        log_path: Option<PathBuf>,
+    },
+}
+
+impl Cli {
+    pub fn dir_overrides(&self) -> DirOverrides {
+        DirOverrides {
+            config: self.config.clone(),
+            state_dir: self.state_dir.clone(),
+            cache_dir: self.cache_dir.clone(),
+        }
+    }
+}
+
+// Parses `args` (without the program name). `--help` and `--version` come
+// back as errors of their kind, which `Error::exit` prints to stdout.
+pub fn parse(args: &[String]) -> Result<Cli, clap::Error> {
+    let program = env!("CARGO_PKG_NAME").to_string();
+    let matches = Cli::command().try_get_matches_from(std::iter::once(&program).chain(args))?;
+    let mut cli = Cli::from_arg_matches(&matches)?;
+    // Which path comes first decides the main log, so positionals and
+    // `--file` values are merged back into command-line order.
+    let indices = |id: &str| matches.indices_of(id).into_iter().flatten();
+    let mut logs: Vec<(usize, String)> = indices("logs")
+        .zip(std::mem::take(&mut cli.logs))
+        .chain(indices("file").zip(std::mem::take(&mut cli.file)))
+        .collect();
+    logs.sort_by_key(|(index, _)| *index);
+    cli.logs = logs.into_iter().map(|(_, path)| path).collect();
+    Ok(cli)
+}
+
+// A usage error for problems only found after parsing, e.g. a glob that
+// matches nothing.
+pub fn error(message: impl std::fmt::Display) -> clap::Error {
+    Cli::command().error(ErrorKind::ValueValidation, message)
+}
+
+fn log_format(name: &str) -> Result<LogFormat, String> {
+    LogFormat::from_name(name)
+        .ok_or_else(|| format!("Unknown format: {} (expected {})", name, LogFormat::NAMES))
+}
+
+fn siem_format(name: &str) -> Result<SiemFormat, String> {
+    SiemFormat::from_name(name)
+        .ok_or_else(|| format!("Unknown export format: {} (expected cef, leef)", name))
+}
+
+fn export_target(value: &str) -> Result<ExportTarget, String> {
+    Ok(ExportTarget::parse(value))
+}
+
+fn line_format(name: &str) -> Result<LineFormat, String> {
+    match name {
+        "raw" => Ok(LineFormat::Raw),
+        "json" => Ok(LineFormat::Json),
+        _ => Err(format!("expected raw or json, got {}", name)),
+    }
+}
+
+fn trend_format(name: &str) -> Result<TrendFormat, String> {
+    match name {
+        "csv" => Ok(TrendFormat::Csv),
+        "json" => Ok(TrendFormat::Json),
+        _ => Err(format!("expected csv or json, got {}", name)),
+    }
+}
+
+fn mqtt_url(value: &str) -> Result<String, String> {
+    MqttTarget::parse(value)?;
+    Ok(value.to_string())
+}
+
+fn abuse_top(count: &str) -> Result<usize, String> {
+    count
+        .parse::<usize>()
+        .ok()
+        .filter(|count| *count > 0)
+        .ok_or_else(|| format!("Invalid number of offenders: {}", count))
+}
+
+fn ipv6_prefix(len: &str) -> Result<u8, String> {
+    len.trim_start_matches('/')
+        .parse::<u8>()
+        .ok()
+        .filter(|len| (1..=128).contains(len))
+        .ok_or_else(|| format!("Invalid IPv6 prefix length: {} (expected 1-128)", len))
+}
+
+fn since(value: &str) -> Result<i64, String> {
+    parse_since(value, now_seconds())
+}
+
+fn theme(name: &str) -> Result<Theme, String> {
+    Theme::from_name(name)
+        .ok_or_else(|| format!("Unknown theme: {} (expected {})", name, Theme::NAMES))
+}
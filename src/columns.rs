@@ -0,0 +1,252 @@
+use std::path::Path;
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::report::{Report, heading};
+
+// A data column of the events table. The gutter, File and aggregate columns
+// are not listed: they come and go with their views and always lead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Time,
+    Action,
+    Direction,
+    Source,
+    // Only drawn with `--geoip`.
+    Country,
+    Dest,
+    Proto,
+    SrcPort,
+    DstPort,
+    Service,
+    InIface,
+    OutIface,
+    Ttl,
+    Length,
+    Flags,
+}
+
+impl Column {
+    pub const ALL: [Column; 15] = [
+        Self::Time,
+        Self::Action,
+        Self::Direction,
+        Self::Source,
+        Self::Country,
+        Self::Dest,
+        Self::Proto,
+        Self::SrcPort,
+        Self::DstPort,
+        Self::Service,
+        Self::InIface,
+        Self::OutIface,
+        Self::Ttl,
+        Self::Length,
+        Self::Flags,
+    ];
+
+    pub const DEFAULT: [Column; 10] = [
+        Self::Time,
+        Self::Action,
+        Self::Direction,
+        Self::Source,
+        Self::Country,
+        Self::Dest,
+        Self::Proto,
+        Self::SrcPort,
+        Self::DstPort,
+        Self::Service,
+    ];
+
+    // Name in config.ini's `[columns]` section.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Time => "time",
+            Self::Action => "action",
+            Self::Direction => "dir",
+            Self::Source => "src",
+            Self::Country => "cc",
+            Self::Dest => "dst",
+            Self::Proto => "proto",
+            Self::SrcPort => "spt",
+            Self::DstPort => "dpt",
+            Self::Service => "service",
+            Self::InIface => "in",
+            Self::OutIface => "out",
+            Self::Ttl => "ttl",
+            Self::Length => "len",
+            Self::Flags => "flags",
+        }
+    }
+
+    // Table header; Time's depends on the view and is set by the caller.
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Time => "Time",
+            Self::Action => "Action",
+            Self::Direction => "Dir",
+            Self::Source => "Source",
+            Self::Country => "CC",
+            Self::Dest => "Dest",
+            Self::Proto => "Proto",
+            Self::SrcPort => "SPT",
+            Self::DstPort => "DPT",
+            Self::Service => "Service",
+            Self::InIface => "In",
+            Self::OutIface => "Out",
+            Self::Ttl => "TTL",
+            Self::Length => "Len",
+            Self::Flags => "Flags",
+        }
+    }
+
+    // "time, action, dpt, ttl": the columns to show, in order.
+    pub fn parse_list(text: &str) -> Result<Vec<Column>, String> {
+        let mut columns = Vec::new();
+        for name in text
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let column = Self::ALL
+                .into_iter()
+                .find(|column| column.key().eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    format!(
+                        "unknown column {} (expected {})",
+                        name,
+                        Self::ALL.map(Column::key).join(", ")
+                    )
+                })?;
+            if columns.contains(&column) {
+                return Err(format!("column {} is listed twice", name));
+            }
+            columns.push(column);
+        }
+        if columns.is_empty() {
+            return Err("no columns listed".to_string());
+        }
+        Ok(columns)
+    }
+
+    pub fn list_text(columns: &[Column]) -> String {
+        columns
+            .iter()
+            .map(|column| column.key())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// State of the column picker popup: every column, shown ones first in table
+// order, then the hidden ones. Changes apply to the table as they are made.
+pub struct ColumnPicker {
+    pub columns: Vec<(Column, bool)>,
+    pub cursor: usize,
+}
+
+impl ColumnPicker {
+    pub fn new(shown: &[Column]) -> Self {
+        let mut columns: Vec<(Column, bool)> = shown.iter().map(|column| (*column, true)).collect();
+        columns.extend(
+            Column::ALL
+                .into_iter()
+                .filter(|column| !shown.contains(column))
+                .map(|column| (column, false)),
+        );
+        Self { columns, cursor: 0 }
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(self.columns.len() - 1);
+    }
+
+    // Shows or hides the column under the cursor; the last shown one stays.
+    pub fn toggle(&mut self) -> bool {
+        let shown = self.shown().len();
+        let (_, visible) = &mut self.columns[self.cursor];
+        if *visible && shown == 1 {
+            return false;
+        }
+        *visible = !*visible;
+        true
+    }
+
+    // Moves the column under the cursor one place, taking the cursor along.
+    pub fn shift(&mut self, delta: isize) {
+        let target = self.cursor.saturating_add_signed(delta);
+        if target < self.columns.len() && target != self.cursor {
+            self.columns.swap(self.cursor, target);
+            self.cursor = target;
+        }
+    }
+
+    pub fn shown(&self) -> Vec<Column> {
+        self.columns
+            .iter()
+            .filter(|(_, visible)| *visible)
+            .map(|(column, _)| *column)
+            .collect()
+    }
+
+    pub fn report(&self, path: Option<&Path>) -> Report {
+        let mut lines = vec![heading(format!("    {:<8} {}", "COLUMN", "HEADER"))];
+        for (pos, (column, visible)) in self.columns.iter().enumerate() {
+            let style = if pos == self.cursor {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{} {:<8} {}",
+                    if *visible { "[x]" } else { "[ ]" },
+                    column.key(),
+                    column.header()
+                ),
+                style,
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(
+            "j/k move · Space show/hide · J/K reorder · s save · Esc close",
+        ));
+        if let Some(path) = path {
+            lines.push(Line::from(format!("File: {}", path.display())));
+        }
+        Report::new("Columns", lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lists_and_reorders_in_the_picker() {
+        assert_eq!(
+            Column::parse_list("Service, time,ttl"),
+            Ok(vec![Column::Service, Column::Time, Column::Ttl])
+        );
+        assert!(Column::parse_list("time, colour").is_err());
+        assert!(Column::parse_list("time, time").is_err());
+        assert!(Column::parse_list(" , ").is_err());
+
+        let mut picker = ColumnPicker::new(&[Column::Time, Column::Service]);
+        assert_eq!(picker.columns.len(), Column::ALL.len());
+        picker.move_cursor(1);
+        picker.shift(-1);
+        assert_eq!(picker.shown(), [Column::Service, Column::Time]);
+        picker.toggle();
+        assert_eq!(picker.shown(), [Column::Time]);
+        picker.move_cursor(1);
+        assert!(!picker.toggle());
+        picker.move_cursor(1);
+        picker.toggle();
+        assert_eq!(picker.shown(), [Column::Time, Column::Action]);
+    }
+}
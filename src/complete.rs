@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+// Tab completion in filter prompts. Only the last comma-separated term is
+// completed, after any `!`, so "CN,r" offers "CN,RU" and "!8." offers "!8.8.8.8".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    // Everything in front of the completed term, kept as typed.
+    head: String,
+    candidates: Vec<String>,
+    index: usize,
+}
+
+impl Completion {
+    // Candidates are `values` starting with the typed term (ignoring case),
+    // most frequent first; None when nothing matches.
+    pub fn new<'a>(input: &str, values: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let term_start = input.rfind(',').map_or(0, |comma| comma + 1);
+        let term_start = term_start + usize::from(input[term_start..].starts_with('!'));
+        let (head, term) = input.split_at(term_start);
+        let term = term.to_ascii_lowercase();
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for value in values {
+            if !value.is_empty() && value.to_ascii_lowercase().starts_with(&term) {
+                *counts.entry(value).or_insert(0) += 1;
+            }
+        }
+        let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        (!ranked.is_empty()).then(|| Self {
+            head: head.to_string(),
+            candidates: ranked
+                .into_iter()
+                .map(|(value, _)| value.to_string())
+                .collect(),
+            index: 0,
+        })
+    }
+
+    // The input with the current candidate in place of the typed term.
+    pub fn text(&self) -> String {
+        format!("{}{}", self.head, self.candidates[self.index])
+    }
+
+    pub fn cycle(&mut self, backwards: bool) {
+        let len = self.candidates.len();
+        self.index = if backwards {
+            (self.index + len - 1) % len
+        } else {
+            (self.index + 1) % len
+        };
+    }
+
+    // "2/5" for the prompt.
+    pub fn position(&self) -> String {
+        format!("{}/{}", self.index + 1, self.candidates.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_the_last_term_by_frequency() {
+        let values = ["RU", "CN", "RU", "LV", "US"];
+        let completion = Completion::new("!cn,r", values).unwrap();
+        assert_eq!(completion.text(), "!cn,RU");
+
+        let mut completion = Completion::new("", values).unwrap();
+        assert_eq!(completion.text(), "RU");
+        completion.cycle(false);
+        assert_eq!(
+            (completion.text(), completion.position()),
+            ("CN".to_string(), "2/4".to_string())
+        );
+        completion.cycle(true);
+        completion.cycle(true);
+        assert_eq!(completion.text(), "US");
+
+        let completion = Completion::new("8.", ["8.8.8.8", "1.1.1.1"]).unwrap();
+        assert_eq!(completion.text(), "8.8.8.8");
+        assert_eq!(Completion::new("!9", ["8.8.8.8"]), None);
+    }
+}
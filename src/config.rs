@@ -0,0 +1,154 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cache::{config_dir, overrides};
+use crate::columns::Column;
+use crate::ini;
+use crate::keymap::{self, Command, KeyBinding};
+use crate::timefmt::TimeFormat;
+
+const FILE_NAME: &str = "config.ini";
+
+// Settings from config.ini in the config directory, e.g.
+//
+//   [timestamps]
+//   format = %d/%m/%Y %H:%M:%S
+//   format = %Y-%m-%d %H:%M:%S%f
+//
+//   [columns]
+//   show = time, action, src, dst, dpt, service, ttl
+//
+//   [keys]
+//   up = j Up
+//   down = k Down
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    // Tried in order before the built-in syslog and ISO 8601 stamps.
+    pub timestamp_formats: Vec<TimeFormat>,
+    // Events table columns in order; None keeps the built-in set.
+    pub columns: Option<Vec<Column>>,
+    // Rebound table commands, each with all of its keys.
+    pub keys: Vec<(Command, Vec<KeyBinding>)>,
+}
+
+// `--config` names the file itself; otherwise it lives in the config directory.
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = &overrides().config {
+        return Some(path.clone());
+    }
+    config_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+// A missing file is the default config.
+pub fn load(path: &Path) -> Result<Config, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text).map_err(|err| format!("{}: {}", path.display(), err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(err) => Err(format!("Failed to read {}: {}", path.display(), err)),
+    }
+}
+
+fn parse(text: &str) -> Result<Config, String> {
+    let mut config = Config::default();
+    for (section, entries) in ini::parse(text) {
+        for (key, value) in entries {
+            match (section.as_str(), key.as_str()) {
+                ("timestamps", "format") => {
+                    config.timestamp_formats.push(TimeFormat::parse(&value)?);
+                }
+                ("columns", "show") => config.columns = Some(Column::parse_list(&value)?),
+                ("keys", name) => config.keys.push(keymap::parse_binding(name, &value)?),
+                _ => return Err(format!("unknown setting {} in [{}]", key, section)),
+            }
+        }
+    }
+    Ok(config)
+}
+
+// Writes `columns` as the `[columns]` section, replacing an existing one and
+// keeping every other line, comments included, as it was.
+pub fn save_columns(path: &Path, columns: &[Column]) -> io::Result<()> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let mut out = replace_section(&text, "columns");
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "[columns]\nshow = {}\n",
+        Column::list_text(columns)
+    ));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, out)
+}
+
+// `text` without the lines of section `name`, header included.
+fn replace_section(text: &str, name: &str) -> String {
+    let mut out = String::new();
+    let mut skipping = false;
+    for line in text.lines() {
+        if let Some(header) = line
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            skipping = header.trim() == name;
+        }
+        if !skipping {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_timestamp_formats_in_order() {
+        let config = parse(
+            "# odd syslog\n[timestamps]\nformat = %d/%m/%Y %H:%M:%S\nformat=%y%m%d %H:%M:%S\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.timestamp_formats,
+            [
+                TimeFormat::parse("%d/%m/%Y %H:%M:%S").unwrap(),
+                TimeFormat::parse("%y%m%d %H:%M:%S").unwrap(),
+            ]
+        );
+        assert!(parse("[timestamps]\nformat = %H:%M\n").is_err());
+        assert!(parse("[timestamps]\npattern = %d %H\n").is_err());
+        assert_eq!(parse("[keys]\nup = j Up\n").unwrap().keys.len(), 1);
+        assert!(parse("[keys]\nleap = j\n").is_err());
+    }
+
+    #[test]
+    fn saving_columns_replaces_only_their_section() {
+        let path = std::env::temp_dir().join(format!("ufw-config-{}.ini", std::process::id()));
+        fs::write(
+            &path,
+            "# mine\n[columns]\nshow = time\n\n[timestamps]\nformat = %d/%m/%Y %H:%M:%S\n",
+        )
+        .unwrap();
+        save_columns(&path, &[Column::Service, Column::Ttl]).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            text,
+            "# mine\n[timestamps]\nformat = %d/%m/%Y %H:%M:%S\n\n[columns]\nshow = service, ttl\n"
+        );
+        assert_eq!(
+            load(&path).unwrap().columns,
+            Some(vec![Column::Service, Column::Ttl])
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+use crate::parser::timestamp_sort_key;
+use crate::{FilterField, Filters, LogEntry, filter_assignment};
+
+// Half-width of the window `t` locks around the selected entry.
+pub const TIME_LOCK_RADIUS_SECS: i64 = 15 * 60;
+
+// Entries within `radius_secs` of the entry the lock was set on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub anchor: String,
+    pub center: i64,
+    pub radius_secs: i64,
+}
+
+impl TimeWindow {
+    pub fn around(timestamp: &str, radius_secs: i64) -> Option<Self> {
+        Some(Self {
+            anchor: timestamp.to_string(),
+            center: timestamp_sort_key(timestamp)?,
+            radius_secs,
+        })
+    }
+
+    // Entries without a parsable timestamp are outside every window.
+    pub fn contains(&self, entry: &LogEntry) -> bool {
+        timestamp_sort_key(&entry.timestamp)
+            .is_some_and(|secs| (secs - self.center).abs() <= self.radius_secs)
+    }
+
+    pub fn label(&self) -> String {
+        format!("±{}m around {}", self.radius_secs / 60, self.anchor)
+    }
+}
+
+// The investigation scope every view shares: the table, the statistics view,
+// the drill-down and the summary reports all narrow to the same filters,
+// interface and time window, so switching between them keeps the scope.
+pub struct ViewContext<'a> {
+    pub filters: &'a Filters,
+    pub interface: Option<&'a str>,
+    pub window: Option<&'a TimeWindow>,
+}
+
+impl ViewContext<'_> {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        self.matches_interface(entry) && self.matches_on_any_interface(entry)
+    }
+
+    pub fn matches_interface(&self, entry: &LogEntry) -> bool {
+        self.interface.is_none_or(|iface| {
+            entry.in_iface.as_deref() == Some(iface) || entry.out_iface.as_deref() == Some(iface)
+        })
+    }
+
+    pub fn matches_on_any_interface(&self, entry: &LogEntry) -> bool {
+        self.window.is_none_or(|window| window.contains(entry)) && self.filters.matches(entry)
+    }
+
+    // "port=22 · eth0 · ±15m around …" for report titles; None when unscoped.
+    pub fn describe(&self) -> Option<String> {
+        let mut parts: Vec<String> = FilterField::ALL
+            .into_iter()
+            .filter(|field| !self.filters.value(*field).is_empty())
+            .map(|field| filter_assignment(field.label(), self.filters.value(field)))
+            .collect();
+        parts.extend(self.interface.map(str::to_string));
+        parts.extend(self.window.map(TimeWindow::label));
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn scope_combines_filters_interface_and_window() {
+        let entry = |time: &str, iface: &str| {
+            parse_ufw_line(&format!(
+                "Feb 11 {time} h kernel: [UFW BLOCK] IN={iface} OUT= SRC=1.2.3.4 DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22"
+            ))
+            .unwrap()
+        };
+        let mut filters = Filters::default();
+        filters.set(FilterField::Port, "22".to_string());
+        let window = TimeWindow::around("Feb 11 20:00:00 h", TIME_LOCK_RADIUS_SECS).unwrap();
+        let context = ViewContext {
+            filters: &filters,
+            interface: Some("eth0"),
+            window: Some(&window),
+        };
+        assert!(context.matches(&entry("20:15:00", "eth0")));
+        assert!(!context.matches(&entry("20:15:01", "eth0")));
+        assert!(!context.matches(&entry("19:50:00", "wlan0")));
+        assert!(context.matches_on_any_interface(&entry("19:50:00", "wlan0")));
+        assert_eq!(
+            context.describe().as_deref(),
+            Some("port=22 · eth0 · ±15m around Feb 11 20:00:00 h")
+        );
+
+        let filters = Filters::default();
+        let unscoped = ViewContext {
+            filters: &filters,
+            interface: None,
+            window: None,
+        };
+        assert_eq!(unscoped.describe(), None);
+    }
+}
@@ -0,0 +1,181 @@
+use std::process::Command;
+
+// Packet/byte counters of one firewall rule in a ufw-* chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleCounter {
+    pub chain: String,
+    pub rule: String,
+    pub packets: u64,
+    pub bytes: u64,
+    pub dport: Option<u16>,
+}
+
+// Reads counters from iptables/ip6tables, falling back to nft when the
+// legacy tools are missing. Both usually need root.
+pub fn read_rule_counters() -> Result<Vec<RuleCounter>, String> {
+    let mut errors = Vec::new();
+    let mut counters = Vec::new();
+    for tool in ["iptables", "ip6tables"] {
+        match run(tool, &["-L", "-v", "-n", "-x"]) {
+            Ok(output) => counters.extend(parse_iptables(&output)),
+            Err(err) => errors.push(err),
+        }
+    }
+    if !counters.is_empty() {
+        return Ok(counters);
+    }
+    match run("nft", &["list", "ruleset"]) {
+        Ok(output) => Ok(parse_nft(&output)),
+        Err(err) => {
+            errors.push(err);
+            Err(errors.join("; "))
+        }
+    }
+}
+
+fn run(tool: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(tool)
+        .args(args)
+        .output()
+        .map_err(|err| format!("{}: {}", tool, err))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}: {}", tool, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// `iptables -L -v -n -x`: "Chain NAME (...)" headers followed by rows of
+// pkts bytes target prot opt in out source destination [matches].
+pub fn parse_iptables(output: &str) -> Vec<RuleCounter> {
+    let mut counters = Vec::new();
+    let mut chain: Option<&str> = None;
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("Chain ") {
+            chain = rest
+                .split_whitespace()
+                .next()
+                .filter(|name| name.starts_with("ufw"));
+            continue;
+        }
+        let Some(chain) = chain else {
+            continue;
+        };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (Some(packets), Some(bytes)) = (
+            fields.first().and_then(|v| v.parse::<u64>().ok()),
+            fields.get(1).and_then(|v| v.parse::<u64>().ok()),
+        ) else {
+            continue;
+        };
+        let rule = fields[2..].join(" ");
+        let dport = fields
+            .iter()
+            .find_map(|field| field.strip_prefix("dpt:"))
+            .and_then(|port| port.parse::<u16>().ok());
+        counters.push(RuleCounter {
+            chain: chain.to_string(),
+            rule,
+            packets,
+            bytes,
+            dport,
+        });
+    }
+    counters
+}
+
+// `nft list ruleset`: rules with a "counter packets N bytes M" statement
+// inside "chain ufw..." blocks.
+pub fn parse_nft(output: &str) -> Vec<RuleCounter> {
+    let mut counters = Vec::new();
+    let mut chain: Option<&str> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("chain ") {
+            chain = rest
+                .split_whitespace()
+                .next()
+                .filter(|name| name.starts_with("ufw"));
+            continue;
+        }
+        if line == "}" {
+            chain = None;
+            continue;
+        }
+        let Some(chain) = chain else {
+            continue;
+        };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some(pos) = tokens.iter().position(|token| *token == "counter") else {
+            continue;
+        };
+        let number = |key: &str| {
+            tokens[pos..]
+                .windows(2)
+                .find(|pair| pair[0] == key)
+                .and_then(|pair| pair[1].parse::<u64>().ok())
+        };
+        let (Some(packets), Some(bytes)) = (number("packets"), number("bytes")) else {
+            continue;
+        };
+        let mut rule: Vec<&str> = tokens[..pos].to_vec();
+        rule.extend(tokens.iter().skip(pos + 5));
+        let dport = tokens
+            .windows(2)
+            .find(|pair| pair[0] == "dport")
+            .and_then(|pair| pair[1].parse::<u16>().ok());
+        counters.push(RuleCounter {
+            chain: chain.to_string(),
+            rule: rule.join(" "),
+            packets,
+            bytes,
+            dport,
+        });
+    }
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_iptables_and_nft_counters() {
+        let iptables = "\
+Chain INPUT (policy DROP 0 packets, 0 bytes)
+    pkts      bytes target     prot opt in     out     source               destination
+     500    40000 ufw-before-input  0    --  *      *       0.0.0.0/0            0.0.0.0/0
+
+Chain ufw-user-input (1 references)
+    pkts      bytes target     prot opt in     out     source               destination
+     120     7200 ACCEPT     6    --  *      *       0.0.0.0/0            0.0.0.0/0            tcp dpt:22
+";
+        let counters = parse_iptables(iptables);
+        assert_eq!(counters.len(), 1);
+        assert_eq!(counters[0].chain, "ufw-user-input");
+        assert_eq!(counters[0].packets, 120);
+        assert_eq!(counters[0].dport, Some(22));
+
+        let nft = "\
+table ip filter {
+\tchain ufw-user-input {
+\t\ttcp dport 443 counter packets 42 bytes 2520 accept
+\t\tudp dport 53 accept
+\t}
+\tchain INPUT {
+\t\tcounter packets 9 bytes 900 jump ufw-before-input
+\t}
+}";
+        let counters = parse_nft(nft);
+        assert_eq!(
+            counters,
+            vec![RuleCounter {
+                chain: "ufw-user-input".to_string(),
+                rule: "tcp dport 443 accept".to_string(),
+                packets: 42,
+                bytes: 2520,
+                dport: Some(443),
+            }]
+        );
+    }
+}
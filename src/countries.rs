@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use ratatui::text::Line;
+
+use crate::LogEntry;
+use crate::report::{Report, heading};
+
+// Cells of the longest bar; the others are drawn in proportion.
+const BAR_WIDTH: usize = 30;
+
+// State of the `C` summary: blocked entries per source country, busiest
+// first, and the cursor. Entries GeoIP could not place count towards the
+// total only, since a country filter cannot pick them.
+pub struct CountrySummary {
+    pub countries: Vec<(String, usize)>,
+    pub total: usize,
+    pub selected: usize,
+}
+
+impl CountrySummary {
+    pub fn new<'a>(entries: impl IntoIterator<Item = &'a LogEntry>) -> Self {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let mut total = 0;
+        for entry in entries {
+            if entry.action == "ALLOW" || entry.action == "AUDIT" {
+                continue;
+            }
+            total += 1;
+            if let Some(country) = entry.country.as_deref() {
+                *counts.entry(country).or_insert(0) += 1;
+            }
+        }
+        let mut countries: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(country, count)| (country.to_string(), count))
+            .collect();
+        countries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        Self {
+            countries,
+            total,
+            selected: 0,
+        }
+    }
+
+    pub fn selected_country(&self) -> Option<&str> {
+        self.countries
+            .get(self.selected)
+            .map(|(country, _)| country.as_str())
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.countries.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    // `visible` is how many lines fit in the popup.
+    pub fn report(&self, visible: usize) -> Report {
+        let mut lines = vec![heading(format!(
+            "  {:<4} {:<width$} {:>8} {:>6}",
+            "CC",
+            "",
+            "BLOCKED",
+            "SHARE",
+            width = BAR_WIDTH
+        ))];
+        if self.countries.is_empty() {
+            lines.push(Line::from("  No blocked entries with a known country."));
+        }
+        let busiest = self.countries.first().map_or(1, |(_, count)| *count);
+        for (pos, (country, count)) in self.countries.iter().enumerate() {
+            let bar = (count * BAR_WIDTH).div_ceil(busiest);
+            lines.push(Line::from(format!(
+                "{} {:<4} {:<width$} {:>8} {:>5.1}%",
+                if pos == self.selected { ">" } else { " " },
+                country,
+                "█".repeat(bar),
+                count,
+                *count as f64 * 100.0 / self.total as f64,
+                width = BAR_WIDTH
+            )));
+        }
+        let placed: usize = self.countries.iter().map(|(_, count)| count).sum();
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "{} blocked in view, {} without a country · j/k move · Enter filter by country",
+            self.total,
+            self.total - placed
+        )));
+        let mut report = Report::new("Blocked by country", lines);
+        // Keep the cursor line (below the heading) on screen.
+        report.scroll = (self.selected + 2).saturating_sub(visible) as u16;
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn ranks_blocked_countries_with_bars_and_shares() {
+        let entry = |action: &str, country: Option<&str>| {
+            let line = format!(
+                "Feb 11 20:00:00 h kernel: [UFW {action}] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22"
+            );
+            LogEntry {
+                country: country.map(str::to_string),
+                ..parse_ufw_line(&line).unwrap()
+            }
+        };
+        let entries = [
+            entry("BLOCK", Some("CN")),
+            entry("BLOCK", Some("CN")),
+            entry("BLOCK", Some("RU")),
+            entry("BLOCK", None),
+            entry("ALLOW", Some("LV")),
+        ];
+        let mut summary = CountrySummary::new(&entries);
+        assert_eq!(
+            summary.countries,
+            [("CN".to_string(), 2), ("RU".to_string(), 1)]
+        );
+        assert_eq!(summary.total, 4);
+
+        let text: Vec<String> = summary
+            .report(10)
+            .lines
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert!(text[1].starts_with(&format!("> CN   {} ", "█".repeat(30))));
+        assert!(text[1].ends_with("       2  50.0%"));
+        assert!(text[2].starts_with(&format!("  RU   {} ", "█".repeat(15))));
+        assert!(text[2].ends_with("       1  25.0%"));
+        assert!(text[4].starts_with("4 blocked in view, 1 without a country"));
+
+        summary.move_by(5);
+        assert_eq!(summary.selected_country(), Some("RU"));
+    }
+}
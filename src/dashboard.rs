@@ -0,0 +1,244 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::offline;
+use crate::stats::ViewStats;
+
+// Newest rows of the view put on the page; the rest are only counted.
+pub const MAX_ROWS: usize = 500;
+// How often the viewer rebuilds the page, and how often browsers reload it.
+const PUBLISH_EVERY: Duration = Duration::from_secs(2);
+const REFRESH_SECS: u32 = 5;
+// A client that has not sent its request by then is dropped, as is one
+// that does not take the response.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+// Bytes read of a request; the page needs no more than a request line and
+// a few headers.
+const MAX_REQUEST: u64 = 8 * 1024;
+// Clients served at once; more are turned away until one finishes.
+const MAX_CONNECTIONS: usize = 16;
+
+// `--http ADDR`: a read-only page with the view's table and stats. The
+// viewer publishes a fresh page every few seconds; server threads only ever
+// hand out the latest one.
+pub struct Dashboard {
+    pub addr: String,
+    page: Arc<Mutex<String>>,
+    published: Option<Instant>,
+}
+
+impl Dashboard {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        if !offline::network_allowed() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the HTTP dashboard is disabled by --offline",
+            ));
+        }
+        let listener = TcpListener::bind(addr)?;
+        // The bound address, so port 0 shows the one picked.
+        let addr = listener.local_addr()?.to_string();
+        let page = Arc::new(Mutex::new(String::new()));
+        let shared = Arc::clone(&page);
+        thread::spawn(move || accept(listener, shared));
+        Ok(Self {
+            addr,
+            page,
+            published: None,
+        })
+    }
+
+    // True when the page is older than PUBLISH_EVERY.
+    pub fn due(&self) -> bool {
+        self.published
+            .is_none_or(|published| published.elapsed() >= PUBLISH_EVERY)
+    }
+
+    pub fn publish(&mut self, page: String) {
+        if let Ok(mut current) = self.page.lock() {
+            *current = page;
+        }
+        self.published = Some(Instant::now());
+    }
+}
+
+fn accept(listener: TcpListener, page: Arc<Mutex<String>>) {
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming().flatten() {
+        if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            open.fetch_sub(1, Ordering::SeqCst);
+            continue;
+        }
+        let page = Arc::clone(&page);
+        let open = Arc::clone(&open);
+        thread::spawn(move || {
+            let _ = serve(stream, &page);
+            open.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+fn serve(stream: TcpStream, page: &Mutex<String>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Headers are read and ignored so the client sees a clean close.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let (status, body) = match request.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET" | "HEAD", "/" | "/index.html", _] => (
+            "200 OK",
+            page.lock().map(|page| page.clone()).unwrap_or_default(),
+        ),
+        ["GET" | "HEAD", _, _] => ("404 Not Found", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "Read-only\n".to_string()),
+    };
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    )?;
+    if !request.starts_with("HEAD ") {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+// The page: a title line, action counts, top sources and ports, then the
+// table. `shown` is how many entries the view holds, `rows` the newest of
+// them as cell text under `headers`.
+pub fn page(
+    title: &str,
+    shown: usize,
+    headers: &[&str],
+    rows: &[Vec<String>],
+    stats: &ViewStats,
+) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <meta http-equiv=\"refresh\" content=\"{}\"><title>{}</title>\
+         <style>body{{font:13px monospace;margin:1em}}table{{border-collapse:collapse}}\
+         td,th{{padding:1px 8px;text-align:left;white-space:nowrap}}\
+         tr:nth-child(even){{background:#f0f0f0}}.stats{{display:flex;gap:3em}}</style>\
+         </head><body>\n<h1>{}</h1>\n",
+        REFRESH_SECS,
+        escape(title),
+        escape(title)
+    );
+    let counts = |label: &str, items: Vec<(String, usize)>| {
+        let mut out = format!("<div><h2>{}</h2><table>\n", label);
+        for (key, count) in items {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                escape(&key),
+                count
+            ));
+        }
+        out.push_str("</table></div>\n");
+        out
+    };
+    html.push_str("<div class=\"stats\">\n");
+    html.push_str(&counts("Actions", stats.actions.clone()));
+    html.push_str(&counts("Top sources", stats.sources.clone()));
+    html.push_str(&counts(
+        "Top ports",
+        stats
+            .ports
+            .iter()
+            .map(|(port, count)| (port.to_string(), *count))
+            .collect(),
+    ));
+    html.push_str("</div>\n");
+    html.push_str(&format!(
+        "<h2>Events ({} of {})</h2>\n<table>\n<tr>",
+        rows.len(),
+        shown
+    ));
+    for header in headers {
+        html.push_str(&format!("<th>{}</th>", escape(header)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", escape(cell)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</body></html>\n");
+    html
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn serves_the_latest_page_read_only() {
+        let stats = ViewStats {
+            total: 2,
+            sources: vec![("1.2.3.4".to_string(), 2)],
+            ports: vec![(22, 2)],
+            actions: vec![("BLOCK".to_string(), 2)],
+            interfaces: Vec::new(),
+        };
+        let html = page(
+            "ufw.log <view>",
+            2,
+            &["Action", "Source"],
+            &[vec!["BLOCK".to_string(), "1.2.3.4".to_string()]],
+            &stats,
+        );
+        assert!(html.contains("<title>ufw.log &lt;view&gt;</title>"));
+        assert!(html.contains("<h2>Events (1 of 2)</h2>"));
+        assert!(html.contains("<tr><td>BLOCK</td><td>1.2.3.4</td></tr>"));
+        assert!(html.contains("<tr><td>22</td><td>2</td></tr>"));
+
+        let mut dashboard = Dashboard::bind("127.0.0.1:0").unwrap();
+        assert!(dashboard.due());
+        dashboard.publish("<p>hello</p>".to_string());
+        assert!(!dashboard.due());
+        let fetch = |request: &str| {
+            let mut stream = TcpStream::connect(&dashboard.addr).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = fetch("GET / HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("\r\n\r\n<p>hello</p>"), "{}", response);
+        assert!(fetch("GET /x HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404"));
+        assert!(fetch("POST / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 405"));
+        // A header that never ends is read up to the cap, not buffered on.
+        let mut endless = "GET / HTTP/1.1\r\nX: ".to_string();
+        endless.push_str(&"a".repeat(MAX_REQUEST as usize - endless.len()));
+        assert!(fetch(&endless).starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
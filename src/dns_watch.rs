@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use crate::LogEntry;
+use crate::parser::entry_epoch_seconds;
+
+// Queries within one minute to a single resolver; a busy desktop rarely
+// passes a few dozen, a DNS tunnel pushes data through every query.
+const MAX_QUERIES_PER_MINUTE: usize = 100;
+// Packets to one destination before its port spread is judged.
+const MIN_SPREAD_PACKETS: usize = 20;
+// Shannon entropy of the destination ports; 3 bits is eight evenly used ports.
+const MIN_PORT_ENTROPY_BITS: f64 = 3.0;
+
+// `--dns-watch` findings for one sending host, shown in the Enter drill-down.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DnsFinding {
+    // Busiest minute of UDP/53 queries to `resolver`.
+    QueryRate {
+        resolver: String,
+        peak: usize,
+    },
+    // UDP to `destination` scattered over many ports other than 53, as
+    // tunnels do when port 53 is filtered.
+    PortSpread {
+        destination: String,
+        packets: usize,
+        ports: usize,
+        bits: f64,
+    },
+}
+
+// Outbound (or forwarded) UDP from `source`, judged per destination. `now`
+// resolves the year of syslog timestamps.
+pub fn dns_findings<'a>(
+    entries: impl IntoIterator<Item = &'a LogEntry>,
+    source: &str,
+    now: i64,
+) -> Vec<DnsFinding> {
+    let mut per_minute: HashMap<(&str, i64), usize> = HashMap::new();
+    let mut odd_ports: HashMap<&str, HashMap<u16, usize>> = HashMap::new();
+    for entry in entries {
+        let outbound = matches!(entry.direction(), "OUT" | "FWD");
+        let udp = entry.proto.as_deref() == Some("UDP");
+        let (Some(dst), Some(port)) = (entry.dst_ip.as_deref(), entry.dst_port) else {
+            continue;
+        };
+        if !outbound || !udp || entry.src_ip.as_deref() != Some(source) {
+            continue;
+        }
+        if port == 53 {
+            if let Some(secs) = entry_epoch_seconds(&entry.timestamp, now) {
+                *per_minute.entry((dst, secs.div_euclid(60))).or_insert(0) += 1;
+            }
+        } else {
+            *odd_ports.entry(dst).or_default().entry(port).or_insert(0) += 1;
+        }
+    }
+
+    let mut peaks: HashMap<&str, usize> = HashMap::new();
+    for ((resolver, _), count) in per_minute {
+        let peak = peaks.entry(resolver).or_insert(0);
+        *peak = (*peak).max(count);
+    }
+    let mut floods: Vec<(&str, usize)> = peaks
+        .into_iter()
+        .filter(|(_, peak)| *peak > MAX_QUERIES_PER_MINUTE)
+        .collect();
+    floods.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let mut spreads: Vec<(&str, usize, usize, f64)> = odd_ports
+        .into_iter()
+        .filter_map(|(destination, ports)| {
+            let packets: usize = ports.values().sum();
+            let bits = port_entropy(&ports, packets);
+            (packets >= MIN_SPREAD_PACKETS && bits >= MIN_PORT_ENTROPY_BITS).then_some((
+                destination,
+                packets,
+                ports.len(),
+                bits,
+            ))
+        })
+        .collect();
+    spreads.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    // Query floods first, busiest first within each kind.
+    let floods = floods
+        .into_iter()
+        .map(|(resolver, peak)| DnsFinding::QueryRate {
+            resolver: resolver.to_string(),
+            peak,
+        });
+    let spreads =
+        spreads.into_iter().map(
+            |(destination, packets, ports, bits)| DnsFinding::PortSpread {
+                destination: destination.to_string(),
+                packets,
+                ports,
+                bits,
+            },
+        );
+    floods.chain(spreads).collect()
+}
+
+// Shannon entropy, in bits, of the port distribution.
+fn port_entropy(ports: &HashMap<u16, usize>, packets: usize) -> f64 {
+    ports
+        .values()
+        .map(|&count| {
+            let p = count as f64 / packets as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+impl DnsFinding {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::QueryRate { resolver, peak } => {
+                format!("{} queries/min to resolver {}", peak, resolver)
+            }
+            Self::PortSpread {
+                destination,
+                packets,
+                ports,
+                bits,
+            } => format!(
+                "{} UDP packets to {} over {} ports ({:.1} bits of port entropy)",
+                packets, destination, ports, bits
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    fn query(time: &str, dst: &str, dpt: u16) -> LogEntry {
+        parse_ufw_line(&format!(
+            "Feb 11 {time} h kernel: [UFW AUDIT] IN= OUT=eth0 SRC=10.0.0.5 DST={dst} PROTO=UDP SPT=40000 DPT={dpt}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn flags_query_floods_and_port_spread() {
+        let now = 1_770_840_000;
+        let mut entries: Vec<LogEntry> = (0..101)
+            .map(|n| query(&format!("20:00:{:02}", n % 60), "9.9.9.9", 53))
+            .collect();
+        // Spread over two minutes, so under the rate.
+        entries.extend((0..120).map(|n| query(&format!("20:0{}:00", n % 2), "1.1.1.1", 53)));
+        entries.extend((0..32).map(|n| query("20:00:00", "203.0.113.7", 1000 + n % 16)));
+        // Steady traffic to one odd port is just a service.
+        entries.extend((0..32).map(|_| query("20:00:00", "203.0.113.8", 51820)));
+
+        let findings = dns_findings(&entries, "10.0.0.5", now);
+        assert_eq!(
+            findings,
+            [
+                DnsFinding::QueryRate {
+                    resolver: "9.9.9.9".to_string(),
+                    peak: 101,
+                },
+                DnsFinding::PortSpread {
+                    destination: "203.0.113.7".to_string(),
+                    packets: 32,
+                    ports: 16,
+                    bits: 4.0,
+                },
+            ]
+        );
+        assert!(dns_findings(&entries, "10.0.0.6", now).is_empty());
+    }
+}
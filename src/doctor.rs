@@ -0,0 +1,269 @@
+use std::fs::{self, File};
+use std::io::{IsTerminal, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+
+const UFW_CONF: &str = "/etc/ufw/ufw.conf";
+// How much of the log's tail to scan for UFW lines.
+const SAMPLE_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+// One line of `ufw-log-viewer doctor`, with what to do about it when it is
+// not fine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub status: Status,
+    pub name: &'static str,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn new(status: Status, name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            name,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+// Every check, in the order they are printed.
+pub fn run_checks(log_path: &Path) -> Vec<Check> {
+    let env = |name: &str| std::env::var(name).ok();
+    let mut checks = vec![
+        log_check(log_path),
+        ufw_logging_check(fs::read_to_string(UFW_CONF).ok().as_deref()),
+        journald_check(),
+    ];
+    checks.extend(terminal_checks(&env, std::io::stdout().is_terminal()));
+    checks
+}
+
+// Aligned report; the second value is true when nothing failed.
+pub fn format_report(checks: &[Check]) -> (String, bool) {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let mut out = format!("{} doctor\n\n", env!("CARGO_PKG_NAME"));
+    for check in checks {
+        out.push_str(&format!(
+            "  [{:<4}] {:<width$}  {}\n",
+            check.status.label(),
+            check.name,
+            check.detail
+        ));
+        if let Some(hint) = check.hint.as_deref() {
+            out.push_str(&format!("  {:<6} {:<width$}  -> {}\n", "", "", hint));
+        }
+    }
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    let warned = checks.iter().filter(|c| c.status == Status::Warn).count();
+    out.push_str(&format!("\n{} failed, {} warnings\n", failed, warned));
+    (out, failed == 0)
+}
+
+fn log_check(path: &Path) -> Check {
+    const NAME: &str = "Log file";
+    let shown = path.display();
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Check::new(Status::Fail, NAME, format!("{} does not exist", shown))
+                .hint("pass the log path, or use --journal if UFW only logs to journald");
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+            return Check::new(Status::Fail, NAME, format!("{} is not readable", shown))
+                .hint("run with sudo, or join the adm group: sudo usermod -aG adm $USER");
+        }
+        Err(err) => return Check::new(Status::Fail, NAME, format!("{}: {}", shown, err)),
+    };
+    let len = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    if len == 0 {
+        return Check::new(Status::Warn, NAME, format!("{} is empty", shown))
+            .hint("check the UFW logging level below, or try the rotated log with --rotated");
+    }
+    let mut sample = Vec::new();
+    let _ = file.seek(SeekFrom::Start(len.saturating_sub(SAMPLE_BYTES)));
+    let _ = file.take(SAMPLE_BYTES).read_to_end(&mut sample);
+    let size = format!("{:.1} MB", len as f64 / 1_048_576.0);
+    if String::from_utf8_lossy(&sample).contains("[UFW ") {
+        Check::new(
+            Status::Ok,
+            NAME,
+            format!("{} is readable ({})", shown, size),
+        )
+    } else {
+        Check::new(
+            Status::Warn,
+            NAME,
+            format!(
+                "{} is readable ({}) but has no recent [UFW] lines",
+                shown, size
+            ),
+        )
+        .hint("pfSense or Windows logs are fine with --format; otherwise check the logging level")
+    }
+}
+
+// Reads LOGLEVEL and ENABLED from ufw.conf.
+fn ufw_logging_check(conf: Option<&str>) -> Check {
+    const NAME: &str = "UFW logging";
+    let Some(conf) = conf else {
+        return Check::new(Status::Warn, NAME, format!("{} not found", UFW_CONF))
+            .hint("is ufw installed? Other firewalls work with --format");
+    };
+    let value = |key: &str| {
+        conf.lines()
+            .filter_map(|line| line.trim().strip_prefix(key)?.strip_prefix('='))
+            .next_back()
+            .map(|value| value.trim().trim_matches(['"', '\'']).to_ascii_lowercase())
+    };
+    let level = value("LOGLEVEL").unwrap_or_else(|| "low".to_string());
+    if value("ENABLED").as_deref() != Some("yes") {
+        return Check::new(Status::Warn, NAME, "ufw is not enabled").hint("sudo ufw enable");
+    }
+    match level.as_str() {
+        "off" => Check::new(Status::Fail, NAME, "logging is off").hint("sudo ufw logging low"),
+        "full" => Check::new(Status::Warn, NAME, "level full logs every packet")
+            .hint("large logs slow loading; sudo ufw logging medium is usually enough"),
+        level => Check::new(Status::Ok, NAME, format!("level {}", level)),
+    }
+}
+
+fn journald_check() -> Check {
+    const NAME: &str = "journald";
+    match Command::new("journalctl").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            let version = version.lines().next().unwrap_or("").trim();
+            Check::new(
+                Status::Ok,
+                NAME,
+                format!("{} (--journal available)", version),
+            )
+        }
+        _ => Check::new(
+            Status::Ok,
+            NAME,
+            "journalctl not found (--journal unavailable)",
+        ),
+    }
+}
+
+// Clipboard and terminal features, judged from the environment: there is no
+// reliable way to query a terminal for OSC52 or mouse support.
+fn terminal_checks(env: &dyn Fn(&str) -> Option<String>, is_tty: bool) -> Vec<Check> {
+    let term = env("TERM").unwrap_or_default();
+    let mut checks = Vec::new();
+    if !is_tty {
+        checks.push(Check::new(
+            Status::Warn,
+            "Terminal",
+            "stdout is not a terminal",
+        ));
+    } else if term.is_empty() || term == "dumb" {
+        checks.push(
+            Check::new(Status::Fail, "Terminal", format!("TERM={:?}", term))
+                .hint("set TERM, e.g. export TERM=xterm-256color"),
+        );
+    } else {
+        let size = crossterm::terminal::size()
+            .map(|(w, h)| format!(", {}x{}", w, h))
+            .unwrap_or_default();
+        checks.push(Check::new(
+            Status::Ok,
+            "Terminal",
+            format!("TERM={}{}", term, size),
+        ));
+    }
+
+    let clipboard = if env("TMUX").is_some() {
+        Check::new(Status::Warn, "Clipboard", "OSC52 inside tmux")
+            .hint("y copies only with: set -g set-clipboard on")
+    } else if term.starts_with("screen") {
+        Check::new(Status::Warn, "Clipboard", "GNU screen drops OSC52")
+            .hint("run outside screen, or use tmux with set-clipboard on")
+    } else if term.is_empty() || term == "dumb" || term == "linux" {
+        Check::new(Status::Warn, "Clipboard", "the console has no clipboard")
+            .hint("y copies via OSC52, which needs a terminal emulator")
+    } else {
+        Check::new(
+            Status::Ok,
+            "Clipboard",
+            "OSC52 (works over SSH if the terminal allows it)",
+        )
+    };
+    checks.push(clipboard);
+
+    checks.push(if term.is_empty() || term == "dumb" || term == "linux" {
+        Check::new(Status::Warn, "Mouse", "not supported by this terminal")
+            .hint("every action also has a key; see --help")
+    } else {
+        Check::new(Status::Ok, "Mouse", "clicks, scrolling and hover")
+    });
+
+    let colorterm = env("COLORTERM").unwrap_or_default();
+    checks.push(if matches!(colorterm.as_str(), "truecolor" | "24bit") {
+        Check::new(Status::Ok, "Colors", "truecolor")
+    } else if term.contains("256color") {
+        Check::new(Status::Ok, "Colors", "256 colors")
+    } else {
+        Check::new(
+            Status::Warn,
+            "Colors",
+            format!("basic colors (TERM={})", term),
+        )
+        .hint("export TERM=xterm-256color if the terminal supports it")
+    });
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_ufw_conf_and_terminal_environment() {
+        let conf = |text: &str| ufw_logging_check(Some(text)).status;
+        assert_eq!(conf("ENABLED=yes\nLOGLEVEL=low\n"), Status::Ok);
+        assert_eq!(conf("ENABLED=yes\nLOGLEVEL=off\n"), Status::Fail);
+        assert_eq!(conf("ENABLED=no\nLOGLEVEL=low\n"), Status::Warn);
+        assert_eq!(ufw_logging_check(None).status, Status::Warn);
+
+        let tmux = |name: &str| match name {
+            "TERM" => Some("tmux-256color".to_string()),
+            "TMUX" => Some("/tmp/tmux-1000/default,1,0".to_string()),
+            _ => None,
+        };
+        let checks = terminal_checks(&tmux, true);
+        let status = |name: &str| checks.iter().find(|c| c.name == name).unwrap().status;
+        assert_eq!(status("Clipboard"), Status::Warn);
+        assert_eq!(status("Colors"), Status::Ok);
+
+        let (report, ok) = format_report(&checks);
+        assert!(ok);
+        assert!(report.contains("-> y copies only with: set -g set-clipboard on"));
+        assert!(report.ends_with("0 failed, 1 warnings\n"));
+    }
+}
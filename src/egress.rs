@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ratatui::text::Line;
+
+use crate::LogEntry;
+use crate::cache::state_dir;
+use crate::ini;
+use crate::report::{Report, heading};
+
+const FILE_NAME: &str = "egress.ini";
+const SECTION: &str = "expected";
+
+// Where blocked outbound traffic was headed: one group in the review.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EgressTarget {
+    pub destination: String,
+    pub port: Option<u16>,
+    pub proto: String,
+}
+
+impl EgressTarget {
+    // "443/tcp", or just the protocol for portless traffic such as ICMP.
+    pub fn port_label(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}/{}", port, self.proto.to_ascii_lowercase()),
+            None => self.proto.to_ascii_lowercase(),
+        }
+    }
+
+    fn from_setting(destination: &str, value: &str) -> Option<Self> {
+        let (port, proto) = match value.split_once('/') {
+            Some((port, proto)) => (Some(port.parse().ok()?), proto),
+            None => (None, value),
+        };
+        Some(Self {
+            destination: destination.to_string(),
+            port,
+            proto: proto.to_ascii_uppercase(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EgressGroup {
+    pub target: EgressTarget,
+    pub count: usize,
+    pub last_seen: String,
+}
+
+// Blocked outbound entries grouped by destination, port and protocol,
+// busiest first. Entries are expected newest first, as the app stores them.
+pub fn blocked_egress<'a>(entries: impl IntoIterator<Item = &'a LogEntry>) -> Vec<EgressGroup> {
+    let mut groups: HashMap<EgressTarget, EgressGroup> = HashMap::new();
+    for entry in entries {
+        let blocked = entry.action != "ALLOW" && entry.action != "AUDIT";
+        let Some(destination) = entry.dst_ip.as_deref() else {
+            continue;
+        };
+        if !blocked || entry.direction() != "OUT" {
+            continue;
+        }
+        let target = EgressTarget {
+            destination: destination.to_string(),
+            port: entry.dst_port,
+            proto: entry.proto.clone().unwrap_or_else(|| "-".to_string()),
+        };
+        groups
+            .entry(target.clone())
+            .or_insert_with(|| EgressGroup {
+                target,
+                count: 0,
+                last_seen: entry.timestamp.clone(),
+            })
+            .count += 1;
+    }
+    let mut groups: Vec<EgressGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then(a.target.cmp(&b.target)));
+    groups
+}
+
+// State of the `>` review: groups not yet marked expected, and the cursor.
+pub struct EgressReview {
+    pub groups: Vec<EgressGroup>,
+    pub expected: Vec<EgressTarget>,
+    pub selected: usize,
+}
+
+impl EgressReview {
+    pub fn new(groups: Vec<EgressGroup>, expected: Vec<EgressTarget>) -> Self {
+        let groups = groups
+            .into_iter()
+            .filter(|group| !expected.contains(&group.target))
+            .collect();
+        Self {
+            groups,
+            expected,
+            selected: 0,
+        }
+    }
+
+    pub fn selected_group(&self) -> Option<&EgressGroup> {
+        self.groups.get(self.selected)
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.groups.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    // Hides the selected group from now on; returns it for the status line.
+    pub fn mark_selected_expected(&mut self) -> Option<EgressTarget> {
+        if self.selected >= self.groups.len() {
+            return None;
+        }
+        let group = self.groups.remove(self.selected);
+        self.move_by(0);
+        self.expected.push(group.target.clone());
+        Some(group.target)
+    }
+
+    // `show_ip` formats destinations (redaction); `pending` is the
+    // confirmation line of an allow rule waiting for Enter. `visible` is how
+    // many lines fit in the popup.
+    pub fn report(
+        &self,
+        show_ip: impl Fn(&str) -> String,
+        pending: Option<String>,
+        visible: usize,
+    ) -> Report {
+        let mut lines = vec![heading(format!(
+            "  {:<40} {:<10} {:>8}  {}",
+            "DESTINATION", "PORT", "BLOCKED", "LAST SEEN"
+        ))];
+        if self.groups.is_empty() {
+            lines.push(Line::from("  No blocked outbound traffic left to review."));
+        }
+        for (pos, group) in self.groups.iter().enumerate() {
+            lines.push(Line::from(format!(
+                "{} {:<40} {:<10} {:>8}  {}",
+                if pos == self.selected { ">" } else { " " },
+                show_ip(&group.target.destination),
+                group.target.port_label(),
+                group.count,
+                group.last_seen
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(pending.unwrap_or_else(|| {
+            format!(
+                "j/k move · a allow out (ufw rule) · x mark expected ({} hidden)",
+                self.expected.len()
+            )
+        })));
+        let mut report = Report::new("Egress review", lines);
+        // Keep the cursor line (below the heading) on screen.
+        report.scroll = (self.selected + 2).saturating_sub(visible) as u16;
+        report
+    }
+}
+
+pub fn expected_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+// A missing file is an empty list.
+pub fn load_expected(path: &Path) -> io::Result<Vec<EgressTarget>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(parse(&text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn save_expected(path: &Path, expected: &[EgressTarget]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serialize(expected))
+}
+
+// `destination=port/proto` lines under `[expected]`.
+fn parse(text: &str) -> Vec<EgressTarget> {
+    ini::parse(text)
+        .into_iter()
+        .filter(|(name, _)| name == SECTION)
+        .flat_map(|(_, settings)| settings)
+        .filter_map(|(destination, value)| EgressTarget::from_setting(&destination, &value))
+        .collect()
+}
+
+fn serialize(expected: &[EgressTarget]) -> String {
+    let settings = expected
+        .iter()
+        .map(|target| (target.destination.clone(), target.port_label()))
+        .collect();
+    ini::serialize(
+        "Outbound traffic marked expected in the ufw-log-viewer egress review",
+        &[(SECTION.to_string(), settings)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn groups_blocked_outbound_and_hides_expected() {
+        let line = |action: &str, ifaces: &str, dst: &str, proto: &str| {
+            parse_ufw_line(&format!(
+                "Feb 11 20:00:00 h kernel: [UFW {action}] {ifaces} SRC=10.0.0.5 DST={dst} PROTO={proto} SPT=40000 DPT=443"
+            ))
+            .unwrap()
+        };
+        let out = "IN= OUT=eth0";
+        let entries = [
+            line("BLOCK", out, "203.0.113.7", "TCP"),
+            line("BLOCK", out, "203.0.113.7", "TCP"),
+            line("BLOCK", out, "203.0.113.7", "UDP"),
+            line("ALLOW", out, "203.0.113.9", "TCP"),
+            line("BLOCK", "IN=eth0 OUT=", "203.0.113.9", "TCP"),
+        ];
+        let groups = blocked_egress(&entries);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            (groups[0].target.port_label(), groups[0].count),
+            ("443/tcp".to_string(), 2)
+        );
+
+        let mut review = EgressReview::new(groups.clone(), Vec::new());
+        let marked = review.mark_selected_expected().unwrap();
+        assert_eq!(review.groups.len(), 1);
+        let saved = parse(&serialize(&review.expected));
+        assert_eq!(saved, [marked]);
+        assert_eq!(EgressReview::new(groups, saved).groups.len(), 1);
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use crate::LogEntry;
+use crate::sink::{CsvSink, JsonArraySink, OutputSink};
+
+// File formats for `e`, which saves the filtered view (unlike `--export`,
+// which streams the whole log as SIEM records).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Csv => Self::Json,
+            Self::Json => Self::Csv,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Json => "JSON",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => ".csv",
+            Self::Json => ".json",
+        }
+    }
+}
+
+// The `e` prompt: an editable output path and the chosen format.
+pub struct ExportPrompt {
+    pub format: ExportFormat,
+    pub path: String,
+}
+
+impl Default for ExportPrompt {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            path: "ufw-export.csv".to_string(),
+        }
+    }
+}
+
+impl ExportPrompt {
+    // Switches format; a path still ending in the old extension follows along.
+    pub fn toggle_format(&mut self) {
+        let next = self.format.next();
+        if let Some(stem) = self.path.strip_suffix(self.format.extension()) {
+            self.path = format!("{}{}", stem, next.extension());
+        }
+        self.format = next;
+    }
+}
+
+pub fn write_entries<'a>(
+    path: &Path,
+    format: ExportFormat,
+    rows: impl IntoIterator<Item = (&'a LogEntry, Vec<&'a str>)>,
+) -> io::Result<()> {
+    let out = BufWriter::new(File::create(path)?);
+    let mut sink: Box<dyn OutputSink> = match format {
+        ExportFormat::Csv => Box::new(CsvSink::new(out, false)),
+        ExportFormat::Json => Box::new(JsonArraySink::new(out)),
+    };
+    for (entry, tags) in rows {
+        sink.write(entry, &tags)?;
+    }
+    sink.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggling_format_follows_the_extension() {
+        let mut prompt = ExportPrompt::default();
+        prompt.toggle_format();
+        assert_eq!(
+            (prompt.format, prompt.path.as_str()),
+            (ExportFormat::Json, "ufw-export.json")
+        );
+        prompt.path = "out.txt".to_string();
+        prompt.toggle_format();
+        assert_eq!(prompt.path, "out.txt");
+    }
+}
@@ -0,0 +1,69 @@
+// The `/` prompt: the term being typed and the row the search started from,
+// so each keystroke searches again from there and Esc can go back.
+pub struct FindPrompt {
+    pub term: String,
+    pub origin: usize,
+    // Rows in view matching `term`, for the prompt.
+    pub matches: usize,
+}
+
+impl FindPrompt {
+    pub fn new(origin: usize) -> Self {
+        Self {
+            term: String::new(),
+            origin,
+            matches: 0,
+        }
+    }
+}
+
+// Case-insensitive substring match; `term` is expected lowercase already.
+pub fn matches(line: &str, term: &str) -> bool {
+    !term.is_empty() && line.to_lowercase().contains(term)
+}
+
+// Position of the next line matching `term` after `from` (before it when
+// `backwards`), wrapping around; `from` itself is tried last, or first when
+// `include_from` is set.
+pub fn next_match(
+    lines: &[&str],
+    term: &str,
+    from: usize,
+    backwards: bool,
+    include_from: bool,
+) -> Option<usize> {
+    let len = lines.len();
+    if len == 0 || term.is_empty() {
+        return None;
+    }
+    let term = term.to_lowercase();
+    let from = from.min(len - 1);
+    let steps = if include_from { 0..len } else { 1..len + 1 };
+    steps
+        .map(|step| {
+            if backwards {
+                (from + len - step % len) % len
+            } else {
+                (from + step) % len
+            }
+        })
+        .find(|&pos| matches(lines[pos], &term))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_through_matches_and_wraps() {
+        let lines = ["SRC=1.1.1.1 DPT=22", "SRC=2.2.2.2", "src=1.1.1.1 DPT=80"];
+        assert_eq!(next_match(&lines, "1.1.1.1", 0, false, true), Some(0));
+        assert_eq!(next_match(&lines, "1.1.1.1", 0, false, false), Some(2));
+        assert_eq!(next_match(&lines, "1.1.1.1", 2, false, false), Some(0));
+        assert_eq!(next_match(&lines, "1.1.1.1", 0, true, false), Some(2));
+        assert_eq!(next_match(&lines, "DPT=22", 0, false, false), Some(0));
+        assert_eq!(next_match(&lines, "SRC=2", 2, true, true), Some(1));
+        assert_eq!(next_match(&lines, "3.3.3.3", 0, false, true), None);
+        assert_eq!(next_match(&lines, "", 0, false, true), None);
+    }
+}
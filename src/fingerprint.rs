@@ -0,0 +1,57 @@
+use crate::parser::parse_field;
+
+// Best-effort guess of the sender's OS family from the packet's remaining TTL
+// (or IPv6 hop limit) and TCP window. Purely informational.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsHint {
+    pub family: &'static str,
+    pub ttl: u8,
+    pub initial_ttl: u8,
+}
+
+impl OsHint {
+    pub fn label(&self) -> String {
+        format!("{} (TTL {} of {})", self.family, self.ttl, self.initial_ttl)
+    }
+}
+
+pub fn os_hint(raw: &str) -> Option<OsHint> {
+    let ttl = parse_field(raw, "TTL")
+        .or_else(|| parse_field(raw, "HOPLIMIT"))?
+        .parse::<u8>()
+        .ok()?;
+    let window = parse_field(raw, "WINDOW").and_then(|w| w.parse::<u32>().ok());
+    // Senders start from one of a few well-known values and every hop
+    // decrements it, so the next value up is the likely starting point.
+    let initial_ttl = [32u8, 64, 128, 255]
+        .into_iter()
+        .find(|start| ttl <= *start)?;
+    let family = match (initial_ttl, window) {
+        (64, Some(65535)) => "macOS/BSD",
+        (64, _) => "Linux/Unix",
+        (128, _) => "Windows",
+        (255, _) => "network device",
+        _ => "embedded/legacy",
+    };
+    Some(OsHint {
+        family,
+        ttl,
+        initial_ttl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_family_from_ttl_and_window() {
+        let hint =
+            |fields: &str| os_hint(&format!("[UFW BLOCK] IN=eth0 {}", fields)).map(|h| h.family);
+        assert_eq!(hint("TTL=52 WINDOW=64240"), Some("Linux/Unix"));
+        assert_eq!(hint("TTL=57 WINDOW=65535"), Some("macOS/BSD"));
+        assert_eq!(hint("TTL=117 WINDOW=8192"), Some("Windows"));
+        assert_eq!(hint("HOPLIMIT=247"), Some("network device"));
+        assert_eq!(hint("PROTO=TCP"), None);
+    }
+}
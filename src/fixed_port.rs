@@ -0,0 +1,100 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::LogEntry;
+
+// A source needs this many packets before its source ports are judged.
+const MIN_PACKETS: usize = 5;
+// Share of packets (in percent) that must reuse the same source port.
+const MIN_SHARE_PERCENT: usize = 80;
+// Distinct (destination, port) targets; one retried connection is not a scan.
+const MIN_TARGETS: usize = 3;
+
+// A source that keeps one source port across many connections, as stateless
+// scanners like masscan or zmap do by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedSourcePort {
+    pub source: String,
+    pub port: u16,
+    pub hits: usize,
+    pub packets: usize,
+    pub targets: usize,
+}
+
+// Sources whose source port is not randomized, most packets first. Ports
+// below 1024 are skipped: replies from services legitimately reuse them.
+pub fn fixed_source_ports<'a>(
+    entries: impl IntoIterator<Item = &'a LogEntry>,
+) -> Vec<FixedSourcePort> {
+    type Stats<'a> = (usize, HashMap<u16, usize>, HashSet<(&'a str, u16)>);
+    let mut by_source: HashMap<&str, Stats> = HashMap::new();
+    for entry in entries {
+        let (Some(source), Some(src_port), Some(dst_port)) =
+            (entry.src_ip.as_deref(), entry.src_port, entry.dst_port)
+        else {
+            continue;
+        };
+        let stats = by_source.entry(source).or_default();
+        stats.0 += 1;
+        *stats.1.entry(src_port).or_insert(0) += 1;
+        stats
+            .2
+            .insert((entry.dst_ip.as_deref().unwrap_or("-"), dst_port));
+    }
+
+    let mut found: Vec<FixedSourcePort> = by_source
+        .into_iter()
+        .filter_map(|(source, (packets, ports, targets))| {
+            let (port, hits) = ports
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+            (packets >= MIN_PACKETS
+                && port >= 1024
+                && hits * 100 >= packets * MIN_SHARE_PERCENT
+                && targets.len() >= MIN_TARGETS)
+                .then(|| FixedSourcePort {
+                    source: source.to_string(),
+                    port,
+                    hits,
+                    packets,
+                    targets: targets.len(),
+                })
+        })
+        .collect();
+    found.sort_by(|a, b| b.packets.cmp(&a.packets).then(a.source.cmp(&b.source)));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    fn entry(src: &str, spt: u16, dpt: u16) -> LogEntry {
+        parse_ufw_line(&format!(
+            "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT={spt} DPT={dpt}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn flags_sources_reusing_one_source_port() {
+        let mut entries: Vec<LogEntry> = (0..9).map(|n| entry("1.1.1.1", 61000, 20 + n)).collect();
+        entries.push(entry("1.1.1.1", 40000, 80));
+        // Randomized ports, too few packets, retries of one target, replies.
+        entries.extend((0..6).map(|n| entry("2.2.2.2", 40000 + n, 22)));
+        entries.extend((0..4).map(|n| entry("3.3.3.3", 50000, 22 + n)));
+        entries.extend((0..6).map(|_| entry("4.4.4.4", 50000, 22)));
+        entries.extend((0..6).map(|n| entry("5.5.5.5", 443, 40000 + n)));
+
+        assert_eq!(
+            fixed_source_ports(&entries),
+            vec![FixedSourcePort {
+                source: "1.1.1.1".to_string(),
+                port: 61000,
+                hits: 9,
+                packets: 10,
+                targets: 10,
+            }]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::cache::{DiskCache, GEOIP_CACHE};
+
+// Start of the metadata section, which sits at the end of every .mmdb file.
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+// Pointers inside well-formed data never chain this deep.
+const MAX_DEPTH: usize = 32;
+// Countries looked up in earlier runs, about 25000 addresses.
+const DISK_CACHE_BYTES: u64 = 512 * 1024;
+
+// A MaxMind DB (GeoLite2-Country/City or compatible) read into memory for
+// `--geoip`. Only the parts needed for country codes are decoded.
+pub struct GeoIp {
+    db: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    data_start: usize,
+    data_end: usize,
+    // Node where IPv4 addresses start in an IPv6 tree (::a.b.c.d).
+    ipv4_start: Option<usize>,
+    // Country per source IP; most logs repeat a small set of addresses.
+    cache: HashMap<String, Option<String>>,
+    // Past this many addresses (`--low-memory`) the cache starts over.
+    cache_limit: Option<usize>,
+    // The same, kept between runs until the database file changes.
+    disk: DiskCache,
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    String(String),
+    Uint(u64),
+    Map(Vec<(String, Value)>),
+    Array(Vec<Value>),
+    Other,
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(pairs) => pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_usize(&self) -> Option<usize> {
+        match self {
+            Value::Uint(value) => usize::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl GeoIp {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+        let modified = fs::metadata(path)?.modified();
+        let mut geoip =
+            Self::from_bytes(fs::read(path)?).ok_or_else(|| invalid("not a MaxMind DB file"))?;
+        geoip.disk = DiskCache::open(GEOIP_CACHE, DISK_CACHE_BYTES);
+        if let Ok(modified) = modified {
+            geoip.disk.expire_before(modified);
+        }
+        for (ip, country) in geoip.disk.load() {
+            geoip
+                .cache
+                .insert(ip, Some(country).filter(|code| !code.is_empty()));
+        }
+        Ok(geoip)
+    }
+
+    fn from_bytes(db: Vec<u8>) -> Option<Self> {
+        let marker = db
+            .windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)?;
+        let (metadata, _) = decode(&db[marker + METADATA_MARKER.len()..], 0, 0)?;
+        let node_count = metadata.get("node_count")?.as_usize()?;
+        let record_size = metadata.get("record_size")?.as_usize()?;
+        if ![24, 28, 32].contains(&record_size) {
+            return None;
+        }
+        let data_start = node_count.checked_mul(record_size / 4)?.checked_add(16)?;
+        if data_start > marker {
+            return None;
+        }
+        let mut geoip = Self {
+            db,
+            node_count,
+            record_size,
+            data_start,
+            data_end: marker,
+            ipv4_start: None,
+            cache: HashMap::new(),
+            cache_limit: None,
+            disk: DiskCache::none(),
+        };
+        if metadata.get("ip_version")?.as_usize()? == 6 {
+            let mut node = 0;
+            for _ in 0..96 {
+                if node >= node_count {
+                    break;
+                }
+                node = geoip.record(node, 0)?;
+            }
+            geoip.ipv4_start = Some(node);
+        } else {
+            geoip.ipv4_start = Some(0);
+        }
+        Some(geoip)
+    }
+
+    // ISO country code ("US") for `ip`, falling back to the registered country
+    // for anycast and satellite ranges. Cached per address.
+    pub fn country(&mut self, ip: &str) -> Option<String> {
+        if let Some(cached) = self.cache.get(ip) {
+            return cached.clone();
+        }
+        let country = ip.parse::<IpAddr>().ok().and_then(|ip| self.lookup(ip));
+        self.disk.store(ip, country.as_deref().unwrap_or(""));
+        if self
+            .cache_limit
+            .is_some_and(|limit| self.cache.len() >= limit)
+        {
+            self.cache.clear();
+        }
+        self.cache.insert(ip.to_string(), country.clone());
+        country
+    }
+
+    pub fn limit_cache(&mut self, limit: usize) {
+        self.cache_limit = Some(limit);
+        if self.cache.len() > limit {
+            self.cache.clear();
+        }
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Option<String> {
+        let (bytes, start) = match ip {
+            IpAddr::V4(v4) => (v4.octets().to_vec(), self.ipv4_start?),
+            IpAddr::V6(v6) if self.ipv4_start != Some(0) => (v6.octets().to_vec(), 0),
+            IpAddr::V6(_) => return None,
+        };
+        let mut node = start;
+        for bit in 0..bytes.len() * 8 {
+            if node >= self.node_count {
+                break;
+            }
+            node = self.record(node, (bytes[bit / 8] >> (7 - bit % 8)) & 1)?;
+        }
+        // Equal to the node count means "no data"; below it, the tree ran out.
+        let offset = node.checked_sub(self.node_count + 16)?;
+        let data = &self.db[self.data_start..self.data_end];
+        let (record, _) = decode(data, offset, 0)?;
+        ["country", "registered_country"]
+            .into_iter()
+            .find_map(|key| record.get(key)?.get("iso_code")?.as_str())
+            .map(str::to_string)
+    }
+
+    // Left (bit 0) or right (bit 1) record of a search tree node.
+    fn record(&self, node: usize, bit: u8) -> Option<usize> {
+        let node_bytes = self.record_size / 4;
+        let base = node.checked_mul(node_bytes)?;
+        let bytes = self.db.get(base..base + node_bytes)?;
+        let be = |slice: &[u8]| {
+            slice
+                .iter()
+                .fold(0usize, |acc, b| acc << 8 | usize::from(*b))
+        };
+        Some(match (self.record_size, bit) {
+            (24, 0) => be(&bytes[0..3]),
+            (24, _) => be(&bytes[3..6]),
+            (28, 0) => (usize::from(bytes[3] & 0xF0) << 20) | be(&bytes[0..3]),
+            (28, _) => (usize::from(bytes[3] & 0x0F) << 24) | be(&bytes[4..7]),
+            (_, 0) => be(&bytes[0..4]),
+            _ => be(&bytes[4..8]),
+        })
+    }
+}
+
+// Decodes the value at `pos` in a data section, returning it and the position
+// after it. Pointers are followed but do not advance past their target.
+fn decode(data: &[u8], mut pos: usize, depth: usize) -> Option<(Value, usize)> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let ctrl = *data.get(pos)?;
+    pos += 1;
+    let mut kind = ctrl >> 5;
+    if kind == 1 {
+        let size = usize::from((ctrl >> 3) & 0x3);
+        let high = usize::from(ctrl & 0x7);
+        let bytes = data.get(pos..pos + size + 1)?;
+        let low = bytes
+            .iter()
+            .fold(0usize, |acc, b| acc << 8 | usize::from(*b));
+        let target = match size {
+            0 => (high << 8) | low,
+            1 => ((high << 16) | low) + 2_048,
+            2 => ((high << 24) | low) + 526_336,
+            _ => low,
+        };
+        let (value, _) = decode(data, target, depth + 1)?;
+        return Some((value, pos + size + 1));
+    }
+    if kind == 0 {
+        kind = data.get(pos)?.checked_add(7)?;
+        pos += 1;
+    }
+    let mut size = usize::from(ctrl & 0x1f);
+    if size >= 29 {
+        let extra = size - 28;
+        let bytes = data.get(pos..pos + extra)?;
+        let value = bytes
+            .iter()
+            .fold(0usize, |acc, b| acc << 8 | usize::from(*b));
+        size = [29, 285, 65_821][extra - 1] + value;
+        pos += extra;
+    }
+    match kind {
+        2 => {
+            let text = String::from_utf8_lossy(data.get(pos..pos + size)?).into_owned();
+            Some((Value::String(text), pos + size))
+        }
+        5 | 6 | 9 | 10 => {
+            let bytes = data.get(pos..pos + size)?;
+            let value = bytes.iter().fold(0u64, |acc, b| acc << 8 | u64::from(*b));
+            Some((Value::Uint(value), pos + size))
+        }
+        7 => {
+            let mut pairs = Vec::with_capacity(size.min(64));
+            for _ in 0..size {
+                let (key, next) = decode(data, pos, depth + 1)?;
+                let (value, next) = decode(data, next, depth + 1)?;
+                pairs.push((key.as_str()?.to_string(), value));
+                pos = next;
+            }
+            Some((Value::Map(pairs), pos))
+        }
+        11 => {
+            let mut items = Vec::with_capacity(size.min(64));
+            for _ in 0..size {
+                let (item, next) = decode(data, pos, depth + 1)?;
+                items.push(item);
+                pos = next;
+            }
+            Some((Value::Array(items), pos))
+        }
+        // Booleans keep their value in the size bits.
+        14 => Some((Value::Other, pos)),
+        3 | 15 | 4 | 8 => {
+            let width = match kind {
+                3 => 8,
+                15 => 4,
+                _ => size,
+            };
+            data.get(pos..pos + width)?;
+            Some((Value::Other, pos + width))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> Vec<u8> {
+        let mut out = vec![0x40 | text.len() as u8];
+        out.extend_from_slice(text.as_bytes());
+        out
+    }
+
+    #[test]
+    fn looks_up_country_codes() {
+        // One node: 0.0.0.0/1 points at the first data record, the rest is empty.
+        let mut db = vec![0, 0, 17, 0, 0, 1];
+        db.extend([0; 16]);
+        db.push(0xE1);
+        db.extend(string("country"));
+        db.push(0xE1);
+        db.extend(string("iso_code"));
+        db.extend(string("US"));
+        db.extend_from_slice(METADATA_MARKER);
+        db.push(0xE3);
+        db.extend(string("node_count"));
+        db.extend([0xC1, 1]);
+        db.extend(string("record_size"));
+        db.extend([0xA1, 24]);
+        db.extend(string("ip_version"));
+        db.extend([0xA1, 4]);
+
+        let mut geoip = GeoIp::from_bytes(db).unwrap();
+        assert_eq!(geoip.country("1.2.3.4").as_deref(), Some("US"));
+        assert_eq!(geoip.country("200.1.1.1"), None);
+        assert_eq!(geoip.country("2001:db8::1"), None);
+        assert_eq!(geoip.cache.len(), 3);
+        geoip.limit_cache(2);
+        assert!(geoip.cache.is_empty());
+        assert_eq!(geoip.country("1.2.3.5").as_deref(), Some("US"));
+        assert_eq!(geoip.country("1.2.3.6").as_deref(), Some("US"));
+        assert_eq!(geoip.country("1.2.3.7").as_deref(), Some("US"));
+        assert_eq!(geoip.cache.len(), 1);
+
+        assert!(GeoIp::from_bytes(b"not a database".to_vec()).is_none());
+    }
+}
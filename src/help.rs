@@ -0,0 +1,64 @@
+use ratatui::text::Line;
+
+use crate::FilterField;
+use crate::keymap::Keymap;
+use crate::report::{Report, heading};
+
+// Keys that are not table commands and so never show up in the keymap.
+const FIXED_KEYS: [(&str, &str); 5] = [
+    ("F1..F9", "Edit a filter"),
+    ("Shift+F1..F9", "Clear a filter"),
+    (
+        "Tab",
+        "Complete a filter value while editing (again to cycle)",
+    ),
+    ("\"1..\"5", "Recall one of the last five filter states"),
+    (
+        "Esc",
+        "Close the newest popup, prompt, picker or view (s, =, ^)",
+    ),
+];
+
+// What each filter prompt accepts, besides plain text.
+fn syntax(field: FilterField) -> &'static str {
+    match field {
+        FilterField::Service => "Service name, or part of one: ssh, http",
+        FilterField::Port => {
+            "Source or destination port: 22, a list 22,80,443, a range 1000-2000, >1024"
+        }
+        FilterField::Ip => "Source or destination address, or part of one: 10.0.0.",
+        FilterField::Action => "BLOCK, ALLOW, AUDIT, or part of one",
+        FilterField::Proto => "TCP, UDP, ICMP, ...",
+        FilterField::Text => "Anywhere in the raw log line",
+        FilterField::Time => {
+            "last 1h (s/m/h/d/w), today, yesterday, 20:00..20:30, 2026-02-10 08:00..Feb 11"
+        }
+        FilterField::Country => "Source country codes with --geoip: CN,RU",
+        FilterField::Flags => "TCP flags set, ! for unset: syn,!ack",
+    }
+}
+
+// The `?` popup: every table key as bound now, the fixed keys, then the
+// filter syntax.
+pub fn report(keymap: &Keymap) -> Report {
+    let row = |keys: &str, text: &str| Line::from(format!("  {:<14} {}", keys, text));
+    let mut lines = vec![heading("TABLE KEYS".to_string())];
+    for (keys, text) in keymap.help_rows() {
+        lines.push(row(&keys, text));
+    }
+    lines.push(Line::default());
+    lines.push(heading("OTHER KEYS".to_string()));
+    for (keys, text) in FIXED_KEYS {
+        lines.push(row(keys, text));
+    }
+    lines.push(Line::default());
+    lines.push(heading("FILTERS".to_string()));
+    for field in FilterField::ALL {
+        lines.push(row(field.summary_label(), syntax(field)));
+    }
+    lines.push(row(
+        "!value",
+        "Exclude matches instead, e.g. !443 or !10.0.0.5",
+    ));
+    Report::new("Help", lines)
+}
@@ -0,0 +1,91 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+// The raw kernel line in the Log Entry pane, colored for scanning: the
+// `[UFW ACTION]` marker in the action's table color, `KEY=` dim, values
+// bright, addresses and ports in their own colors. Lines in other formats
+// keep their text, with only `KEY=VALUE` tokens colored.
+pub fn raw_line(text: &str) -> Line<'static> {
+    let mut spans = Vec::new();
+    let rest = match marker(text) {
+        Some((start, end)) => {
+            push_fields(&mut spans, &text[..start]);
+            let action = text[start + 5..end - 1].trim();
+            let color = match action {
+                "ALLOW" => Color::Green,
+                "BLOCK" | "REJECT" => Color::Red,
+                _ => Color::Yellow,
+            };
+            spans.push(Span::styled(
+                text[start..end].to_string(),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ));
+            &text[end..]
+        }
+        None => text,
+    };
+    push_fields(&mut spans, rest);
+    Line::from(spans)
+}
+
+// Byte range of `[UFW ...]`, brackets included.
+fn marker(text: &str) -> Option<(usize, usize)> {
+    let start = text.find("[UFW ")?;
+    let end = start + text[start..].find(']')? + 1;
+    Some((start, end))
+}
+
+fn push_fields(spans: &mut Vec<Span<'static>>, text: &str) {
+    let dim = Style::default().fg(Color::DarkGray);
+    // Whitespace stays attached to the token before it, so the text is
+    // rebuilt exactly.
+    for token in text.split_inclusive(' ') {
+        let word = token.trim_end_matches(' ');
+        let space = &token[word.len()..];
+        match word.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                let value_color = match key {
+                    "SRC" | "DST" => Color::LightCyan,
+                    "SPT" | "DPT" => Color::LightMagenta,
+                    _ => Color::White,
+                };
+                spans.push(Span::styled(format!("{}=", key), dim));
+                spans.push(Span::styled(
+                    value.to_string(),
+                    Style::default().fg(value_color),
+                ));
+                spans.push(Span::raw(space.to_string()));
+            }
+            _ => spans.push(Span::raw(token.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_marker_keys_and_values_without_changing_the_text() {
+        let text = "Feb 11 20:00:00 host kernel: [UFW BLOCK] IN=eth0 OUT= SRC=45.33.2.1 DPT=22 SYN";
+        let line = raw_line(text);
+        let rebuilt: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(rebuilt, text);
+
+        let style_of = |content: &str| {
+            line.spans
+                .iter()
+                .find(|span| span.content == content)
+                .map(|span| span.style.fg)
+        };
+        assert_eq!(style_of("[UFW BLOCK]"), Some(Some(Color::Red)));
+        assert_eq!(style_of("SRC="), Some(Some(Color::DarkGray)));
+        assert_eq!(style_of("45.33.2.1"), Some(Some(Color::LightCyan)));
+        assert_eq!(style_of("22"), Some(Some(Color::LightMagenta)));
+        assert_eq!(style_of("eth0"), Some(Some(Color::White)));
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::mem;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+use crate::parser::{LogFormat, parse_line};
+use crate::rotation::open_log;
+use crate::{FileFingerprint, LogEntry, source_label};
+
+// Lines parsed between batches sent back to the UI.
+const BATCH_LINES: usize = 50_000;
+
+// One source of a reload: contents the app already holds (journald output,
+// appended tails) or a file the worker reads itself.
+pub enum Source {
+    Loaded(String),
+    File(PathBuf),
+}
+
+enum Update {
+    Batch(Vec<LogEntry>, usize),
+    Done(Vec<String>),
+    MainFailed(String),
+}
+
+pub enum Poll {
+    Busy,
+    // Labels of watched sources that could not be read.
+    Done(Vec<String>),
+    MainFailed(String),
+}
+
+// A reload running on a worker thread. The UI drains parsed batches each tick
+// and keeps the previous entries on screen until the worker is done; dropping
+// it abandons the worker at its next batch.
+pub struct Ingest {
+    updates: Receiver<Update>,
+    pub parsed: Vec<LogEntry>,
+    pub fingerprints: Vec<Option<FileFingerprint>>,
+    pub source_count: usize,
+    pub progress: usize,
+    // Set when the sources hold only bytes appended since the last ingest.
+    pub append: bool,
+}
+
+impl Ingest {
+    // `bases` are where each source's contents start in its file.
+    pub fn spawn(
+        sources: Vec<Source>,
+        bases: Vec<usize>,
+        fingerprints: Vec<Option<FileFingerprint>>,
+        format: LogFormat,
+        raw_limit: Option<usize>,
+        append: bool,
+    ) -> Self {
+        let (sender, updates) = mpsc::channel();
+        let source_count = sources.len();
+        thread::spawn(move || run(sources, bases, format, raw_limit, sender));
+        Self {
+            updates,
+            parsed: Vec::new(),
+            fingerprints,
+            source_count,
+            progress: 0,
+            append,
+        }
+    }
+
+    pub fn poll(&mut self) -> Poll {
+        loop {
+            match self.updates.try_recv() {
+                Ok(Update::Batch(batch, progress)) => {
+                    self.parsed.extend(batch);
+                    self.progress = progress;
+                }
+                Ok(Update::Done(failed_watched)) => return Poll::Done(failed_watched),
+                Ok(Update::MainFailed(message)) => return Poll::MainFailed(message),
+                Err(TryRecvError::Empty) => return Poll::Busy,
+                Err(TryRecvError::Disconnected) => {
+                    return Poll::MainFailed("Log reader stopped unexpectedly".to_string());
+                }
+            }
+        }
+    }
+}
+
+fn run(
+    sources: Vec<Source>,
+    bases: Vec<usize>,
+    format: LogFormat,
+    raw_limit: Option<usize>,
+    updates: Sender<Update>,
+) {
+    let total = sources
+        .iter()
+        .map(|source| match source {
+            Source::Loaded(text) => text.len() as u64,
+            Source::File(path) => fs::metadata(path).map_or(0, |meta| meta.len()),
+        })
+        .sum();
+    let mut parser = Parser::new(format, raw_limit, total, BATCH_LINES, updates);
+    let mut failed_watched = Vec::new();
+    // Bytes read so far of each file. When logrotate renames the log between
+    // reading it and reading its rotations, the same file turns up again as
+    // LOG.1; only what it gained since is parsed, so every physical line
+    // yields one entry.
+    let mut read_lens: HashMap<(u64, u64), usize> = HashMap::new();
+    for (idx, source) in sources.into_iter().enumerate() {
+        let base = bases.get(idx).copied().unwrap_or(0);
+        let path = match source {
+            Source::Loaded(text) => {
+                let _ = parser.parse(idx, base, 0, text.as_bytes());
+                None
+            }
+            Source::File(path) => {
+                let parsed = open_log(&path).and_then(|log| {
+                    let read = log.id.map(|id| read_lens.entry(id).or_insert(0));
+                    let skip = read.as_deref().copied().unwrap_or(0);
+                    let end = parser.parse(idx, base, skip, log.lines)?;
+                    if let Some(read) = read {
+                        *read = end.max(skip);
+                    }
+                    Ok(())
+                });
+                Some((path, parsed))
+            }
+        };
+        if parser.closed {
+            return;
+        }
+        match path {
+            Some((path, Err(err))) if idx == 0 => {
+                let message = format!("Failed to read {}: {}", path.display(), err);
+                let _ = parser.updates.send(Update::MainFailed(message));
+                return;
+            }
+            Some((path, Err(_))) => failed_watched.push(source_label(&path)),
+            _ => {}
+        }
+    }
+    parser.read = parser.read.max(parser.total);
+    if parser.send_batch() {
+        let _ = parser.updates.send(Update::Done(failed_watched));
+    }
+}
+
+// Parses sources line by line as they are read, so a large log is never held
+// whole, sending the entries back every `batch_lines` lines.
+struct Parser {
+    format: LogFormat,
+    raw_limit: Option<usize>,
+    batch_lines: usize,
+    lines: usize,
+    parsed: Vec<LogEntry>,
+    // Bytes read of all sources, against their total size for progress.
+    read: u64,
+    total: u64,
+    updates: Sender<Update>,
+    // Set once the UI dropped the ingest.
+    closed: bool,
+}
+
+impl Parser {
+    fn new(
+        format: LogFormat,
+        raw_limit: Option<usize>,
+        total: u64,
+        batch_lines: usize,
+        updates: Sender<Update>,
+    ) -> Self {
+        Self {
+            format,
+            raw_limit,
+            batch_lines,
+            lines: 0,
+            parsed: Vec::new(),
+            read: 0,
+            total,
+            updates,
+            closed: false,
+        }
+    }
+
+    // Parses one source, skipping lines that start before byte `skip`.
+    // `base` is where the contents start in the file. Returns the bytes read.
+    fn parse(
+        &mut self,
+        source: usize,
+        base: usize,
+        skip: usize,
+        mut reader: impl BufRead,
+    ) -> io::Result<usize> {
+        let mut offset = 0;
+        let mut bytes = Vec::new();
+        loop {
+            bytes.clear();
+            let len = reader.read_until(b'\n', &mut bytes)?;
+            if len == 0 {
+                return Ok(offset);
+            }
+            let start = offset;
+            offset += len;
+            self.read += len as u64;
+            if start < skip {
+                continue;
+            }
+            let line = std::str::from_utf8(&bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            if let Some(mut entry) =
+                parse_line(line.strip_suffix('\r').unwrap_or(line), self.format)
+            {
+                entry.source = source;
+                if let Some(limit) = self.raw_limit.filter(|limit| entry.raw.len() > *limit) {
+                    let cut = entry.raw.floor_char_boundary(limit);
+                    entry.raw.truncate(cut);
+                    entry.raw.push('…');
+                    entry.raw_offset = Some(base + start);
+                }
+                self.parsed.push(entry);
+            }
+            self.lines += 1;
+            if self.lines >= self.batch_lines && !self.send_batch() {
+                return Ok(offset);
+            }
+        }
+    }
+
+    // Returns false once the UI dropped the ingest.
+    fn send_batch(&mut self) -> bool {
+        self.lines = 0;
+        let batch = mem::take(&mut self.parsed);
+        let progress = self.progress_percent();
+        self.closed = self.updates.send(Update::Batch(batch, progress)).is_err();
+        !self.closed
+    }
+
+    fn progress_percent(&self) -> usize {
+        if self.total == 0 {
+            return 100;
+        }
+        // Compressed rotations read as more bytes than they hold on disk.
+        (self.read * 100 / self.total).min(100) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn parses_in_slices_and_streams_batches() {
+        let contents = (0..5)
+            .map(|i| format!("Feb 11 20:21:0{} h kernel: [UFW BLOCK] SRC=8.8.8.{}", i, i))
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let (sender, updates) = mpsc::channel();
+        let total = contents.len() as u64;
+        let mut parser = Parser::new(LogFormat::Ufw, None, total, 2, sender);
+        assert_eq!(
+            parser.parse(0, 0, 0, contents.as_bytes()).unwrap(),
+            contents.len()
+        );
+        let batches: Vec<(usize, usize)> = updates
+            .try_iter()
+            .map(|update| match update {
+                Update::Batch(batch, progress) => (batch.len(), progress),
+                _ => panic!("expected a batch"),
+            })
+            .collect();
+        assert_eq!(batches, [(2, 40), (2, 80)]);
+        assert_eq!(parser.parsed.len(), 1);
+        assert_eq!(parser.parsed[0].src_ip.as_deref(), Some("8.8.8.4"));
+        assert_eq!(parser.progress_percent(), 100);
+
+        let sources = vec![
+            Source::Loaded(contents),
+            Source::File(PathBuf::from("/nonexistent/extra.log")),
+        ];
+        let mut ingest =
+            Ingest::spawn(sources, Vec::new(), Vec::new(), LogFormat::Ufw, None, false);
+        let started = Instant::now();
+        let failed = loop {
+            match ingest.poll() {
+                Poll::Busy => assert!(started.elapsed() < Duration::from_secs(5)),
+                Poll::Done(failed) => break failed,
+                Poll::MainFailed(message) => panic!("{}", message),
+            }
+        };
+        assert_eq!(failed, ["extra"]);
+        assert_eq!((ingest.parsed.len(), ingest.progress), (5, 100));
+
+        let missing = vec![Source::File(PathBuf::from("/nonexistent/ufw.log"))];
+        let mut ingest =
+            Ingest::spawn(missing, Vec::new(), Vec::new(), LogFormat::Ufw, None, false);
+        let message = loop {
+            if let Poll::MainFailed(message) = ingest.poll() {
+                break message;
+            }
+        };
+        assert!(message.starts_with("Failed to read /nonexistent/ufw.log"));
+    }
+
+    #[test]
+    fn a_file_reached_under_two_names_is_parsed_once() {
+        let dir = std::env::temp_dir().join(format!("ufw-ingest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("ufw.log");
+        let lines = "Feb 11 20:21:01 h kernel: [UFW BLOCK] SRC=8.8.8.1\n\
+                     Feb 11 20:21:02 h kernel: [UFW BLOCK] SRC=8.8.8.2\n";
+        std::fs::write(&log, lines).unwrap();
+        // As after a rename between reading LOG and reading LOG.1.
+        std::fs::hard_link(&log, dir.join("ufw.log.1")).unwrap();
+        let sources = vec![Source::File(log), Source::File(dir.join("ufw.log.1"))];
+        let mut ingest =
+            Ingest::spawn(sources, Vec::new(), Vec::new(), LogFormat::Ufw, None, false);
+        while let Poll::Busy = ingest.poll() {}
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(ingest.parsed.len(), 2);
+    }
+}
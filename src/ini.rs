@@ -0,0 +1,32 @@
+// The INI dialect of the files under the config directory: `[section]`
+// headers, then `key=value` lines; `#` starts a comment. Lines before the
+// first header are ignored.
+pub type Section = (String, Vec<(String, String)>);
+
+pub fn parse(text: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), Vec::new()));
+        } else if let (Some((_, entries)), Some((key, value))) =
+            (sections.last_mut(), line.split_once('='))
+        {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+pub fn serialize(comment: &str, sections: &[Section]) -> String {
+    let mut out = format!("# {}\n", comment);
+    for (name, entries) in sections {
+        out.push_str(&format!("\n[{}]\n", name));
+        for (key, value) in entries {
+            out.push_str(&format!("{}={}\n", key, value));
+        }
+    }
+    out
+}
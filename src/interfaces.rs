@@ -0,0 +1,80 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cache::state_dir;
+use crate::ini;
+
+const FILE_NAME: &str = "interfaces.ini";
+const SECTION: &str = "pinned";
+
+// Interfaces pinned with `*`, in pinning order. They lead the chip row and
+// `,`/`.` cycling so the busiest-first order cannot push them around.
+pub fn pinned_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+// A missing file pins nothing.
+pub fn load_pinned(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(parse(&text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn save_pinned(path: &Path, pinned: &[String]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serialize(pinned))
+}
+
+// Pinned interfaces that were seen, in pinning order, then the rest as given.
+pub fn pinned_first(by_count: Vec<String>, pinned: &[String]) -> Vec<String> {
+    let mut ordered: Vec<String> = pinned
+        .iter()
+        .filter(|iface| by_count.contains(iface))
+        .cloned()
+        .collect();
+    ordered.extend(by_count.into_iter().filter(|iface| !pinned.contains(iface)));
+    ordered
+}
+
+// One `iface = name` line per pin under `[pinned]`.
+fn parse(text: &str) -> Vec<String> {
+    ini::parse(text)
+        .into_iter()
+        .filter(|(name, _)| name == SECTION)
+        .flat_map(|(_, settings)| settings)
+        .filter(|(key, value)| key == "iface" && !value.is_empty())
+        .map(|(_, value)| value)
+        .collect()
+}
+
+fn serialize(pinned: &[String]) -> String {
+    let settings = pinned
+        .iter()
+        .map(|iface| ("iface".to_string(), iface.clone()))
+        .collect();
+    ini::serialize(
+        "Interfaces pinned with * in ufw-log-viewer, shown first",
+        &[(SECTION.to_string(), settings)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_interfaces_lead_in_pinning_order() {
+        let by_count = ["eth0", "docker0", "wg0", "lo"].map(String::from).to_vec();
+        let pinned = ["wg0", "ppp0", "lo"].map(String::from);
+        assert_eq!(
+            pinned_first(by_count, &pinned),
+            ["wg0", "lo", "eth0", "docker0"]
+        );
+        assert_eq!(parse(&serialize(&pinned)), pinned);
+    }
+}
@@ -0,0 +1,76 @@
+use std::io;
+use std::process::Command;
+
+use crate::stream::StreamLog;
+
+const CURSOR_PREFIX: &str = "-- cursor: ";
+
+// Kernel messages from the systemd journal, for hosts where UFW only logs to
+// journald. Each poll asks `journalctl` for what arrived after the last
+// cursor and keeps the UFW lines.
+#[derive(Default)]
+pub struct Journal {
+    cursor: Option<String>,
+    pub contents: StreamLog,
+}
+
+impl Journal {
+    // Appends new UFW lines; returns true when any arrived.
+    pub fn poll(&mut self) -> io::Result<bool> {
+        let mut command = Command::new("journalctl");
+        command.args(["-k", "-q", "--no-pager", "-o", "short", "--show-cursor"]);
+        if let Some(cursor) = self.cursor.as_deref() {
+            command.arg(format!("--after-cursor={}", cursor));
+        }
+        let output = command.output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(format!(
+                "journalctl failed: {}",
+                stderr.trim()
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (lines, cursor) = parse_journal_output(&stdout);
+        if cursor.is_some() {
+            self.cursor = cursor;
+        }
+        for line in &lines {
+            self.contents.push_line(line);
+        }
+        Ok(!lines.is_empty())
+    }
+}
+
+// UFW lines and the trailing cursor from `journalctl --show-cursor` output.
+fn parse_journal_output(stdout: &str) -> (Vec<&str>, Option<String>) {
+    let mut lines = Vec::new();
+    let mut cursor = None;
+    for line in stdout.lines() {
+        if let Some(value) = line.strip_prefix(CURSOR_PREFIX) {
+            cursor = Some(value.trim().to_string());
+        } else if line.contains("[UFW ") {
+            lines.push(line);
+        }
+    }
+    (lines, cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_ufw_lines_and_cursor() {
+        let stdout = "Feb 11 20:21:00 h kernel: usb 1-1: new device\n\
+            Feb 11 20:21:01 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1\n\
+            -- cursor: s=abc;i=12;b=def\n";
+        let (lines, cursor) = parse_journal_output(stdout);
+        assert_eq!(
+            lines,
+            ["Feb 11 20:21:01 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1"]
+        );
+        assert_eq!(cursor.as_deref(), Some("s=abc;i=12;b=def"));
+        assert_eq!(parse_journal_output(""), (Vec::new(), None));
+    }
+}
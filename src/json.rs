@@ -0,0 +1,119 @@
+use crate::LogEntry;
+
+// One entry as a flat JSON object. Missing values are null; the ID is a hex
+// string because JSON consumers often lose precision on 64-bit integers.
+pub fn entry_json(entry: &LogEntry, tags: &[&str]) -> String {
+    let text = |value: Option<&str>| value.map_or_else(|| "null".to_string(), quote);
+    let port = |value: Option<u16>| value.map_or_else(|| "null".to_string(), |p| p.to_string());
+    format!(
+        "{{\"id\":\"{:016x}\",\"timestamp\":{},\"action\":{},\"direction\":{},\"in\":{},\"out\":{},\"src\":{},\"dst\":{},\"proto\":{},\"spt\":{},\"dpt\":{},\"service\":{},\"tags\":[{}],\"raw\":{}}}",
+        entry.id,
+        quote(&entry.timestamp),
+        quote(&entry.action),
+        quote(entry.direction()),
+        text(entry.in_iface.as_deref()),
+        text(entry.out_iface.as_deref()),
+        text(entry.src_ip.as_deref()),
+        text(entry.dst_ip.as_deref()),
+        text(entry.proto.as_deref()),
+        port(entry.src_port),
+        port(entry.dst_port),
+        text(entry.service.as_deref()),
+        tags.iter()
+            .map(|tag| quote(tag))
+            .collect::<Vec<_>>()
+            .join(","),
+        quote(&entry.raw),
+    )
+}
+
+// Every parsed field of one entry, for pasting into tickets and SIEM
+// queries: `entry_json`'s keys plus the country, packet and TCP/ICMP fields.
+pub fn entry_detail_json(entry: &LogEntry, tags: &[&str]) -> String {
+    let text = |value: Option<&str>| value.map_or_else(|| "null".to_string(), quote);
+    let list = |values: &[&str]| {
+        let values: Vec<String> = values.iter().map(|value| quote(value)).collect();
+        format!("[{}]", values.join(","))
+    };
+    let fields = [
+        ("id", quote(&format!("{:016x}", entry.id))),
+        ("timestamp", quote(&entry.timestamp)),
+        ("action", quote(&entry.action)),
+        ("direction", quote(entry.direction())),
+        ("in", text(entry.in_iface.as_deref())),
+        ("out", text(entry.out_iface.as_deref())),
+        ("src", text(entry.src_ip.as_deref())),
+        ("dst", text(entry.dst_ip.as_deref())),
+        ("country", text(entry.country.as_deref())),
+        ("proto", text(entry.proto.as_deref())),
+        ("spt", number(entry.src_port)),
+        ("dpt", number(entry.dst_port)),
+        ("service", text(entry.service.as_deref())),
+        ("len", number(entry.length)),
+        ("ttl", number(entry.ttl)),
+        ("tos", number(entry.tos)),
+        ("mac", text(entry.mac.as_deref())),
+        ("window", number(entry.window)),
+        ("flags", list(&entry.tcp_flags.names())),
+        ("icmp_type", number(entry.icmp_type)),
+        ("icmp_code", number(entry.icmp_code)),
+        ("tags", list(tags)),
+        ("raw", quote(&entry.raw)),
+    ];
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("\"{}\":{}", key, value))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn number(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+pub fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn quotes_control_characters() {
+        assert_eq!(quote("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn details_carry_every_parsed_field() {
+        let entry = parse_ufw_line(
+            "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= MAC=aa:bb SRC=1.2.3.4 DST=10.0.0.1 LEN=60 TOS=0x00 TTL=52 PROTO=TCP SPT=40000 DPT=22 WINDOW=64240 SYN URGP=0",
+        )
+        .unwrap();
+        let json = entry_detail_json(&entry, &["scan"]);
+        assert!(json.starts_with("{\"id\":\""), "{}", json);
+        assert!(json.contains("\"src\":\"1.2.3.4\",\"dst\":\"10.0.0.1\",\"country\":null,"));
+        assert!(
+            json.contains("\"len\":60,\"ttl\":52,\"tos\":0,\"mac\":\"aa:bb\",\"window\":64240,")
+        );
+        assert!(json.contains(
+            "\"flags\":[\"SYN\"],\"icmp_type\":null,\"icmp_code\":null,\"tags\":[\"scan\"],"
+        ));
+        assert!(json.ends_with("SYN URGP=0\"}"));
+    }
+}
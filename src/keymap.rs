@@ -0,0 +1,674 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// What a key does in the events table. Prompts, pickers and popups keep
+// their own fixed keys; only these can be rebound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    Quit,
+    Reload,
+    TogglePause,
+    ClearFilters,
+    ToggleLocal,
+    LoadOlder,
+    ToggleWan,
+    ToggleBroadcast,
+    ToggleReplies,
+    RotatedHistory,
+    BogonsOnly,
+    ListedOnly,
+    Alerts,
+    Talkers,
+    CycleFlow,
+    CycleDirection,
+    PrevInterface,
+    NextInterface,
+    AllInterfaces,
+    PinInterface,
+    PinEntry,
+    MinuteGutter,
+    Redact,
+    CycleSort,
+    ReverseSort,
+    CopyRowCaptureFilter,
+    CopyFilterCaptureFilter,
+    Capture,
+    RuleCounters,
+    LimitSummaries,
+    FixedSourcePorts,
+    AbuseReports,
+    Presets,
+    Columns,
+    CopyCommandLine,
+    Burst,
+    RecentFilters,
+    EgressReview,
+    Countries,
+    Export,
+    OpenSource,
+    Stats,
+    Aggregate,
+    Find,
+    Dismiss,
+    TruncatedCells,
+    DetailRaw,
+    Block,
+    // The next search match while searching, else a snapshot.
+    Next,
+    // The previous search match while searching, else the snapshot diff.
+    Previous,
+    TimeLock,
+    CopyKeyValues,
+    SelectionLock,
+    Drilldown,
+    AllowWithoutListener,
+    WanInterface,
+    ScrollLeft,
+    ScrollRight,
+    Up,
+    Down,
+    CopySourceIp,
+    CopyArpa,
+    CopyRow,
+    CopyPretty,
+    CopyJson,
+    // Handled before the key is recorded, so neither lands in a macro.
+    RecordMacro,
+    PlayMacro,
+    Help,
+}
+
+// Every command with its name in config.ini's `[keys]`, default keys and
+// the description `?` shows.
+const COMMANDS: [(Command, &str, &str, &str); 68] = [
+    (Command::Quit, "quit", "q", "Quit"),
+    (Command::Reload, "reload", "r", "Reload the log"),
+    (
+        Command::TogglePause,
+        "pause",
+        "a A",
+        "Pause or resume live updates",
+    ),
+    (
+        Command::ClearFilters,
+        "clear_filters",
+        "c",
+        "Clear every filter and toggle",
+    ),
+    (
+        Command::ToggleLocal,
+        "toggle_local",
+        "l",
+        "Show or hide local sources",
+    ),
+    (
+        Command::LoadOlder,
+        "load_older",
+        "L",
+        "Load older entries cut by --since or --low-memory",
+    ),
+    (
+        Command::ToggleWan,
+        "toggle_wan",
+        "p P",
+        "Show or hide WAN sources",
+    ),
+    (
+        Command::ToggleBroadcast,
+        "toggle_broadcast",
+        "B",
+        "Show or hide broadcast and multicast",
+    ),
+    (
+        Command::ToggleReplies,
+        "toggle_replies",
+        "H",
+        "Show or hide replies to outbound traffic",
+    ),
+    (
+        Command::RotatedHistory,
+        "rotated_history",
+        "h",
+        "Load or drop rotated logs",
+    ),
+    (
+        Command::BogonsOnly,
+        "bogons_only",
+        "!",
+        "Only bogon sources",
+    ),
+    (
+        Command::ListedOnly,
+        "listed_only",
+        "#",
+        "Only sources on a --blocklist",
+    ),
+    (Command::Alerts, "alerts", "^", "Port scan alerts panel"),
+    (Command::Talkers, "talkers", "|", "Top talkers sidebar"),
+    (
+        Command::CycleFlow,
+        "cycle_flow",
+        "f F",
+        "Cycle the flow filter",
+    ),
+    (
+        Command::CycleDirection,
+        "cycle_direction",
+        "d D",
+        "Cycle the direction filter",
+    ),
+    (
+        Command::PrevInterface,
+        "prev_interface",
+        ",",
+        "Previous interface",
+    ),
+    (
+        Command::NextInterface,
+        "next_interface",
+        ".",
+        "Next interface",
+    ),
+    (
+        Command::AllInterfaces,
+        "all_interfaces",
+        "0",
+        "All interfaces",
+    ),
+    (
+        Command::PinInterface,
+        "pin_interface",
+        "*",
+        "Pin or unpin the selected interface",
+    ),
+    (
+        Command::PinEntry,
+        "pin_entry",
+        "m M",
+        "Pin the entry the filters hid",
+    ),
+    (
+        Command::MinuteGutter,
+        "minute_gutter",
+        "g G",
+        "Per-minute activity gutter",
+    ),
+    (
+        Command::Redact,
+        "redact",
+        "x X",
+        "Redact addresses and hostnames",
+    ),
+    (
+        Command::CycleSort,
+        "cycle_sort",
+        "o",
+        "Sort by the next column",
+    ),
+    (
+        Command::ReverseSort,
+        "reverse_sort",
+        "v V",
+        "Reverse the sort",
+    ),
+    (
+        Command::CopyRowCaptureFilter,
+        "copy_row_capture_filter",
+        "y",
+        "Copy a tcpdump filter for the row",
+    ),
+    (
+        Command::CopyFilterCaptureFilter,
+        "copy_filter_capture_filter",
+        "Y",
+        "Copy a tcpdump filter for the filters",
+    ),
+    (
+        Command::Capture,
+        "capture",
+        "T",
+        "Start or stop a tcpdump capture",
+    ),
+    (
+        Command::RuleCounters,
+        "rule_counters",
+        "R",
+        "Rule hit counters",
+    ),
+    (
+        Command::LimitSummaries,
+        "limit_summaries",
+        "%",
+        "Rate limit summaries",
+    ),
+    (
+        Command::FixedSourcePorts,
+        "fixed_source_ports",
+        "S",
+        "Sources reusing one source port",
+    ),
+    (
+        Command::AbuseReports,
+        "abuse_reports",
+        "E",
+        "Write abuse reports for top offenders",
+    ),
+    (Command::Presets, "presets", "'", "Filter presets"),
+    (Command::Columns, "columns", ";", "Column picker"),
+    (
+        Command::CopyCommandLine,
+        "copy_command_line",
+        ":",
+        "Copy a command line reproducing the view",
+    ),
+    (
+        Command::Burst,
+        "burst",
+        "+",
+        "Unfold or fold a flood of identical rows",
+    ),
+    (
+        Command::RecentFilters,
+        "recent_filters",
+        "\"",
+        "Recent filters (then 1-5)",
+    ),
+    (
+        Command::EgressReview,
+        "egress_review",
+        ">",
+        "Review blocked outbound traffic",
+    ),
+    (
+        Command::Countries,
+        "countries",
+        "C",
+        "Blocked entries by source country (--geoip)",
+    ),
+    (Command::Export, "export", "e", "Export the view"),
+    (
+        Command::OpenSource,
+        "open_source",
+        "O",
+        "Open another log, directory or the journal",
+    ),
+    (Command::Stats, "stats", "s", "Statistics view"),
+    (
+        Command::Aggregate,
+        "aggregate",
+        "=",
+        "Aggregate identical flows",
+    ),
+    (Command::Find, "find", "/", "Find in the table"),
+    (
+        Command::Dismiss,
+        "dismiss",
+        "Esc",
+        "End the search or dismiss the status",
+    ),
+    (
+        Command::TruncatedCells,
+        "truncated_cells",
+        "I",
+        "Show the row's truncated cells",
+    ),
+    (
+        Command::DetailRaw,
+        "detail_raw",
+        "J",
+        "Raw line or fields in the detail pane",
+    ),
+    (
+        Command::Block,
+        "block",
+        "b",
+        "Block the selected source with ufw",
+    ),
+    (Command::Next, "next", "n", "Next match, or take a snapshot"),
+    (
+        Command::Previous,
+        "previous",
+        "N",
+        "Previous match, or compare snapshots",
+    ),
+    (
+        Command::TimeLock,
+        "time_lock",
+        "t",
+        "Lock the view to the selected time",
+    ),
+    (
+        Command::CopyKeyValues,
+        "copy_key_values",
+        "K",
+        "Copy the row as key=value lines",
+    ),
+    (
+        Command::SelectionLock,
+        "selection_lock",
+        "z Z",
+        "Keep the selection on its entry",
+    ),
+    (
+        Command::Drilldown,
+        "drilldown",
+        "Enter",
+        "Drill down into the selected source",
+    ),
+    (
+        Command::AllowWithoutListener,
+        "allow_without_listener",
+        "u U",
+        "ALLOW traffic to ports nothing listens on",
+    ),
+    (
+        Command::WanInterface,
+        "wan_interface",
+        "w W",
+        "Select the default WAN interface",
+    ),
+    (
+        Command::ScrollLeft,
+        "scroll_left",
+        "Left",
+        "Scroll the detail pane left",
+    ),
+    (
+        Command::ScrollRight,
+        "scroll_right",
+        "Right",
+        "Scroll the detail pane right",
+    ),
+    (Command::Up, "up", "k Up", "Move up"),
+    (Command::Down, "down", "j Down", "Move down"),
+    (
+        Command::CopySourceIp,
+        "copy_source_ip",
+        "Tab ctrl+i",
+        "Copy the source IP",
+    ),
+    (
+        Command::CopyArpa,
+        "copy_arpa",
+        "ctrl+r",
+        "Copy the source's reverse DNS name",
+    ),
+    (
+        Command::CopyRow,
+        "copy_row",
+        "ctrl+c",
+        "Copy the selected log line (a Ctrl key also in prompts and popups)",
+    ),
+    (
+        Command::CopyPretty,
+        "copy_pretty",
+        "ctrl+p",
+        "Copy the row as aligned text",
+    ),
+    (
+        Command::CopyJson,
+        "copy_json",
+        "{",
+        "Copy the row as a JSON object",
+    ),
+    (
+        Command::RecordMacro,
+        "record_macro",
+        "Q",
+        "Record the keys up to the next Q into register a-z or 0-9",
+    ),
+    (
+        Command::PlayMacro,
+        "play_macro",
+        "@",
+        "Replay a recorded register",
+    ),
+    (Command::Help, "help", "?", "This help"),
+];
+
+// Named keys besides single characters; matched case-insensitively.
+const NAMED_KEYS: [(&str, KeyCode); 13] = [
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Tab", KeyCode::Tab),
+    ("Space", KeyCode::Char(' ')),
+    ("Backspace", KeyCode::Backspace),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+];
+
+// A key with or without Ctrl. Shift is part of the character ("K").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub ctrl: bool,
+}
+
+impl KeyBinding {
+    // "k", "K", "Up", "Space", "ctrl+r".
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (ctrl, name) = match text.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("ctrl+") && text.len() > 5 => {
+                (true, &text[5..])
+            }
+            _ => (false, text),
+        };
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            // Terminals send Ctrl+letter without case.
+            (Some(c), None) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, code)| *code)
+                .ok_or_else(|| format!("unknown key {}", text))?,
+        };
+        Ok(Self { code, ctrl })
+    }
+
+    // The key as `parse` reads it, e.g. "Ctrl+R" or "Space".
+    pub fn label(&self) -> String {
+        let key = match NAMED_KEYS.iter().find(|(_, code)| *code == self.code) {
+            Some((name, _)) => name.to_string(),
+            None => match self.code {
+                KeyCode::Char(c) if self.ctrl => c.to_ascii_uppercase().to_string(),
+                KeyCode::Char(c) => c.to_string(),
+                code => format!("{:?}", code),
+            },
+        };
+        if self.ctrl {
+            format!("Ctrl+{}", key)
+        } else {
+            key
+        }
+    }
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Self> {
+        COMMANDS
+            .iter()
+            .find(|(_, key, _, _)| *key == name)
+            .map(|(command, _, _, _)| *command)
+    }
+}
+
+// A `[keys]` line: `up = j Up` binds Up and j to `up` instead of its
+// default keys.
+pub fn parse_binding(name: &str, keys: &str) -> Result<(Command, Vec<KeyBinding>), String> {
+    let command = Command::from_name(name).ok_or_else(|| {
+        format!(
+            "unknown command {} in [keys] (expected one of {})",
+            name,
+            COMMANDS.map(|(_, name, _, _)| name).join(", ")
+        )
+    })?;
+    let keys = parse_keys(keys)?;
+    if keys.is_empty() {
+        return Err(format!("no keys for {}", name));
+    }
+    Ok((command, keys))
+}
+
+fn parse_keys(text: &str) -> Result<Vec<KeyBinding>, String> {
+    text.split_whitespace().map(KeyBinding::parse).collect()
+}
+
+// Keys of the events table. Rebinding a command drops its default keys, and
+// a key bound in config.ini no longer runs what it did by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyBinding, Command>,
+    overrides: Vec<(Command, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl Keymap {
+    pub fn new(overrides: &[(Command, Vec<KeyBinding>)]) -> Self {
+        let mut bindings = HashMap::new();
+        for (command, _, keys, _) in COMMANDS {
+            if overrides.iter().any(|(rebound, _)| *rebound == command) {
+                continue;
+            }
+            for key in parse_keys(keys).unwrap_or_default() {
+                bindings.insert(key, command);
+            }
+        }
+        for (command, keys) in overrides {
+            for key in keys {
+                bindings.insert(*key, *command);
+            }
+        }
+        Self {
+            bindings,
+            overrides: overrides.to_vec(),
+        }
+    }
+
+    // Every command with the keys that still run it, in COMMANDS order;
+    // commands left without a key are skipped.
+    pub fn help_rows(&self) -> Vec<(String, &'static str)> {
+        let mut rows = Vec::new();
+        for (command, _, keys, description) in COMMANDS {
+            let keys = match self
+                .overrides
+                .iter()
+                .find(|(rebound, _)| *rebound == command)
+            {
+                Some((_, keys)) => keys.clone(),
+                None => parse_keys(keys).unwrap_or_default(),
+            };
+            let labels: Vec<String> = keys
+                .iter()
+                .filter(|key| self.bindings.get(key) == Some(&command))
+                .map(KeyBinding::label)
+                .collect();
+            if !labels.is_empty() {
+                rows.push((labels.join(" "), description));
+            }
+        }
+        rows
+    }
+
+    // Ctrl+key falls back to the plain key, as the table always allowed.
+    pub fn command(&self, key: &KeyEvent) -> Option<Command> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let code = match key.code {
+            KeyCode::Char(c) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        ctrl.then(|| self.bindings.get(&KeyBinding { code, ctrl: true }))
+            .flatten()
+            .or_else(|| {
+                self.bindings.get(&KeyBinding {
+                    code: key.code,
+                    ctrl: false,
+                })
+            })
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_replaces_defaults_and_takes_over_keys() {
+        let press = |code: KeyCode, modifiers: KeyModifiers| KeyEvent::new(code, modifiers);
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.command(&press(KeyCode::Char('k'), KeyModifiers::NONE)),
+            Some(Command::Up)
+        );
+        assert_eq!(
+            keymap.command(&press(KeyCode::Char('R'), KeyModifiers::CONTROL)),
+            Some(Command::CopyArpa)
+        );
+        // Unbound Ctrl combinations run the plain key.
+        assert_eq!(
+            keymap.command(&press(KeyCode::Char('x'), KeyModifiers::CONTROL)),
+            Some(Command::Redact)
+        );
+
+        let swapped = [
+            parse_binding("up", "j Up").unwrap(),
+            parse_binding("down", "k Down").unwrap(),
+            parse_binding("copy_source_ip", "ctrl+y").unwrap(),
+        ];
+        let keymap = Keymap::new(&swapped);
+        let plain = |c: char| keymap.command(&press(KeyCode::Char(c), KeyModifiers::NONE));
+        assert_eq!(plain('j'), Some(Command::Up));
+        assert_eq!(plain('k'), Some(Command::Down));
+        assert_eq!(
+            keymap.command(&press(KeyCode::Char('y'), KeyModifiers::CONTROL)),
+            Some(Command::CopySourceIp)
+        );
+        assert_eq!(
+            keymap.command(&press(KeyCode::Tab, KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(plain('y'), Some(Command::CopyRowCaptureFilter));
+
+        let help = keymap.help_rows();
+        assert!(help.contains(&("j Up".to_string(), "Move up")));
+        assert!(help.contains(&("Ctrl+Y".to_string(), "Copy the source IP")));
+        // Tab lost copy_source_ip to ctrl+y and runs nothing else.
+        assert!(!help.iter().any(|(keys, _)| keys.contains("Tab")));
+
+        assert!(parse_binding("jump", "j").is_err());
+        assert!(parse_binding("up", "Hyper").is_err());
+        assert!(parse_binding("up", " ").is_err());
+        assert_eq!(
+            KeyBinding::parse("Space"),
+            Ok(KeyBinding {
+                code: KeyCode::Char(' '),
+                ctrl: false
+            })
+        );
+    }
+
+    #[test]
+    fn every_default_key_is_valid_and_unique() {
+        let mut seen = HashMap::new();
+        for (command, name, keys, _) in COMMANDS {
+            assert_eq!(Command::from_name(name), Some(command));
+            for key in parse_keys(keys).unwrap() {
+                assert_eq!(seen.insert(key, name), None, "{} reuses {:?}", name, key);
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::LogEntry;
+use crate::parser::timestamp_sort_key;
+
+// All `[UFW LIMIT BLOCK]` hits from one source, collapsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitSummary {
+    pub source: String,
+    pub hits: usize,
+    pub first: String,
+    pub last: String,
+    pub window_secs: Option<i64>,
+    pub ports: BTreeSet<u16>,
+}
+
+// Groups rate-limited entries by source IP, busiest first. Entries are
+// expected newest first, as the app stores them.
+pub fn limit_summaries<'a>(entries: impl IntoIterator<Item = &'a LogEntry>) -> Vec<LimitSummary> {
+    let mut by_source: HashMap<&str, LimitSummary> = HashMap::new();
+    for entry in entries
+        .into_iter()
+        .filter(|entry| entry.action.starts_with("LIMIT"))
+    {
+        let source = entry.src_ip.as_deref().unwrap_or("-");
+        let summary = by_source.entry(source).or_insert_with(|| LimitSummary {
+            source: source.to_string(),
+            hits: 0,
+            first: entry.timestamp.clone(),
+            last: entry.timestamp.clone(),
+            window_secs: None,
+            ports: BTreeSet::new(),
+        });
+        summary.hits += 1;
+        summary.first = entry.timestamp.clone();
+        summary.ports.extend(entry.dst_port);
+    }
+
+    let mut summaries: Vec<LimitSummary> = by_source
+        .into_values()
+        .map(|mut summary| {
+            summary.window_secs = timestamp_sort_key(&summary.last)
+                .zip(timestamp_sort_key(&summary.first))
+                .map(|(last, first)| (last - first).max(0));
+            summary
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.hits.cmp(&a.hits).then(a.source.cmp(&b.source)));
+    summaries
+}
+
+// Compact duration: 45s, 5m12s, 2h03m, 3d04h.
+pub fn format_window(secs: i64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3_600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else if secs < 86_400 {
+        format!("{}h{:02}m", secs / 3_600, secs % 3_600 / 60)
+    } else {
+        format!("{}d{:02}h", secs / 86_400, secs % 86_400 / 3_600)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn collapses_limit_hits_per_source() {
+        let line = |time: &str, action: &str, src: &str, dpt: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 {time} h kernel: [UFW {action}] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        let entries = vec![
+            line("20:05:12", "LIMIT BLOCK", "1.2.3.4", 22),
+            line("20:04:00", "BLOCK", "1.2.3.4", 22),
+            line("20:03:00", "LIMIT BLOCK", "5.6.7.8", 22),
+            line("20:01:00", "LIMIT BLOCK", "1.2.3.4", 2222),
+            line("20:00:00", "LIMIT BLOCK", "1.2.3.4", 22),
+        ];
+        let summaries = limit_summaries(&entries);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].source, "1.2.3.4");
+        assert_eq!(summaries[0].hits, 3);
+        assert_eq!(summaries[0].first, "Feb 11 20:00:00 h");
+        assert_eq!(summaries[0].window_secs, Some(312));
+        assert_eq!(summaries[0].ports, BTreeSet::from([22, 2222]));
+        assert_eq!(summaries[1].hits, 1);
+        assert_eq!(summaries[1].window_secs, Some(0));
+
+        assert_eq!(format_window(312), "5m12s");
+        assert_eq!(format_window(7_380), "2h03m");
+    }
+}
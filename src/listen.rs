@@ -0,0 +1,221 @@
+use std::io::{self, Read};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::offline;
+use crate::stream::StreamLog;
+
+// Longest TCP frame kept while waiting for its end; a peer sending more
+// without a newline is cut off.
+const MAX_FRAME: usize = 64 * 1024;
+
+// `--listen`: a syslog collector on one address, UDP and TCP alike. Reader
+// threads hand UFW lines over a channel; each poll appends them to
+// `contents`, which stands in for the main log the way the journal does.
+pub struct Listener {
+    pub addr: String,
+    pub contents: StreamLog,
+    lines: Receiver<String>,
+}
+
+impl Listener {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        if !offline::network_allowed() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "the syslog listener is disabled by --offline",
+            ));
+        }
+        let udp = UdpSocket::bind(addr)?;
+        let tcp = TcpListener::bind(addr)?;
+        let (sender, lines) = mpsc::channel();
+        let udp_sender = sender.clone();
+        thread::spawn(move || receive_datagrams(udp, udp_sender));
+        thread::spawn(move || accept_streams(tcp, sender));
+        Ok(Self {
+            addr: addr.to_string(),
+            contents: StreamLog::default(),
+            lines,
+        })
+    }
+
+    // Appends lines received since the last poll; returns true when any did.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(line) = self.lines.try_recv() {
+            self.contents.push_line(&line);
+            changed = true;
+        }
+        changed
+    }
+}
+
+fn receive_datagrams(socket: UdpSocket, sender: Sender<String>) {
+    let mut buf = vec![0; MAX_FRAME];
+    while let Ok(len) = socket.recv(&mut buf) {
+        let text = String::from_utf8_lossy(&buf[..len]);
+        for line in text.lines().filter_map(to_log_line) {
+            if sender.send(line).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn accept_streams(listener: TcpListener, sender: Sender<String>) {
+    for stream in listener.incoming().flatten() {
+        let sender = sender.clone();
+        thread::spawn(move || read_stream(stream, sender));
+    }
+}
+
+fn read_stream(mut stream: TcpStream, sender: Sender<String>) {
+    let mut pending = Vec::new();
+    let mut buf = [0; 8192];
+    while let Ok(len) = stream.read(&mut buf) {
+        if len == 0 {
+            break;
+        }
+        pending.extend_from_slice(&buf[..len]);
+        for frame in take_frames(&mut pending) {
+            if let Some(line) = to_log_line(&frame)
+                && sender.send(line).is_err()
+            {
+                return;
+            }
+        }
+        if pending.len() > MAX_FRAME {
+            return;
+        }
+    }
+}
+
+// Complete frames at the start of `pending`, removed from it. TCP syslog
+// uses either newline framing or RFC 6587 octet counting ("LEN <PRI>...").
+fn take_frames(pending: &mut Vec<u8>) -> Vec<String> {
+    let mut frames = Vec::new();
+    let mut start = 0;
+    loop {
+        let rest = &pending[start..];
+        let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+        let counted = digits > 0 && rest.get(digits) == Some(&b' ');
+        let (frame, used) = if counted {
+            let Some(len) = std::str::from_utf8(&rest[..digits])
+                .ok()
+                .and_then(|len| len.parse::<usize>().ok())
+            else {
+                break;
+            };
+            let end = digits + 1 + len;
+            if rest.len() < end {
+                break;
+            }
+            (&rest[digits + 1..end], end)
+        } else {
+            let Some(newline) = rest.iter().position(|&b| b == b'\n') else {
+                break;
+            };
+            (&rest[..newline], newline + 1)
+        };
+        let text = String::from_utf8_lossy(frame);
+        let text = text.trim_end_matches(['\r', '\n']);
+        if !text.is_empty() {
+            frames.push(text.to_string());
+        }
+        start += used;
+    }
+    pending.drain(..start);
+    frames
+}
+
+// A syslog message as a log line the parser reads, or None when it is not
+// from UFW. RFC 3164 messages only lose their `<PRI>`; RFC 5424 ones are
+// rewritten as "TIMESTAMP HOST APP: MSG".
+pub fn to_log_line(message: &str) -> Option<String> {
+    if !message.contains("[UFW ") {
+        return None;
+    }
+    let message = message.trim_start_matches('\u{feff}');
+    let body = match message.strip_prefix('<') {
+        Some(rest) => &rest[rest.find('>')? + 1..],
+        None => message,
+    };
+    let Some(rest) = body.strip_prefix("1 ") else {
+        return Some(body.to_string());
+    };
+    let mut fields = rest.splitn(5, ' ');
+    let (timestamp, host, app, _procid, rest) = (
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+    );
+    let (_msgid, rest) = rest.split_once(' ')?;
+    let msg = skip_structured_data(rest)?.trim_start_matches('\u{feff}');
+    Some(format!("{} {} {}: {}", timestamp, host, app, msg))
+}
+
+// The MSG after RFC 5424 structured data: "-" or one or more "[...]"
+// elements, whose values may contain escaped `]`.
+fn skip_structured_data(text: &str) -> Option<&str> {
+    if let Some(rest) = text.strip_prefix('-') {
+        return Some(rest.strip_prefix(' ').unwrap_or(rest));
+    }
+    let mut escaped = false;
+    let mut depth = 0;
+    for (pos, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' if depth == 0 => depth = 1,
+            ']' if depth == 1 => depth = 0,
+            ' ' if depth == 0 => return Some(&text[pos + 1..]),
+            _ => {}
+        }
+    }
+    Some("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rfc3164_and_rfc5424_messages() {
+        let ufw = "[UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1";
+        assert_eq!(
+            to_log_line(&format!("<4>Feb 11 20:00:00 web01 kernel: {}", ufw)),
+            Some(format!("Feb 11 20:00:00 web01 kernel: {}", ufw))
+        );
+        assert_eq!(
+            to_log_line(&format!(
+                "<4>1 2026-02-11T20:00:00.123Z web01 kernel - - [meta x=\"a\\]b\"] {}",
+                ufw
+            )),
+            Some(format!("2026-02-11T20:00:00.123Z web01 kernel: {}", ufw))
+        );
+        assert_eq!(
+            to_log_line(&format!(
+                "<4>1 2026-02-11T20:00:00Z web01 kernel - - - {}",
+                ufw
+            )),
+            Some(format!("2026-02-11T20:00:00Z web01 kernel: {}", ufw))
+        );
+        assert_eq!(
+            to_log_line("<30>Feb 11 20:00:00 web01 sshd[1]: hello"),
+            None
+        );
+    }
+
+    #[test]
+    fn splits_newline_and_octet_counted_frames() {
+        let mut pending = b"11 <4>counted!first\r\nsecond\npart".to_vec();
+        assert_eq!(
+            take_frames(&mut pending),
+            ["<4>counted!", "first", "second"]
+        );
+        assert_eq!(pending, b"part");
+    }
+}
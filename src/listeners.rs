@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+
+// TCP sockets in LISTEN and UDP sockets in the unconnected (bound) state.
+const TCP_LISTEN: &str = "0A";
+const UDP_BOUND: &str = "07";
+
+// Local listening (protocol, port) pairs from /proc/net, e.g. ("TCP", 22).
+pub fn listening_ports() -> io::Result<HashSet<(&'static str, u16)>> {
+    let mut ports = HashSet::new();
+    let mut read_any = false;
+    for (file, proto, state) in [
+        ("/proc/net/tcp", "TCP", TCP_LISTEN),
+        ("/proc/net/tcp6", "TCP", TCP_LISTEN),
+        ("/proc/net/udp", "UDP", UDP_BOUND),
+        ("/proc/net/udp6", "UDP", UDP_BOUND),
+    ] {
+        let Ok(contents) = fs::read_to_string(file) else {
+            continue;
+        };
+        read_any = true;
+        ports.extend(parse_proc_net(&contents, state).map(|port| (proto, port)));
+    }
+    if !read_any {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "/proc/net is not available",
+        ));
+    }
+    Ok(ports)
+}
+
+// Local ports of sockets in `state`; columns are "sl local_address
+// rem_address st ...", with addresses as HEXIP:HEXPORT.
+fn parse_proc_net<'a>(contents: &'a str, state: &'a str) -> impl Iterator<Item = u16> + 'a {
+    contents.lines().skip(1).filter_map(move |line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(3) != Some(&state) {
+            return None;
+        }
+        let (_, port) = fields.get(1)?.rsplit_once(':')?;
+        u16::from_str_radix(port, 16).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_listening_ports_from_proc_net() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1 1 0 100 0 0 10 0
+   1: 0100007F:0CEA 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000     0        0 2 1 0 20 4 30 10 -1";
+        assert_eq!(
+            parse_proc_net(tcp, TCP_LISTEN).collect::<Vec<_>>(),
+            vec![22]
+        );
+    }
+}
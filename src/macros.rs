@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+// What the next key press names a register for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroPrefix {
+    Record,
+    Play,
+}
+
+// Recorded key sequences by register, vim style: `Q<reg>` records until the
+// next `Q`, `@<reg>` replays.
+#[derive(Default)]
+pub struct Macros {
+    registers: HashMap<char, Vec<KeyEvent>>,
+    recording: Option<(char, Vec<KeyEvent>)>,
+    pub pending: Option<MacroPrefix>,
+    pub replaying: bool,
+}
+
+impl Macros {
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    pub fn start(&mut self, register: char) {
+        self.recording = Some((register, Vec::new()));
+    }
+
+    // Stores the sequence being recorded; returns its register and length.
+    pub fn stop(&mut self) -> Option<(char, usize)> {
+        let (register, keys) = self.recording.take()?;
+        let len = keys.len();
+        self.registers.insert(register, keys);
+        Some((register, len))
+    }
+
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some((_, keys)) = self.recording.as_mut() {
+            keys.push(key);
+        }
+    }
+
+    pub fn keys(&self, register: char) -> Option<Vec<KeyEvent>> {
+        self.registers.get(&register).cloned()
+    }
+}
+
+// Registers are a-z and 0-9.
+pub fn register_name(key: &KeyEvent) -> Option<char> {
+    match key.code {
+        KeyCode::Char(c) if c.is_ascii_lowercase() || c.is_ascii_digit() => Some(c),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn recording_stores_keys_under_register() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut macros = Macros::default();
+        macros.record(key('l'));
+        assert_eq!(macros.stop(), None);
+
+        macros.start('a');
+        assert_eq!(macros.recording(), Some('a'));
+        macros.record(key('l'));
+        macros.record(key('j'));
+        assert_eq!(macros.stop(), Some(('a', 2)));
+        assert_eq!(macros.recording(), None);
+        assert_eq!(macros.keys('a'), Some(vec![key('l'), key('j')]));
+        assert_eq!(register_name(&key('Q')), None);
+        assert_eq!(register_name(&key('3')), Some('3'));
+    }
+}
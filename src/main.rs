@@ -1,41 +1,193 @@
-use std::cmp::min;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::cmp::{Ordering, min};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant, SystemTime};
 
+mod abuse;
+mod actions;
+mod aggregate;
+mod authlog;
+mod blocklist;
+mod burst;
+mod cache;
+mod capture;
+mod cli;
 mod clipboard;
+mod columns;
+mod complete;
+mod config;
+mod context;
+mod counters;
+mod countries;
+mod dashboard;
+mod dns_watch;
+mod doctor;
+mod egress;
+mod export;
+mod find;
+mod fingerprint;
+mod fixed_port;
+mod geoip;
+mod help;
+mod highlight;
+mod ingest;
+mod ini;
+mod interfaces;
+mod journal;
+mod json;
+mod keymap;
+mod limits;
+mod listen;
+mod listeners;
+mod macros;
+mod mode;
+mod mqtt;
 mod net;
+mod offline;
+mod open_source;
+mod pager;
 mod parser;
+mod ports;
+mod portscan;
+mod presets;
+mod pretty;
+mod redact;
+mod render_cache;
+mod report;
+mod rotation;
 mod services;
+mod siem;
+mod sink;
+mod snapshot;
+mod stats;
+mod status;
+mod stream;
+mod syslog;
+mod tags;
+mod talkers;
+mod tcpdump;
+mod theme;
+mod timefmt;
+mod timeline;
+mod timerange;
+mod trend;
+#[cfg(test)]
+mod ui_tests;
 
+use actions::{BlockAction, RuleKind};
+use authlog::AuthLog;
+use blocklist::Blocklists;
+use burst::Burst;
 use clipboard::copy_text_via_osc52;
+use columns::{Column, ColumnPicker};
+use complete::Completion;
+use config::Config;
+use context::{TIME_LOCK_RADIUS_SECS, TimeWindow, ViewContext};
+use countries::CountrySummary;
 use crossterm::cursor::Show;
 use crossterm::event::{
-    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
-    MouseButton, MouseEvent, MouseEventKind,
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use crossterm::{execute, terminal};
+use dashboard::Dashboard;
+use egress::EgressReview;
+use export::ExportPrompt;
+use find::FindPrompt;
+use geoip::GeoIp;
+use ingest::{Ingest, Poll, Source};
+use journal::Journal;
+use keymap::{Command, KeyBinding, Keymap};
+use listen::Listener;
+use macros::{MacroPrefix, Macros, register_name};
+use mode::Mode;
 use net::{
-    default_wan_interface, is_local_src_ip, is_wan_candidate_interface, is_wan_src_ip,
-    matches_direction_filter, matches_flow_filter,
+    bogon_range, default_wan_interface, glob_match, interface_allowed,
+    is_broadcast_or_multicast_ip, is_local_src_ip, is_return_traffic, is_wan_candidate_interface,
+    is_wan_src_ip, matches_direction_filter, matches_flow_filter, reverse_dns_name,
+};
+use open_source::{SourcePrompt, SourceTarget};
+use parser::{
+    LogFormat, TcpFlags, civil_from_days, entry_epoch_seconds, parse_line, timestamp_sort_key,
 };
-use parser::parse_ufw_line;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ports::PortSet;
+use portscan::ScanDetector;
+use presets::{Preset, PresetPicker};
+use pretty::{field_columns, packet_summary, present_fields};
+use ratatui::layout::{Alignment, Constraint, Direction, Flex, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
 use ratatui::{Frame, Terminal};
-use services::service_description_from_port;
+use redact::{REDACTED_MAC, redact_ip, redact_raw_line};
+use render_cache::{Breakpoints, RenderCache};
+use report::{Report, heading, render_report};
+use rotation::{modified_seconds, open_log, read_appended, read_line_at, rotated_files};
+use services::{port_from_service, service_description_from_port};
+use siem::SiemFormat;
+use sink::{LineFormat, LineSink, LiveSink, OutputSink, SinkSpec, SyslogSink};
+use snapshot::{Snapshot, signed};
+use status::StatusLine;
+use stream::StreamLog;
+use tags::{TagRule, Tagger};
+use talkers::{TalkerSidebar, Talkers};
+use tcpdump::CaptureSession;
+use theme::Theme;
+use timerange::{TimeRange, local_offset, now_seconds};
+use trend::TrendFormat;
 
 const APP_TITLE: &str = "UFW Log Viewer";
 const MIN_UI_WIDTH: u16 = 90;
 const MIN_UI_HEIGHT: u16 = 15;
+const SIDEBAR_WIDTH: u16 = 30;
+// Width of the `--geoip` country column ("CC").
+const COUNTRY_COLUMN: u16 = 2;
+// Widths of the In/Out interface and TCP Flags columns.
+const IFACE_COLUMN: u16 = 8;
+const FLAGS_COLUMN: u16 = 11;
+// Width of the File column shown when several logs are merged.
+const FILE_COLUMN: u16 = 8;
+const DEFAULT_ABUSE_DIR: &str = "abuse-reports";
+const DEFAULT_ABUSE_TOP: usize = 10;
+// IPv6 sources are counted per /64 by default (`--ipv6-prefix`).
+const DEFAULT_IPV6_PREFIX: u8 = 64;
+// Rows per section in the `N` snapshot comparison.
+const SNAPSHOT_LISTED: usize = 20;
+// Sources and ports listed on the `--http` page.
+const DASHBOARD_TOP: usize = 10;
+// `--low-memory` limits: newest entries kept, raw bytes kept per line and
+// rows held in the render cache.
+const LOW_MEMORY_MAX_ENTRIES: usize = 20_000;
+const LOW_MEMORY_RAW_BYTES: usize = 256;
+const LOW_MEMORY_CACHED_ROWS: usize = 256;
+// Addresses whose GeoIP country is remembered.
+const LOW_MEMORY_GEOIP_ADDRESSES: usize = 1_024;
+// Filter states kept for `"1`..`"5`.
+const RECENT_FILTERS: usize = 5;
+// How many more older entries each `L` loads past the entry cap.
+const LOAD_MORE_ENTRIES: usize = 20_000;
+// Lines of a journal or listener stream kept for full reloads when there is
+// no entry cap.
+const STREAM_MAX_LINES: usize = 100_000;
+// `--interface all`: start with every interface in view.
+const ALL_INTERFACES: &str = "all";
 
 #[derive(Debug, Clone, Default)]
 struct LogEntry {
+    // Stable identity of the physical log line, assigned at ingest.
+    id: u64,
+    // Index of the source the line was read from (0 is the main log).
+    source: usize,
+    // Byte offset of the line in its source when `--low-memory` cut `raw`
+    // short; the full line is re-read from there when it is shown.
+    raw_offset: Option<usize>,
     timestamp: String,
     action: String,
     in_iface: Option<String>,
@@ -46,6 +198,18 @@ struct LogEntry {
     dst_port: Option<u16>,
     proto: Option<String>,
     service: Option<String>,
+    // Source country code from `--geoip`, filled in when entries are applied.
+    country: Option<String>,
+    // IP packet length, TTL (hop limit on IPv6) and type-of-service byte.
+    length: Option<u16>,
+    ttl: Option<u8>,
+    tos: Option<u8>,
+    mac: Option<String>,
+    // TCP receive window and flags.
+    window: Option<u16>,
+    tcp_flags: TcpFlags,
+    icmp_type: Option<u8>,
+    icmp_code: Option<u8>,
     raw: String,
 }
 
@@ -62,14 +226,21 @@ impl LogEntry {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct Filters {
     service: String,
     port: String,
+    // Parsed from `port` by `set`; None when empty or invalid.
+    port_set: Option<PortSet>,
     ip: String,
     action: String,
     proto: String,
     text: String,
+    time: String,
+    // Parsed from `time` by `set`; None when empty or invalid.
+    time_range: Option<TimeRange>,
+    country: String,
+    flags: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -80,9 +251,39 @@ enum FilterField {
     Action,
     Proto,
     Text,
+    Time,
+    Country,
+    Flags,
 }
 
 impl FilterField {
+    const ALL: [FilterField; 9] = [
+        Self::Service,
+        Self::Port,
+        Self::Ip,
+        Self::Action,
+        Self::Proto,
+        Self::Text,
+        Self::Time,
+        Self::Country,
+        Self::Flags,
+    ];
+
+    // Summary-row label, prefixed with the F-key that edits it.
+    fn summary_label(self) -> &'static str {
+        match self {
+            Self::Service => "F1 svc",
+            Self::Port => "F2 port",
+            Self::Ip => "F3 ip",
+            Self::Action => "F4 action",
+            Self::Proto => "F5 proto",
+            Self::Text => "F6 text",
+            Self::Time => "F7 time",
+            Self::Country => "F8 cc",
+            Self::Flags => "F9 flags",
+        }
+    }
+
     fn label(self) -> &'static str {
         match self {
             Self::Service => "service",
@@ -91,6 +292,17 @@ impl FilterField {
             Self::Action => "action",
             Self::Proto => "protocol",
             Self::Text => "text",
+            Self::Time => "time",
+            Self::Country => "country",
+            Self::Flags => "flags",
+        }
+    }
+
+    // `--filter FIELD=VALUE` names; `proto` is accepted for protocol.
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "proto" => Some(Self::Proto),
+            label => Self::ALL.into_iter().find(|field| field.label() == label),
         }
     }
 }
@@ -127,6 +339,12 @@ impl FlowFilter {
             Self::LocalToExternal => "local->external",
         }
     }
+
+    fn from_label(label: &str) -> Option<Self> {
+        [Self::All, Self::LocalToLocal, Self::LocalToExternal]
+            .into_iter()
+            .find(|flow| flow.label() == label)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -165,50 +383,284 @@ impl DirectionFilter {
             Self::Forwarded => "forwarded",
         }
     }
+
+    fn from_label(label: &str) -> Option<Self> {
+        [Self::Both, Self::In, Self::Out, Self::Forwarded]
+            .into_iter()
+            .find(|dir| dir.label() == label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum SortColumn {
+    #[default]
+    Time,
+    Action,
+    Direction,
+    Source,
+    Dest,
+    Proto,
+    SrcPort,
+    DstPort,
+    Service,
+}
+
+impl SortColumn {
+    // The sort key behind a table column; the extra columns do not sort.
+    fn for_column(column: Column) -> Option<Self> {
+        match column {
+            Column::Time => Some(Self::Time),
+            Column::Action => Some(Self::Action),
+            Column::Direction => Some(Self::Direction),
+            Column::Source => Some(Self::Source),
+            Column::Dest => Some(Self::Dest),
+            Column::Proto => Some(Self::Proto),
+            Column::SrcPort => Some(Self::SrcPort),
+            Column::DstPort => Some(Self::DstPort),
+            Column::Service => Some(Self::Service),
+            Column::Country
+            | Column::InIface
+            | Column::OutIface
+            | Column::Ttl
+            | Column::Length
+            | Column::Flags => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Time => "time",
+            Self::Action => "action",
+            Self::Direction => "direction",
+            Self::Source => "source",
+            Self::Dest => "destination",
+            Self::Proto => "protocol",
+            Self::SrcPort => "source port",
+            Self::DstPort => "destination port",
+            Self::Service => "service",
+        }
+    }
+
+    // Time reads naturally newest-first; everything else A→Z / low→high.
+    fn default_descending(self) -> bool {
+        self == Self::Time
+    }
 }
 
 fn display_filter_value(value: &str) -> &str {
     if value.is_empty() { "*" } else { value }
 }
 
+// Splits a leading `!` off a filter value: `!443` excludes port 443.
+fn negated_filter(value: &str) -> (bool, &str) {
+    match value.strip_prefix('!') {
+        Some(term) => (true, term),
+        None => (false, value),
+    }
+}
+
+// "F3 ip=10.0.0.5", or "F3 ip≠10.0.0.5" for a negated value.
+fn filter_assignment(label: &str, value: &str) -> String {
+    match negated_filter(value) {
+        (true, term) => format!("{}≠{}", label, term),
+        (false, value) => format!("{}={}", label, display_filter_value(value)),
+    }
+}
+
 #[derive(Debug, Clone)]
 enum ToggleTarget {
     Local,
     WanSrc,
+    Broadcast,
+    Replies,
     Flow,
     Direction,
     PauseUpdates,
     AllIfaces,
     Interface(String),
+    SortBy(SortColumn),
+}
+
+// What the event loop should do after an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Continue,
+    Quit,
 }
 
 struct App {
     log_path: PathBuf,
     entries: Vec<LogEntry>,
     interface_options: Vec<String>,
+    // Interfaces pinned with `*` lead `interface_options`; kept in
+    // `interfaces_path`.
+    pinned_interfaces: Vec<String>,
+    interfaces_path: Option<PathBuf>,
+    // `--iface`: only interfaces matching one of these globs become chips.
+    interface_globs: Vec<String>,
     selected_interface: Option<String>,
+    // `--interface`: the chip to start on, `Some(None)` for all interfaces;
+    // kept until an ingest finds interfaces to pick from.
+    start_interface: Option<Option<String>>,
+    // Set with `t`: only entries near the one it was set on are in scope.
+    time_window: Option<TimeWindow>,
     filters: Filters,
     show_local_src: bool,
     show_wan_src: bool,
+    show_broadcast: bool,
+    show_replies: bool,
+    bogon_only: bool,
+    // `--blocklist` networks; sources in them are marked `#` in the table.
+    blocklists: Option<Blocklists>,
+    // `#`: only rows whose source is on a blocklist.
+    listed_only: bool,
+    cgnat_local: bool,
+    // `--dns-watch`: the Enter drill-down checks outbound UDP for DNS tunnels.
+    dns_watch: bool,
+    // The statistics panel replaces the table while set.
+    show_stats: bool,
+    // `=`: one row per (src, dst, dpt, proto, action) with a count.
+    aggregate: bool,
+    // Flood runs `+` unfolded, by `burst::run_key`.
+    expanded_bursts: HashSet<u64>,
+    // `--geoip`: adds the country column and makes F8 useful.
+    geoip: Option<GeoIp>,
+    breakpoints: Breakpoints,
+    // Taken with `n`; `N` compares the current view against it.
+    snapshot: Option<Snapshot>,
+    snapshots_taken: usize,
     flow_filter: FlowFilter,
     direction_filter: DirectionFilter,
     updates_paused: bool,
+    show_minute_gutter: bool,
+    redact: bool,
+    sort_column: SortColumn,
+    sort_descending: bool,
+    pinned_raw: Option<String>,
+    pin_candidate: Option<String>,
     selected: usize,
     log_entry_scroll: u16,
+    // A mouse drag over the detail line as (anchor, cursor) character
+    // columns; releasing copies the span between them.
+    detail_drag: Option<(usize, usize)>,
+    // `J`: the log-entry pane shows the raw line instead of parsed fields.
+    detail_raw: bool,
     table_state: TableState,
+    render_cache: RenderCache,
+    low_memory: bool,
+    // Newest entries kept per ingest (`--low-memory`); `L` raises it.
+    entry_cap: Option<usize>,
+    // `--since`: entries older than this (epoch seconds) are not loaded; `L`
+    // moves it back by `since_step`.
+    load_since: Option<i64>,
+    since_step: i64,
+    // Entries the cap or `--since` dropped from the last ingest, shown below
+    // the table.
+    unloaded_older: usize,
+    // The last raw line re-read for a truncated entry, keyed by entry ID.
+    raw_reread: Option<(u64, String)>,
     last_watch_check: Instant,
-    last_fingerprint: Option<FileFingerprint>,
+    last_fingerprints: Vec<Option<FileFingerprint>>,
+    // Further logs given on the command line, e.g. kern.log next to ufw.log.
+    extra_sources: Vec<PathBuf>,
+    watch_dirs: Vec<PathBuf>,
+    watched_sources: Vec<PathBuf>,
+    include_rotated: bool,
+    // With `--journal`, the main source is read from journald, not log_path.
+    journal: Option<Journal>,
+    // With `--listen`, the main source is what the syslog listener received.
+    listener: Option<Listener>,
+    // Rotations of the main log, loaded after the watched sources, and when
+    // each was rotated out.
+    rotated_sources: Vec<PathBuf>,
+    rotated_times: Vec<Option<i64>>,
+    // Table lines below the header taken by rotation separators last frame.
+    separator_lines: Vec<usize>,
+    log_format: LogFormat,
+    sinks: Vec<LiveSink>,
+    capture: Option<CaptureSession>,
+    capture_dir: Option<PathBuf>,
+    // Port scans in the loaded entries, fed as they arrive; `^` shows them
+    // in the Alerts panel.
+    scans: ScanDetector,
+    show_alerts: bool,
+    // `--http`: the page is rebuilt from the view every few seconds.
+    dashboard: Option<Dashboard>,
+    // `|`: top sources and ports of the view beside the table.
+    talkers: Option<TalkerSidebar>,
+    // Keys of the events table, from the defaults and config.ini's `[keys]`.
+    keymap: Keymap,
+    // Events table columns in order, from config.ini's `[columns]`; the `;`
+    // picker edits them and saves back to `config_path`.
+    columns: Vec<Column>,
+    column_picker: Option<ColumnPicker>,
+    config_path: Option<PathBuf>,
+    // Where `E` writes abuse reports, and for how many top offenders.
+    abuse_dir: PathBuf,
+    abuse_top: usize,
+    // Prefix length IPv6 offenders are grouped by in `s` and `E`.
+    ipv6_prefix: u8,
+    expected_ports: Option<HashSet<u16>>,
+    tagger: Tagger,
+    report: Option<Report>,
+    help: Option<Report>,
+    // Open layers above the table, oldest first; see `mode`.
+    mode_stack: Vec<Mode>,
+    auth_log: Option<AuthLog>,
+    // ID of the entry the view is locked on; it stays selected and centered
+    // however many rows arrive.
+    selection_lock: Option<u64>,
+    macros: Macros,
+    filter_exclusions: Option<FilterExclusions>,
+    interface_counts: HashMap<String, usize>,
+    // Area of the last drawn frame, for mouse hit-testing.
+    frame_area: Rect,
+    // IDs from the last completed ingest, whether shown or held.
+    ingested_ids: HashSet<u64>,
+    // Per (source, line hash) counts behind the entry IDs, kept so appended
+    // lines get the IDs a full reload would give them.
+    line_occurrences: HashMap<(usize, u64), u64>,
+    // Parsed entries waiting for the user to scroll back to the top, with the
+    // number of rows they add.
+    held_ingest: Option<(Vec<LogEntry>, usize)>,
+    pending_ingest: Option<Ingest>,
     input_mode: Option<FilterField>,
+    export_prompt: Option<ExportPrompt>,
+    // The `O` prompt switching to another log, directory or the journal.
+    source_prompt: Option<SourcePrompt>,
+    // Set by `b` until confirmed or cancelled.
+    block_prompt: Option<BlockAction>,
+    // The `'` popup; presets are re-read from `presets_path` each time it opens.
+    preset_picker: Option<PresetPicker>,
+    presets_path: Option<PathBuf>,
+    // The `>` review of blocked outbound traffic; groups marked expected are
+    // kept in `egress_path`.
+    egress_review: Option<EgressReview>,
+    egress_path: Option<PathBuf>,
+    // The `C` popup of blocked entries per source country.
+    country_summary: Option<CountrySummary>,
+    // Views left by recent filter changes, newest first, recalled with
+    // `"1`..`"5`; `recent_leader` is set while waiting for the digit.
+    recent_filters: Vec<Preset>,
+    recent_leader: bool,
+    // `--dry-run`: confirmed actions only copy their command.
+    dry_run: bool,
     input_buffer: String,
-    status: String,
-    status_snapshot: String,
-    status_since: Option<Instant>,
+    // Tab completion in progress in the filter prompt.
+    completion: Option<Completion>,
+    // The `/` prompt while typing, then the kept term `n`/`N` step through
+    // until Esc; unlike F6 it moves the selection instead of hiding rows.
+    find_prompt: Option<FindPrompt>,
+    find_term: Option<String>,
+    status: StatusLine,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct FileFingerprint {
     modified: Option<SystemTime>,
     len: u64,
+    // A rotated-in file is a new inode even when it is already larger.
+    inode: u64,
 }
 
 impl App {
@@ -217,2008 +669,8180 @@ impl App {
             log_path,
             entries: Vec::new(),
             interface_options: Vec::new(),
+            pinned_interfaces: Vec::new(),
+            interfaces_path: interfaces::pinned_path(),
+            interface_globs: Vec::new(),
             selected_interface: None,
+            start_interface: None,
+            time_window: None,
             filters: Filters::default(),
             show_local_src: false,
             show_wan_src: true,
+            show_broadcast: true,
+            show_replies: true,
+            bogon_only: false,
+            blocklists: None,
+            listed_only: false,
+            cgnat_local: false,
+            dns_watch: false,
+            show_stats: false,
+            aggregate: false,
+            expanded_bursts: HashSet::new(),
+            geoip: None,
+            breakpoints: Breakpoints::default(),
+            snapshot: None,
+            snapshots_taken: 0,
             flow_filter: FlowFilter::All,
             direction_filter: DirectionFilter::Both,
             updates_paused: false,
+            show_minute_gutter: false,
+            redact: false,
+            sort_column: SortColumn::Time,
+            sort_descending: true,
+            pinned_raw: None,
+            pin_candidate: None,
             selected: 0,
             log_entry_scroll: 0,
+            detail_drag: None,
+            detail_raw: false,
             table_state: TableState::default().with_selected(Some(0)),
+            render_cache: RenderCache::default(),
+            low_memory: false,
+            entry_cap: None,
+            load_since: None,
+            since_step: 0,
+            unloaded_older: 0,
+            raw_reread: None,
             last_watch_check: Instant::now(),
-            last_fingerprint: None,
+            last_fingerprints: Vec::new(),
+            extra_sources: Vec::new(),
+            watch_dirs: Vec::new(),
+            watched_sources: Vec::new(),
+            include_rotated: false,
+            journal: None,
+            listener: None,
+            rotated_sources: Vec::new(),
+            rotated_times: Vec::new(),
+            separator_lines: Vec::new(),
+            log_format: LogFormat::default(),
+            sinks: Vec::new(),
+            capture: None,
+            scans: ScanDetector::default(),
+            show_alerts: false,
+            dashboard: None,
+            talkers: None,
+            keymap: Keymap::default(),
+            columns: Column::DEFAULT.to_vec(),
+            column_picker: None,
+            config_path: config::config_path(),
+            capture_dir: None,
+            abuse_dir: PathBuf::from(DEFAULT_ABUSE_DIR),
+            abuse_top: DEFAULT_ABUSE_TOP,
+            ipv6_prefix: DEFAULT_IPV6_PREFIX,
+            expected_ports: None,
+            tagger: Tagger::default(),
+            report: None,
+            help: None,
+            mode_stack: Vec::new(),
+            auth_log: None,
+            selection_lock: None,
+            macros: Macros::default(),
+            filter_exclusions: None,
+            interface_counts: HashMap::new(),
+            frame_area: Rect::default(),
+            ingested_ids: HashSet::new(),
+            line_occurrences: HashMap::new(),
+            held_ingest: None,
+            pending_ingest: None,
             input_mode: None,
+            export_prompt: None,
+            source_prompt: None,
+            block_prompt: None,
+            preset_picker: None,
+            presets_path: presets::presets_path(),
+            egress_review: None,
+            egress_path: egress::expected_path(),
+            country_summary: None,
+            recent_filters: Vec::new(),
+            recent_leader: false,
+            dry_run: false,
             input_buffer: String::new(),
-            status: String::new(),
-            status_snapshot: String::new(),
-            status_since: None,
+            completion: None,
+            find_prompt: None,
+            find_term: None,
+            status: StatusLine::default(),
         };
         let _ = app.reload();
         app
     }
 
+    // Hands every source to a background reader; entries are swapped in by
+    // `ingest_tick` once it is done. Only a failure on the main log counts as
+    // a failed reload, so it is checked here before the worker starts.
     fn reload(&mut self) -> bool {
-        let prev_selected = self.selected;
-        let prev_selected_raw = self.current_selected_raw();
-        let prev_iface = self.selected_interface.clone();
-        match load_entries(&self.log_path) {
-            Ok(entries) => {
-                self.entries = entries;
-                self.refresh_interface_options(prev_iface);
-
-                let filtered = self.filtered_indices();
-                self.selected = if let Some(raw) = prev_selected_raw.as_deref() {
-                    selected_position_for_raw(&self.entries, &filtered, raw)
-                        .unwrap_or_else(|| min(prev_selected, filtered.len().saturating_sub(1)))
-                } else {
-                    min(prev_selected, filtered.len().saturating_sub(1))
-                };
-                self.sync_selection_with_len(filtered.len());
-                self.last_fingerprint = file_fingerprint(&self.log_path).ok();
-                self.status.clear();
-                true
-            }
-            Err(err) => {
-                self.entries.clear();
-                self.selected = 0;
-                self.table_state.select(None);
-                self.last_fingerprint = None;
-                self.status = format!("Failed to read {}: {}", self.log_path.display(), err);
-                false
-            }
+        self.rotated_sources = if self.include_rotated {
+            rotated_files(&self.log_path).split_off(1)
+        } else {
+            Vec::new()
+        };
+        self.rotated_times = self
+            .rotated_sources
+            .iter()
+            .map(|path| modified_seconds(path))
+            .collect();
+        if self.main_stream().is_none()
+            && let Err(err) = fs::File::open(&self.log_path)
+        {
+            self.fail_main_log(format!(
+                "Failed to read {}: {}",
+                self.log_path.display(),
+                err
+            ));
+            return false;
         }
+        let fingerprints = self.source_fingerprints();
+        let mut streamed = self.main_stream_mut().map(StreamLog::take_all);
+        let sources = self
+            .source_paths()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, path)| match streamed.take() {
+                Some(contents) if idx == 0 => Source::Loaded(contents),
+                _ => Source::File(path.to_path_buf()),
+            })
+            .collect();
+        self.pending_ingest = Some(Ingest::spawn(
+            sources,
+            Vec::new(),
+            fingerprints,
+            self.log_format,
+            self.low_memory.then_some(LOW_MEMORY_RAW_BYTES),
+            false,
+        ));
+        self.status.clear();
+        true
     }
 
-    fn current_selected_raw(&self) -> Option<String> {
-        let filtered = self.filtered_indices();
-        if filtered.is_empty() {
-            return None;
+    // Contents of a main source that is not a file: journald or the syslog
+    // listener.
+    fn main_stream(&self) -> Option<&StreamLog> {
+        match (&self.journal, &self.listener) {
+            (Some(journal), _) => Some(&journal.contents),
+            (None, Some(listener)) => Some(&listener.contents),
+            (None, None) => None,
         }
-        let selected = min(self.selected, filtered.len() - 1);
-        Some(self.entries[filtered[selected]].raw.clone())
     }
 
-    fn select_visible_row_index(&mut self, row_index: usize) {
-        let filtered_len = self.filtered_indices().len();
-        if filtered_len == 0 {
-            return;
+    fn main_stream_mut(&mut self) -> Option<&mut StreamLog> {
+        match (&mut self.journal, &mut self.listener) {
+            (Some(journal), _) => Some(&mut journal.contents),
+            (None, Some(listener)) => Some(&mut listener.contents),
+            (None, None) => None,
         }
-        let offset = self.table_state.offset();
-        let absolute = min(
-            offset.saturating_add(row_index),
-            filtered_len.saturating_sub(1),
-        );
-        self.selected = absolute;
-        self.sync_selection_with_len(filtered_len);
     }
 
-    fn copy_selected_log_entry(&mut self) {
-        let Some(raw) = self.current_selected_raw() else {
-            return;
-        };
-        match copy_text_via_osc52(&raw) {
-            Ok(()) => {
-                self.status = "Copied selected log entry (OSC52)".to_string();
-            }
-            Err(err) => {
-                self.status = format!("Clipboard copy failed: {}", err);
-            }
-        }
+    fn fail_main_log(&mut self, message: String) {
+        self.entries.clear();
+        self.pending_ingest = None;
+        self.selected = 0;
+        self.table_state.select(None);
+        self.last_fingerprints.clear();
+        self.status.error(message);
     }
 
-    fn copy_selected_src_ip(&mut self) {
-        let filtered = self.filtered_indices();
-        if filtered.is_empty() {
-            return;
-        }
-        let selected = min(self.selected, filtered.len() - 1);
-        let src_ip = self.entries[filtered[selected]]
-            .src_ip
-            .as_deref()
-            .unwrap_or("")
-            .trim()
-            .to_string();
-        if src_ip.is_empty() {
-            self.status = "No source IP on selected row".to_string();
-            return;
+    // Queues only the lines appended since the last ingest when every changed
+    // source is a plain file that grew in place; a journal or listener stream
+    // hands over the lines that arrived since. Returns false when a full
+    // reload is needed: a file shrank or was replaced (truncation, rotation),
+    // or it is compressed.
+    fn tail_sources(&mut self) -> bool {
+        let current = self.source_fingerprints();
+        if current.len() != self.last_fingerprints.len() {
+            return false;
         }
-        match copy_text_via_osc52(&src_ip) {
-            Ok(()) => {
-                self.status = format!("Copied source IP: {}", src_ip);
+        let paths: Vec<PathBuf> = self
+            .source_paths()
+            .into_iter()
+            .map(Path::to_path_buf)
+            .collect();
+        let mut sources = Vec::with_capacity(paths.len());
+        let mut bases = Vec::with_capacity(paths.len());
+        let mut fingerprints = Vec::with_capacity(paths.len());
+        for (idx, path) in paths.iter().enumerate() {
+            if idx == 0
+                && let Some(stream) = self.main_stream_mut()
+            {
+                let (fresh, base) = stream.take_fresh();
+                sources.push(Source::Loaded(fresh));
+                bases.push(base);
+                fingerprints.push(current[idx].clone());
+                continue;
             }
-            Err(err) => {
-                self.status = format!("Source IP copy failed: {}", err);
+            let (old, new) = (&self.last_fingerprints[idx], &current[idx]);
+            if old == new {
+                sources.push(Source::Loaded(String::new()));
+                bases.push(0);
+                fingerprints.push(old.clone());
+                continue;
+            }
+            let (Some(old), Some(new)) = (old, new) else {
+                return false;
+            };
+            let compressed = path.extension().is_some_and(|ext| ext == "gz");
+            if compressed || new.inode != old.inode || new.len <= old.len {
+                return false;
             }
+            let Ok(Some(appended)) = read_appended(path, old.len) else {
+                return false;
+            };
+            // A partial last line is re-read once it is complete.
+            fingerprints.push(Some(FileFingerprint {
+                len: old.len + appended.len() as u64,
+                ..new.clone()
+            }));
+            bases.push(old.len as usize);
+            sources.push(Source::Loaded(appended));
         }
+        self.pending_ingest = Some(Ingest::spawn(
+            sources,
+            bases,
+            fingerprints,
+            self.log_format,
+            self.low_memory.then_some(LOW_MEMORY_RAW_BYTES),
+            true,
+        ));
+        true
     }
 
-    fn clear_filters(&mut self) {
-        self.filters = Filters::default();
-        self.show_local_src = false;
-        self.show_wan_src = true;
-        self.flow_filter = FlowFilter::All;
-        self.direction_filter = DirectionFilter::Both;
-        self.selected_interface = default_wan_interface(&self.interface_options);
-        self.selected = 0;
-        self.log_entry_scroll = 0;
-        self.table_state.select(Some(0));
-        self.status = format!(
-            "Cleared filters (local src hidden, wan src shown, flow all, dir in+out, interface: {})",
-            selected_iface_label(self.selected_interface.as_deref())
-        );
-    }
-
-    fn set_input_mode(&mut self, field: FilterField) {
-        self.input_mode = Some(field);
-        self.input_buffer = self.get_filter_value(field).to_string();
+    // Source 0 is the main log; logs named on the command line follow, then
+    // watched files in discovery order, then rotations of the main log when
+    // rotated history is included.
+    fn source_paths(&self) -> Vec<&Path> {
+        std::iter::once(self.log_path.as_path())
+            .chain(self.extra_sources.iter().map(PathBuf::as_path))
+            .chain(self.watched_sources.iter().map(PathBuf::as_path))
+            .chain(self.rotated_sources.iter().map(PathBuf::as_path))
+            .collect()
     }
 
-    fn get_filter_value(&self, field: FilterField) -> &str {
-        match field {
-            FilterField::Service => &self.filters.service,
-            FilterField::Port => &self.filters.port,
-            FilterField::Ip => &self.filters.ip,
-            FilterField::Action => &self.filters.action,
-            FilterField::Proto => &self.filters.proto,
-            FilterField::Text => &self.filters.text,
+    // Label for an entry's source in the detail pane; none for the main log.
+    fn source_display(&self, source: usize) -> Option<String> {
+        if source == 0 {
+            return None;
+        }
+        let labelled = self.extra_sources.len() + self.watched_sources.len();
+        if source <= labelled {
+            let path = self
+                .extra_sources
+                .iter()
+                .chain(&self.watched_sources)
+                .nth(source - 1)?;
+            return Some(source_label(path));
         }
+        self.rotated_sources
+            .get(source - 1 - labelled)
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
     }
 
-    fn set_filter_value(&mut self, field: FilterField, value: String) {
-        let cleaned = value.trim().to_string();
-        match field {
-            FilterField::Service => self.filters.service = cleaned,
-            FilterField::Port => self.filters.port = cleaned,
-            FilterField::Ip => self.filters.ip = cleaned,
-            FilterField::Action => self.filters.action = cleaned,
-            FilterField::Proto => self.filters.proto = cleaned,
-            FilterField::Text => self.filters.text = cleaned,
+    // Whether view row `pos` starts another file of the merged rotated
+    // history. Only in time order, where each file is one block; the logs
+    // named on the command line interleave with it and never count.
+    fn rotation_boundary(&self, view: &[usize], pos: usize) -> bool {
+        if self.rotated_sources.is_empty()
+            || self.sort_column != SortColumn::Time
+            || self.aggregate
+            || pos == 0
+        {
+            return false;
         }
-        self.selected = 0;
-        self.log_entry_scroll = 0;
+        let labelled = self.extra_sources.len() + self.watched_sources.len();
+        let rotation = |source: usize| source == 0 || source > labelled;
+        let source = self.entries[view[pos]].source;
+        let previous = self.entries[view[pos - 1]].source;
+        source != previous && rotation(source) && rotation(previous)
     }
 
-    fn clear_filter(&mut self, field: FilterField) {
-        self.set_filter_value(field, String::new());
-        let matches = self.filtered_indices().len();
-        self.status = format!(
-            "Cleared {} filter. Matching rows: {}",
-            field.label(),
-            matches
-        );
+    // "ufw.log.1 · rotated 2026-02-10 06:25:01" for the separator above a
+    // rotated file's block.
+    fn rotation_separator(&self, source: usize) -> String {
+        let name = self
+            .source_paths()
+            .get(source)
+            .and_then(|path| path.file_name())
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let labelled = self.extra_sources.len() + self.watched_sources.len();
+        let when = match source.checked_sub(labelled + 1) {
+            None => "current log".to_string(),
+            Some(rotation) => self
+                .rotated_times
+                .get(rotation)
+                .copied()
+                .flatten()
+                .map_or_else(
+                    || "rotated".to_string(),
+                    |secs| format!("rotated {}", timestamp_text(secs)),
+                ),
+        };
+        format!("{} · {}", name, when)
     }
 
-    fn filtered_indices(&self) -> Vec<usize> {
-        self.entries
-            .iter()
-            .enumerate()
-            .filter_map(|(idx, entry)| {
-                if let Some(selected) = self.selected_interface.as_deref() {
-                    let in_match = entry.in_iface.as_deref() == Some(selected);
-                    let out_match = entry.out_iface.as_deref() == Some(selected);
-                    if !in_match && !out_match {
-                        return None;
-                    }
-                }
-                if !self.show_local_src && is_local_src_ip(entry.src_ip.as_deref()) {
-                    return None;
-                }
-                if !self.show_wan_src && is_wan_src_ip(entry.src_ip.as_deref()) {
-                    return None;
-                }
-                if !matches_flow_filter(self.flow_filter, entry) {
-                    return None;
-                }
-                if !matches_direction_filter(self.direction_filter, entry) {
-                    return None;
-                }
-                self.filters.matches(entry).then_some(idx)
-            })
-            .collect()
+    // Viewport row drawn at table line `line`; None on a separator.
+    fn row_at_line(&self, line: usize) -> Option<usize> {
+        if self.separator_lines.contains(&line) {
+            return None;
+        }
+        Some(
+            line - self
+                .separator_lines
+                .iter()
+                .filter(|&&sep| sep < line)
+                .count(),
+        )
     }
 
-    fn refresh_interface_options(&mut self, previous: Option<String>) {
-        let mut counts: HashMap<String, usize> = HashMap::new();
-        for entry in &self.entries {
-            for iface in [&entry.in_iface, &entry.out_iface] {
-                let Some(name) = iface.as_ref() else {
-                    continue;
-                };
-                if name.is_empty() {
-                    continue;
-                }
-                *counts.entry(name.clone()).or_insert(0) += 1;
-            }
-        }
+    fn show_file_column(&self) -> bool {
+        !self.extra_sources.is_empty()
+    }
 
-        let mut options: Vec<(String, usize)> = counts.into_iter().collect();
-        options.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
-        self.interface_options = options.into_iter().map(|(name, _)| name).collect();
+    // Label for the File column, shown once several logs were given.
+    fn source_name(&self, source: usize) -> String {
+        self.source_display(source)
+            .unwrap_or_else(|| source_label(&self.log_path))
+    }
 
-        if let Some(prev) = previous
-            && self.interface_options.iter().any(|iface| iface == &prev)
-        {
-            self.selected_interface = Some(prev);
+    // Raises the entry cap and moves `--since` back so the next ingest keeps
+    // more older entries.
+    fn load_older_entries(&mut self) {
+        if self.unloaded_older == 0 {
+            self.status.info("All entries are loaded");
             return;
         }
-        self.selected_interface = default_wan_interface(&self.interface_options);
+        let unloaded = self.unloaded_older;
+        if let Some(cap) = self.entry_cap.as_mut() {
+            *cap += LOAD_MORE_ENTRIES;
+        }
+        if let Some(since) = self.load_since.as_mut() {
+            *since -= self.since_step;
+        }
+        if self.reload() {
+            self.status.info(format!(
+                "Loading older entries ({} not loaded yet)",
+                group_thousands(unloaded)
+            ));
+        }
     }
 
-    fn cycle_interface(&mut self, forward: bool) {
-        if self.interface_options.is_empty() {
-            self.selected_interface = None;
-            self.status = "No interfaces found in logs".to_string();
+    fn toggle_rotated_history(&mut self) {
+        self.include_rotated = !self.include_rotated;
+        if !self.reload() {
             return;
         }
+        self.status.info(if self.include_rotated {
+            format!(
+                "Including rotated history: {} file(s)",
+                self.rotated_sources.len()
+            )
+        } else {
+            "Showing the current log only".to_string()
+        });
+    }
 
-        let len = self.interface_options.len() as isize;
-        let mut idx = self
-            .selected_interface
-            .as_ref()
-            .and_then(|selected| {
-                self.interface_options
-                    .iter()
-                    .position(|iface| iface == selected)
-            })
-            .map_or(-1, |i| i as isize);
-
-        idx = if forward {
-            if idx >= len - 1 { -1 } else { idx + 1 }
-        } else if idx < 0 {
-            len - 1
+    fn open_source_prompt(&mut self) {
+        let current = if self.journal.is_some() {
+            "journal".to_string()
+        } else if self.listener.is_some() {
+            String::new()
         } else {
-            idx - 1
+            self.log_path.display().to_string()
+        };
+        self.source_prompt = Some(SourcePrompt::new(current));
+        self.push_mode(Mode::SourcePrompt);
+    }
+
+    // Replaces the main log with the source typed into the `O` prompt. Filters
+    // stay as they are; with "keep", the files read so far stay merged in as
+    // extra sources. A source that cannot be read leaves the prompt open.
+    fn switch_source(&mut self) {
+        let Some(prompt) = self.source_prompt.take() else {
+            return;
+        };
+        let opened = SourceTarget::parse(&prompt.input).and_then(|target| match target {
+            SourceTarget::File(path) => match fs::File::open(&path) {
+                Ok(_) => Ok((path, None, None)),
+                Err(err) => Err(format!("Failed to read {}: {}", path.display(), err)),
+            },
+            SourceTarget::Journal => {
+                let mut journal = Journal::default();
+                match journal.poll() {
+                    Ok(_) => Ok((PathBuf::from("journalctl -k"), Some(journal), None)),
+                    Err(err) => Err(format!("Failed to read the journal: {}", err)),
+                }
+            }
+            SourceTarget::Dir(dir) => {
+                let mut logs: Vec<PathBuf> = fs::read_dir(&dir)
+                    .map_err(|err| format!("Failed to read {}: {}", dir.display(), err))?
+                    .filter_map(|item| item.ok().map(|item| item.path()))
+                    .filter(|path| is_watchable_log_file(path))
+                    .collect();
+                logs.sort();
+                match logs.into_iter().next() {
+                    Some(first) => Ok((first, None, Some(dir))),
+                    None => Err(format!("No logs to follow in {}", dir.display())),
+                }
+            }
+        });
+        let (log_path, journal, watch_dir) = match opened {
+            Ok(opened) => opened,
+            Err(err) => {
+                self.status.error(err);
+                self.source_prompt = Some(prompt);
+                self.push_mode(Mode::SourcePrompt);
+                return;
+            }
         };
 
-        self.set_selected_interface(if idx < 0 {
-            None
+        let previous = std::mem::replace(&mut self.log_path, log_path);
+        let dropped_stream = std::mem::replace(&mut self.journal, journal).is_some()
+            | self.listener.take().is_some();
+        if prompt.keep {
+            if !dropped_stream {
+                self.extra_sources.insert(0, previous);
+            }
+            let current = &self.log_path;
+            self.extra_sources.retain(|path| path != current);
+            self.watched_sources.retain(|path| path != current);
         } else {
-            Some(self.interface_options[idx as usize].clone())
+            self.extra_sources.clear();
+            self.watched_sources.clear();
+            self.watch_dirs.clear();
+        }
+        self.watch_dirs.extend(watch_dir);
+        self.discover_watched_sources();
+        // A new source starts at its newest row rather than being held back
+        // behind the old scroll position.
+        self.selected = 0;
+        self.held_ingest = None;
+        if !self.reload() {
+            return;
+        }
+        let kept = self.source_paths().len() - 1;
+        self.status.info(match (prompt.keep, dropped_stream) {
+            (true, true) => format!(
+                "Opened {}; streamed entries cannot be kept ({} other sources)",
+                self.log_path.display(),
+                kept
+            ),
+            (true, false) => format!(
+                "Opened {}, merged with {} other sources",
+                self.log_path.display(),
+                kept
+            ),
+            (false, _) => format!("Opened {}", self.log_path.display()),
         });
-        let matches = self.filtered_indices().len();
-        self.status = format!(
-            "Interface: {}. Matching rows: {}",
-            selected_iface_label(self.selected_interface.as_deref()),
-            matches
-        );
     }
 
-    fn select_all_interfaces(&mut self) {
-        self.set_selected_interface(None);
-        let matches = self.filtered_indices().len();
-        self.status = format!("Interface: all. Matching rows: {}", matches);
+    fn source_fingerprints(&self) -> Vec<Option<FileFingerprint>> {
+        self.source_paths()
+            .into_iter()
+            .map(|path| file_fingerprint(path).ok())
+            .collect()
     }
 
-    fn select_default_wan_interface(&mut self) {
-        self.set_selected_interface(default_wan_interface(&self.interface_options));
-        let matches = self.filtered_indices().len();
-        self.status = format!(
-            "Interface: {}. Matching rows: {}",
-            selected_iface_label(self.selected_interface.as_deref()),
-            matches
-        );
+    // Adds files that appeared in watched directories as new sources. Returns
+    // true when anything was added.
+    fn discover_watched_sources(&mut self) -> bool {
+        let mut added = Vec::new();
+        for dir in &self.watch_dirs {
+            let Ok(read_dir) = fs::read_dir(dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = read_dir
+                .filter_map(|item| item.ok().map(|item| item.path()))
+                .filter(|path| is_watchable_log_file(path))
+                .collect();
+            paths.sort();
+            for path in paths {
+                if path != self.log_path
+                    && !self.extra_sources.contains(&path)
+                    && !self.watched_sources.contains(&path)
+                    && !added.contains(&path)
+                {
+                    added.push(path);
+                }
+            }
+        }
+        if added.is_empty() {
+            return false;
+        }
+        let labels: Vec<String> = added.iter().map(|path| source_label(path)).collect();
+        self.status
+            .info(format!("New source(s): {}", labels.join(", ")));
+        self.watched_sources.extend(added);
+        true
     }
 
-    fn set_selected_interface(&mut self, interface: Option<String>) {
-        self.selected_interface = interface;
-        self.selected = 0;
-        self.log_entry_scroll = 0;
-        self.table_state.select(Some(0));
-    }
+    fn ingest_tick(&mut self) {
+        let Some(pending) = self.pending_ingest.as_mut() else {
+            return;
+        };
+        match pending.poll() {
+            Poll::Busy => return,
+            Poll::MainFailed(message) => {
+                self.fail_main_log(message);
+                return;
+            }
+            Poll::Done(failed_watched) => {
+                if !failed_watched.is_empty() {
+                    self.status.error(format!(
+                        "Failed to read watched source(s): {}",
+                        failed_watched.join(", ")
+                    ));
+                }
+            }
+        }
+        let Some(pending) = self.pending_ingest.take() else {
+            return;
+        };
 
-    fn toggle_show_local_src(&mut self) {
-        self.show_local_src = !self.show_local_src;
-        self.selected = 0;
-        self.log_entry_scroll = 0;
-        self.table_state.select(Some(0));
-        let matches = self.filtered_indices().len();
-        self.status = if self.show_local_src {
-            format!("Showing local source IP rows. Matching rows: {}", matches)
+        let mut entries = pending.parsed;
+        let unloaded_before = if pending.append {
+            assign_entry_ids(&mut entries, &mut self.line_occurrences);
+            let previous = match self.held_ingest.take() {
+                Some((held, _)) => held,
+                None => self.entries.clone(),
+            };
+            entries.reverse();
+            entries.extend(previous);
+            if pending.source_count > 1 {
+                entries.reverse();
+                merge_chronologically(&mut entries);
+                entries.reverse();
+            }
+            self.unloaded_older
         } else {
-            format!("Hiding local source IP rows. Matching rows: {}", matches)
+            self.line_occurrences.clear();
+            assign_entry_ids(&mut entries, &mut self.line_occurrences);
+            if pending.source_count > 1 {
+                merge_chronologically(&mut entries);
+            }
+            entries.reverse();
+            0
         };
+        let loaded = entries.len();
+        if let Some(since) = self.load_since {
+            let now = now_seconds();
+            entries.retain(|entry| {
+                entry_epoch_seconds(&entry.timestamp, now).is_none_or(|secs| secs >= since)
+            });
+        }
+        if let Some(cap) = self.entry_cap {
+            entries.truncate(cap);
+        }
+        self.unloaded_older = unloaded_before + loaded - entries.len();
+        let first_ingest = self.last_fingerprints.is_empty();
+        let previous_ids = std::mem::take(&mut self.ingested_ids);
+        if pending.append {
+            let fresh = entries
+                .iter()
+                .rev()
+                .filter(|entry| !previous_ids.contains(&entry.id));
+            self.scans.feed(fresh, now_seconds());
+        } else {
+            self.scans.reset();
+            self.scans.feed(entries.iter().rev(), now_seconds());
+        }
+        if !first_ingest {
+            self.forward_new_entries(&entries, &previous_ids);
+        }
+        self.ingested_ids = entries.iter().map(|entry| entry.id).collect();
+        self.last_fingerprints = pending.fingerprints;
+
+        if self.follow_held_by_scroll() {
+            let shown: HashSet<u64> = self.entries.iter().map(|entry| entry.id).collect();
+            let new_count = entries
+                .iter()
+                .filter(|entry| !shown.contains(&entry.id))
+                .count();
+            self.held_ingest = Some((entries, new_count));
+            return;
+        }
+        self.held_ingest = None;
+        self.apply_entries(entries);
     }
 
-    fn toggle_show_wan_src(&mut self) {
-        self.show_wan_src = !self.show_wan_src;
-        self.selected = 0;
-        self.log_entry_scroll = 0;
-        self.table_state.select(Some(0));
-        let matches = self.filtered_indices().len();
-        self.status = if self.show_wan_src {
-            format!("Showing WAN source IP rows. Matching rows: {}", matches)
-        } else {
-            format!("Hiding WAN source IP rows. Matching rows: {}", matches)
-        };
+    // While live, scrolling away from the newest row holds new entries back
+    // so rows do not move under the cursor; returning to the top applies them.
+    fn follow_held_by_scroll(&self) -> bool {
+        !self.entries.is_empty()
+            && self.selected > 0
+            && self.selection_lock.is_none()
+            && self.sort_column == SortColumn::Time
+            && self.sort_descending
     }
 
-    fn cycle_flow_filter(&mut self) {
-        self.flow_filter = self.flow_filter.next();
-        self.selected = 0;
-        self.log_entry_scroll = 0;
-        self.table_state.select(Some(0));
-        let matches = self.filtered_indices().len();
-        self.status = format!(
-            "Flow filter: {}. Matching rows: {}",
-            self.flow_filter.label(),
-            matches
-        );
+    fn apply_held_ingest(&mut self) {
+        if self.held_ingest.is_some()
+            && !self.follow_held_by_scroll()
+            && let Some((entries, _)) = self.held_ingest.take()
+        {
+            self.apply_entries(entries);
+        }
     }
 
-    fn cycle_direction_filter(&mut self) {
-        self.direction_filter = self.direction_filter.next();
-        self.selected = 0;
-        self.log_entry_scroll = 0;
-        self.table_state.select(Some(0));
-        let matches = self.filtered_indices().len();
-        self.status = format!(
-            "Direction filter: {}. Matching rows: {}",
-            self.direction_filter.label(),
-            matches
-        );
+    // Swaps in a freshly parsed entry list, keeping the selection on the same
+    // entry where possible.
+    fn apply_entries(&mut self, entries: Vec<LogEntry>) {
+        let prev_selected = self.selected;
+        let prev_selected_raw = self.current_selected_raw();
+        let prev_iface = match self.start_interface.clone() {
+            Some(choice) => Some(choice),
+            None if self.interface_options.is_empty() => None,
+            // Once there are chips, the selection, all interfaces included,
+            // is a choice to keep.
+            None => Some(self.selected_interface.clone()),
+        };
+        self.entries = entries;
+        self.tagger.refresh(&self.entries);
+        if let Some(geoip) = self.geoip.as_mut() {
+            for entry in &mut self.entries {
+                entry.country = entry.src_ip.as_deref().and_then(|ip| geoip.country(ip));
+            }
+        }
+        self.refresh_interface_options(prev_iface);
+        if !self.interface_options.is_empty() {
+            self.start_interface = None;
+        }
+
+        let filtered = self.view_indices();
+        let locked_position = self
+            .selection_lock
+            .and_then(|id| filtered.iter().position(|&idx| self.entries[idx].id == id));
+        if self.selection_lock.is_some() && locked_position.is_none() {
+            self.selection_lock = None;
+            self.status.info("Locked entry is gone; selection unlocked");
+        }
+        self.selected = if let Some(position) = locked_position {
+            position
+        } else if let Some(raw) = prev_selected_raw.as_deref() {
+            selected_position_for_raw(&self.entries, &filtered, raw)
+                .unwrap_or_else(|| min(prev_selected, filtered.len().saturating_sub(1)))
+        } else {
+            min(prev_selected, filtered.len().saturating_sub(1))
+        };
+        self.sync_selection_with_len(filtered.len());
     }
 
-    fn toggle_pause_updates(&mut self) {
-        self.updates_paused = !self.updates_paused;
-        if !self.updates_paused {
-            let _ = self.reload();
+    // Sends entries that were not present in the previous ingest, oldest
+    // first, to every live sink when they match the current view.
+    fn forward_new_entries(&mut self, entries: &[LogEntry], previous_ids: &HashSet<u64>) {
+        if self.sinks.is_empty() {
+            return;
+        }
+        let fresh: Vec<&LogEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| !previous_ids.contains(&entry.id) && self.matches_view(entry))
+            .collect();
+        for sink in &mut self.sinks {
+            for entry in &fresh {
+                sink.send(entry, &self.tagger.tags(entry));
+            }
         }
     }
 
-    fn sync_selection_with_len(&mut self, len: usize) {
-        let prev = self.selected;
-        if len == 0 {
-            self.selected = 0;
-            self.log_entry_scroll = 0;
-            self.table_state.select(None);
-        } else {
-            self.selected = min(self.selected, len - 1);
-            self.table_state.select(Some(self.selected));
-            if self.selected != prev {
-                self.log_entry_scroll = 0;
+    // Shows delivery failures the sink workers ran into since the last call.
+    fn report_sink_errors(&mut self) {
+        for sink in &mut self.sinks {
+            if let Some(err) = sink.take_error() {
+                self.status.error(format!(
+                    "{} to {} failed: {}",
+                    sink.kind.verb, sink.target, err
+                ));
             }
         }
     }
 
-    fn scroll_log_entry_left(&mut self) {
-        const STEP: u16 = 8;
-        self.log_entry_scroll = self.log_entry_scroll.saturating_sub(STEP);
+    // An entry's raw line, re-read from its source when `--low-memory` cut it
+    // short. Falls back to the cut line if the source no longer matches.
+    fn full_raw(&mut self, idx: usize) -> String {
+        let entry = &self.entries[idx];
+        let Some(offset) = entry.raw_offset else {
+            return entry.raw.clone();
+        };
+        if let Some((id, raw)) = self.raw_reread.as_ref()
+            && *id == entry.id
+        {
+            return raw.clone();
+        }
+        let prefix = entry.raw.trim_end_matches('…');
+        let streamed = entry.source == 0 && self.main_stream().is_some();
+        let raw = self
+            .source_paths()
+            .get(entry.source)
+            .filter(|_| !streamed)
+            .and_then(|path| read_line_at(path, offset).ok())
+            .filter(|line| line.starts_with(prefix))
+            .unwrap_or_else(|| entry.raw.clone());
+        self.raw_reread = Some((entry.id, raw.clone()));
+        raw
     }
 
-    fn scroll_log_entry_right(&mut self) {
-        const STEP: u16 = 8;
-        let max = self.current_log_entry_max_scroll();
-        self.log_entry_scroll = min(self.log_entry_scroll.saturating_add(STEP), max);
+    fn ingest_pending(&self) -> bool {
+        self.pending_ingest.is_some()
     }
 
-    fn current_log_entry_max_scroll(&self) -> u16 {
-        let filtered = self.filtered_indices();
+    fn current_selected_raw(&self) -> Option<String> {
+        let filtered = self.view_indices();
         if filtered.is_empty() {
-            return 0;
+            return None;
         }
         let selected = min(self.selected, filtered.len() - 1);
-        self.entries[filtered[selected]]
-            .raw
-            .len()
-            .saturating_sub(1)
-            .min(u16::MAX as usize) as u16
+        Some(self.entries[filtered[selected]].raw.clone())
     }
 
-    fn maybe_reload(&mut self) {
-        if self.updates_paused {
+    // Source, Dest and Service text of `entry` too wide for its column in the
+    // last drawn table, with the column's header rect.
+    fn truncated_cells(&self, entry: &LogEntry) -> Vec<(&'static str, String, Rect)> {
+        let area = self.frame_area;
+        let chunks = main_chunks(
+            area,
+            filter_panel_height(self, area.width),
+            lower_pane_height(self, area.height),
+            detail_pane_height(self, area.height),
+            sidebar_width(self, area.width),
+        );
+        let rects = table_column_rects(self, chunks[2]);
+        let layout = self.breakpoints.row_layout(chunks[2].width, self.redact);
+        let values = [
+            ("Source", display_ip(entry.src_ip.as_deref(), self.redact)),
+            ("Dest", display_ip(entry.dst_ip.as_deref(), self.redact)),
+            (
+                "Service",
+                service_display_for_entry(entry, layout.show_description),
+            ),
+        ];
+        values
+            .into_iter()
+            .zip(wide_column_indices(self))
+            .filter_map(|((label, value), column)| {
+                let rect = *rects.get(column?)?;
+                (text_cells(&value) > rect.width).then_some((label, value, rect))
+            })
+            .collect()
+    }
+
+    // `I`: the selected row's cut-off cells in full, e.g. long IPv6 addresses.
+    fn show_truncated_cells(&mut self) {
+        let filtered = self.view_indices();
+        let Some(&idx) = filtered.get(min(self.selected, filtered.len().saturating_sub(1))) else {
             return;
-        }
-        if self.last_watch_check.elapsed() < Duration::from_secs(1) {
+        };
+        let cells = self.truncated_cells(&self.entries[idx]);
+        if cells.is_empty() {
+            self.status.info("Nothing in the selected row is truncated");
             return;
         }
-        self.last_watch_check = Instant::now();
+        let lines = cells
+            .into_iter()
+            .map(|(label, value, _)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<8} ", label), Style::default().fg(Color::Cyan)),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+        self.show_report(Report::new(
+            format!("Full values · {}", self.entries[idx].timestamp),
+            lines,
+        ));
+    }
 
-        let Ok(current) = file_fingerprint(&self.log_path) else {
+    fn select_visible_row_index(&mut self, row_index: usize) {
+        let filtered_len = self.view_indices().len();
+        if filtered_len == 0 {
             return;
-        };
-
-        if self.last_fingerprint.as_ref() != Some(&current) {
-            let previous_status = self.status.clone();
-            if self.reload() {
-                self.status = previous_status;
-            }
         }
+        let offset = self.table_state.offset();
+        let absolute = min(
+            offset.saturating_add(row_index),
+            filtered_len.saturating_sub(1),
+        );
+        self.selected = absolute;
+        self.sync_selection_with_len(filtered_len);
     }
 
-    fn refresh_status_lifetime(&mut self) {
-        // Any status change restarts the visibility timer.
-        if self.status != self.status_snapshot {
-            self.status_snapshot = self.status.clone();
-            self.status_since = if self.status.is_empty() {
-                None
-            } else {
-                Some(Instant::now())
-            };
+    fn copy_selected_log_entry(&mut self) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
         }
-
-        if !self.status.is_empty()
-            && let Some(since) = self.status_since
-            && since.elapsed() >= Duration::from_secs(5)
-        {
-            self.status.clear();
-            self.status_snapshot.clear();
-            self.status_since = None;
+        let raw = self.full_raw(filtered[min(self.selected, filtered.len() - 1)]);
+        match copy_text_via_osc52(&raw) {
+            Ok(()) => {
+                self.status.info("Copied selected log entry (OSC52)");
+            }
+            Err(err) => {
+                self.status.error(format!("Clipboard copy failed: {}", err));
+            }
         }
     }
-}
-
-impl Filters {
-    fn active_count(&self) -> usize {
-        [
-            &self.service,
-            &self.port,
-            &self.ip,
-            &self.action,
-            &self.proto,
-            &self.text,
-        ]
-        .into_iter()
-        .filter(|value| !value.is_empty())
-        .count()
-    }
 
-    fn matches(&self, entry: &LogEntry) -> bool {
-        if !self.service.is_empty() {
-            let term = self.service.to_ascii_lowercase();
-            let service = entry.service.as_deref().unwrap_or("").to_ascii_lowercase();
-            if !service.contains(&term) {
-                return false;
+    // Copies a tcpdump filter for the selected flow, or for the active IP/port/
+    // protocol filters when `from_filters` is set.
+    fn copy_capture_filter(&mut self, from_filters: bool) {
+        let expression = if from_filters {
+            capture::filters_bpf(&self.filters.ip, &self.filters.port, &self.filters.proto)
+        } else {
+            let filtered = self.view_indices();
+            if filtered.is_empty() {
+                return;
+            }
+            let entry = &self.entries[filtered[min(self.selected, filtered.len() - 1)]];
+            capture::entry_bpf(entry).ok_or_else(|| "Selected row has no flow fields".to_string())
+        };
+        let expression = match expression {
+            Ok(expression) => expression,
+            Err(err) => {
+                self.status.warn(format!("No capture filter: {}", err));
+                return;
+            }
+        };
+        match copy_text_via_osc52(&expression) {
+            Ok(()) => {
+                let shown = if self.redact {
+                    "(redacted)".to_string()
+                } else {
+                    expression
+                };
+                self.status
+                    .info(format!("Copied capture filter: {}", shown));
+            }
+            Err(err) => {
+                self.status.error(format!("Clipboard copy failed: {}", err));
             }
         }
+    }
 
-        if !self.port.is_empty() {
-            let wanted = self.port.trim();
-            let port_match = if let Ok(port) = wanted.parse::<u16>() {
-                entry.src_port == Some(port) || entry.dst_port == Some(port)
+    // Starts tcpdump for the selected flow in the capture pane; a second press
+    // stops it and a third closes the pane.
+    fn toggle_capture(&mut self) {
+        if let Some(capture) = self.capture.as_mut() {
+            if capture.running() {
+                capture.stop();
+                capture.poll();
+                self.status.info("Stopped tcpdump");
             } else {
-                let src = entry.src_port.map(|p| p.to_string()).unwrap_or_default();
-                let dst = entry.dst_port.map(|p| p.to_string()).unwrap_or_default();
-                src.contains(wanted) || dst.contains(wanted)
-            };
-            if !port_match {
-                return false;
+                self.capture = None;
             }
+            return;
         }
-
-        if !self.ip.is_empty() {
-            let term = self.ip.to_ascii_lowercase();
-            let src = entry.src_ip.as_deref().unwrap_or("").to_ascii_lowercase();
-            let dst = entry.dst_ip.as_deref().unwrap_or("").to_ascii_lowercase();
-            if !src.contains(&term) && !dst.contains(&term) {
-                return false;
-            }
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
         }
-
-        if !self.action.is_empty() {
-            let term = self.action.to_ascii_lowercase();
-            if !entry.action.to_ascii_lowercase().contains(&term) {
-                return false;
+        let entry = &self.entries[filtered[min(self.selected, filtered.len() - 1)]];
+        let Some(expression) = capture::entry_bpf(entry) else {
+            self.status
+                .info("Selected row has no flow fields to capture");
+            return;
+        };
+        let iface = capture_interface(entry);
+        match CaptureSession::start(&iface, &expression, self.capture_dir.as_deref()) {
+            Ok(session) => {
+                self.status.info(format!("Started tcpdump on {}", iface));
+                self.capture = Some(session);
             }
-        }
-
-        if !self.proto.is_empty() {
-            let term = self.proto.to_ascii_lowercase();
-            let proto = entry.proto.as_deref().unwrap_or("").to_ascii_lowercase();
-            if !proto.contains(&term) {
-                return false;
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                self.status.error("tcpdump not found in PATH");
             }
+            Err(err) => self
+                .status
+                .error(format!("Failed to start tcpdump: {}", err)),
         }
+    }
 
-        if !self.text.is_empty() {
-            let term = self.text.to_ascii_lowercase();
-            if !entry.raw.to_ascii_lowercase().contains(&term) {
-                return false;
+    // Opens a report of ufw-* rule counters from iptables/nft next to how many
+    // logged entries hit each rule's destination port.
+    fn show_rule_counters(&mut self) {
+        let mut counters = match counters::read_rule_counters() {
+            Ok(counters) => counters,
+            Err(err) => {
+                self.status
+                    .error(format!("Could not read rule counters: {}", err));
+                return;
+            }
+        };
+        counters.sort_by_key(|counter| std::cmp::Reverse(counter.packets));
+        let mut logged: HashMap<u16, usize> = HashMap::new();
+        for entry in &self.entries {
+            if let Some(port) = entry.dst_port {
+                *logged.entry(port).or_insert(0) += 1;
             }
         }
 
-        true
+        let mut lines = vec![heading(format!(
+            "{:<22} {:>10} {:>12} {:>8}  RULE",
+            "CHAIN", "PACKETS", "BYTES", "LOGGED"
+        ))];
+        for counter in &counters {
+            let logged = counter
+                .dport
+                .map(|port| logged.get(&port).copied().unwrap_or(0).to_string())
+                .unwrap_or_else(|| "-".to_string());
+            lines.push(Line::from(format!(
+                "{:<22} {:>10} {:>12} {:>8}  {}",
+                counter.chain, counter.packets, counter.bytes, logged, counter.rule
+            )));
+        }
+        if counters.is_empty() {
+            lines.push(Line::from("No ufw-* chains with counters found."));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(
+            "LOGGED counts log entries to the rule's destination port; UFW only logs rules with logging enabled.",
+        ));
+        self.show_report(Report::new(
+            format!("Rule hit counters ({} rules)", counters.len()),
+            lines,
+        ));
     }
-}
 
-fn load_entries(path: &Path) -> io::Result<Vec<LogEntry>> {
-    let contents = fs::read_to_string(path)?;
-    let mut entries: Vec<LogEntry> = contents.lines().filter_map(parse_ufw_line).collect();
-    entries.reverse();
-    Ok(entries)
-}
+    // Writes the filtered rows, in view order, to the path in the `e` prompt.
+    fn export_view(&mut self) {
+        let Some(prompt) = self.export_prompt.take() else {
+            return;
+        };
+        let indices = self.filtered_indices();
+        let rows = indices.iter().map(|&idx| {
+            let entry = &self.entries[idx];
+            (entry, self.tagger.tags(entry))
+        });
+        self.status.report(
+            match export::write_entries(Path::new(&prompt.path), prompt.format, rows) {
+                Ok(()) => Ok(format!(
+                    "Exported {} rows as {} to {}",
+                    indices.len(),
+                    prompt.format.label(),
+                    prompt.path
+                )),
+                Err(err) => Err(format!("Export to {} failed: {}", prompt.path, err)),
+            },
+        );
+    }
 
-fn file_fingerprint(path: &Path) -> io::Result<FileFingerprint> {
-    let meta = fs::metadata(path)?;
-    Ok(FileFingerprint {
-        modified: meta.modified().ok(),
-        len: meta.len(),
-    })
-}
+    // One abuse@ report per top blocked source in the current view.
+    fn export_abuse_reports(&mut self) {
+        let mut indices = self.filtered_indices();
+        indices.sort_unstable();
+        let offenders = abuse::top_offenders(
+            indices.iter().map(|&idx| &self.entries[idx]),
+            self.abuse_top,
+            self.ipv6_prefix,
+        );
+        if offenders.is_empty() {
+            self.status.warn("No blocked sources in the current view");
+            return;
+        }
+        self.status
+            .report(match abuse::write_reports(&self.abuse_dir, &offenders) {
+                Ok(written) => Ok(format!(
+                    "Wrote {} abuse reports to {}",
+                    written.len(),
+                    self.abuse_dir.display()
+                )),
+                Err(err) => Err(format!(
+                    "Writing abuse reports to {} failed: {}",
+                    self.abuse_dir.display(),
+                    err
+                )),
+            });
+    }
 
-fn field_from_fkey(n: u8) -> Option<FilterField> {
-    match n {
-        1 => Some(FilterField::Service),
-        2 => Some(FilterField::Port),
-        3 => Some(FilterField::Ip),
-        4 => Some(FilterField::Action),
-        5 => Some(FilterField::Proto),
-        6 => Some(FilterField::Text),
-        _ => None,
+    // Offenders that reuse one source port across many connections.
+    fn show_fixed_source_ports(&mut self) {
+        let found = fixed_port::fixed_source_ports(self.scoped_entries());
+        let mut lines = vec![heading(format!(
+            "{:<40} {:>6} {:>12} {:>8}",
+            "SOURCE", "SPT", "PACKETS", "TARGETS"
+        ))];
+        for fixed in &found {
+            lines.push(Line::from(format!(
+                "{:<40} {:>6} {:>12} {:>8}",
+                display_ip(Some(&fixed.source), self.redact),
+                fixed.port,
+                format!("{}/{}", fixed.hits, fixed.packets),
+                fixed.targets
+            )));
+        }
+        if found.is_empty() {
+            lines.push(Line::from("No source reuses a fixed source port."));
+        }
+        self.show_report(Report::new(
+            format!(
+                "Fixed source-port scanners ({}){}",
+                found.len(),
+                self.scope_suffix()
+            ),
+            lines,
+        ));
     }
-}
 
-fn selected_iface_label(value: Option<&str>) -> &str {
-    value.unwrap_or("all")
-}
+    // LIMIT BLOCK hits collapsed per source; single limited packets are noise.
+    fn show_limit_summaries(&mut self) {
+        let summaries = limits::limit_summaries(self.scoped_entries());
+        let mut lines = Vec::with_capacity(summaries.len() + 1);
+        for summary in &summaries {
+            let window = summary
+                .window_secs
+                .map_or_else(|| "?".to_string(), limits::format_window);
+            let ports: Vec<String> = summary.ports.iter().map(u16::to_string).collect();
+            lines.push(Line::from(format!(
+                "rate-limited: {} hits from {} in {} → port {} ({} – {})",
+                summary.hits,
+                display_ip(Some(&summary.source), self.redact),
+                window,
+                ports.join(","),
+                format_timestamp_for_width(&summary.first, true),
+                format_timestamp_for_width(&summary.last, false)
+            )));
+        }
+        if summaries.is_empty() {
+            lines.push(Line::from("No [UFW LIMIT BLOCK] entries in scope."));
+        }
+        self.show_report(Report::new(
+            format!(
+                "Rate-limited sources ({}){}",
+                summaries.len(),
+                self.scope_suffix()
+            ),
+            lines,
+        ));
+    }
 
-fn selected_position_for_raw(
-    entries: &[LogEntry],
-    filtered_indices: &[usize],
-    raw: &str,
-) -> Option<usize> {
-    filtered_indices
-        .iter()
-        .position(|entry_idx| entries[*entry_idx].raw == raw)
-}
+    // Opens a report of inbound ALLOW traffic to ports nothing listens on, a
+    // hint that the rule that let it through is stale.
+    fn show_allow_without_listener(&mut self) {
+        let listening = match listeners::listening_ports() {
+            Ok(listening) => listening,
+            Err(err) => {
+                self.status
+                    .error(format!("Could not read listening ports: {}", err));
+                return;
+            }
+        };
+        // (proto, port) -> (hits, newest timestamp, distinct sources)
+        let mut unserved: HashMap<(&str, u16), (usize, &str, HashSet<&str>)> = HashMap::new();
+        for entry in &self.entries {
+            let (Some(proto @ ("TCP" | "UDP")), Some(port)) =
+                (entry.proto.as_deref(), entry.dst_port)
+            else {
+                continue;
+            };
+            if entry.action != "ALLOW"
+                || !matches!(entry.direction(), "IN" | "FWD")
+                || listening.contains(&(proto, port))
+            {
+                continue;
+            }
+            // Entries are newest first, so the first hit carries the latest time.
+            let slot = unserved.entry((proto, port)).or_insert((
+                0,
+                entry.timestamp.as_str(),
+                HashSet::new(),
+            ));
+            slot.0 += 1;
+            if let Some(src) = entry.src_ip.as_deref() {
+                slot.2.insert(src);
+            }
+        }
 
-fn pause_chip_label(paused: bool) -> &'static str {
-    if paused { "[paused]" } else { "[live]" }
-}
+        let mut rows: Vec<_> = unserved.into_iter().collect();
+        rows.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(a.0.cmp(&b.0)));
+        let mut lines = vec![heading(format!(
+            "{:<6} {:>6} {:>7} {:>8}  {:<16} LAST SEEN",
+            "PROTO", "PORT", "HITS", "SOURCES", "SERVICE"
+        ))];
+        for ((proto, port), (hits, last_seen, sources)) in &rows {
+            lines.push(Line::from(format!(
+                "{:<6} {:>6} {:>7} {:>8}  {:<16} {}",
+                proto,
+                port,
+                hits,
+                sources.len(),
+                services::service_from_port(*port).unwrap_or("-"),
+                format_timestamp_for_width(last_seen, true)
+            )));
+        }
+        if rows.is_empty() {
+            lines.push(Line::from(
+                "Every allowed inbound port in the log has a local listener.",
+            ));
+        }
+        self.show_report(Report::new(
+            format!("ALLOW without listener ({} ports)", rows.len()),
+            lines,
+        ));
+    }
 
-fn max_horizontal_scroll(text: &str, content_width: u16) -> u16 {
-    if content_width == 0 {
-        return 0;
+    // Per-IP drill-down for the selected row's source: firewall activity plus
+    // sshd outcomes from the correlated auth log.
+    fn show_ip_drilldown(&mut self) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let selected = &self.entries[filtered[min(self.selected, filtered.len() - 1)]];
+        let Some(ip) = selected.src_ip.clone() else {
+            self.status.warn("No source IP on selected row");
+            return;
+        };
+
+        let scoped = self.scoped_entries();
+        let hits: Vec<&LogEntry> = scoped
+            .iter()
+            .copied()
+            .filter(|entry| entry.src_ip.as_deref() == Some(ip.as_str()))
+            .collect();
+        let mut actions: HashMap<&str, usize> = HashMap::new();
+        let mut ports: HashMap<u16, usize> = HashMap::new();
+        let mut ifaces: HashSet<&str> = HashSet::new();
+        for entry in &hits {
+            *actions.entry(entry.action.as_str()).or_insert(0) += 1;
+            if let Some(port) = entry.dst_port {
+                *ports.entry(port).or_insert(0) += 1;
+            }
+            for iface in [entry.in_iface.as_deref(), entry.out_iface.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|iface| !iface.is_empty())
+            {
+                ifaces.insert(iface);
+            }
+        }
+        let ranked = |counts: HashMap<String, usize>, limit: usize| {
+            let mut rows: Vec<(String, usize)> = counts.into_iter().collect();
+            rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            rows.truncate(limit);
+            rows.iter()
+                .map(|(key, count)| format!("{} ({})", key, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let actions = ranked(
+            actions
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            usize::MAX,
+        );
+        let ports = ranked(
+            ports.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            8,
+        );
+        let mut ifaces: Vec<&str> = ifaces.into_iter().collect();
+        ifaces.sort_unstable();
+        // Entries are newest first.
+        let last_seen = hits.first().map(|entry| entry.timestamp.as_str());
+        let first_seen = hits.last().map(|entry| entry.timestamp.as_str());
+
+        let mut lines = vec![
+            heading("Firewall log".to_string()),
+            Line::from(format!("  Entries:    {}", hits.len())),
+            Line::from(format!(
+                "  Seen:       {} .. {}",
+                first_seen.map_or("-".to_string(), |ts| format_timestamp_for_width(ts, true)),
+                last_seen.map_or("-".to_string(), |ts| format_timestamp_for_width(ts, true)),
+            )),
+            Line::from(format!("  Actions:    {}", actions)),
+            Line::from(format!(
+                "  Dst ports:  {}",
+                if ports.is_empty() { "-" } else { &ports }
+            )),
+            Line::from(format!("  Interfaces: {}", ifaces.join(", "))),
+        ];
+        if let Some(fixed) = fixed_port::fixed_source_ports(scoped.iter().copied())
+            .into_iter()
+            .find(|fixed| fixed.source == ip)
+        {
+            lines.push(Line::from(format!(
+                "  Fixed SPT:  {} in {} of {} packets to {} targets (scanner signature)",
+                fixed.port, fixed.hits, fixed.packets, fixed.targets
+            )));
+        }
+        if self.dns_watch {
+            let findings = dns_watch::dns_findings(scoped.iter().copied(), &ip, now_seconds());
+            if findings.is_empty() {
+                lines.push(Line::from("  DNS watch:  no query floods or port spread"));
+            }
+            for finding in findings {
+                lines.push(Line::from(format!(
+                    "  DNS watch:  {} (possible tunnel)",
+                    finding.describe()
+                )));
+            }
+        }
+        lines.extend([
+            Line::from(""),
+            heading("Allowed vs blocked over time".to_string()),
+        ]);
+        let buckets = timeline::action_buckets(hits.iter().copied(), now_seconds());
+        if buckets.is_empty() {
+            lines.push(Line::from(
+                "  No ALLOW or BLOCK entries with a readable time",
+            ));
+        }
+        lines.extend(timeline::chart_lines(&buckets));
+        lines.extend([Line::from(""), heading("Auth log".to_string())]);
+        lines.push(Line::from(match self.auth_log.as_ref() {
+            None => "  Not configured (start with --auth-log /var/log/auth.log)".to_string(),
+            Some(auth_log) => match auth_log.stats(&ip) {
+                None => format!("  No sshd activity in {}", auth_log.path.display()),
+                Some(stats) => format!(
+                    "  {} failed SSH logins, {} invalid users, {} accepted (last {})",
+                    stats.failed,
+                    stats.invalid_user,
+                    stats.accepted,
+                    format_timestamp_for_width(&stats.last_seen, true)
+                ),
+            },
+        }));
+        self.show_report(Report::new(
+            format!(
+                "IP {}{}",
+                display_ip(Some(&ip), self.redact),
+                self.scope_suffix()
+            ),
+            lines,
+        ));
     }
-    let longest_line = text
-        .lines()
-        .map(|line| line.chars().count())
-        .max()
-        .unwrap_or(0);
-    longest_line
-        .saturating_sub(content_width as usize)
-        .min(u16::MAX as usize) as u16
-}
 
-fn format_timestamp_for_width(timestamp: &str, show_date: bool) -> String {
-    let token = timestamp
-        .split_whitespace()
-        .next()
-        .unwrap_or_default()
-        .trim();
+    // Copies the selected entry rendered by `format`; `label` names the form.
+    fn copy_selected_as(&mut self, format: fn(&LogEntry) -> String, label: &str) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let text = format(&self.entries[filtered[min(self.selected, filtered.len() - 1)]]);
+        match copy_text_via_osc52(&text) {
+            Ok(()) => self
+                .status
+                .info(format!("Copied selected entry as {}", label)),
+            Err(err) => self.status.error(format!("Clipboard copy failed: {}", err)),
+        }
+    }
 
-    // ISO-8601 style: 2026-02-11T23:00:39.987820+02:00
-    if let Some((date, time_part)) = token.split_once('T') {
-        let time = time_part
-            .split(['.', '+', 'Z'])
-            .next()
-            .unwrap_or_default()
-            .split('-')
-            .next()
-            .unwrap_or_default();
-        if !date.is_empty() && !time.is_empty() {
-            return if show_date {
-                format!("{} {}", date, time)
-            } else {
-                time.to_string()
-            };
+    // `{`: every parsed field and tag of the row as one JSON object, with the
+    // full raw line even under `--low-memory`.
+    fn copy_selected_json(&mut self) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let idx = filtered[min(self.selected, filtered.len() - 1)];
+        let mut entry = self.entries[idx].clone();
+        entry.raw = self.full_raw(idx);
+        let json = json::entry_detail_json(&entry, &self.tagger.tags(&entry));
+        match copy_text_via_osc52(&json) {
+            Ok(()) => self.status.info("Copied selected entry as JSON"),
+            Err(err) => self.status.error(format!("Clipboard copy failed: {}", err)),
         }
     }
 
-    // Syslog style: Feb 11 20:21:00 host
-    let parts: Vec<&str> = timestamp.split_whitespace().collect();
-    if parts.len() >= 3 && parts[2].contains(':') {
-        return if show_date {
-            format!("{} {} {}", parts[0], parts[1], parts[2])
+    // `b`: asks to deny the selected source with ufw.
+    fn prompt_block_selected(&mut self) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let entry = &self.entries[filtered[min(self.selected, filtered.len() - 1)]];
+        let Some(ip) = entry.src_ip.as_deref() else {
+            self.status.warn("No source IP on selected row");
+            return;
+        };
+        match BlockAction::deny_source(ip) {
+            Ok(action) => {
+                self.block_prompt = Some(action);
+                self.push_mode(Mode::Confirm);
+            }
+            Err(err) => self.status.error(err),
+        }
+    }
+
+    // The confirmation line shown while a block is pending.
+    fn block_prompt_text(&self) -> Option<String> {
+        let action = self.block_prompt.as_ref()?;
+        let command = if self.redact {
+            action
+                .command_line()
+                .replace(&action.ip, &display_ip(Some(&action.ip), true))
         } else {
-            parts[2].to_string()
+            action.command_line()
         };
+        Some(if self.dry_run {
+            format!(
+                "Copy `{}` to the clipboard? (Enter copy, Esc cancel)",
+                command
+            )
+        } else {
+            format!("Run `{}`? (Enter run, c copy only, Esc cancel)", command)
+        })
     }
 
-    timestamp.to_string()
-}
+    // Runs the pending block, or only copies it in `--dry-run` or with `c`.
+    fn confirm_block(&mut self, run: bool) {
+        let Some(action) = self.block_prompt.take() else {
+            return;
+        };
+        let ip = display_ip(Some(&action.ip), self.redact);
+        self.status.report(if run && !self.dry_run {
+            action.run().map(|output| match action.kind {
+                RuleKind::DenySource => format!("Blocked {}: {}", ip, output),
+                RuleKind::AllowOut => format!("Allowed outbound traffic to {}: {}", ip, output),
+            })
+        } else {
+            copy_text_via_osc52(&action.command_line())
+                .map(|()| format!("Copied the ufw command to {}", action.describe(&ip)))
+                .map_err(|err| format!("Clipboard copy failed: {}", err))
+        });
+    }
 
-fn service_display_for_entry(entry: &LogEntry, show_description: bool) -> String {
-    let name = entry.service.as_deref().unwrap_or("-");
-    if name == "-" || !show_description {
-        return name.to_string();
+    fn open_egress_review(&mut self) {
+        let expected = match self.egress_path.as_deref() {
+            Some(path) => egress::load_expected(path),
+            None => Ok(Vec::new()),
+        };
+        match expected {
+            Ok(expected) => {
+                let groups = egress::blocked_egress(self.scoped_entries());
+                self.egress_review = Some(EgressReview::new(groups, expected));
+                self.push_mode(Mode::EgressReview);
+            }
+            Err(err) => self
+                .status
+                .error(format!("Reading expected egress failed: {}", err)),
+        }
     }
 
-    let port = entry.dst_port.or(entry.src_port);
-    let Some(port) = port else {
-        return name.to_string();
-    };
-    let Some(description) = service_description_from_port(port) else {
-        return name.to_string();
-    };
-    if description.eq_ignore_ascii_case(name) {
-        return name.to_string();
+    fn open_country_summary(&mut self) {
+        if self.geoip.is_none() {
+            self.status
+                .warn("The country summary needs a GeoIP database (--geoip)");
+            return;
+        }
+        self.country_summary = Some(CountrySummary::new(self.scoped_entries()));
+        self.push_mode(Mode::Countries);
     }
 
-    format!("{}: {}", name, description)
-}
+    // Enter in the country summary: filters the view to the selected
+    // country and closes the summary.
+    fn apply_selected_country(&mut self) {
+        let Some(country) = self
+            .country_summary
+            .as_ref()
+            .and_then(|summary| summary.selected_country())
+            .map(str::to_string)
+        else {
+            return;
+        };
+        self.pop_mode();
+        let before = self.current_view_preset();
+        self.set_filter_value(FilterField::Country, country.clone());
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Set {} filter='{}'. Matching rows: {}",
+            FilterField::Country.label(),
+            country,
+            matches
+        ));
+        self.offer_pin();
+        self.remember_filters(before);
+    }
 
-fn key_span(text: &'static str) -> Span<'static> {
-    Span::styled(
-        text,
-        Style::default()
-            .fg(Color::White)
-            .add_modifier(Modifier::BOLD),
-    )
-}
+    // `a` in the review: the allow rule goes through the usual confirmation.
+    fn prompt_allow_egress(&mut self) {
+        let Some(group) = self.egress_review.as_ref().and_then(|r| r.selected_group()) else {
+            return;
+        };
+        let target = &group.target;
+        match BlockAction::allow_out(&target.destination, target.port, &target.proto) {
+            Ok(action) => {
+                self.block_prompt = Some(action);
+                self.push_mode(Mode::Confirm);
+            }
+            Err(err) => self.status.error(err),
+        }
+    }
 
-fn desc_span(text: &'static str) -> Span<'static> {
-    Span::styled(text, Style::default().fg(Color::Gray))
-}
+    fn mark_egress_expected(&mut self) {
+        let Some(review) = self.egress_review.as_mut() else {
+            return;
+        };
+        let Some(target) = review.mark_selected_expected() else {
+            return;
+        };
+        let shown = format!(
+            "{} {}",
+            display_ip(Some(&target.destination), self.redact),
+            target.port_label()
+        );
+        let Some(path) = self.egress_path.as_deref() else {
+            self.status.warn(format!(
+                "Marked {} expected for this session only (no config directory)",
+                shown
+            ));
+            return;
+        };
+        self.status.report(
+            egress::save_expected(path, &review.expected)
+                .map(|()| format!("Marked {} expected; it stays out of the review", shown))
+                .map_err(|err| format!("Saving expected egress failed: {}", err)),
+        );
+    }
 
-fn sep_span() -> Span<'static> {
-    Span::styled(" | ", Style::default().fg(Color::DarkGray))
-}
+    // Copies the source IP's in-addr.arpa / ip6.arpa name for DNS tooling.
+    fn copy_selected_src_arpa(&mut self) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let entry = &self.entries[filtered[min(self.selected, filtered.len() - 1)]];
+        let Some(name) = entry.src_ip.as_deref().and_then(reverse_dns_name) else {
+            self.status.warn("No source IP on selected row");
+            return;
+        };
+        match copy_text_via_osc52(&name) {
+            Ok(()) if self.redact => self.status.info("Copied reverse-DNS name"),
+            Ok(()) => self
+                .status
+                .info(format!("Copied reverse-DNS name: {}", name)),
+            Err(err) => self.status.error(format!("Clipboard copy failed: {}", err)),
+        }
+    }
 
-fn footer_help_lines(width: u16) -> Vec<Line<'static>> {
-    let width = width.max(MIN_UI_WIDTH);
+    // The command line that opens this view again, or prints it with
+    // `--print`: sources, filters, the interface chips and the selected one
+    // (or all) and `--since`. The flow, direction and address-class toggles
+    // have no flags and are left out.
+    fn view_command_line(&self) -> String {
+        self.view_args()
+            .iter()
+            .map(|arg| shell_word(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 
-    if width >= 140 {
-        vec![Line::from(vec![
-            key_span("q"),
-            desc_span(" quit"),
-            sep_span(),
-            key_span("r"),
-            desc_span(" reload"),
-            sep_span(),
-            key_span("a"),
-            desc_span(" pause/resume"),
-            sep_span(),
-            key_span("c"),
-            desc_span(" clear"),
-            sep_span(),
-            key_span("l"),
-            desc_span(" local"),
-            sep_span(),
-            key_span("p"),
-            desc_span(" wan"),
-            sep_span(),
-            key_span("f"),
-            desc_span(" flow"),
-            sep_span(),
-            key_span("d"),
-            desc_span(" dir"),
-            sep_span(),
-            key_span("F1..F6"),
-            desc_span(" edit"),
-            sep_span(),
-            key_span("Shift+F"),
-            desc_span(" clear"),
-            sep_span(),
-            key_span(",/."),
-            desc_span(" iface"),
-            sep_span(),
-            key_span("0"),
-            desc_span(" all"),
-            sep_span(),
-            key_span("w"),
-            desc_span(" wan"),
-            sep_span(),
-            key_span("<-/->"),
-            desc_span(" log"),
-            sep_span(),
-            key_span("Ctrl+C"),
-            desc_span(" copy row"),
-            sep_span(),
-            key_span("Ctrl+I"),
-            desc_span(" copy src ip"),
-        ])]
-    } else if width >= 110 {
-        vec![
-            Line::from(vec![
-                key_span("q"),
-                desc_span(" quit"),
-                sep_span(),
-                key_span("r"),
-                desc_span(" reload"),
-                sep_span(),
-                key_span("a"),
-                desc_span(" pause"),
-                sep_span(),
-                key_span("c"),
-                desc_span(" clear"),
-                sep_span(),
-                key_span("l/p/f"),
-                desc_span(" toggles"),
-                sep_span(),
-                key_span("d"),
-                desc_span(" dir"),
-                sep_span(),
-                key_span("F1..F6"),
-                desc_span(" edit"),
-                sep_span(),
-                key_span("Shift+F"),
-                desc_span(" clear"),
-            ]),
-            Line::from(vec![
-                key_span(",/."),
-                desc_span(" iface"),
-                sep_span(),
-                key_span("0"),
-                desc_span(" all"),
-                sep_span(),
-                key_span("w"),
-                desc_span(" wan"),
-                sep_span(),
-                key_span("<-/->"),
-                desc_span(" log"),
-                sep_span(),
-                key_span("Ctrl+C"),
-                desc_span(" row"),
-                sep_span(),
-                key_span("Ctrl+I"),
-                desc_span(" src ip"),
-            ]),
-        ]
-    } else {
-        vec![
-            Line::from(vec![
-                key_span("q"),
-                desc_span(" quit"),
-                sep_span(),
-                key_span("r"),
-                desc_span(" reload"),
-                sep_span(),
-                key_span("a"),
-                desc_span(" pause"),
-                sep_span(),
-                key_span("c"),
-                desc_span(" clear"),
-                sep_span(),
-                key_span("l/p/f"),
-                desc_span(" toggles"),
-                sep_span(),
-                key_span("d"),
-                desc_span(" dir"),
-            ]),
-            Line::from(vec![
-                key_span("F1..F6"),
-                desc_span(" edit"),
-                sep_span(),
-                key_span("Shift+F"),
-                desc_span(" clear"),
-                sep_span(),
-                key_span(",/."),
-                desc_span(" iface"),
-                sep_span(),
-                key_span("0"),
-                desc_span(" all"),
-                sep_span(),
-                key_span("w"),
-                desc_span(" wan"),
-                sep_span(),
-                key_span("<-/->"),
-                desc_span(" log"),
-                sep_span(),
-                key_span("Ctrl+C"),
-                desc_span(" row"),
-                sep_span(),
-                key_span("Ctrl+I"),
-                desc_span(" ip"),
-            ]),
-        ]
+    fn view_args(&self) -> Vec<String> {
+        let mut args = vec![env!("CARGO_PKG_NAME").to_string()];
+        match (&self.journal, &self.listener) {
+            (Some(_), _) => args.push("--journal".to_string()),
+            (None, Some(listener)) => args.extend(["--listen".to_string(), listener.addr.clone()]),
+            (None, None) => args.extend(
+                std::iter::once(&self.log_path)
+                    .chain(&self.extra_sources)
+                    .map(|path| path.display().to_string()),
+            ),
+        }
+        if self.include_rotated {
+            args.push("--rotated".to_string());
+        }
+        for field in FilterField::ALL {
+            let value = self.filters.value(field);
+            if !value.is_empty() {
+                args.extend([
+                    "--filter".to_string(),
+                    format!("{}={}", field.label(), value),
+                ]);
+            }
+        }
+        if !self.interface_globs.is_empty() {
+            args.extend(["--iface".to_string(), self.interface_globs.join(",")]);
+        }
+        args.extend([
+            "--interface".to_string(),
+            self.selected_interface
+                .as_deref()
+                .unwrap_or(ALL_INTERFACES)
+                .to_string(),
+        ]);
+        if let Some(since) = self.load_since {
+            args.extend(["--since".to_string(), timestamp_text(since)]);
+        }
+        args
     }
-}
 
-fn max_visible_ifaces(width: u16) -> usize {
-    if width >= 160 {
-        8
-    } else if width >= 120 {
-        6
-    } else {
-        4
+    // The view `--iface`, `--interface`, `--filter` and `--since` ask for.
+    fn open_view(
+        &mut self,
+        interface_globs: Vec<String>,
+        interface: Option<String>,
+        filters: Vec<(FilterField, String)>,
+        since: Option<i64>,
+    ) {
+        self.interface_globs = interface_globs;
+        self.start_interface =
+            interface.map(|name| Some(name).filter(|name| name != ALL_INTERFACES));
+        for (field, value) in filters {
+            self.filters.set(field, value);
+        }
+        if let Some(since) = since {
+            self.load_since = Some(since);
+            self.since_step = (now_seconds() - since).max(3_600);
+        }
     }
-}
 
-fn interface_prefix(width: u16) -> &'static str {
-    if width >= 120 {
-        "interface: "
-    } else {
-        "iface "
+    fn copy_view_command_line(&mut self) {
+        let command = self.view_command_line();
+        match copy_text_via_osc52(&command) {
+            Ok(()) => {
+                let shown = if self.redact {
+                    "(redacted)".to_string()
+                } else {
+                    command
+                };
+                self.status.info(format!("Copied command: {}", shown));
+            }
+            Err(err) => {
+                self.status.error(format!("Clipboard copy failed: {}", err));
+            }
+        }
     }
-}
 
-fn left_toggle_width(app: &App) -> u16 {
-    let local = text_cells("[local]");
-    let wan = text_cells("[wan]");
-    let flow = text_cells(app.flow_filter.chip());
-    let dir = text_cells(app.direction_filter.chip());
-    local
-        .saturating_add(1)
-        .saturating_add(wan)
-        .saturating_add(1)
-        .saturating_add(flow)
-        .saturating_add(1)
-        .saturating_add(dir)
-}
+    fn copy_selected_src_ip(&mut self) {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let selected = min(self.selected, filtered.len() - 1);
+        let src_ip = self.entries[filtered[selected]]
+            .src_ip
+            .as_deref()
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        if src_ip.is_empty() {
+            self.status.warn("No source IP on selected row");
+            return;
+        }
+        match copy_text_via_osc52(&src_ip) {
+            Ok(()) => {
+                let shown = if self.redact {
+                    redact_ip(&src_ip)
+                } else {
+                    src_ip
+                };
+                self.status.info(format!("Copied source IP: {}", shown));
+            }
+            Err(err) => {
+                self.status.error(format!("Source IP copy failed: {}", err));
+            }
+        }
+    }
 
-fn interface_row_width(app: &App, max_interfaces: usize, row_width: u16) -> u16 {
-    let mut width = text_cells(interface_prefix(row_width));
-    let all_label = if app.selected_interface.is_none() {
-        "[all]"
-    } else {
-        "all"
-    };
-    width = width.saturating_add(text_cells(all_label));
+    fn clear_filters(&mut self) {
+        let before = self.current_view_preset();
+        self.filters = Filters::default();
+        self.time_window = None;
+        self.show_local_src = false;
+        self.show_wan_src = true;
+        self.show_broadcast = true;
+        self.show_replies = true;
+        self.bogon_only = false;
+        self.listed_only = false;
+        self.flow_filter = FlowFilter::All;
+        self.direction_filter = DirectionFilter::Both;
+        self.selected_interface = default_wan_interface(&self.interface_options);
+        self.pinned_raw = None;
+        self.pin_candidate = None;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        self.status.info(format!(
+            "Cleared filters (local src hidden, wan src shown, bcast shown, replies shown, flow all, dir in+out, interface: {})",
+            selected_iface_label(self.selected_interface.as_deref())
+        ));
+        self.remember_filters(before);
+    }
 
-    for iface in app.interface_options.iter().take(max_interfaces) {
-        let selected = app.selected_interface.as_deref() == Some(iface.as_str());
-        let label = if selected {
-            format!("[{}]", iface)
-        } else {
-            iface.clone()
-        };
-        width = width.saturating_add(1).saturating_add(text_cells(&label));
+    // Puts the current view on the `--http` page once the last one is stale.
+    fn publish_dashboard(&mut self) {
+        if !self.dashboard.as_ref().is_some_and(Dashboard::due) {
+            return;
+        }
+        let indices = self.filtered_indices();
+        let view = || indices.iter().map(|&idx| &self.entries[idx]);
+        let mut stats = stats::ViewStats::compute(view(), DASHBOARD_TOP, self.ipv6_prefix);
+        if self.redact {
+            for (source, _) in &mut stats.sources {
+                *source = redact_ip(source);
+            }
+        }
+        let columns = self.visible_columns();
+        let headers: Vec<&str> = columns.iter().map(|column| column.header()).collect();
+        let rows: Vec<Vec<String>> = view()
+            .take(dashboard::MAX_ROWS)
+            .map(|entry| {
+                columns
+                    .iter()
+                    .map(|column| column_text(entry, *column, self.redact))
+                    .collect()
+            })
+            .collect();
+        let title = format!(
+            "{} · {}",
+            self.log_path.display(),
+            self.filters_label(self.redact)
+        );
+        let page = dashboard::page(&title, indices.len(), &headers, &rows, &stats);
+        if let Some(dashboard) = self.dashboard.as_mut() {
+            dashboard.publish(page);
+        }
     }
 
-    if app.interface_options.len() > max_interfaces {
-        let extra = format!("+{}", app.interface_options.len() - max_interfaces);
-        width = width.saturating_add(1).saturating_add(text_cells(&extra));
+    // The configured columns; the country column needs `--geoip`.
+    fn visible_columns(&self) -> Vec<Column> {
+        self.columns
+            .iter()
+            .copied()
+            .filter(|column| *column != Column::Country || self.geoip.is_some())
+            .collect()
     }
 
-    width
-}
+    fn toggle_column_picker(&mut self) {
+        self.column_picker = match self.column_picker {
+            Some(_) => None,
+            None => Some(ColumnPicker::new(&self.columns)),
+        };
+        let open = self.column_picker.is_some();
+        self.set_view_layer(Mode::ColumnMenu, open);
+    }
 
-fn controls_layout(app: &App, width: u16) -> (bool, usize, u16) {
-    let max_ifaces = max_visible_ifaces(width);
-    let left_width = left_toggle_width(app);
+    fn save_columns(&mut self) {
+        let Some(path) = self.config_path.clone() else {
+            self.status
+                .error("Saving columns failed: no config directory");
+            return;
+        };
+        match config::save_columns(&path, &self.columns) {
+            Ok(()) => self
+                .status
+                .info(format!("Saved columns to {}", path.display())),
+            Err(err) => self.status.error(format!("Saving columns failed: {}", err)),
+        }
+    }
 
-    for visible_ifaces in (0..=max_ifaces).rev() {
-        let right_width = interface_row_width(app, visible_ifaces, width);
-        if left_width.saturating_add(2).saturating_add(right_width) <= width {
-            let right_start = width.saturating_sub(right_width);
-            return (true, visible_ifaces, right_start);
+    fn open_preset_picker(&mut self) {
+        let loaded = match self.presets_path.as_deref() {
+            Some(path) => presets::load(path),
+            None => Ok(Vec::new()),
+        };
+        match loaded {
+            Ok(list) => {
+                self.preset_picker = Some(PresetPicker::new(list));
+                self.push_mode(Mode::PresetMenu);
+            }
+            Err(err) => self
+                .status
+                .error(format!("Reading presets failed: {}", err)),
         }
     }
 
-    (false, max_ifaces, 0)
-}
+    // The current view constraints in preset form; defaults are left out.
+    fn preset_settings(&self) -> Vec<(String, String)> {
+        let mut settings: Vec<(String, String)> = FilterField::ALL
+            .iter()
+            .filter(|field| !self.filters.value(**field).is_empty())
+            .map(|field| {
+                (
+                    field.label().to_string(),
+                    self.filters.value(*field).to_string(),
+                )
+            })
+            .collect();
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        settings.extend([
+            (
+                "iface".to_string(),
+                self.selected_interface
+                    .as_deref()
+                    .unwrap_or("*")
+                    .to_string(),
+            ),
+            ("flow".to_string(), self.flow_filter.label().to_string()),
+            ("dir".to_string(), self.direction_filter.label().to_string()),
+            ("local".to_string(), on_off(self.show_local_src)),
+            ("wan".to_string(), on_off(self.show_wan_src)),
+            ("bcast".to_string(), on_off(self.show_broadcast)),
+            ("replies".to_string(), on_off(self.show_replies)),
+            ("bogon".to_string(), on_off(self.bogon_only)),
+            ("listed".to_string(), on_off(self.listed_only)),
+        ]);
+        settings
+    }
 
-fn left_toggle_spans(app: &App) -> Vec<Span<'static>> {
-    vec![
-        Span::styled(
-            "[local]",
-            if app.show_local_src {
-                Style::default()
-                    .fg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-            } else {
-                Style::default().fg(Color::Gray)
-            },
-        ),
-        Span::raw(" "),
-        Span::styled(
-            "[wan]",
-            if app.show_wan_src {
-                Style::default()
-                    .fg(Color::LightCyan)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-            } else {
-                Style::default().fg(Color::Gray)
-            },
-        ),
-        Span::raw(" "),
-        Span::styled(
-            app.flow_filter.chip(),
-            if app.flow_filter == FlowFilter::All {
-                Style::default().fg(Color::Gray)
-            } else {
-                Style::default()
-                    .fg(Color::LightYellow)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-            },
-        ),
-        Span::raw(" "),
-        Span::styled(
-            app.direction_filter.chip(),
-            if app.direction_filter == DirectionFilter::Both {
-                Style::default().fg(Color::Gray)
-            } else {
-                Style::default()
-                    .fg(Color::LightMagenta)
-                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-            },
-        ),
-    ]
-}
+    fn apply_preset(&mut self, pos: usize) {
+        let Some(preset) = self
+            .preset_picker
+            .as_ref()
+            .and_then(|picker| picker.presets.get(pos))
+            .cloned()
+        else {
+            return;
+        };
+        self.preset_picker = None;
+        let before = self.current_view_preset();
+        self.apply_view_settings(&preset);
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Applied preset {}. Matching rows: {}",
+            preset.name, matches
+        ));
+        self.offer_pin();
+        self.remember_filters(before);
+    }
 
-fn interface_display_spans(app: &App, max_interfaces: usize, row_width: u16) -> Vec<Span<'static>> {
-    let mut spans: Vec<Span<'static>> = Vec::new();
-    spans.push(Span::styled(
-        interface_prefix(row_width),
-        Style::default().fg(Color::DarkGray),
-    ));
-
-    let all_selected = app.selected_interface.is_none();
-    let all_style = if all_selected {
-        Style::default()
-            .fg(Color::White)
-            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-    } else {
-        Style::default().fg(Color::Gray)
-    };
-    spans.push(Span::styled(
-        if all_selected {
-            "[all]".to_string()
+    // "port=22 ip≠10.0.0.5" for the active filters.
+    // With `redact`, the ip filter's address is masked like the table's.
+    fn filters_label(&self, redact: bool) -> String {
+        let active: Vec<String> = FilterField::ALL
+            .iter()
+            .filter(|field| !self.filters.value(**field).is_empty())
+            .map(|field| {
+                let value = self.filters.value(*field);
+                let value = match value.strip_prefix('!') {
+                    _ if !redact || *field != FilterField::Ip => value.to_string(),
+                    Some(ip) => format!("!{}", redact_ip(ip)),
+                    None => redact_ip(value),
+                };
+                filter_assignment(field.label(), &value)
+            })
+            .collect();
+        if active.is_empty() {
+            "no filters".to_string()
         } else {
-            "all".to_string()
-        },
-        all_style,
-    ));
+            active.join(" ")
+        }
+    }
 
-    for iface in app.interface_options.iter().take(max_interfaces) {
-        spans.push(Span::raw(" "));
-        let is_selected = app.selected_interface.as_deref() == Some(iface.as_str());
-        let base = if is_wan_candidate_interface(iface) {
-            Color::LightGreen
-        } else {
-            Color::LightCyan
+    fn current_view_preset(&self) -> Preset {
+        Preset {
+            name: self.filters_label(false),
+            settings: self.preset_settings(),
+        }
+    }
+
+    // Puts the current view first in the recent list. The view it replaced
+    // is kept too, so the very first change can already be flipped back.
+    fn remember_filters(&mut self, before: Preset) {
+        if self.recent_filters.is_empty() {
+            self.recent_filters.push(before);
+        }
+        let current = self.current_view_preset();
+        self.recent_filters
+            .retain(|recent| recent.settings != current.settings);
+        self.recent_filters.insert(0, current);
+        self.recent_filters.truncate(RECENT_FILTERS);
+    }
+
+    // `"`: lists the recent views; the next digit recalls one.
+    fn start_recent_filters(&mut self) {
+        if self.recent_filters.is_empty() {
+            self.status.info("No recent filters yet");
+            return;
+        }
+        let listed: Vec<String> = self
+            .recent_filters
+            .iter()
+            .enumerate()
+            .map(|(pos, recent)| format!("{} {}", pos + 1, recent.name))
+            .collect();
+        self.status
+            .info(format!("Recent filters: {}", listed.join(" · ")));
+        self.recent_leader = true;
+        self.push_mode(Mode::RecentLeader);
+    }
+
+    // Recalling a view moves it to the front, so `"2` flips between the two
+    // latest contexts.
+    fn apply_recent_filters(&mut self, pos: usize) {
+        let Some(recent) = self.recent_filters.get(pos).cloned() else {
+            return;
         };
-        let style = if is_selected {
-            Style::default()
-                .fg(base)
-                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-        } else {
-            Style::default().fg(Color::Gray)
+        let before = self.current_view_preset();
+        self.apply_view_settings(&recent);
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Recalled {}. Matching rows: {}",
+            recent.name, matches
+        ));
+        self.offer_pin();
+        self.remember_filters(before);
+    }
+
+    // Replaces the view constraints with the preset's; keys it lacks get the
+    // same defaults as `c`.
+    fn apply_view_settings(&mut self, preset: &Preset) {
+        self.pin_candidate = self.current_selected_raw();
+        self.filters = Filters::default();
+        for field in FilterField::ALL {
+            if let Some(value) = preset.get(field.label()) {
+                self.filters.set(field, value.to_string());
+            }
+        }
+        let toggle = |key: &str, default: bool| preset.get(key).map_or(default, |v| v == "on");
+        self.show_local_src = toggle("local", false);
+        self.show_wan_src = toggle("wan", true);
+        self.show_broadcast = toggle("bcast", true);
+        self.show_replies = toggle("replies", true);
+        self.bogon_only = toggle("bogon", false);
+        self.listed_only = toggle("listed", false);
+        self.flow_filter = preset
+            .get("flow")
+            .and_then(FlowFilter::from_label)
+            .unwrap_or_default();
+        self.direction_filter = preset
+            .get("dir")
+            .and_then(DirectionFilter::from_label)
+            .unwrap_or_default();
+        self.selected_interface = match preset.get("iface") {
+            Some("*") => None,
+            Some(name) => Some(name.to_string()),
+            None => default_wan_interface(&self.interface_options),
         };
-        let label = if is_selected {
-            format!("[{}]", iface)
-        } else {
-            iface.clone()
+        self.time_window = None;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+    }
+
+    fn save_preset(&mut self, name: String) {
+        let Some(path) = self.presets_path.clone() else {
+            self.status
+                .error("Cannot save presets: neither XDG_CONFIG_HOME nor HOME is set");
+            return;
         };
-        spans.push(Span::styled(label, style));
+        let preset = Preset {
+            name: name.clone(),
+            settings: self.preset_settings(),
+        };
+        let Some(picker) = self.preset_picker.as_mut() else {
+            return;
+        };
+        let mut list = picker.presets.clone();
+        presets::upsert(&mut list, preset);
+        if list.len() > presets::MAX_PRESETS {
+            self.status.warn(format!(
+                "Preset not saved: at most {} presets; delete one with d first",
+                presets::MAX_PRESETS
+            ));
+            return;
+        }
+        match presets::save(&path, &list) {
+            Ok(()) => {
+                picker.presets = list;
+                self.status.info(format!("Saved preset {}", name));
+            }
+            Err(err) => self.status.error(format!(
+                "Saving preset to {} failed: {}",
+                path.display(),
+                err
+            )),
+        }
     }
 
-    if app.interface_options.len() > max_interfaces {
-        spans.push(Span::raw(" "));
-        spans.push(Span::styled(
-            format!(" +{}", app.interface_options.len() - max_interfaces),
-            Style::default().fg(Color::DarkGray),
-        ));
+    fn delete_preset(&mut self, pos: usize) {
+        let (Some(path), Some(picker)) = (self.presets_path.clone(), self.preset_picker.as_mut())
+        else {
+            return;
+        };
+        if pos >= picker.presets.len() {
+            return;
+        }
+        let mut list = picker.presets.clone();
+        let removed = list.remove(pos);
+        match presets::save(&path, &list) {
+            Ok(()) => {
+                picker.presets = list;
+                self.status.info(format!("Deleted preset {}", removed.name));
+            }
+            Err(err) => self.status.error(format!(
+                "Saving presets to {} failed: {}",
+                path.display(),
+                err
+            )),
+        }
     }
 
-    spans
-}
+    // The layer that takes the keys: the newest one still open. Layers that
+    // close themselves (a submitted prompt, an applied preset) are skipped
+    // here and pruned on the next push.
+    fn mode(&self) -> Mode {
+        self.mode_stack
+            .iter()
+            .rev()
+            .copied()
+            .find(|mode| self.layer_open(*mode))
+            .unwrap_or(Mode::Normal)
+    }
 
-fn handle_modifier_shortcuts(app: &mut App, key: &crossterm::event::KeyEvent) -> bool {
-    if let KeyCode::F(n) = key.code
-        && let Some(field) = field_from_fkey(n)
-    {
-        if key.modifiers.contains(KeyModifiers::SHIFT) {
-            app.clear_filter(field);
-        } else {
-            app.set_input_mode(field);
+    fn layer_open(&self, mode: Mode) -> bool {
+        match mode {
+            Mode::Normal => true,
+            Mode::RecentLeader => self.recent_leader,
+            Mode::FilterEdit(field) => self.input_mode == Some(field),
+            Mode::Search => self.find_prompt.is_some(),
+            Mode::SourcePrompt => self.source_prompt.is_some(),
+            Mode::ExportPrompt => self.export_prompt.is_some(),
+            Mode::Talkers => self.talkers.as_ref().is_some_and(|sidebar| sidebar.focused),
+            Mode::EgressReview => self.egress_review.is_some(),
+            Mode::Countries => self.country_summary.is_some(),
+            Mode::ColumnMenu => self.column_picker.is_some(),
+            Mode::PresetMenu => self.preset_picker.is_some(),
+            Mode::PresetName => self
+                .preset_picker
+                .as_ref()
+                .is_some_and(|picker| picker.naming.is_some()),
+            Mode::PresetDelete => self
+                .preset_picker
+                .as_ref()
+                .is_some_and(|picker| picker.deleting),
+            Mode::Confirm => self.block_prompt.is_some(),
+            Mode::Popup => self.report.is_some(),
+            Mode::Help => self.help.is_some(),
+            Mode::Stats => self.show_stats,
+            Mode::Aggregate => self.aggregate,
+            Mode::Alerts => self.show_alerts,
         }
-        return true;
     }
 
-    false
-}
+    // Puts `mode` on top, moving it there if it was already open lower down.
+    // Call after setting the layer's state.
+    fn push_mode(&mut self, mode: Mode) {
+        let stack = std::mem::take(&mut self.mode_stack);
+        self.mode_stack = stack
+            .into_iter()
+            .filter(|open| *open != mode && self.layer_open(*open))
+            .collect();
+        self.mode_stack.push(mode);
+    }
 
-fn main_chunks(area: Rect, filters_height: u16) -> Vec<Rect> {
-    Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1),
-            Constraint::Length(filters_height),
-            Constraint::Min(5),
-            Constraint::Length(3),
-            Constraint::Length(2),
-        ])
-        .split(area)
-        .to_vec()
-}
+    // Pushes or drops a view layer after its key toggled it.
+    fn set_view_layer(&mut self, mode: Mode, open: bool) {
+        if open {
+            self.push_mode(mode);
+        } else {
+            self.mode_stack.retain(|layer| *layer != mode);
+        }
+    }
 
-fn text_cells(text: &str) -> u16 {
-    text.chars().count().min(u16::MAX as usize) as u16
-}
+    fn show_report(&mut self, report: Report) {
+        self.report = Some(report);
+        self.push_mode(Mode::Popup);
+    }
 
-fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
-    if max_width == 0 {
-        return String::new();
+    // Help or the other popup, whichever was opened last.
+    fn report_layer(&self) -> Option<Mode> {
+        self.mode_stack
+            .iter()
+            .rev()
+            .copied()
+            .find(|mode| matches!(mode, Mode::Help | Mode::Popup) && self.layer_open(*mode))
     }
-    let cell_count = text.chars().count();
-    if cell_count <= max_width {
-        return text.to_string();
+
+    fn shown_report(&self) -> Option<&Report> {
+        match self.report_layer()? {
+            Mode::Help => self.help.as_ref(),
+            _ => self.report.as_ref(),
+        }
     }
-    if max_width <= 3 {
-        return ".".repeat(max_width);
+
+    fn shown_report_mut(&mut self) -> Option<&mut Report> {
+        match self.report_layer()? {
+            Mode::Help => self.help.as_mut(),
+            _ => self.report.as_mut(),
+        }
     }
-    let mut out = String::with_capacity(max_width);
-    for ch in text.chars().take(max_width - 3) {
-        out.push(ch);
+
+    // Closes the top layer, as Esc does; the table itself stays.
+    fn pop_mode(&mut self) {
+        let mode = self.mode();
+        while let Some(top) = self.mode_stack.pop() {
+            if top == mode {
+                break;
+            }
+        }
+        match mode {
+            Mode::Normal => {}
+            Mode::RecentLeader => self.recent_leader = false,
+            Mode::FilterEdit(_) => {
+                self.input_mode = None;
+                self.input_buffer.clear();
+                self.completion = None;
+            }
+            Mode::Search => self.finish_find(false),
+            Mode::SourcePrompt => self.source_prompt = None,
+            Mode::ExportPrompt => self.export_prompt = None,
+            Mode::Talkers => {
+                if let Some(sidebar) = self.talkers.as_mut() {
+                    sidebar.focused = false;
+                }
+            }
+            Mode::EgressReview => self.egress_review = None,
+            Mode::Countries => self.country_summary = None,
+            Mode::ColumnMenu => self.column_picker = None,
+            Mode::PresetMenu => self.preset_picker = None,
+            Mode::PresetName | Mode::PresetDelete => {
+                if let Some(picker) = self.preset_picker.as_mut() {
+                    picker.naming = None;
+                    picker.deleting = false;
+                }
+            }
+            Mode::Confirm => {
+                let kind = self.block_prompt.take().map(|action| action.kind);
+                self.status.info(match kind {
+                    Some(RuleKind::AllowOut) => "Allow rule cancelled",
+                    _ => "Block cancelled",
+                });
+            }
+            Mode::Popup => self.report = None,
+            Mode::Help => self.help = None,
+            Mode::Stats => self.toggle_stats(),
+            Mode::Aggregate => self.toggle_aggregate(),
+            Mode::Alerts => self.toggle_alerts(),
+        }
     }
-    out.push_str("...");
-    out
-}
 
-fn pad_to_width(text: &str, width: usize) -> String {
-    let used = text.chars().count();
-    if used >= width {
-        return text.to_string();
+    fn set_input_mode(&mut self, field: FilterField) {
+        self.input_mode = Some(field);
+        self.push_mode(Mode::FilterEdit(field));
+        self.input_buffer = self.get_filter_value(field).to_string();
+        self.completion = None;
     }
-    format!("{}{}", text, " ".repeat(width - used))
-}
 
-fn build_three_column_line(width: u16, cells: [String; 3]) -> Line<'static> {
-    let total = usize::from(width.max(3));
-    let gap = if total >= 120 {
-        3
-    } else if total >= 90 {
-        2
-    } else {
-        1
-    };
-    let usable = total.saturating_sub(gap * 2);
-    let col1 = usable / 3;
-    let col2 = usable / 3;
-    let col3 = usable.saturating_sub(col1 + col2);
-    let c1 = truncate_with_ellipsis(&cells[0], col1);
-    let c2 = truncate_with_ellipsis(&cells[1], col2);
-    let c3 = truncate_with_ellipsis(&cells[2], col3);
-    let line = format!(
-        "{}{}{}{}{}",
-        pad_to_width(&c1, col1),
-        " ".repeat(gap),
-        pad_to_width(&c2, col2),
-        " ".repeat(gap),
-        c3
-    );
-    Line::from(vec![Span::raw(line)])
-}
+    // Values of `field` in the loaded entries, one per entry, for completion.
+    // Free text and time ranges have none.
+    fn observed_values(&self, field: FilterField) -> Vec<String> {
+        let mut values = Vec::new();
+        for entry in &self.entries {
+            match field {
+                FilterField::Service => values.extend(entry.service.clone()),
+                FilterField::Port => values.extend(entry.dst_port.map(|port| port.to_string())),
+                FilterField::Ip => values.extend(entry.src_ip.iter().chain(&entry.dst_ip).cloned()),
+                FilterField::Action => values.push(entry.action.clone()),
+                FilterField::Proto => values.extend(entry.proto.clone()),
+                FilterField::Country => values.extend(entry.country.clone()),
+                FilterField::Flags => {
+                    values.extend(entry.tcp_flags.names().into_iter().map(str::to_string))
+                }
+                FilterField::Text | FilterField::Time => return values,
+            }
+        }
+        values
+    }
 
-fn filter_summary_one_line_text(app: &App) -> String {
-    format!(
-        "F1 svc={} | F2 port={} | F3 ip={} | F4 action={} | F5 proto={} | F6 text={} ({} active)",
-        display_filter_value(&app.filters.service),
-        display_filter_value(&app.filters.port),
-        display_filter_value(&app.filters.ip),
-        display_filter_value(&app.filters.action),
-        display_filter_value(&app.filters.proto),
-        display_filter_value(&app.filters.text),
-        app.filters.active_count()
-    )
-}
+    // Tab in a filter prompt fills in the most common matching value; further
+    // presses cycle through the rest.
+    fn complete_filter_input(&mut self, field: FilterField, backwards: bool) {
+        if let Some(completion) = self.completion.as_mut() {
+            completion.cycle(backwards);
+        } else {
+            let values = self.observed_values(field);
+            self.completion =
+                Completion::new(&self.input_buffer, values.iter().map(String::as_str));
+        }
+        match self.completion.as_ref() {
+            Some(completion) => self.input_buffer = completion.text(),
+            None => self
+                .status
+                .info(format!("No loaded {} values to complete", field.label())),
+        }
+    }
 
-fn filter_summary_line_count(app: &App, width: u16) -> u16 {
-    let one_line = filter_summary_one_line_text(app);
-    if text_cells(&one_line) <= width { 1 } else { 2 }
-}
+    fn get_filter_value(&self, field: FilterField) -> &str {
+        self.filters.value(field)
+    }
 
-fn filter_summary_lines(app: &App, width: u16) -> Vec<Line<'static>> {
-    let one_line = filter_summary_one_line_text(app);
-    if text_cells(&one_line) <= width {
-        return vec![Line::from(one_line)];
+    // Rows each interface chip would show under the other view constraints.
+    fn refresh_interface_counts(&mut self) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in &self.entries {
+            if !self.matches_view_on_any_interface(entry) {
+                continue;
+            }
+            let in_iface = entry.in_iface.as_deref().filter(|name| !name.is_empty());
+            let out_iface = entry.out_iface.as_deref().filter(|name| !name.is_empty());
+            for name in in_iface
+                .into_iter()
+                .chain(out_iface.filter(|out| Some(*out) != in_iface))
+                .filter(|name| interface_allowed(&self.interface_globs, name))
+            {
+                *counts.entry(name.to_string()).or_default() += 1;
+            }
+        }
+        self.interface_counts = counts;
     }
 
-    let row1 = build_three_column_line(
-        width,
-        [
-            format!("F1 svc={}", display_filter_value(&app.filters.service)),
-            format!("F2 port={}", display_filter_value(&app.filters.port)),
-            format!("F3 ip={}", display_filter_value(&app.filters.ip)),
-        ],
-    );
-    let row2 = build_three_column_line(
-        width,
-        [
-            format!("F4 action={}", display_filter_value(&app.filters.action)),
-            format!("F5 proto={}", display_filter_value(&app.filters.proto)),
-            format!(
-                "F6 text={} ({} active)",
-                display_filter_value(&app.filters.text),
-                app.filters.active_count()
-            ),
-        ],
-    );
-    vec![row1, row2]
-}
+    // Recounts per-filter exclusions only when the filters or entries changed.
+    fn refresh_filter_exclusions(&mut self) {
+        let newest_id = self.entries.first().map(|entry| entry.id);
+        if self.filter_exclusions.as_ref().is_some_and(|cached| {
+            cached.filters == self.filters
+                && cached.entries == self.entries.len()
+                && cached.newest_id == newest_id
+        }) {
+            return;
+        }
+        let mut counts = [0; FilterField::ALL.len()];
+        for (count, field) in counts.iter_mut().zip(FilterField::ALL) {
+            if self.filters.value(field).is_empty() {
+                continue;
+            }
+            let only = self.filters.only(field);
+            *count = self
+                .entries
+                .iter()
+                .filter(|entry| !only.matches(entry))
+                .count();
+        }
+        self.filter_exclusions = Some(FilterExclusions {
+            filters: self.filters.clone(),
+            entries: self.entries.len(),
+            newest_id,
+            counts,
+        });
+    }
 
-fn filter_panel_height(app: &App, width: u16) -> u16 {
-    // Keep the rendered filter panel height and mouse-hitbox rows in sync.
-    let summary_rows = filter_summary_line_count(app, width);
-    let controls_rows = if controls_layout(app, width).0 { 1 } else { 2 };
-    summary_rows.saturating_add(controls_rows)
-}
+    // How many entries `field` excludes on its own, if known and active.
+    fn filter_exclusion(&self, field: FilterField) -> Option<usize> {
+        let cached = self.filter_exclusions.as_ref()?;
+        if self.filters.value(field).is_empty() || cached.filters != self.filters {
+            return None;
+        }
+        let pos = FilterField::ALL.iter().position(|f| *f == field)?;
+        Some(cached.counts[pos])
+    }
 
-fn rect_contains(rect: Rect, x: u16, y: u16) -> bool {
-    x >= rect.x
-        && x < rect.x.saturating_add(rect.width)
-        && y >= rect.y
-        && y < rect.y.saturating_add(rect.height)
-}
+    fn set_filter_value(&mut self, field: FilterField, value: String) {
+        self.pin_candidate = self.current_selected_raw();
+        self.filters.set(field, value.trim().to_string());
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+    }
 
-fn push_toggle_hitbox(
-    hitboxes: &mut Vec<(Rect, ToggleTarget)>,
-    x: u16,
-    y: u16,
-    label: &str,
-    target: ToggleTarget,
-    bounds: Rect,
-) {
-    let width = text_cells(label);
-    if width == 0 {
-        return;
+    fn clear_filter(&mut self, field: FilterField) {
+        let before = self.current_view_preset();
+        self.set_filter_value(field, String::new());
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Cleared {} filter. Matching rows: {}",
+            field.label(),
+            matches
+        ));
+        self.offer_pin();
+        self.remember_filters(before);
     }
-    let end_x = x.saturating_add(width);
-    let bounds_end_x = bounds.x.saturating_add(bounds.width);
-    if x >= bounds_end_x || end_x <= bounds.x || !rect_contains(bounds, x.max(bounds.x), y) {
-        return;
+
+    // Applies one input event. Mouse hit-testing uses the area of the last
+    // drawn frame, so no terminal handle is needed.
+    fn handle_event(&mut self, event: &Event) -> Action {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => handle_key(self, *key),
+            Event::Mouse(mouse) => {
+                handle_mouse_event(self, *mouse, self.frame_area);
+                Action::Continue
+            }
+            Event::Resize(width, height) => {
+                self.frame_area = Rect::new(0, 0, *width, *height);
+                // Column sets and truncation follow the new width on the next draw.
+                self.render_cache.invalidate();
+                Action::Continue
+            }
+            _ => Action::Continue,
+        }
     }
-    let rect_x = x.max(bounds.x);
-    let rect_end_x = end_x.min(bounds_end_x);
-    let rect_width = rect_end_x.saturating_sub(rect_x);
-    if rect_width == 0 {
-        return;
+
+    fn bogon_source(&self, entry: &LogEntry) -> Option<&'static str> {
+        bogon_range(entry.src_ip.as_deref(), self.cgnat_local)
     }
-    hitboxes.push((
-        Rect {
-            x: rect_x,
-            y,
-            width: rect_width,
-            height: 1,
-        },
-        target,
-    ));
-}
 
-fn build_toggle_hitboxes(app: &App, filters_area: Rect) -> Vec<(Rect, ToggleTarget)> {
-    if filters_area.height < 2 || filters_area.width == 0 {
-        return Vec::new();
+    // Name of the `--blocklist` file listing the entry's source.
+    fn blocklisted(&self, entry: &LogEntry) -> Option<&str> {
+        self.blocklists.as_ref()?.list_for(entry.src_ip.as_deref())
     }
-    let mut hitboxes = Vec::new();
-    let (combined, visible_ifaces, iface_right_start) = controls_layout(app, filters_area.width);
-    let summary_rows = filter_summary_line_count(app, filters_area.width);
-    let toggles_y = filters_area.y.saturating_add(summary_rows);
-    let mut x = filters_area.x;
 
-    let local = "[local]";
-    push_toggle_hitbox(
-        &mut hitboxes,
-        x,
-        toggles_y,
-        local,
-        ToggleTarget::Local,
-        filters_area,
-    );
-    x = x.saturating_add(text_cells(local)).saturating_add(1);
+    // Inbound ALLOW to a port outside `--expect-ports`: usually a rule that
+    // opened more than intended.
+    fn is_unexpected_allow(&self, entry: &LogEntry) -> bool {
+        let Some(expected) = self.expected_ports.as_ref() else {
+            return false;
+        };
+        entry.action == "ALLOW"
+            && matches!(entry.direction(), "IN" | "FWD")
+            && entry.dst_port.is_some_and(|port| !expected.contains(&port))
+    }
 
-    let wan = "[wan]";
-    push_toggle_hitbox(
-        &mut hitboxes,
-        x,
-        toggles_y,
-        wan,
-        ToggleTarget::WanSrc,
-        filters_area,
-    );
-    x = x.saturating_add(text_cells(wan)).saturating_add(1);
+    // Interface, source scope, flow, direction and text filters combined.
+    fn matches_view(&self, entry: &LogEntry) -> bool {
+        self.view_context().matches_interface(entry) && self.matches_view_on_any_interface(entry)
+    }
 
-    let flow = app.flow_filter.chip();
-    push_toggle_hitbox(
-        &mut hitboxes,
-        x,
-        toggles_y,
-        flow,
-        ToggleTarget::Flow,
-        filters_area,
-    );
-    x = x.saturating_add(text_cells(flow)).saturating_add(2);
+    // Filters, interface and time window shared by every view.
+    fn view_context(&self) -> ViewContext<'_> {
+        ViewContext {
+            filters: &self.filters,
+            interface: self.selected_interface.as_deref(),
+            window: self.time_window.as_ref(),
+        }
+    }
 
-    let dir = app.direction_filter.chip();
-    push_toggle_hitbox(
-        &mut hitboxes,
-        x,
-        toggles_y,
-        dir,
-        ToggleTarget::Direction,
-        filters_area,
-    );
+    // Entries inside the shared scope, newest first. Reports use these so
+    // they cover what the table shows instead of the whole log.
+    fn scoped_entries(&self) -> Vec<&LogEntry> {
+        let context = self.view_context();
+        self.entries
+            .iter()
+            .filter(|entry| context.matches(entry))
+            .collect()
+    }
 
-    let iface_y = if combined {
-        toggles_y
-    } else if filters_area.height > summary_rows.saturating_add(1) {
-        toggles_y.saturating_add(1)
-    } else {
-        return hitboxes;
-    };
-    let iface_start_x = if combined {
-        filters_area.x.saturating_add(iface_right_start)
-    } else {
-        filters_area.x
-    };
-    let mut iface_x =
-        iface_start_x.saturating_add(text_cells(interface_prefix(filters_area.width)));
+    // " · port=22 · eth0" for report titles; empty when nothing is scoped.
+    fn scope_suffix(&self) -> String {
+        self.view_context()
+            .describe()
+            .map(|scope| format!(" · {}", scope))
+            .unwrap_or_default()
+    }
 
-    let all_label = if app.selected_interface.is_none() {
-        "[all]"
-    } else {
-        "all"
-    };
-    push_toggle_hitbox(
-        &mut hitboxes,
-        iface_x,
-        iface_y,
-        all_label,
-        ToggleTarget::AllIfaces,
-        filters_area,
-    );
-    iface_x = iface_x.saturating_add(text_cells(all_label));
+    // Locks the time window around the selected entry, or releases it.
+    fn toggle_time_lock(&mut self) {
+        if self.time_window.take().is_none() {
+            let filtered = self.view_indices();
+            let Some(&idx) = filtered.get(min(self.selected, filtered.len().saturating_sub(1)))
+            else {
+                return;
+            };
+            let timestamp = self.entries[idx].timestamp.clone();
+            let Some(window) = TimeWindow::around(&timestamp, TIME_LOCK_RADIUS_SECS) else {
+                self.status.info("Selected row has no parsable timestamp");
+                return;
+            };
+            self.pin_candidate = self.current_selected_raw();
+            self.time_window = Some(window);
+        } else {
+            self.pin_candidate = self.current_selected_raw();
+        }
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(match self.time_window.as_ref() {
+            Some(window) => format!(
+                "Time locked to {} in every view. Matching rows: {}",
+                window.label(),
+                matches
+            ),
+            None => format!("Time lock released. Matching rows: {}", matches),
+        });
+        self.offer_pin();
+    }
 
-    for iface in app.interface_options.iter().take(visible_ifaces) {
-        iface_x = iface_x.saturating_add(1);
-        let selected = app.selected_interface.as_deref() == Some(iface.as_str());
-        let label = if selected {
-            format!("[{}]", iface)
+    // Every view constraint except the interface selection.
+    fn matches_view_on_any_interface(&self, entry: &LogEntry) -> bool {
+        if !self.show_local_src && is_local_src_ip(entry.src_ip.as_deref()) {
+            return false;
+        }
+        if !self.show_wan_src && is_wan_src_ip(entry.src_ip.as_deref()) {
+            return false;
+        }
+        if !self.show_broadcast && is_broadcast_or_multicast_ip(entry.dst_ip.as_deref()) {
+            return false;
+        }
+        if !self.show_replies && is_return_traffic(entry) {
+            return false;
+        }
+        if self.bogon_only && self.bogon_source(entry).is_none() {
+            return false;
+        }
+        if self.listed_only && self.blocklisted(entry).is_none() {
+            return false;
+        }
+        matches_flow_filter(self.flow_filter, entry)
+            && matches_direction_filter(self.direction_filter, entry)
+            && self.view_context().matches_on_any_interface(entry)
+    }
+
+    fn filtered_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| self.matches_view(entry).then_some(idx))
+            .collect();
+        if self.sort_column != SortColumn::Time || !self.sort_descending {
+            indices.sort_by(|a, b| self.compare_view_order(*a, *b));
+        }
+        indices
+    }
+
+    // Row order for the active sort. Entries are stored newest first, so index
+    // order is time-descending; ties keep that order.
+    fn compare_view_order(&self, a: usize, b: usize) -> Ordering {
+        let ordering = match self.sort_column {
+            SortColumn::Time => b.cmp(&a),
+            column => compare_entries_by(column, &self.entries[a], &self.entries[b]),
+        };
+        let ordering = if self.sort_descending {
+            ordering.reverse()
         } else {
-            iface.clone()
+            ordering
         };
-        push_toggle_hitbox(
-            &mut hitboxes,
-            iface_x,
-            iface_y,
-            &label,
-            ToggleTarget::Interface(iface.clone()),
-            filters_area,
-        );
-        iface_x = iface_x.saturating_add(text_cells(&label));
+        ordering.then(a.cmp(&b))
     }
 
-    hitboxes
-}
+    fn set_sort(&mut self, column: SortColumn, descending: bool) {
+        let selected_raw = self.current_selected_raw();
+        self.sort_column = column;
+        self.sort_descending = descending;
+        let view = self.view_indices();
+        if let Some(raw) = selected_raw.as_deref()
+            && let Some(position) = selected_position_for_raw(&self.entries, &view, raw)
+        {
+            self.selected = position;
+        }
+        self.sync_selection_with_len(view.len());
+        self.status.info(format!(
+            "Sorted by {} {}",
+            column.label(),
+            sort_indicator(descending)
+        ));
+    }
 
-fn build_header_hitboxes(app: &App, header_area: Rect) -> Vec<(Rect, ToggleTarget)> {
-    if header_area.width == 0 || header_area.height == 0 {
-        return Vec::new();
+    // `o` steps through the sortable columns in table order.
+    fn cycle_sort_column(&mut self) {
+        let sortable: Vec<SortColumn> = self
+            .visible_columns()
+            .into_iter()
+            .filter_map(SortColumn::for_column)
+            .collect();
+        let column = match sortable.iter().position(|c| *c == self.sort_column) {
+            Some(pos) => sortable[(pos + 1) % sortable.len()],
+            None => sortable.first().copied().unwrap_or_default(),
+        };
+        self.set_sort(column, column.default_descending());
     }
-    let mut hitboxes = Vec::new();
-    let y = header_area.y;
-    let x = header_area.x.saturating_add(text_cells(APP_TITLE) + 1);
-    let chip = pause_chip_label(app.updates_paused);
-    push_toggle_hitbox(
-        &mut hitboxes,
-        x,
-        y,
-        chip,
-        ToggleTarget::PauseUpdates,
-        header_area,
-    );
-    hitboxes
-}
 
-fn apply_toggle_target(app: &mut App, target: ToggleTarget) {
-    match target {
-        ToggleTarget::Local => app.toggle_show_local_src(),
-        ToggleTarget::WanSrc => app.toggle_show_wan_src(),
-        ToggleTarget::Flow => app.cycle_flow_filter(),
-        ToggleTarget::Direction => app.cycle_direction_filter(),
-        ToggleTarget::PauseUpdates => app.toggle_pause_updates(),
-        ToggleTarget::AllIfaces => app.select_all_interfaces(),
-        ToggleTarget::Interface(name) => {
-            app.set_selected_interface(Some(name));
-            let matches = app.filtered_indices().len();
-            app.status = format!(
-                "Interface: {}. Matching rows: {}",
-                selected_iface_label(app.selected_interface.as_deref()),
-                matches
-            );
+    fn reverse_sort(&mut self) {
+        self.set_sort(self.sort_column, !self.sort_descending);
+    }
+
+    // Header click: a new column sorts in its natural direction, the active
+    // column flips direction.
+    fn sort_by_header(&mut self, column: SortColumn) {
+        if column == self.sort_column {
+            self.reverse_sort();
+        } else {
+            self.set_sort(column, column.default_descending());
         }
     }
-}
 
-fn handle_mouse_event(app: &mut App, mouse: MouseEvent, area: Rect) {
-    let filters_height = filter_panel_height(app, area.width);
-    let chunks = main_chunks(area, filters_height);
-    let mouse_x = mouse.column;
-    let mouse_y = mouse.row;
+    // Rows shown in the table: filter matches plus the pinned entry (when the
+    // filters hide it) at its chronological position.
+    fn view_indices(&self) -> Vec<usize> {
+        self.view_indices_with_pin().0
+    }
 
-    match mouse.kind {
-        MouseEventKind::Down(MouseButton::Left) => {
-            for (rect, target) in build_header_hitboxes(app, chunks[0]) {
-                if rect_contains(rect, mouse_x, mouse_y) {
-                    apply_toggle_target(app, target);
-                    return;
-                }
-            }
+    fn view_indices_with_pin(&self) -> (Vec<usize>, Option<usize>) {
+        if self.aggregate {
+            return (
+                aggregate::group(&self.entries, &self.filtered_indices()).0,
+                None,
+            );
+        }
+        let (indices, pinned_extra, _) = self.view_rows();
+        (indices, pinned_extra)
+    }
 
-            for (rect, target) in build_toggle_hitboxes(app, chunks[1]) {
-                if rect_contains(rect, mouse_x, mouse_y) {
-                    apply_toggle_target(app, target);
-                    return;
-                }
-            }
+    // Rows of the per-line table: the filtered entries with flood runs folded
+    // (in time order only) plus the pinned entry, and the burst behind each
+    // folded row.
+    fn view_rows(&self) -> (Vec<usize>, Option<usize>, HashMap<usize, Burst>) {
+        let filtered = self.filtered_indices();
+        let (mut indices, bursts) = if self.sort_column == SortColumn::Time {
+            burst::compress(&self.entries, &filtered, &self.expanded_bursts)
+        } else {
+            (filtered, HashMap::new())
+        };
+        let mut pinned_extra = None;
+        if let Some(pinned) = self.pinned_entry_index()
+            && let Err(pos) =
+                indices.binary_search_by(|probe| self.compare_view_order(*probe, pinned))
+        {
+            indices.insert(pos, pinned);
+            pinned_extra = Some(pinned);
+        }
+        (indices, pinned_extra, bursts)
+    }
 
-            let table_area = chunks[2];
-            if rect_contains(table_area, mouse_x, mouse_y) && table_area.height >= 3 {
-                let rows_start_y = table_area.y + 2;
-                let rows_end_y = table_area.y + table_area.height - 1;
-                if mouse_y >= rows_start_y && mouse_y < rows_end_y {
-                    let visible_row_index = (mouse_y - rows_start_y) as usize;
-                    app.select_visible_row_index(visible_row_index);
-                }
-            }
+    // `+` unfolds the flood run under the cursor, or folds it back.
+    fn toggle_burst(&mut self) {
+        if self.aggregate || self.sort_column != SortColumn::Time {
+            self.status
+                .warn("Bursts fold in the per-line view sorted by time");
+            return;
         }
-        MouseEventKind::ScrollUp => {
-            let table_area = chunks[2];
-            if rect_contains(table_area, mouse_x, mouse_y) {
-                let filtered_len = app.filtered_indices().len();
-                if filtered_len > 0 {
-                    app.selected = app.selected.saturating_sub(1);
-                    app.sync_selection_with_len(filtered_len);
-                }
-            }
+        let Some(&selected) = self.view_indices().get(self.selected) else {
+            return;
+        };
+        let indices = self.filtered_indices();
+        let run = indices
+            .iter()
+            .position(|&idx| idx == selected)
+            .and_then(|pos| {
+                burst::runs(&self.entries, &indices)
+                    .into_iter()
+                    .find(|run| run.contains(&pos))
+            });
+        let Some(run) = run else {
+            self.status.warn(format!(
+                "No burst here: runs of {}+ identical rows fold",
+                burst::MIN_RUN
+            ));
+            return;
+        };
+        let key = burst::run_key(&self.entries, &indices, &run);
+        let count = group_thousands(run.len());
+        if self.expanded_bursts.insert(key) {
+            self.status
+                .info(format!("Expanded {} identical rows (+ folds them)", count));
+        } else {
+            self.expanded_bursts.remove(&key);
+            self.status.info(format!("Folded {} identical rows", count));
+        }
+        let first = indices[run.start];
+        let view = self.view_indices();
+        if let Some(position) = view.iter().position(|&idx| idx == first) {
+            self.selected = position;
+        }
+        self.sync_selection_with_len(view.len());
+    }
+
+    fn pinned_entry_index(&self) -> Option<usize> {
+        let raw = self.pinned_raw.as_deref()?;
+        self.entries.iter().position(|entry| entry.raw == raw)
+    }
+
+    // Called after a view change once the status is set: if the entry that was
+    // selected before the change is now hidden, hint that it can be pinned.
+    fn offer_pin(&mut self) {
+        let Some(raw) = self.pin_candidate.as_deref() else {
+            return;
+        };
+        let filtered = self.filtered_indices();
+        if self.pinned_raw.as_deref() == Some(raw)
+            || selected_position_for_raw(&self.entries, &filtered, raw).is_some()
+        {
+            self.pin_candidate = None;
+            return;
+        }
+        self.status
+            .append(" (selected entry hidden, press m to pin it)");
+    }
+
+    fn toggle_pin(&mut self) {
+        if self.pinned_raw.take().is_some() {
+            let len = self.view_indices().len();
+            self.sync_selection_with_len(len);
+            self.status.info("Unpinned entry");
+            return;
+        }
+        let Some(raw) = self.pin_candidate.take() else {
+            self.status
+                .warn("Nothing to pin: no selected entry was hidden by filters");
+            return;
+        };
+        self.pinned_raw = Some(raw.clone());
+        let view = self.view_indices();
+        if let Some(position) = selected_position_for_raw(&self.entries, &view, &raw) {
+            self.selected = position;
+        }
+        self.sync_selection_with_len(view.len());
+        self.status
+            .info("Pinned previously selected entry (m to unpin)");
+    }
+
+    // `previous` is the selection to keep if its chip is still there, with
+    // `Some(None)` for all interfaces; otherwise the WAN-like default is picked.
+    fn refresh_interface_options(&mut self, previous: Option<Option<String>>) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in &self.entries {
+            for iface in [&entry.in_iface, &entry.out_iface] {
+                let Some(name) = iface.as_ref() else {
+                    continue;
+                };
+                if name.is_empty() || !interface_allowed(&self.interface_globs, name) {
+                    continue;
+                }
+                *counts.entry(name.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let mut options: Vec<(String, usize)> = counts.into_iter().collect();
+        options.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        self.interface_options = interfaces::pinned_first(
+            options.into_iter().map(|(name, _)| name).collect(),
+            &self.pinned_interfaces,
+        );
+
+        self.selected_interface = match previous {
+            Some(None) => None,
+            Some(Some(prev)) if self.interface_options.contains(&prev) => Some(prev),
+            _ => default_wan_interface(&self.interface_options),
+        };
+    }
+
+    fn cycle_interface(&mut self, forward: bool) {
+        if self.interface_options.is_empty() {
+            self.selected_interface = None;
+            self.status.warn("No interfaces found in logs");
+            return;
+        }
+
+        let len = self.interface_options.len() as isize;
+        let mut idx = self
+            .selected_interface
+            .as_ref()
+            .and_then(|selected| {
+                self.interface_options
+                    .iter()
+                    .position(|iface| iface == selected)
+            })
+            .map_or(-1, |i| i as isize);
+
+        idx = if forward {
+            if idx >= len - 1 { -1 } else { idx + 1 }
+        } else if idx < 0 {
+            len - 1
+        } else {
+            idx - 1
+        };
+
+        self.set_selected_interface(if idx < 0 {
+            None
+        } else {
+            Some(self.interface_options[idx as usize].clone())
+        });
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Interface: {}. Matching rows: {}",
+            selected_iface_label(self.selected_interface.as_deref()),
+            matches
+        ));
+        self.offer_pin();
+    }
+
+    // `*`: pins the selected interface after the already pinned ones, or
+    // unpins it.
+    fn toggle_interface_pin(&mut self) {
+        let Some(iface) = self.selected_interface.clone() else {
+            self.status
+                .warn("Select an interface with , or . to pin it");
+            return;
+        };
+        let pinned = if let Some(pos) = self.pinned_interfaces.iter().position(|p| *p == iface) {
+            self.pinned_interfaces.remove(pos);
+            false
+        } else {
+            self.pinned_interfaces.push(iface.clone());
+            true
+        };
+        self.refresh_interface_options(Some(Some(iface.clone())));
+        let message = if pinned {
+            format!(
+                "Pinned {} ahead of the busier interfaces (* to unpin)",
+                iface
+            )
+        } else {
+            format!("Unpinned {}", iface)
+        };
+        let Some(path) = self.interfaces_path.as_deref() else {
+            self.status.warn(format!(
+                "{} for this session only (no config directory)",
+                message
+            ));
+            return;
+        };
+        self.status.report(
+            interfaces::save_pinned(path, &self.pinned_interfaces)
+                .map(|()| message)
+                .map_err(|err| format!("Saving pinned interfaces failed: {}", err)),
+        );
+    }
+
+    fn select_all_interfaces(&mut self) {
+        self.set_selected_interface(None);
+        let matches = self.filtered_indices().len();
+        self.status
+            .info(format!("Interface: all. Matching rows: {}", matches));
+        self.offer_pin();
+    }
+
+    fn select_default_wan_interface(&mut self) {
+        self.set_selected_interface(default_wan_interface(&self.interface_options));
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Interface: {}. Matching rows: {}",
+            selected_iface_label(self.selected_interface.as_deref()),
+            matches
+        ));
+        self.offer_pin();
+    }
+
+    fn set_selected_interface(&mut self, interface: Option<String>) {
+        self.pin_candidate = self.current_selected_raw();
+        self.selected_interface = interface;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+    }
+
+    fn toggle_show_local_src(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.show_local_src = !self.show_local_src;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(if self.show_local_src {
+            format!("Showing local source IP rows. Matching rows: {}", matches)
+        } else {
+            format!("Hiding local source IP rows. Matching rows: {}", matches)
+        });
+        self.offer_pin();
+    }
+
+    fn toggle_show_wan_src(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.show_wan_src = !self.show_wan_src;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(if self.show_wan_src {
+            format!("Showing WAN source IP rows. Matching rows: {}", matches)
+        } else {
+            format!("Hiding WAN source IP rows. Matching rows: {}", matches)
+        });
+        self.offer_pin();
+    }
+
+    fn toggle_show_broadcast(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.show_broadcast = !self.show_broadcast;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(if self.show_broadcast {
+            format!(
+                "Showing broadcast/multicast rows. Matching rows: {}",
+                matches
+            )
+        } else {
+            format!(
+                "Hiding broadcast/multicast rows. Matching rows: {}",
+                matches
+            )
+        });
+        self.offer_pin();
+    }
+
+    fn toggle_show_replies(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.show_replies = !self.show_replies;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(if self.show_replies {
+            format!("Showing return-traffic rows. Matching rows: {}", matches)
+        } else {
+            format!("Hiding return-traffic rows. Matching rows: {}", matches)
+        });
+        self.offer_pin();
+    }
+
+    fn toggle_bogon_only(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.bogon_only = !self.bogon_only;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(if self.bogon_only {
+            format!("Showing only bogon source rows. Matching rows: {}", matches)
+        } else {
+            format!("Showing all source ranges. Matching rows: {}", matches)
+        });
+        self.offer_pin();
+    }
+
+    // `|` opens the sidebar focused; once Esc hands the keys back to the
+    // table, `|` focuses it again, and `|` while focused closes it.
+    fn toggle_talkers(&mut self) {
+        match self.talkers.as_mut() {
+            None => {
+                self.talkers = Some(TalkerSidebar {
+                    focused: true,
+                    cursor: 0,
+                });
+                if sidebar_width(self, self.frame_area.width) == 0 {
+                    self.status.warn(format!(
+                        "Top talkers need a terminal at least {} columns wide",
+                        MIN_UI_WIDTH + SIDEBAR_WIDTH
+                    ));
+                } else {
+                    self.status
+                        .info("Top talkers: j/k to move, Enter to filter, Esc back to the table");
+                }
+            }
+            Some(sidebar) if !sidebar.focused => sidebar.focused = true,
+            Some(_) => self.talkers = None,
+        }
+        let focused = self.layer_open(Mode::Talkers);
+        self.set_view_layer(Mode::Talkers, focused);
+    }
+
+    // Sources and ports the sidebar lists now, sized to its last drawn height.
+    fn top_talkers(&self) -> Talkers {
+        let chunks = main_chunks(
+            self.frame_area,
+            filter_panel_height(self, self.frame_area.width),
+            lower_pane_height(self, self.frame_area.height),
+            detail_pane_height(self, self.frame_area.height),
+            sidebar_width(self, self.frame_area.width),
+        );
+        let rows = usize::from(chunks[6].height.saturating_sub(2));
+        let entries = self.filtered_indices();
+        Talkers::top(entries.iter().map(|&idx| &self.entries[idx]), rows)
+    }
+
+    fn move_talker_cursor(&mut self, delta: isize) {
+        let count = self.top_talkers().item_count();
+        if let Some(sidebar) = self.talkers.as_mut() {
+            sidebar.cursor = sidebar
+                .cursor
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
+        }
+    }
+
+    // Filters the view to sidebar item `pos` and hands the keys back to the
+    // table.
+    fn apply_talker(&mut self, pos: usize) {
+        let Some((field, value)) = self.top_talkers().filter(pos) else {
+            return;
+        };
+        if let Some(sidebar) = self.talkers.as_mut() {
+            sidebar.focused = false;
+            sidebar.cursor = 0;
+        }
+        let before = self.current_view_preset();
+        self.set_filter_value(field, value.clone());
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Set {} filter='{}'. Matching rows: {}",
+            field.label(),
+            value,
+            matches
+        ));
+        self.offer_pin();
+        self.remember_filters(before);
+    }
+
+    fn toggle_alerts(&mut self) {
+        self.show_alerts = !self.show_alerts;
+        self.set_view_layer(Mode::Alerts, self.show_alerts);
+        if self.show_alerts {
+            self.status
+                .info(format!("Alerts: {} port scan(s)", self.scans.alerts.len()));
+        }
+    }
+
+    fn toggle_listed_only(&mut self) {
+        if self.blocklists.is_none() && !self.listed_only {
+            self.status
+                .warn("No blocklists loaded (start with --blocklist FILE)");
+            return;
+        }
+        self.pin_candidate = self.current_selected_raw();
+        self.listed_only = !self.listed_only;
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(if self.listed_only {
+            format!(
+                "Showing only blocklisted source rows. Matching rows: {}",
+                matches
+            )
+        } else {
+            format!(
+                "Showing listed and unlisted sources. Matching rows: {}",
+                matches
+            )
+        });
+        self.offer_pin();
+    }
+
+    fn toggle_stats(&mut self) {
+        self.show_stats = !self.show_stats;
+        self.set_view_layer(Mode::Stats, self.show_stats);
+        self.status.info(if self.show_stats {
+            "Statistics for the current view (s to return to the table)".to_string()
+        } else {
+            "Back to the event table".to_string()
+        });
+    }
+
+    // Keeps the selection on the same flow: entering the aggregated view
+    // selects the selected entry's group, leaving it selects the group's row.
+    fn toggle_aggregate(&mut self) {
+        let selected = self
+            .view_indices()
+            .get(self.selected)
+            .map(|&idx| self.entries[idx].clone());
+        self.aggregate = !self.aggregate;
+        self.set_view_layer(Mode::Aggregate, self.aggregate);
+        let view = self.view_indices();
+        if let Some(selected) = selected.as_ref()
+            && let Some(position) = view
+                .iter()
+                .position(|&idx| aggregate::same_group(&self.entries[idx], selected))
+        {
+            self.selected = position;
+        }
+        self.sync_selection_with_len(view.len());
+        self.status.info(if self.aggregate {
+            format!(
+                "Aggregated {} rows into {} groups by source, dest, port, proto and action (= for raw lines)",
+                group_thousands(self.filtered_indices().len()),
+                group_thousands(view.len())
+            )
+        } else {
+            "Back to raw per-line rows".to_string()
+        });
+    }
+
+    // Snapshots are named by number and the newest entry they include.
+    fn take_snapshot(&mut self) {
+        self.snapshots_taken += 1;
+        let entries = self.scoped_entries();
+        let newest = entries
+            .first()
+            .map(|entry| format!(" at {}", entry.timestamp))
+            .unwrap_or_default();
+        let name = format!("#{}{}{}", self.snapshots_taken, newest, self.scope_suffix());
+        let snapshot = Snapshot::take(name, entries);
+        self.status.info(format!(
+            "Snapshot {} of {} entries (N to compare)",
+            snapshot.name,
+            group_thousands(snapshot.total)
+        ));
+        self.snapshot = Some(snapshot);
+    }
+
+    fn show_snapshot_diff(&mut self) {
+        let Some(snapshot) = &self.snapshot else {
+            self.status.warn("No snapshot yet (n takes one)");
+            return;
+        };
+        let diff = snapshot.diff(&Snapshot::take(String::new(), self.scoped_entries()));
+        let mut lines = vec![Line::from(format!(
+            "{} entries ({} → {})",
+            signed(diff.after as i64 - diff.before as i64),
+            group_thousands(diff.before),
+            group_thousands(diff.after)
+        ))];
+        lines.push(Line::from(""));
+        lines.push(heading(format!(
+            "{} new source IPs",
+            signed(diff.new_sources.len() as i64)
+        )));
+        for ip in diff.new_sources.iter().take(SNAPSHOT_LISTED) {
+            lines.push(Line::from(format!(
+                "  {}",
+                display_ip(Some(ip), self.redact)
+            )));
+        }
+        if diff.new_sources.len() > SNAPSHOT_LISTED {
+            lines.push(Line::from(format!(
+                "  … {} more",
+                diff.new_sources.len() - SNAPSHOT_LISTED
+            )));
+        }
+        for (title, deltas) in [
+            ("Actions", &diff.actions),
+            ("Blocks by service", &diff.blocks),
+        ] {
+            lines.push(Line::from(""));
+            lines.push(heading(title.to_string()));
+            for (key, delta) in deltas.iter().take(SNAPSHOT_LISTED) {
+                lines.push(Line::from(format!("{:>10}  {}", signed(*delta), key)));
+            }
+            if deltas.is_empty() {
+                lines.push(Line::from("  unchanged"));
+            }
+        }
+        self.show_report(Report::new(
+            format!("Since snapshot {}: {}", snapshot.name, diff.summary()),
+            lines,
+        ));
+    }
+
+    fn cycle_flow_filter(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.flow_filter = self.flow_filter.next();
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Flow filter: {}. Matching rows: {}",
+            self.flow_filter.label(),
+            matches
+        ));
+        self.offer_pin();
+    }
+
+    fn cycle_direction_filter(&mut self) {
+        self.pin_candidate = self.current_selected_raw();
+        self.direction_filter = self.direction_filter.next();
+        self.selected = 0;
+        self.log_entry_scroll = 0;
+        self.table_state.select(Some(0));
+        let matches = self.filtered_indices().len();
+        self.status.info(format!(
+            "Direction filter: {}. Matching rows: {}",
+            self.direction_filter.label(),
+            matches
+        ));
+        self.offer_pin();
+    }
+
+    fn toggle_minute_gutter(&mut self) {
+        self.show_minute_gutter = !self.show_minute_gutter;
+        self.status.info(if self.show_minute_gutter {
+            "Showing per-minute density gutter".to_string()
+        } else {
+            "Hiding per-minute density gutter".to_string()
+        });
+    }
+
+    fn toggle_redact(&mut self) {
+        self.redact = !self.redact;
+        self.status.info(if self.redact {
+            "Redaction on: IP host parts, hostnames and MACs are masked".to_string()
+        } else {
+            "Redaction off".to_string()
+        });
+    }
+
+    fn toggle_pause_updates(&mut self) {
+        self.updates_paused = !self.updates_paused;
+        if !self.updates_paused {
+            let _ = self.reload();
+        }
+    }
+
+    fn sync_selection_with_len(&mut self, len: usize) {
+        let prev = self.selected;
+        if len == 0 {
+            self.selected = 0;
+            self.log_entry_scroll = 0;
+            self.table_state.select(None);
+        } else {
+            self.selected = min(self.selected, len - 1);
+            self.table_state.select(Some(self.selected));
+            if self.selected != prev {
+                self.log_entry_scroll = 0;
+            }
+        }
+    }
+
+    fn toggle_selection_lock(&mut self) {
+        if self.selection_lock.take().is_some() {
+            self.status.info("Selection unlocked");
+            return;
+        }
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return;
+        }
+        let idx = filtered[min(self.selected, filtered.len() - 1)];
+        self.selection_lock = Some(self.entries[idx].id);
+        self.status
+            .info("Selection locked on this entry (z to unlock)");
+    }
+
+    // Moving the selection while locked moves the lock with it.
+    fn retarget_selection_lock(&mut self, filtered: &[usize]) {
+        if self.selection_lock.is_some()
+            && let Some(&idx) = filtered.get(self.selected)
+        {
+            self.selection_lock = Some(self.entries[idx].id);
+        }
+    }
+
+    fn toggle_detail_raw(&mut self) {
+        self.detail_raw = !self.detail_raw;
+        self.detail_drag = None;
+        self.status.info(if self.detail_raw {
+            "Log-entry pane: raw line (J for parsed fields)"
+        } else {
+            "Log-entry pane: parsed fields (J for the raw line)"
+        });
+    }
+
+    // The detail pane shows the selected entry unless a prompt took it over.
+    fn shows_log_entry(&self) -> bool {
+        self.input_mode.is_none()
+            && self.export_prompt.is_none()
+            && self.source_prompt.is_none()
+            && self.block_prompt.is_none()
+            && self.find_prompt.is_none()
+    }
+
+    fn start_find(&mut self) {
+        self.find_prompt = Some(FindPrompt::new(self.selected));
+        self.push_mode(Mode::Search);
+    }
+
+    // Row in view of the next raw line containing `term`, and how many match.
+    fn find_in_view(
+        &self,
+        term: &str,
+        from: usize,
+        backwards: bool,
+        include_from: bool,
+    ) -> (Option<usize>, usize) {
+        let view = self.view_indices();
+        let lines: Vec<&str> = view
+            .iter()
+            .map(|&idx| self.entries[idx].raw.as_str())
+            .collect();
+        let lower = term.to_lowercase();
+        let count = lines
+            .iter()
+            .filter(|line| find::matches(line, &lower))
+            .count();
+        (
+            find::next_match(&lines, term, from, backwards, include_from),
+            count,
+        )
+    }
+
+    fn select_view_row(&mut self, pos: usize) {
+        let view = self.view_indices();
+        self.selected = pos;
+        self.sync_selection_with_len(view.len());
+        self.retarget_selection_lock(&view);
+    }
+
+    // Incremental step: each edit searches again from where `/` was pressed.
+    fn update_find(&mut self) {
+        let Some(prompt) = self.find_prompt.as_ref() else {
+            return;
+        };
+        let origin = prompt.origin;
+        let (found, count) = self.find_in_view(&prompt.term, origin, false, true);
+        self.select_view_row(found.unwrap_or(origin));
+        if let Some(prompt) = self.find_prompt.as_mut() {
+            prompt.matches = count;
+        }
+    }
+
+    fn finish_find(&mut self, keep: bool) {
+        let Some(prompt) = self.find_prompt.take() else {
+            return;
+        };
+        if !keep || prompt.term.is_empty() {
+            self.select_view_row(prompt.origin);
+            return;
+        }
+        if prompt.matches == 0 {
+            self.status
+                .warn(format!("No rows in view contain '{}'", prompt.term));
+        } else {
+            self.status.info(format!(
+                "Found '{}' in {} rows (n/N next/previous, Esc ends the search)",
+                prompt.term, prompt.matches
+            ));
+        }
+        self.find_term = Some(prompt.term);
+    }
+
+    fn find_again(&mut self, backwards: bool) {
+        let Some(term) = self.find_term.clone() else {
+            return;
+        };
+        match self.find_in_view(&term, self.selected, backwards, false) {
+            (Some(pos), count) => {
+                let wrapped = if backwards {
+                    pos >= self.selected
+                } else {
+                    pos <= self.selected
+                };
+                self.select_view_row(pos);
+                self.status.info(format!(
+                    "'{}': {} matching rows{}",
+                    term,
+                    count,
+                    if wrapped { " (wrapped)" } else { "" }
+                ));
+            }
+            (None, _) => self
+                .status
+                .warn(format!("No rows in view contain '{}'", term)),
+        }
+    }
+
+    fn detail_raw(&mut self, idx: usize) -> String {
+        let raw = self.full_raw(idx);
+        if self.redact {
+            redact_raw_line(&raw)
+        } else {
+            raw
+        }
+    }
+
+    // Character column of the detail line under `mouse_x`, clamped to the
+    // pane so a drag past its edge stops at the last visible character.
+    fn detail_column(&self, pane: Rect, mouse_x: u16) -> usize {
+        let first = pane.x + 1;
+        let last = pane.right().saturating_sub(2).max(first);
+        usize::from(self.log_entry_scroll) + usize::from(mouse_x.clamp(first, last) - first)
+    }
+
+    fn copy_detail_selection(&mut self) {
+        let Some((anchor, cursor)) = self.detail_drag.take() else {
+            return;
+        };
+        let filtered = self.view_indices();
+        let Some(&idx) = filtered.get(self.selected).filter(|_| anchor != cursor) else {
+            return;
+        };
+        let (start, end) = (anchor.min(cursor), anchor.max(cursor));
+        let text: String = self
+            .detail_raw(idx)
+            .chars()
+            .skip(start)
+            .take(end - start + 1)
+            .collect();
+        if text.is_empty() {
+            return;
+        }
+        match copy_text_via_osc52(&text) {
+            Ok(()) => self.status.info(format!("Copied \"{}\" (OSC52)", text)),
+            Err(err) => self.status.error(format!("Clipboard copy failed: {}", err)),
+        }
+    }
+
+    fn scroll_log_entry_left(&mut self) {
+        const STEP: u16 = 8;
+        self.log_entry_scroll = self.log_entry_scroll.saturating_sub(STEP);
+    }
+
+    fn scroll_log_entry_right(&mut self) {
+        const STEP: u16 = 8;
+        let max = self.current_log_entry_max_scroll();
+        self.log_entry_scroll = min(self.log_entry_scroll.saturating_add(STEP), max);
+    }
+
+    fn current_log_entry_max_scroll(&self) -> u16 {
+        let filtered = self.view_indices();
+        if filtered.is_empty() {
+            return 0;
+        }
+        let selected = min(self.selected, filtered.len() - 1);
+        self.entries[filtered[selected]]
+            .raw
+            .len()
+            .saturating_sub(1)
+            .min(u16::MAX as usize) as u16
+    }
+
+    fn maybe_reload(&mut self) {
+        if self.updates_paused || self.ingest_pending() {
+            return;
+        }
+        if self.last_watch_check.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        self.last_watch_check = Instant::now();
+        self.refresh_auth_log();
+
+        let journal_changed = match self.journal.as_mut().map(Journal::poll) {
+            Some(Ok(changed)) => changed,
+            Some(Err(err)) => {
+                self.status
+                    .error(format!("Failed to read the journal: {}", err));
+                false
+            }
+            None => match self.listener.as_mut() {
+                Some(listener) => listener.poll(),
+                None if file_fingerprint(&self.log_path).is_err() => return,
+                None => false,
+            },
+        };
+        let cap = self.entry_cap.unwrap_or(STREAM_MAX_LINES);
+        if let Some(stream) = self.main_stream_mut() {
+            stream.trim(cap);
+        }
+        let discovered = self.discover_watched_sources();
+        if journal_changed || discovered || self.last_fingerprints != self.source_fingerprints() {
+            if !discovered && self.tail_sources() {
+                return;
+            }
+            let previous_status = self.status.clone();
+            if self.reload() {
+                self.status = previous_status;
+            }
+        }
+    }
+
+    fn refresh_auth_log(&mut self) {
+        let Some(auth_log) = self.auth_log.as_mut() else {
+            return;
+        };
+        if let Err(err) = auth_log.refresh() {
+            self.status.error(format!(
+                "Failed to read {}: {}",
+                auth_log.path.display(),
+                err
+            ));
+        }
+    }
+}
+
+// Per-field counts of entries each active filter excludes on its own, cached
+// until the filters or the entry set change.
+struct FilterExclusions {
+    filters: Filters,
+    entries: usize,
+    newest_id: Option<u64>,
+    counts: [usize; FilterField::ALL.len()],
+}
+
+impl Filters {
+    fn value(&self, field: FilterField) -> &str {
+        match field {
+            FilterField::Service => &self.service,
+            FilterField::Port => &self.port,
+            FilterField::Ip => &self.ip,
+            FilterField::Action => &self.action,
+            FilterField::Proto => &self.proto,
+            FilterField::Text => &self.text,
+            FilterField::Time => &self.time,
+            FilterField::Country => &self.country,
+            FilterField::Flags => &self.flags,
+        }
+    }
+
+    fn set(&mut self, field: FilterField, value: String) {
+        match field {
+            FilterField::Service => self.service = value,
+            FilterField::Port => {
+                self.port_set = PortSet::parse(negated_filter(&value).1).ok();
+                self.port = value;
+            }
+            FilterField::Ip => self.ip = value,
+            FilterField::Action => self.action = value,
+            FilterField::Proto => self.proto = value,
+            FilterField::Text => self.text = value,
+            FilterField::Time => {
+                self.time_range =
+                    TimeRange::parse(negated_filter(&value).1, now_seconds(), local_offset()).ok();
+                self.time = value;
+            }
+            FilterField::Country => self.country = value,
+            FilterField::Flags => self.flags = value,
+        }
+    }
+
+    // A copy with every field but `field` cleared.
+    fn only(&self, field: FilterField) -> Filters {
+        let mut only = Filters::default();
+        only.set(field, self.value(field).to_string());
+        only
+    }
+
+    fn active_count(&self) -> usize {
+        [
+            &self.service,
+            &self.port,
+            &self.ip,
+            &self.action,
+            &self.proto,
+            &self.text,
+            &self.time,
+            &self.country,
+            &self.flags,
+        ]
+        .into_iter()
+        .filter(|value| !value.is_empty())
+        .count()
+    }
+
+    // Every active field must match; a value starting with `!` inverts its field.
+    fn matches(&self, entry: &LogEntry) -> bool {
+        FilterField::ALL.into_iter().all(|field| {
+            let (negated, term) = negated_filter(self.value(field));
+            // An unparsable time range or port filters nothing, negated or not.
+            let inactive = term.is_empty()
+                || (field == FilterField::Time && self.time_range.is_none())
+                || (field == FilterField::Port && self.port_set.is_none());
+            inactive || self.field_matches(field, term, entry) != negated
+        })
+    }
+
+    fn field_matches(&self, field: FilterField, term: &str, entry: &LogEntry) -> bool {
+        let contains = |value: Option<&str>| {
+            value
+                .unwrap_or("")
+                .to_ascii_lowercase()
+                .contains(&term.to_ascii_lowercase())
+        };
+        match field {
+            FilterField::Service => contains(entry.service.as_deref()),
+            // Source or destination port in the set, e.g. "22,80", "1000-2000", ">1024".
+            FilterField::Port => self.port_set.as_ref().is_some_and(|ports| {
+                [entry.src_port, entry.dst_port]
+                    .into_iter()
+                    .flatten()
+                    .any(|port| ports.contains(port))
+            }),
+            FilterField::Ip => {
+                contains(entry.src_ip.as_deref()) || contains(entry.dst_ip.as_deref())
+            }
+            FilterField::Action => contains(Some(&entry.action)),
+            FilterField::Proto => contains(entry.proto.as_deref()),
+            FilterField::Text => contains(Some(&entry.raw)),
+            // Comma-separated codes, e.g. "CN,RU".
+            FilterField::Country => entry.country.as_deref().is_some_and(|country| {
+                term.split(',')
+                    .any(|code| code.trim().eq_ignore_ascii_case(country))
+            }),
+            // TCP flags that must all be set, `!FLAG` for ones that must not,
+            // e.g. "syn,!ack" for connection attempts.
+            FilterField::Flags => {
+                term.split([',', ' '])
+                    .filter(|flag| !flag.is_empty())
+                    .all(|flag| match flag.strip_prefix('!') {
+                        Some(flag) => !entry.tcp_flags.contains(flag),
+                        None => entry.tcp_flags.contains(flag),
+                    })
+            }
+            // Entries whose timestamp cannot be read are outside every range.
+            FilterField::Time => self.time_range.is_some_and(|range| {
+                let now = now_seconds();
+                entry_epoch_seconds(&entry.timestamp, now)
+                    .is_some_and(|secs| range.contains(secs, now))
+            }),
+        }
+    }
+}
+
+// Gives every entry (in file order) an ID derived from its content and how many
+// identical lines preceded it in the same source, so a physical line keeps its
+// ID across reloads. `occurrences` carries the counts over from earlier ingests
+// when only appended lines are parsed.
+fn assign_entry_ids(entries: &mut [LogEntry], occurrences: &mut HashMap<(usize, u64), u64>) {
+    for entry in entries {
+        let content = content_hash(&entry.raw);
+        let occurrence = occurrences.entry((entry.source, content)).or_insert(0);
+        entry.id = entry_id(content, *occurrence);
+        *occurrence += 1;
+    }
+}
+
+// Stable merge of entries from several sources (each in file order) into one
+// chronological list. Lines without a parsable timestamp inherit the previous
+// line's time from the same source.
+fn merge_chronologically(entries: &mut Vec<LogEntry>) {
+    let mut last_key: HashMap<usize, i64> = HashMap::new();
+    let mut keyed: Vec<(i64, LogEntry)> = entries
+        .drain(..)
+        .map(|entry| {
+            let key = match timestamp_sort_key(&entry.timestamp) {
+                Some(key) => {
+                    last_key.insert(entry.source, key);
+                    key
+                }
+                None => last_key.get(&entry.source).copied().unwrap_or(i64::MIN),
+            };
+            (key, entry)
+        })
+        .collect();
+    keyed.sort_by_key(|(key, _)| *key);
+    entries.extend(keyed.into_iter().map(|(_, entry)| entry));
+}
+
+// Interface the packet was seen on: inbound and forwarded traffic use IN=,
+// outbound uses OUT=. Unknown interfaces fall back to tcpdump's "any".
+fn capture_interface(entry: &LogEntry) -> String {
+    let iface = match entry.direction() {
+        "OUT" => entry.out_iface.as_deref(),
+        _ => entry.in_iface.as_deref(),
+    };
+    match iface {
+        Some(name) if !name.is_empty() && name != "host" => name.to_string(),
+        _ => "any".to_string(),
+    }
+}
+
+fn source_label(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+// Watched directories pick up plain per-host logs, skipping hidden, compressed
+// and numbered rotation files.
+fn is_watchable_log_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    if name.starts_with('.') || !path.is_file() {
+        return false;
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz" | "xz" | "bz2" | "zst" | "zip") => false,
+        Some(ext) => !ext.chars().all(|c| c.is_ascii_digit()),
+        None => true,
+    }
+}
+
+fn content_hash(raw: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    raw.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn entry_id(content: u64, occurrence: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (content, occurrence).hash(&mut hasher);
+    hasher.finish()
+}
+
+fn file_fingerprint(path: &Path) -> io::Result<FileFingerprint> {
+    let meta = fs::metadata(path)?;
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&meta);
+    #[cfg(not(unix))]
+    let inode = 0;
+    Ok(FileFingerprint {
+        modified: meta.modified().ok(),
+        len: meta.len(),
+        inode,
+    })
+}
+
+fn field_from_fkey(n: u8) -> Option<FilterField> {
+    match n {
+        1 => Some(FilterField::Service),
+        2 => Some(FilterField::Port),
+        3 => Some(FilterField::Ip),
+        4 => Some(FilterField::Action),
+        5 => Some(FilterField::Proto),
+        6 => Some(FilterField::Text),
+        7 => Some(FilterField::Time),
+        8 => Some(FilterField::Country),
+        9 => Some(FilterField::Flags),
+        _ => None,
+    }
+}
+
+fn selected_iface_label(value: Option<&str>) -> &str {
+    value.unwrap_or("all")
+}
+
+// Parsed fields as one `key=value` line each; missing values become "-".
+fn entry_key_values(entry: &LogEntry) -> String {
+    let text = |value: Option<&str>| value.filter(|v| !v.is_empty()).unwrap_or("-").to_string();
+    let port = |value: Option<u16>| value.map_or_else(|| "-".to_string(), |p| p.to_string());
+    [
+        ("time", entry.timestamp.clone()),
+        ("action", entry.action.clone()),
+        ("direction", entry.direction().to_string()),
+        ("in", text(entry.in_iface.as_deref())),
+        ("out", text(entry.out_iface.as_deref())),
+        ("src", text(entry.src_ip.as_deref())),
+        ("dst", text(entry.dst_ip.as_deref())),
+        ("proto", text(entry.proto.as_deref())),
+        ("spt", port(entry.src_port)),
+        ("dpt", port(entry.dst_port)),
+        ("service", text(entry.service.as_deref())),
+    ]
+    .iter()
+    .map(|(key, value)| format!("{}={}\n", key, value))
+    .collect()
+}
+
+fn selected_position_for_raw(
+    entries: &[LogEntry],
+    filtered_indices: &[usize],
+    raw: &str,
+) -> Option<usize> {
+    filtered_indices
+        .iter()
+        .position(|entry_idx| entries[*entry_idx].raw == raw)
+}
+
+fn pause_chip_label(paused: bool) -> &'static str {
+    if paused { "[paused]" } else { "[live]" }
+}
+
+fn max_horizontal_scroll(text: &str, content_width: u16) -> u16 {
+    if content_width == 0 {
+        return 0;
+    }
+    let longest_line = text
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    longest_line
+        .saturating_sub(content_width as usize)
+        .min(u16::MAX as usize) as u16
+}
+
+fn format_timestamp_for_width(timestamp: &str, show_date: bool) -> String {
+    let token = timestamp
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    // ISO-8601 style: 2026-02-11T23:00:39.987820+02:00
+    if let Some((date, time_part)) = token.split_once('T') {
+        let time = time_part
+            .split(['.', '+', 'Z'])
+            .next()
+            .unwrap_or_default()
+            .split('-')
+            .next()
+            .unwrap_or_default();
+        if !date.is_empty() && !time.is_empty() {
+            return if show_date {
+                format!("{} {}", date, time)
+            } else {
+                time.to_string()
+            };
+        }
+    }
+
+    // Syslog style: Feb 11 20:21:00 host
+    let parts: Vec<&str> = timestamp.split_whitespace().collect();
+    if parts.len() >= 3 && parts[2].contains(':') {
+        return if show_date {
+            format!("{} {} {}", parts[0], parts[1], parts[2])
+        } else {
+            parts[2].to_string()
+        };
+    }
+
+    timestamp.to_string()
+}
+
+// Truncates a timestamp to minute precision for grouping, e.g.
+// "Feb 11 20:21:00 host" -> "Feb 11 20:21". None when the timestamp has
+// neither shape or the cut would split a character.
+fn timestamp_minute_key(timestamp: &str) -> Option<&str> {
+    let token = timestamp.split_whitespace().next()?;
+    if let Some((date, _)) = token.split_once('T') {
+        return token.get(..date.len() + 1 + 5);
+    }
+
+    let time = timestamp
+        .split_whitespace()
+        .nth(2)
+        .filter(|time| time.len() >= 5 && time.contains(':'))?;
+    let start = timestamp.find(time)?;
+    timestamp.get(..start + 5)
+}
+
+// Gutter glyph per visible row: a density bar on the first row of each minute
+// (scaled to the busiest minute in view), blank on that minute's other rows.
+fn minute_gutter_bars(entries: &[LogEntry], indices: &[usize]) -> Vec<&'static str> {
+    let minute_key = |timestamp| timestamp_minute_key(timestamp).unwrap_or(timestamp);
+    const BARS: [&str; 8] = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for idx in indices {
+        *counts
+            .entry(minute_key(&entries[*idx].timestamp))
+            .or_insert(0) += 1;
+    }
+    let max = counts.values().copied().max().unwrap_or(1);
+
+    let mut previous = None;
+    indices
+        .iter()
+        .map(|idx| {
+            let minute = minute_key(&entries[*idx].timestamp);
+            if previous == Some(minute) {
+                return " ";
+            }
+            previous = Some(minute);
+            let count = counts.get(minute).copied().unwrap_or(0);
+            let level = (count * BARS.len()).div_ceil(max).clamp(1, BARS.len());
+            BARS[level - 1]
+        })
+        .collect()
+}
+
+fn compare_entries_by(column: SortColumn, a: &LogEntry, b: &LogEntry) -> Ordering {
+    match column {
+        SortColumn::Time => Ordering::Equal,
+        SortColumn::Action => a.action.cmp(&b.action),
+        SortColumn::Direction => a.direction().cmp(b.direction()),
+        SortColumn::Source => compare_ips(a.src_ip.as_deref(), b.src_ip.as_deref()),
+        SortColumn::Dest => compare_ips(a.dst_ip.as_deref(), b.dst_ip.as_deref()),
+        SortColumn::Proto => a.proto.cmp(&b.proto),
+        SortColumn::SrcPort => a.src_port.cmp(&b.src_port),
+        SortColumn::DstPort => a.dst_port.cmp(&b.dst_port),
+        SortColumn::Service => a.service.cmp(&b.service),
+    }
+}
+
+// Numeric address order (IPv4 before IPv6), unparsable values last.
+fn compare_ips(a: Option<&str>, b: Option<&str>) -> Ordering {
+    let parse = |ip: Option<&str>| ip.and_then(|v| v.trim().parse::<IpAddr>().ok());
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(&b),
+    }
+}
+
+fn sort_indicator(descending: bool) -> &'static str {
+    if descending { "▼" } else { "▲" }
+}
+
+fn display_ip(ip: Option<&str>, redact: bool) -> String {
+    match ip {
+        Some(ip) if redact => redact_ip(ip),
+        Some(ip) => ip.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+// Plain text of a table cell, for columns without highlighting and for the
+// `--http` page.
+fn column_text(entry: &LogEntry, column: Column, redact: bool) -> String {
+    let number = |value: Option<u16>| value.map_or_else(|| "-".to_string(), |v| v.to_string());
+    let text = |value: Option<&str>| {
+        value
+            .filter(|value| !value.is_empty())
+            .unwrap_or("-")
+            .to_string()
+    };
+    match column {
+        Column::Time => format_timestamp_for_width(&entry.timestamp, true),
+        Column::Action => entry.action.clone(),
+        Column::Direction => entry.direction().to_string(),
+        Column::Source => display_ip(entry.src_ip.as_deref(), redact),
+        Column::Country => text(entry.country.as_deref()),
+        Column::Dest => display_ip(entry.dst_ip.as_deref(), redact),
+        Column::Proto => text(entry.proto.as_deref()),
+        Column::SrcPort => number(entry.src_port),
+        Column::DstPort => number(entry.dst_port),
+        Column::Service => service_display_for_entry(entry, true),
+        Column::InIface => text(entry.in_iface.as_deref()),
+        Column::OutIface => text(entry.out_iface.as_deref()),
+        Column::Ttl => number(entry.ttl.map(u16::from)),
+        Column::Length => number(entry.length),
+        Column::Flags => entry.tcp_flags.names().join(" "),
+    }
+}
+
+fn service_display_for_entry(entry: &LogEntry, show_description: bool) -> String {
+    let name = entry.service.as_deref().unwrap_or("-");
+    if name == "-" || !show_description {
+        return name.to_string();
+    }
+
+    let port = entry.dst_port.or(entry.src_port);
+    let Some(port) = port else {
+        return name.to_string();
+    };
+    let Some(description) = service_description_from_port(port) else {
+        return name.to_string();
+    };
+    if description.eq_ignore_ascii_case(name) {
+        return name.to_string();
+    }
+
+    format!("{}: {}", name, description)
+}
+
+fn key_span(text: &'static str) -> Span<'static> {
+    Span::styled(
+        text,
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+fn desc_span(text: &'static str) -> Span<'static> {
+    Span::styled(text, Style::default().fg(Color::Gray))
+}
+
+fn sep_span() -> Span<'static> {
+    Span::styled(" | ", Style::default().fg(Color::DarkGray))
+}
+
+fn footer_help_lines(width: u16) -> Vec<Line<'static>> {
+    let width = width.max(MIN_UI_WIDTH);
+
+    if width >= 140 {
+        vec![Line::from(vec![
+            key_span("q"),
+            desc_span(" quit"),
+            sep_span(),
+            key_span("r"),
+            desc_span(" reload"),
+            sep_span(),
+            key_span("a"),
+            desc_span(" pause/resume"),
+            sep_span(),
+            key_span("c"),
+            desc_span(" clear"),
+            sep_span(),
+            key_span("l"),
+            desc_span(" local"),
+            sep_span(),
+            key_span("p"),
+            desc_span(" wan"),
+            sep_span(),
+            key_span("f"),
+            desc_span(" flow"),
+            sep_span(),
+            key_span("d"),
+            desc_span(" dir"),
+            sep_span(),
+            key_span("F1..F9"),
+            desc_span(" edit"),
+            sep_span(),
+            key_span("Shift+F"),
+            desc_span(" clear"),
+            sep_span(),
+            key_span(",/."),
+            desc_span(" iface"),
+            sep_span(),
+            key_span("0"),
+            desc_span(" all"),
+            sep_span(),
+            key_span("w"),
+            desc_span(" wan"),
+            sep_span(),
+            key_span("<-/->"),
+            desc_span(" log"),
+            sep_span(),
+            key_span("Ctrl+C"),
+            desc_span(" copy row"),
+            sep_span(),
+            key_span("Ctrl+I"),
+            desc_span(" copy src ip"),
+        ])]
+    } else if width >= 110 {
+        vec![
+            Line::from(vec![
+                key_span("q"),
+                desc_span(" quit"),
+                sep_span(),
+                key_span("r"),
+                desc_span(" reload"),
+                sep_span(),
+                key_span("a"),
+                desc_span(" pause"),
+                sep_span(),
+                key_span("c"),
+                desc_span(" clear"),
+                sep_span(),
+                key_span("l/p/f"),
+                desc_span(" toggles"),
+                sep_span(),
+                key_span("d"),
+                desc_span(" dir"),
+                sep_span(),
+                key_span("F1..F9"),
+                desc_span(" edit"),
+                sep_span(),
+                key_span("Shift+F"),
+                desc_span(" clear"),
+            ]),
+            Line::from(vec![
+                key_span(",/."),
+                desc_span(" iface"),
+                sep_span(),
+                key_span("0"),
+                desc_span(" all"),
+                sep_span(),
+                key_span("w"),
+                desc_span(" wan"),
+                sep_span(),
+                key_span("<-/->"),
+                desc_span(" log"),
+                sep_span(),
+                key_span("Ctrl+C"),
+                desc_span(" row"),
+                sep_span(),
+                key_span("Ctrl+I"),
+                desc_span(" src ip"),
+            ]),
+        ]
+    } else {
+        vec![
+            Line::from(vec![
+                key_span("q"),
+                desc_span(" quit"),
+                sep_span(),
+                key_span("r"),
+                desc_span(" reload"),
+                sep_span(),
+                key_span("a"),
+                desc_span(" pause"),
+                sep_span(),
+                key_span("c"),
+                desc_span(" clear"),
+                sep_span(),
+                key_span("l/p/f"),
+                desc_span(" toggles"),
+                sep_span(),
+                key_span("d"),
+                desc_span(" dir"),
+            ]),
+            Line::from(vec![
+                key_span("F1..F9"),
+                desc_span(" edit"),
+                sep_span(),
+                key_span("Shift+F"),
+                desc_span(" clear"),
+                sep_span(),
+                key_span(",/."),
+                desc_span(" iface"),
+                sep_span(),
+                key_span("0"),
+                desc_span(" all"),
+                sep_span(),
+                key_span("w"),
+                desc_span(" wan"),
+                sep_span(),
+                key_span("<-/->"),
+                desc_span(" log"),
+                sep_span(),
+                key_span("Ctrl+C"),
+                desc_span(" row"),
+                sep_span(),
+                key_span("Ctrl+I"),
+                desc_span(" ip"),
+            ]),
+        ]
+    }
+}
+
+fn max_visible_ifaces(width: u16) -> usize {
+    if width >= 160 {
+        8
+    } else if width >= 120 {
+        6
+    } else {
+        4
+    }
+}
+
+fn interface_prefix(width: u16) -> &'static str {
+    if width >= 120 {
+        "interface: "
+    } else {
+        "iface "
+    }
+}
+
+fn left_toggle_width(app: &App) -> u16 {
+    let local = text_cells("[local]");
+    let wan = text_cells("[wan]");
+    let bcast = text_cells("[bcast]");
+    let replies = text_cells("[reply]");
+    let flow = text_cells(app.flow_filter.chip());
+    let dir = text_cells(app.direction_filter.chip());
+    local
+        .saturating_add(1)
+        .saturating_add(wan)
+        .saturating_add(1)
+        .saturating_add(bcast)
+        .saturating_add(1)
+        .saturating_add(replies)
+        .saturating_add(1)
+        .saturating_add(flow)
+        .saturating_add(1)
+        .saturating_add(dir)
+}
+
+fn interface_row_width(app: &App, max_interfaces: usize, row_width: u16) -> u16 {
+    let mut width = text_cells(interface_prefix(row_width));
+    let all_label = if app.selected_interface.is_none() {
+        "[all]"
+    } else {
+        "all"
+    };
+    width = width.saturating_add(text_cells(all_label));
+
+    for iface in app.interface_options.iter().take(max_interfaces) {
+        let selected = app.selected_interface.as_deref() == Some(iface.as_str());
+        let label = interface_chip_label(app, iface, selected);
+        width = width.saturating_add(1).saturating_add(text_cells(&label));
+    }
+
+    if app.interface_options.len() > max_interfaces {
+        let extra = format!("+{}", app.interface_options.len() - max_interfaces);
+        width = width.saturating_add(1).saturating_add(text_cells(&extra));
+    }
+
+    width
+}
+
+fn controls_layout(app: &App, width: u16) -> (bool, usize, u16) {
+    let max_ifaces = max_visible_ifaces(width);
+    let left_width = left_toggle_width(app);
+
+    for visible_ifaces in (0..=max_ifaces).rev() {
+        let right_width = interface_row_width(app, visible_ifaces, width);
+        if left_width.saturating_add(2).saturating_add(right_width) <= width {
+            let right_start = width.saturating_sub(right_width);
+            return (true, visible_ifaces, right_start);
+        }
+    }
+
+    (false, max_ifaces, 0)
+}
+
+fn left_toggle_spans(app: &App) -> Vec<Span<'static>> {
+    vec![
+        Span::styled(
+            "[local]",
+            if app.show_local_src {
+                Style::default()
+                    .fg(Color::LightGreen)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::Gray)
+            },
+        ),
+        Span::raw(" "),
+        Span::styled(
+            "[wan]",
+            if app.show_wan_src {
+                Style::default()
+                    .fg(Color::LightCyan)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::Gray)
+            },
+        ),
+        Span::raw(" "),
+        Span::styled(
+            "[bcast]",
+            if app.show_broadcast {
+                Style::default()
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::Gray)
+            },
+        ),
+        Span::raw(" "),
+        Span::styled(
+            "[reply]",
+            if app.show_replies {
+                Style::default()
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::Gray)
+            },
+        ),
+        Span::raw(" "),
+        Span::styled(
+            app.flow_filter.chip(),
+            if app.flow_filter == FlowFilter::All {
+                Style::default().fg(Color::Gray)
+            } else {
+                Style::default()
+                    .fg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            },
+        ),
+        Span::raw(" "),
+        Span::styled(
+            app.direction_filter.chip(),
+            if app.direction_filter == DirectionFilter::Both {
+                Style::default().fg(Color::Gray)
+            } else {
+                Style::default()
+                    .fg(Color::LightMagenta)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            },
+        ),
+    ]
+}
+
+fn interface_match_count(app: &App, iface: &str) -> usize {
+    app.interface_counts.get(iface).copied().unwrap_or(0)
+}
+
+// "eth0(12)" / "[eth0(12)]": the name plus rows it would show under the other
+// filters.
+// Pinned interfaces are marked with a leading `*`.
+fn interface_chip_label(app: &App, iface: &str, selected: bool) -> String {
+    let pin = if app.pinned_interfaces.iter().any(|p| p == iface) {
+        "*"
+    } else {
+        ""
+    };
+    let label = format!("{}{}({})", pin, iface, interface_match_count(app, iface));
+    if selected {
+        format!("[{}]", label)
+    } else {
+        label
+    }
+}
+
+fn interface_display_spans(app: &App, max_interfaces: usize, row_width: u16) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    spans.push(Span::styled(
+        interface_prefix(row_width),
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    let all_selected = app.selected_interface.is_none();
+    let all_style = if all_selected {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        Style::default().fg(Color::Gray)
+    };
+    spans.push(Span::styled(
+        if all_selected {
+            "[all]".to_string()
+        } else {
+            "all".to_string()
+        },
+        all_style,
+    ));
+
+    for iface in app.interface_options.iter().take(max_interfaces) {
+        spans.push(Span::raw(" "));
+        let is_selected = app.selected_interface.as_deref() == Some(iface.as_str());
+        let base = if is_wan_candidate_interface(iface) {
+            Color::LightGreen
+        } else {
+            Color::LightCyan
+        };
+        let style = if is_selected {
+            Style::default()
+                .fg(base)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else if interface_match_count(app, iface) == 0 {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        let label = interface_chip_label(app, iface, is_selected);
+        spans.push(Span::styled(label, style));
+    }
+
+    if app.interface_options.len() > max_interfaces {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!(" +{}", app.interface_options.len() - max_interfaces),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    spans
+}
+
+// Entry point for key presses: handles macro recording (`Q<reg>` ... `Q`) and
+// replay (`@<reg>`) wherever the keymap applies, then dispatches.
+fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    if let Some(prefix) = app.macros.pending.take() {
+        match (prefix, register_name(&key)) {
+            (MacroPrefix::Record, Some(register)) => {
+                app.macros.start(register);
+                app.status
+                    .info(format!("Recording macro into {} (Q to stop)", register));
+            }
+            (MacroPrefix::Play, Some(register)) => play_macro(app, register),
+            (_, None) => app
+                .status
+                .info("Macro cancelled: registers are a-z and 0-9"),
+        }
+        return Action::Continue;
+    }
+    let command = app
+        .mode()
+        .table_keys()
+        .then(|| app.keymap.command(&key))
+        .flatten();
+    match command {
+        Some(Command::RecordMacro) => {
+            match app.macros.stop() {
+                Some((register, len)) => app
+                    .status
+                    .info(format!("Recorded macro {} ({} keys)", register, len)),
+                None => {
+                    app.macros.pending = Some(MacroPrefix::Record);
+                    app.status
+                        .info("Record macro into register: press a-z or 0-9");
+                }
+            }
+            return Action::Continue;
+        }
+        Some(Command::PlayMacro) => {
+            app.macros.pending = Some(MacroPrefix::Play);
+            app.status
+                .info("Play macro from register: press a-z or 0-9");
+            return Action::Continue;
+        }
+        _ => {}
+    }
+    app.macros.record(key);
+    dispatch_key(app, key)
+}
+
+// Replays a register's keys. Quitting and nested replays are ignored; when a
+// recording is active the replayed keys are recorded in its place.
+fn play_macro(app: &mut App, register: char) {
+    if app.macros.replaying {
+        return;
+    }
+    let Some(keys) = app.macros.keys(register) else {
+        app.status.warn(format!("Macro {} is empty", register));
+        return;
+    };
+    app.macros.replaying = true;
+    for key in &keys {
+        app.macros.record(*key);
+        let _ = dispatch_key(app, *key);
+    }
+    app.macros.replaying = false;
+    app.status.info(format!(
+        "Played macro {} ({} keys). {}",
+        register,
+        keys.len(),
+        app.status.text()
+    ));
+}
+
+// Applies one key press to the app. The Ctrl key of `copy_row` (Ctrl+C)
+// copies the row in any mode, Esc closes the top layer, and other keys go to
+// that layer.
+fn dispatch_key(app: &mut App, key: KeyEvent) -> Action {
+    if key.modifiers.contains(KeyModifiers::CONTROL)
+        && app.keymap.command(&key) == Some(Command::CopyRow)
+    {
+        app.copy_selected_log_entry();
+        return Action::Continue;
+    }
+
+    let mode = app.mode();
+    if key.code == KeyCode::Esc && mode != Mode::Normal {
+        app.pop_mode();
+        return Action::Continue;
+    }
+    match mode {
+        Mode::Normal => return table_key(app, key),
+        // Any key ends the `"` leader; only a listed digit recalls a view.
+        Mode::RecentLeader => {
+            app.pop_mode();
+            match key.code {
+                KeyCode::Char(c @ '1'..='5') => app.apply_recent_filters(c as usize - '1' as usize),
+                _ => return table_key(app, key),
+            }
+        }
+        Mode::FilterEdit(field) => filter_edit_key(app, field, key),
+        Mode::Search => search_key(app, key),
+        Mode::SourcePrompt => source_prompt_key(app, key),
+        Mode::ExportPrompt => export_prompt_key(app, key),
+        Mode::Talkers => talkers_key(app, key),
+        Mode::EgressReview => egress_review_key(app, key),
+        Mode::Countries => country_summary_key(app, key),
+        Mode::ColumnMenu => column_menu_key(app, key),
+        Mode::PresetMenu => preset_menu_key(app, key),
+        Mode::PresetName => preset_name_key(app, key),
+        Mode::PresetDelete => {
+            app.pop_mode();
+            if let KeyCode::Char(c @ '1'..='9') = key.code {
+                app.delete_preset(c as usize - '1' as usize);
+            }
+        }
+        Mode::Confirm => match key.code {
+            KeyCode::Enter | KeyCode::Char('y') => app.confirm_block(true),
+            KeyCode::Char('c') => app.confirm_block(false),
+            KeyCode::Char('n') => app.pop_mode(),
+            _ => {}
+        },
+        Mode::Popup | Mode::Help => popup_key(app, key),
+        Mode::Stats | Mode::Aggregate | Mode::Alerts => return table_key(app, key),
+    }
+    Action::Continue
+}
+
+fn table_key(app: &mut App, key: KeyEvent) -> Action {
+    if handle_modifier_shortcuts(app, &key) {
+        return Action::Continue;
+    }
+    match app.keymap.command(&key) {
+        Some(command) => run_command(app, command),
+        None => Action::Continue,
+    }
+}
+
+fn filter_edit_key(app: &mut App, field: FilterField, key: KeyEvent) {
+    match key.code {
+        KeyCode::Tab | KeyCode::BackTab => {
+            app.complete_filter_input(field, key.code == KeyCode::BackTab);
+        }
+        KeyCode::Enter => {
+            app.completion = None;
+            if let Err(err) = check_filter_value(field, &app.input_buffer) {
+                app.status.warn(err);
+                return;
+            }
+            let before = app.current_view_preset();
+            app.set_filter_value(field, app.input_buffer.clone());
+            let current_value = app.get_filter_value(field).to_string();
+            let matches = app.filtered_indices().len();
+            app.status.info(if current_value.is_empty() {
+                format!(
+                    "Removed {} filter. Matching rows: {}",
+                    field.label(),
+                    matches
+                )
+            } else {
+                format!(
+                    "Set {} filter='{}'. Matching rows: {}",
+                    field.label(),
+                    current_value,
+                    matches
+                )
+            });
+            app.offer_pin();
+            app.remember_filters(before);
+            app.input_mode = None;
+            app.input_buffer.clear();
+        }
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+            app.completion = None;
+        }
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+            app.completion = None;
+        }
+        _ => {}
+    }
+}
+
+fn search_key(app: &mut App, key: KeyEvent) {
+    let Some(prompt) = app.find_prompt.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Enter => app.finish_find(true),
+        KeyCode::Backspace => {
+            prompt.term.pop();
+            app.update_find();
+        }
+        KeyCode::Char(c) => {
+            prompt.term.push(c);
+            app.update_find();
+        }
+        _ => {}
+    }
+}
+
+fn source_prompt_key(app: &mut App, key: KeyEvent) {
+    let Some(prompt) = app.source_prompt.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Tab => prompt.keep = !prompt.keep,
+        KeyCode::Enter => app.switch_source(),
+        KeyCode::Backspace => {
+            prompt.input.pop();
+        }
+        KeyCode::Char(c) => prompt.input.push(c),
+        _ => {}
+    }
+}
+
+fn export_prompt_key(app: &mut App, key: KeyEvent) {
+    let Some(prompt) = app.export_prompt.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Tab => prompt.toggle_format(),
+        KeyCode::Enter => app.export_view(),
+        KeyCode::Backspace => {
+            prompt.path.pop();
+        }
+        KeyCode::Char(c) => prompt.path.push(c),
+        _ => {}
+    }
+}
+
+fn talkers_key(app: &mut App, key: KeyEvent) {
+    let Some(sidebar) = app.talkers.as_ref() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('|') => app.talkers = None,
+        KeyCode::Up | KeyCode::Char('k') => app.move_talker_cursor(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.move_talker_cursor(1),
+        KeyCode::Enter => app.apply_talker(sidebar.cursor),
+        _ => {}
+    }
+}
+
+fn egress_review_key(app: &mut App, key: KeyEvent) {
+    let Some(review) = app.egress_review.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('>') => app.pop_mode(),
+        KeyCode::Up | KeyCode::Char('k') => review.move_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => review.move_by(1),
+        KeyCode::Char('a') => app.prompt_allow_egress(),
+        KeyCode::Char('x') => app.mark_egress_expected(),
+        _ => {}
+    }
+}
+
+fn country_summary_key(app: &mut App, key: KeyEvent) {
+    let Some(summary) = app.country_summary.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('C') => app.pop_mode(),
+        KeyCode::Up | KeyCode::Char('k') => summary.move_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => summary.move_by(1),
+        KeyCode::Enter => app.apply_selected_country(),
+        _ => {}
+    }
+}
+
+fn column_menu_key(app: &mut App, key: KeyEvent) {
+    let Some(picker) = app.column_picker.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char(';') => app.pop_mode(),
+        KeyCode::Up | KeyCode::Char('k') => picker.move_cursor(-1),
+        KeyCode::Down | KeyCode::Char('j') => picker.move_cursor(1),
+        KeyCode::Char(' ') if !picker.toggle() => {
+            app.status.warn("The last column cannot be hidden");
+        }
+        KeyCode::Char('K') => picker.shift(-1),
+        KeyCode::Char('J') => picker.shift(1),
+        KeyCode::Char('s') => app.save_columns(),
+        _ => {}
+    }
+    if let Some(picker) = app.column_picker.as_ref() {
+        app.columns = picker.shown();
+    }
+}
+
+fn preset_menu_key(app: &mut App, key: KeyEvent) {
+    let Some(picker) = app.preset_picker.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char(c @ '1'..='9') => app.apply_preset(c as usize - '1' as usize),
+        KeyCode::Char('q') | KeyCode::Char('\'') => app.pop_mode(),
+        KeyCode::Char('s') => {
+            picker.naming = Some(String::new());
+            app.push_mode(Mode::PresetName);
+        }
+        KeyCode::Char('d') => {
+            picker.deleting = true;
+            app.push_mode(Mode::PresetDelete);
+        }
+        _ => {}
+    }
+}
+
+fn preset_name_key(app: &mut App, key: KeyEvent) {
+    let Some(picker) = app.preset_picker.as_mut() else {
+        return;
+    };
+    let Some(name) = picker.naming.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Enter if !name.trim().is_empty() => {
+            let name = name.trim().to_string();
+            picker.naming = None;
+            app.save_preset(name);
+        }
+        KeyCode::Backspace => {
+            name.pop();
+        }
+        // `[` and `]` would break the file's section headers.
+        KeyCode::Char(c) if c != '[' && c != ']' => name.push(c),
+        _ => {}
+    }
+}
+
+fn popup_key(app: &mut App, key: KeyEvent) {
+    let Some(report) = app.shown_report_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Char('q') => app.pop_mode(),
+        KeyCode::Up | KeyCode::Char('k') => report.scroll_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => report.scroll_by(1),
+        KeyCode::PageUp => report.scroll_by(-10),
+        KeyCode::PageDown => report.scroll_by(10),
+        _ => {}
+    }
+}
+
+// Runs a table command, whichever key it is bound to.
+fn run_command(app: &mut App, command: Command) -> Action {
+    match command {
+        Command::Quit => return Action::Quit,
+        Command::Reload => {
+            let _ = app.reload();
+        }
+        Command::TogglePause => app.toggle_pause_updates(),
+        Command::ClearFilters => app.clear_filters(),
+        Command::ToggleLocal => app.toggle_show_local_src(),
+        Command::LoadOlder => app.load_older_entries(),
+        Command::ToggleWan => app.toggle_show_wan_src(),
+        Command::ToggleBroadcast => app.toggle_show_broadcast(),
+        Command::ToggleReplies => app.toggle_show_replies(),
+        Command::RotatedHistory => app.toggle_rotated_history(),
+        Command::BogonsOnly => app.toggle_bogon_only(),
+        Command::ListedOnly => app.toggle_listed_only(),
+        Command::Alerts => app.toggle_alerts(),
+        Command::Talkers => app.toggle_talkers(),
+        Command::CycleFlow => app.cycle_flow_filter(),
+        Command::CycleDirection => app.cycle_direction_filter(),
+        Command::PrevInterface => app.cycle_interface(false),
+        Command::NextInterface => app.cycle_interface(true),
+        Command::AllInterfaces => app.select_all_interfaces(),
+        Command::PinInterface => app.toggle_interface_pin(),
+        Command::PinEntry => app.toggle_pin(),
+        Command::MinuteGutter => app.toggle_minute_gutter(),
+        Command::Redact => app.toggle_redact(),
+        Command::CycleSort => app.cycle_sort_column(),
+        Command::ReverseSort => app.reverse_sort(),
+        Command::CopyRowCaptureFilter => app.copy_capture_filter(false),
+        Command::CopyFilterCaptureFilter => app.copy_capture_filter(true),
+        Command::Capture => app.toggle_capture(),
+        Command::RuleCounters => app.show_rule_counters(),
+        Command::LimitSummaries => app.show_limit_summaries(),
+        Command::FixedSourcePorts => app.show_fixed_source_ports(),
+        Command::AbuseReports => app.export_abuse_reports(),
+        Command::Presets => app.open_preset_picker(),
+        Command::Columns => app.toggle_column_picker(),
+        Command::CopyCommandLine => app.copy_view_command_line(),
+        Command::Burst => app.toggle_burst(),
+        Command::RecentFilters => app.start_recent_filters(),
+        Command::EgressReview => app.open_egress_review(),
+        Command::Countries => app.open_country_summary(),
+        Command::Export => {
+            app.export_prompt = Some(ExportPrompt::default());
+            app.push_mode(Mode::ExportPrompt);
+        }
+        Command::OpenSource => app.open_source_prompt(),
+        Command::Stats => app.toggle_stats(),
+        Command::Aggregate => app.toggle_aggregate(),
+        Command::Find => app.start_find(),
+        Command::Dismiss if app.find_term.take().is_some() => app.status.info("Search ended"),
+        Command::Dismiss => {
+            app.status.acknowledge();
+        }
+        Command::TruncatedCells => app.show_truncated_cells(),
+        Command::DetailRaw => app.toggle_detail_raw(),
+        Command::Block => app.prompt_block_selected(),
+        Command::Next if app.find_term.is_some() => app.find_again(false),
+        Command::Previous if app.find_term.is_some() => app.find_again(true),
+        Command::Next => app.take_snapshot(),
+        Command::Previous => app.show_snapshot_diff(),
+        Command::TimeLock => app.toggle_time_lock(),
+        Command::CopyKeyValues => app.copy_selected_as(entry_key_values, "key=value lines"),
+        Command::SelectionLock => app.toggle_selection_lock(),
+        Command::Drilldown => app.show_ip_drilldown(),
+        Command::AllowWithoutListener => app.show_allow_without_listener(),
+        Command::WanInterface => app.select_default_wan_interface(),
+        Command::ScrollLeft => app.scroll_log_entry_left(),
+        Command::ScrollRight => app.scroll_log_entry_right(),
+        Command::Up | Command::Down => {
+            let filtered_len = app.view_indices().len();
+            if filtered_len > 0 {
+                app.selected = if command == Command::Up {
+                    app.selected.saturating_sub(1)
+                } else {
+                    min(app.selected + 1, filtered_len - 1)
+                };
+                app.sync_selection_with_len(filtered_len);
+            }
+        }
+        Command::CopySourceIp => app.copy_selected_src_ip(),
+        Command::CopyArpa => app.copy_selected_src_arpa(),
+        Command::CopyRow => app.copy_selected_log_entry(),
+        Command::CopyPretty => app.copy_selected_as(pretty::pretty_entry, "aligned text"),
+        Command::CopyJson => app.copy_selected_json(),
+        // `handle_key` takes these before the keys reach the table.
+        Command::RecordMacro | Command::PlayMacro => {}
+        Command::Help => {
+            app.help = Some(help::report(&app.keymap));
+            app.push_mode(Mode::Help);
+        }
+    }
+    Action::Continue
+}
+
+fn handle_modifier_shortcuts(app: &mut App, key: &crossterm::event::KeyEvent) -> bool {
+    if let KeyCode::F(n) = key.code
+        && let Some(field) = field_from_fkey(n)
+    {
+        if key.modifiers.contains(KeyModifiers::SHIFT) {
+            app.clear_filter(field);
+        } else {
+            app.set_input_mode(field);
+        }
+        return true;
+    }
+
+    false
+}
+
+// First visible row of the table: keeps the selection inside a window of
+// `visible` rows while moving the previous offset as little as possible.
+fn visible_window_offset(
+    previous: usize,
+    selected: Option<usize>,
+    visible: usize,
+    len: usize,
+) -> usize {
+    let mut offset = previous.min(len.saturating_sub(1));
+    if let Some(selected) = selected {
+        if selected < offset {
+            offset = selected;
+        } else if visible > 0 && selected >= offset + visible {
+            offset = selected + 1 - visible;
+        }
+    }
+    offset
+}
+
+// Rows shown from `offset` when the rows `separated` says start another file
+// take a line more for their separator: moves the offset on until `selected`
+// fits, and returns it with the row count and the lines they use.
+fn fit_window(
+    mut offset: usize,
+    selected: Option<usize>,
+    visible: usize,
+    len: usize,
+    separated: impl Fn(usize) -> bool,
+) -> (usize, usize, usize) {
+    loop {
+        let (mut rows, mut lines) = (0, 0);
+        while offset + rows < len {
+            let needed = 1 + usize::from(separated(offset + rows));
+            if lines + needed > visible {
+                break;
+            }
+            rows += 1;
+            lines += needed;
+        }
+        if rows == 0 || selected.is_none_or(|selected| selected < offset + rows) {
+            return (offset, rows, lines);
+        }
+        offset += 1;
+    }
+}
+
+// First visible row that puts `selected` in the middle of the viewport.
+fn centered_window_offset(selected: usize, visible: usize, len: usize) -> usize {
+    selected
+        .saturating_sub(visible / 2)
+        .min(len.saturating_sub(visible))
+}
+
+// Events table columns, shared by rendering and the header sort hitboxes.
+fn table_column_constraints(
+    show_gutter: bool,
+    show_file: bool,
+    aggregate: bool,
+    shown: &[Column],
+    table_width: u16,
+    breakpoints: Breakpoints,
+) -> Vec<Constraint> {
+    let layout = breakpoints.row_layout(table_width, false);
+    let time_col = if layout.show_date { 16 } else { 8 };
+    let (action_col, dir_col, proto_col, port_col, src_weight, dst_weight, service_weight) =
+        if layout.show_description {
+            (8, 5, 6, 6, 5, 5, 3)
+        } else if layout.show_date {
+            (7, 4, 5, 5, 4, 4, 2)
+        } else {
+            (6, 3, 4, 5, 3, 3, 2)
+        };
+    let mut columns = Vec::with_capacity(11);
+    if show_gutter {
+        columns.push(Constraint::Length(1));
+    }
+    if show_file {
+        columns.push(Constraint::Length(FILE_COLUMN));
+    }
+    // Count and first-seen lead the aggregated view; Time becomes last-seen.
+    if aggregate {
+        columns.extend([Constraint::Length(7), Constraint::Length(time_col)]);
+    }
+    columns.extend(shown.iter().map(|column| match column {
+        Column::Time => Constraint::Length(time_col),
+        Column::Action => Constraint::Length(action_col),
+        Column::Direction => Constraint::Length(dir_col),
+        Column::Source => Constraint::Fill(src_weight),
+        Column::Country => Constraint::Length(COUNTRY_COLUMN),
+        Column::Dest => Constraint::Fill(dst_weight),
+        Column::Proto => Constraint::Length(proto_col),
+        Column::SrcPort | Column::DstPort => Constraint::Length(port_col),
+        Column::Service => Constraint::Fill(service_weight),
+        Column::InIface | Column::OutIface => Constraint::Length(IFACE_COLUMN),
+        Column::Ttl => Constraint::Length(3),
+        Column::Length => Constraint::Length(5),
+        Column::Flags => Constraint::Length(FLAGS_COLUMN),
+    }));
+    columns
+}
+
+// The raw line and its packet summary need two rows; parsed fields get about
+// a quarter of the terminal.
+fn detail_pane_height(app: &App, height: u16) -> u16 {
+    if app.detail_raw {
+        4
+    } else {
+        (height / 4).clamp(4, 12)
+    }
+}
+
+// Header, filters, table, lower pane, detail pane and footer; with a
+// sidebar, the table gives up its right edge and the sidebar comes last.
+fn main_chunks(
+    area: Rect,
+    filters_height: u16,
+    lower_pane_height: u16,
+    detail_height: u16,
+    sidebar_width: u16,
+) -> Vec<Rect> {
+    let mut chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(filters_height),
+            Constraint::Min(5),
+            Constraint::Length(lower_pane_height),
+            Constraint::Length(detail_height),
+            Constraint::Length(2),
+        ])
+        .split(area)
+        .to_vec();
+    let [table, sidebar] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Length(sidebar_width)])
+        .areas(chunks[2]);
+    chunks[2] = table;
+    chunks.push(sidebar);
+    chunks
+}
+
+// The `|` sidebar takes SIDEBAR_WIDTH columns once the terminal is wide
+// enough to leave the table its minimum.
+fn sidebar_width(app: &App, width: u16) -> u16 {
+    if app.talkers.is_some() && width >= MIN_UI_WIDTH + SIDEBAR_WIDTH {
+        SIDEBAR_WIDTH
+    } else {
+        0
+    }
+}
+
+// Parsed fields for the log-entry pane, masked like the raw line when
+// redacting (the timestamp loses its hostname).
+fn detail_fields(entry: &LogEntry, redact: bool) -> Vec<(&'static str, String)> {
+    let mut fields = present_fields(entry);
+    if redact {
+        for (key, value) in &mut fields {
+            match *key {
+                "src" | "dst" => *value = redact_ip(value),
+                "mac" => *value = REDACTED_MAC.to_string(),
+                "time" => *value = format_timestamp_for_width(value, true),
+                _ => {}
+            }
+        }
+    }
+    fields
+}
+
+// `text` with characters `start..=end` reversed, as a mouse selection.
+fn highlight_chars(text: &str, start: usize, end: usize) -> Line<'static> {
+    let byte = |n: usize| text.char_indices().nth(n).map_or(text.len(), |(i, _)| i);
+    let (from, to) = (byte(start), byte(end + 1));
+    Line::from(vec![
+        Span::raw(text[..from].to_string()),
+        Span::styled(
+            text[from..to].to_string(),
+            Style::default().add_modifier(Modifier::REVERSED),
+        ),
+        Span::raw(text[to..].to_string()),
+    ])
+}
+
+fn text_cells(text: &str) -> u16 {
+    text.chars().count().min(u16::MAX as usize) as u16
+}
+
+fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
+    if max_width == 0 {
+        return String::new();
+    }
+    let cell_count = text.chars().count();
+    if cell_count <= max_width {
+        return text.to_string();
+    }
+    if max_width <= 3 {
+        return ".".repeat(max_width);
+    }
+    let mut out = String::with_capacity(max_width);
+    for ch in text.chars().take(max_width - 3) {
+        out.push(ch);
+    }
+    out.push_str("...");
+    out
+}
+
+fn pad_to_width(text: &str, width: usize) -> String {
+    let used = text.chars().count();
+    if used >= width {
+        return text.to_string();
+    }
+    format!("{}{}", text, " ".repeat(width - used))
+}
+
+fn build_three_column_line(width: u16, cells: [String; 3]) -> Line<'static> {
+    let total = usize::from(width.max(3));
+    let gap = if total >= 120 {
+        3
+    } else if total >= 90 {
+        2
+    } else {
+        1
+    };
+    let usable = total.saturating_sub(gap * 2);
+    let col1 = usable / 3;
+    let col2 = usable / 3;
+    let col3 = usable.saturating_sub(col1 + col2);
+    let c1 = truncate_with_ellipsis(&cells[0], col1);
+    let c2 = truncate_with_ellipsis(&cells[1], col2);
+    let c3 = truncate_with_ellipsis(&cells[2], col3);
+    let line = format!(
+        "{}{}{}{}{}",
+        pad_to_width(&c1, col1),
+        " ".repeat(gap),
+        pad_to_width(&c2, col2),
+        " ".repeat(gap),
+        c3
+    );
+    Line::from(vec![Span::raw(line)])
+}
+
+// "F2 port=22 (−1,204)": the value plus how many rows it excludes on its own.
+fn filter_summary_item(app: &App, field: FilterField) -> String {
+    let mut item = filter_assignment(field.summary_label(), app.filters.value(field));
+    if let Some(excluded) = app.filter_exclusion(field) {
+        item.push_str(&format!(" (−{})", group_thousands(excluded)));
+    }
+    item
+}
+
+fn group_thousands(value: usize) -> String {
+    let digits = value.to_string();
+    let mut out = String::new();
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+fn filter_summary_one_line_text(app: &App) -> String {
+    let items: Vec<String> = FilterField::ALL
+        .iter()
+        .map(|field| filter_summary_item(app, *field))
+        .collect();
+    format!(
+        "{} ({} active)",
+        items.join(" | "),
+        app.filters.active_count()
+    )
+}
+
+fn filter_summary_line_count(app: &App, width: u16) -> u16 {
+    let one_line = filter_summary_one_line_text(app);
+    if text_cells(&one_line) <= width { 1 } else { 2 }
+}
+
+fn filter_summary_lines(app: &App, width: u16) -> Vec<Line<'static>> {
+    let one_line = filter_summary_one_line_text(app);
+    if text_cells(&one_line) <= width {
+        return vec![Line::from(one_line)];
+    }
+
+    let item = |field| filter_summary_item(app, field);
+    let row1 = build_three_column_line(
+        width,
+        [
+            item(FilterField::Service),
+            item(FilterField::Port),
+            format!("{} | {}", item(FilterField::Ip), item(FilterField::Country)),
+        ],
+    );
+    let row2 = build_three_column_line(
+        width,
+        [
+            item(FilterField::Action),
+            format!(
+                "{} | {}",
+                item(FilterField::Proto),
+                item(FilterField::Flags)
+            ),
+            format!(
+                "{} | {} ({} active)",
+                item(FilterField::Text),
+                item(FilterField::Time),
+                app.filters.active_count()
+            ),
+        ],
+    );
+    vec![row1, row2]
+}
+
+// Room below the table for tcpdump output and the Alerts panel, side by side
+// when both are open.
+fn lower_pane_height(app: &App, height: u16) -> u16 {
+    if app.capture.is_some() || app.show_alerts {
+        (height / 3).clamp(5, 12)
+    } else {
+        0
+    }
+}
+
+fn filter_panel_height(app: &App, width: u16) -> u16 {
+    // Keep the rendered filter panel height and mouse-hitbox rows in sync.
+    let summary_rows = filter_summary_line_count(app, width);
+    let controls_rows = if controls_layout(app, width).0 { 1 } else { 2 };
+    summary_rows.saturating_add(controls_rows)
+}
+
+fn rect_contains(rect: Rect, x: u16, y: u16) -> bool {
+    x >= rect.x
+        && x < rect.x.saturating_add(rect.width)
+        && y >= rect.y
+        && y < rect.y.saturating_add(rect.height)
+}
+
+fn push_toggle_hitbox(
+    hitboxes: &mut Vec<(Rect, ToggleTarget)>,
+    x: u16,
+    y: u16,
+    label: &str,
+    target: ToggleTarget,
+    bounds: Rect,
+) {
+    let width = text_cells(label);
+    if width == 0 {
+        return;
+    }
+    let end_x = x.saturating_add(width);
+    let bounds_end_x = bounds.x.saturating_add(bounds.width);
+    if x >= bounds_end_x || end_x <= bounds.x || !rect_contains(bounds, x.max(bounds.x), y) {
+        return;
+    }
+    let rect_x = x.max(bounds.x);
+    let rect_end_x = end_x.min(bounds_end_x);
+    let rect_width = rect_end_x.saturating_sub(rect_x);
+    if rect_width == 0 {
+        return;
+    }
+    hitboxes.push((
+        Rect {
+            x: rect_x,
+            y,
+            width: rect_width,
+            height: 1,
+        },
+        target,
+    ));
+}
+
+fn build_toggle_hitboxes(app: &App, filters_area: Rect) -> Vec<(Rect, ToggleTarget)> {
+    if filters_area.height < 2 || filters_area.width == 0 {
+        return Vec::new();
+    }
+    let mut hitboxes = Vec::new();
+    let (combined, visible_ifaces, iface_right_start) = controls_layout(app, filters_area.width);
+    let summary_rows = filter_summary_line_count(app, filters_area.width);
+    let toggles_y = filters_area.y.saturating_add(summary_rows);
+    let mut x = filters_area.x;
+
+    let local = "[local]";
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        toggles_y,
+        local,
+        ToggleTarget::Local,
+        filters_area,
+    );
+    x = x.saturating_add(text_cells(local)).saturating_add(1);
+
+    let wan = "[wan]";
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        toggles_y,
+        wan,
+        ToggleTarget::WanSrc,
+        filters_area,
+    );
+    x = x.saturating_add(text_cells(wan)).saturating_add(1);
+
+    let bcast = "[bcast]";
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        toggles_y,
+        bcast,
+        ToggleTarget::Broadcast,
+        filters_area,
+    );
+    x = x.saturating_add(text_cells(bcast)).saturating_add(1);
+
+    let replies = "[reply]";
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        toggles_y,
+        replies,
+        ToggleTarget::Replies,
+        filters_area,
+    );
+    x = x.saturating_add(text_cells(replies)).saturating_add(1);
+
+    let flow = app.flow_filter.chip();
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        toggles_y,
+        flow,
+        ToggleTarget::Flow,
+        filters_area,
+    );
+    x = x.saturating_add(text_cells(flow)).saturating_add(2);
+
+    let dir = app.direction_filter.chip();
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        toggles_y,
+        dir,
+        ToggleTarget::Direction,
+        filters_area,
+    );
+
+    let iface_y = if combined {
+        toggles_y
+    } else if filters_area.height > summary_rows.saturating_add(1) {
+        toggles_y.saturating_add(1)
+    } else {
+        return hitboxes;
+    };
+    let iface_start_x = if combined {
+        filters_area.x.saturating_add(iface_right_start)
+    } else {
+        filters_area.x
+    };
+    let mut iface_x =
+        iface_start_x.saturating_add(text_cells(interface_prefix(filters_area.width)));
+
+    let all_label = if app.selected_interface.is_none() {
+        "[all]"
+    } else {
+        "all"
+    };
+    push_toggle_hitbox(
+        &mut hitboxes,
+        iface_x,
+        iface_y,
+        all_label,
+        ToggleTarget::AllIfaces,
+        filters_area,
+    );
+    iface_x = iface_x.saturating_add(text_cells(all_label));
+
+    for iface in app.interface_options.iter().take(visible_ifaces) {
+        iface_x = iface_x.saturating_add(1);
+        let selected = app.selected_interface.as_deref() == Some(iface.as_str());
+        let label = interface_chip_label(app, iface, selected);
+        push_toggle_hitbox(
+            &mut hitboxes,
+            iface_x,
+            iface_y,
+            &label,
+            ToggleTarget::Interface(iface.clone()),
+            filters_area,
+        );
+        iface_x = iface_x.saturating_add(text_cells(&label));
+    }
+
+    hitboxes
+}
+
+fn build_header_hitboxes(app: &App, header_area: Rect) -> Vec<(Rect, ToggleTarget)> {
+    if header_area.width == 0 || header_area.height == 0 {
+        return Vec::new();
+    }
+    let mut hitboxes = Vec::new();
+    let y = header_area.y;
+    let x = header_area.x.saturating_add(text_cells(APP_TITLE) + 1);
+    let chip = pause_chip_label(app.updates_paused);
+    push_toggle_hitbox(
+        &mut hitboxes,
+        x,
+        y,
+        chip,
+        ToggleTarget::PauseUpdates,
+        header_area,
+    );
+    hitboxes
+}
+
+// Header-row rect of every table column, gutter and country included.
+fn table_column_rects(app: &App, table_area: Rect) -> Vec<Rect> {
+    if table_area.width < 3 || table_area.height < 3 {
+        return Vec::new();
+    }
+    // Mirror the table layout: inside the border, columns separated by one cell.
+    let header = Rect {
+        x: table_area.x + 1,
+        y: table_area.y + 1,
+        width: table_area.width - 2,
+        height: 1,
+    };
+    let columns = table_column_constraints(
+        app.show_minute_gutter,
+        app.show_file_column(),
+        app.aggregate,
+        &app.visible_columns(),
+        table_area.width,
+        app.breakpoints,
+    );
+    Layout::horizontal(columns)
+        .flex(Flex::Start)
+        .spacing(1)
+        .split(header)
+        .to_vec()
+}
+
+// Indices into `table_column_rects` of the Source, Dest and Service columns,
+// when shown.
+fn wide_column_indices(app: &App) -> [Option<usize>; 3] {
+    let lead = lead_columns(app);
+    let shown = app.visible_columns();
+    [Column::Source, Column::Dest, Column::Service].map(|wide| {
+        shown
+            .iter()
+            .position(|column| *column == wide)
+            .map(|pos| lead + pos)
+    })
+}
+
+fn source_prompt_text(prompt: &SourcePrompt) -> String {
+    format!(
+        "Open: {}_ (log, directory or journal; Tab keep current entries: {}, Enter open, Esc cancel)",
+        prompt.input,
+        if prompt.keep { "yes" } else { "no" }
+    )
+}
+
+// Cuts text that does not fit its column and marks the cut with "…".
+fn fit_cell(text: &str, width: u16) -> Cow<'_, str> {
+    if text_cells(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    let mut out: String = text
+        .chars()
+        .take(usize::from(width.saturating_sub(1)))
+        .collect();
+    out.push('…');
+    Cow::Owned(out)
+}
+
+// Gutter, File and aggregate columns in front of Time.
+fn lead_columns(app: &App) -> usize {
+    usize::from(app.show_minute_gutter)
+        + usize::from(app.show_file_column())
+        + 2 * usize::from(app.aggregate)
+}
+
+fn build_sort_header_hitboxes(app: &App, table_area: Rect) -> Vec<(Rect, ToggleTarget)> {
+    table_column_rects(app, table_area)
+        .iter()
+        .skip(lead_columns(app))
+        .zip(app.visible_columns())
+        .filter_map(|(rect, column)| {
+            Some((*rect, ToggleTarget::SortBy(SortColumn::for_column(column)?)))
+        })
+        .collect()
+}
+
+fn apply_toggle_target(app: &mut App, target: ToggleTarget) {
+    match target {
+        ToggleTarget::Local => app.toggle_show_local_src(),
+        ToggleTarget::WanSrc => app.toggle_show_wan_src(),
+        ToggleTarget::Broadcast => app.toggle_show_broadcast(),
+        ToggleTarget::Replies => app.toggle_show_replies(),
+        ToggleTarget::Flow => app.cycle_flow_filter(),
+        ToggleTarget::Direction => app.cycle_direction_filter(),
+        ToggleTarget::PauseUpdates => app.toggle_pause_updates(),
+        ToggleTarget::AllIfaces => app.select_all_interfaces(),
+        ToggleTarget::SortBy(column) => app.sort_by_header(column),
+        ToggleTarget::Interface(name) => {
+            app.set_selected_interface(Some(name));
+            let matches = app.filtered_indices().len();
+            app.status.info(format!(
+                "Interface: {}. Matching rows: {}",
+                selected_iface_label(app.selected_interface.as_deref()),
+                matches
+            ));
+            app.offer_pin();
+        }
+    }
+}
+
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent, area: Rect) {
+    let filters_height = filter_panel_height(app, area.width);
+    let chunks = main_chunks(
+        area,
+        filters_height,
+        lower_pane_height(app, area.height),
+        detail_pane_height(app, area.height),
+        sidebar_width(app, area.width),
+    );
+    let mouse_x = mouse.column;
+    let mouse_y = mouse.row;
+
+    if let Some(report) = app.shown_report_mut() {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => report.scroll_by(-1),
+            MouseEventKind::ScrollDown => report.scroll_by(1),
+            _ => {}
+        }
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            for (rect, target) in build_header_hitboxes(app, chunks[0]) {
+                if rect_contains(rect, mouse_x, mouse_y) {
+                    apply_toggle_target(app, target);
+                    return;
+                }
+            }
+
+            for (rect, target) in build_toggle_hitboxes(app, chunks[1]) {
+                if rect_contains(rect, mouse_x, mouse_y) {
+                    apply_toggle_target(app, target);
+                    return;
+                }
+            }
+
+            for (rect, target) in build_sort_header_hitboxes(app, chunks[2]) {
+                if rect_contains(rect, mouse_x, mouse_y) {
+                    apply_toggle_target(app, target);
+                    return;
+                }
+            }
+
+            let sidebar = chunks[6];
+            if rect_contains(sidebar, mouse_x, mouse_y) && mouse_y > sidebar.y {
+                let line = usize::from(mouse_y - sidebar.y - 1);
+                if let Some(pos) = app.top_talkers().item_at_line(line) {
+                    app.apply_talker(pos);
+                }
+                return;
+            }
+
+            let table_area = chunks[2];
+            if rect_contains(table_area, mouse_x, mouse_y) && table_area.height >= 3 {
+                let rows_start_y = table_area.y + 2;
+                let rows_end_y = table_area.y + table_area.height - 1;
+                if mouse_y >= rows_start_y
+                    && mouse_y < rows_end_y
+                    && let Some(row) = app.row_at_line(usize::from(mouse_y - rows_start_y))
+                {
+                    app.select_visible_row_index(row);
+                }
+            }
+
+            // Dragging across the detail line selects text to copy.
+            let detail = chunks[4];
+            if rect_contains(detail, mouse_x, mouse_y)
+                && mouse_y == detail.y + 1
+                && app.detail_raw
+                && app.shows_log_entry()
+            {
+                let column = app.detail_column(detail, mouse_x);
+                app.detail_drag = Some((column, column));
+            }
+        }
+        MouseEventKind::Drag(MouseButton::Left) => {
+            if let Some((anchor, _)) = app.detail_drag {
+                app.detail_drag = Some((anchor, app.detail_column(chunks[4], mouse_x)));
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) => app.copy_detail_selection(),
+        // Hovering a cut-off Source, Dest or Service cell shows its full value.
+        MouseEventKind::Moved if !app.show_stats => {
+            let rows_start_y = chunks[2].y + 2;
+            if !rect_contains(chunks[2], mouse_x, mouse_y) || mouse_y < rows_start_y {
+                return;
+            }
+            let Some(row) = app.row_at_line(usize::from(mouse_y - rows_start_y)) else {
+                return;
+            };
+            let Some(&idx) = app.view_indices().get(app.table_state.offset() + row) else {
+                return;
+            };
+            let hovered = app
+                .truncated_cells(&app.entries[idx])
+                .into_iter()
+                .find(|(_, _, rect)| mouse_x >= rect.x && mouse_x < rect.right());
+            if let Some((label, value, _)) = hovered {
+                app.status.info(format!("{}: {}", label, value));
+            }
+        }
+        MouseEventKind::ScrollUp => {
+            let table_area = chunks[2];
+            if rect_contains(table_area, mouse_x, mouse_y) {
+                let filtered_len = app.view_indices().len();
+                if filtered_len > 0 {
+                    app.selected = app.selected.saturating_sub(1);
+                    app.sync_selection_with_len(filtered_len);
+                }
+            }
         }
         MouseEventKind::ScrollDown => {
             let table_area = chunks[2];
             if rect_contains(table_area, mouse_x, mouse_y) {
-                let filtered_len = app.filtered_indices().len();
+                let filtered_len = app.view_indices().len();
                 if filtered_len > 0 {
                     app.selected = min(app.selected + 1, filtered_len.saturating_sub(1));
                     app.sync_selection_with_len(filtered_len);
                 }
             }
         }
-        _ => {}
+        _ => {}
+    }
+}
+
+fn ui(frame: &mut Frame, app: &mut App) {
+    app.status.expire();
+    app.refresh_filter_exclusions();
+    app.refresh_interface_counts();
+    let area = frame.area();
+    app.frame_area = area;
+    if area.width < MIN_UI_WIDTH || area.height < MIN_UI_HEIGHT {
+        let target_w = min(area.width.saturating_sub(2), 56).max(20);
+        let target_h = min(area.height.saturating_sub(2), 7).max(5);
+        let vpad = area.height.saturating_sub(target_h) / 2;
+        let hpad = area.width.saturating_sub(target_w) / 2;
+        let popup = Rect {
+            x: area.x.saturating_add(hpad),
+            y: area.y.saturating_add(vpad),
+            width: target_w,
+            height: target_h,
+        };
+        let msg = Paragraph::new(vec![
+            Line::from(format!(
+                "Terminal too small: {}x{}",
+                area.width, area.height
+            )),
+            Line::from(format!("Need at least {}x{}", MIN_UI_WIDTH, MIN_UI_HEIGHT)),
+            Line::from("Resize terminal to continue"),
+        ])
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(APP_TITLE));
+        frame.render_widget(msg, popup);
+        return;
+    }
+
+    let filters_height = filter_panel_height(app, area.width);
+    let chunks = main_chunks(
+        area,
+        filters_height,
+        lower_pane_height(app, area.height),
+        detail_pane_height(app, area.height),
+        sidebar_width(app, area.width),
+    );
+
+    // In the aggregated view the rows are group representatives; pinning
+    // does not apply there.
+    let (filtered_indices, pinned_extra, groups, bursts) = if app.aggregate {
+        let (representatives, groups) = aggregate::group(&app.entries, &app.filtered_indices());
+        (representatives, None, groups, HashMap::new())
+    } else {
+        let (indices, pinned_extra, bursts) = app.view_rows();
+        (indices, pinned_extra, HashMap::new(), bursts)
+    };
+    app.sync_selection_with_len(filtered_indices.len());
+    let match_count = if app.aggregate {
+        groups.values().map(|group| group.count).sum()
+    } else {
+        filtered_indices.len() - usize::from(pinned_extra.is_some())
+            + bursts.values().map(|burst| burst.count - 1).sum::<usize>()
+    };
+    let count_text = if app.aggregate {
+        format!(
+            "{} groups · {}/{}",
+            filtered_indices.len(),
+            match_count,
+            app.entries.len()
+        )
+    } else {
+        format!("{}/{}", match_count, app.entries.len())
+    };
+    let live_style = if app.updates_paused {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        Style::default()
+            .fg(Color::LightGreen)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    };
+    let pause_chip_width = text_cells("[live]").max(text_cells("[paused]"));
+    let offline_chip = if offline::network_allowed() {
+        ""
+    } else {
+        " [offline]"
+    };
+    let redact_chip = if app.redact { " [redacted]" } else { "" };
+    let macro_chip = app
+        .macros
+        .recording()
+        .map(|register| format!(" [rec {}]", register))
+        .unwrap_or_default();
+    let time_chip = app
+        .time_window
+        .as_ref()
+        .map(|window| format!(" [±{}m]", window.radius_secs / 60))
+        .unwrap_or_default();
+    let lock_chip = if app.selection_lock.is_some() {
+        " [locked]".to_string()
+    } else if let Some((_, new_count)) = app.held_ingest.as_ref() {
+        format!(" [+{} new]", new_count)
+    } else {
+        String::new()
+    };
+    let ingest_chip = app
+        .pending_ingest
+        .as_ref()
+        .map(|pending| format!(" [loading… {}%]", pending.progress))
+        .unwrap_or_default();
+    let unexpected_count = if app.expected_ports.is_some() {
+        app.entries
+            .iter()
+            .filter(|entry| app.is_unexpected_allow(entry))
+            .count()
+    } else {
+        0
+    };
+    let unexpected_chip = if unexpected_count > 0 {
+        format!(" [{} unexpected]", unexpected_count)
+    } else {
+        String::new()
+    };
+    let bogon_count = app
+        .entries
+        .iter()
+        .filter(|entry| app.bogon_source(entry).is_some())
+        .count();
+    let bogon_chip = match (bogon_count, app.bogon_only) {
+        (_, true) => format!(" [bogon only: {}]", bogon_count),
+        (0, false) => String::new(),
+        (count, false) => format!(" [{} bogon]", count),
+    };
+    let listed_count = if app.blocklists.is_some() {
+        app.entries
+            .iter()
+            .filter(|entry| app.blocklisted(entry).is_some())
+            .count()
+    } else {
+        0
+    };
+    let listed_chip = match (listed_count, app.listed_only) {
+        (_, true) => format!(" [listed only: {}]", listed_count),
+        (0, false) => String::new(),
+        (count, false) => format!(" [{} listed]", count),
+    };
+    let scan_chip = match app.scans.alerts.len() {
+        0 => String::new(),
+        count => format!(" [{} scans]", count),
+    };
+    let forward_chip: String = app
+        .sinks
+        .iter()
+        .map(|sink| format!(" [{} {}]", sink.kind.chip, sink.sent()))
+        .collect();
+    let left_width = text_cells(APP_TITLE)
+        .saturating_add(1)
+        .saturating_add(pause_chip_width)
+        .saturating_add(text_cells(offline_chip))
+        .saturating_add(text_cells(redact_chip))
+        .saturating_add(text_cells(&macro_chip))
+        .saturating_add(text_cells(&time_chip))
+        .saturating_add(text_cells(&lock_chip))
+        .saturating_add(text_cells(&ingest_chip))
+        .saturating_add(text_cells(&forward_chip))
+        .saturating_add(text_cells(&unexpected_chip))
+        .saturating_add(text_cells(&bogon_chip))
+        .saturating_add(text_cells(&listed_chip))
+        .saturating_add(text_cells(&scan_chip));
+    let count_width = text_cells(&count_text);
+    let header_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(left_width),
+            Constraint::Min(1),
+            Constraint::Length(count_width),
+        ])
+        .split(chunks[0]);
+
+    let header_left = Paragraph::new(Line::from(vec![
+        Span::styled(APP_TITLE, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" "),
+        Span::styled(pause_chip_label(app.updates_paused), live_style),
+        Span::styled(offline_chip, Style::default().fg(Color::DarkGray)),
+        Span::styled(redact_chip, Style::default().fg(Color::LightRed)),
+        Span::styled(macro_chip, Style::default().fg(Color::Red)),
+        Span::styled(time_chip, Style::default().fg(Color::LightBlue)),
+        Span::styled(lock_chip, Style::default().fg(Color::LightYellow)),
+        Span::styled(ingest_chip, Style::default().fg(Color::Yellow)),
+        Span::styled(forward_chip, Style::default().fg(Color::LightCyan)),
+        Span::styled(unexpected_chip, Style::default().fg(Color::LightMagenta)),
+        Span::styled(bogon_chip, Style::default().fg(Color::LightRed)),
+        Span::styled(listed_chip, Style::default().fg(Color::LightYellow)),
+        Span::styled(
+            scan_chip,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    frame.render_widget(header_left, header_chunks[0]);
+
+    let center_text = if app.status.is_empty() {
+        let mut extra = Vec::new();
+        if !app.extra_sources.is_empty() {
+            extra.push(format!("+{} files", app.extra_sources.len()));
+        }
+        if !app.watched_sources.is_empty() {
+            extra.push(format!("+{} watched", app.watched_sources.len()));
+        }
+        if !app.rotated_sources.is_empty() {
+            extra.push(format!("+{} rotated", app.rotated_sources.len()));
+        }
+        if extra.is_empty() {
+            app.log_path.display().to_string()
+        } else {
+            format!("{} ({})", app.log_path.display(), extra.join(", "))
+        }
+    } else if app.status.level() == status::Level::Error {
+        format!("{} (Esc to dismiss)", app.status.text())
+    } else {
+        app.status.text().to_string()
+    };
+    let center_style = Style::default().fg(app.status.level().color());
+    let header_center = Paragraph::new(Line::from(Span::styled(center_text, center_style)))
+        .alignment(Alignment::Center);
+    frame.render_widget(header_center, header_chunks[1]);
+
+    let header_count = Paragraph::new(Line::from(Span::styled(
+        count_text,
+        Style::default().fg(Color::Yellow),
+    )))
+    .alignment(Alignment::Right);
+    frame.render_widget(header_count, header_chunks[2]);
+
+    let filter_summary = filter_summary_lines(app, chunks[1].width);
+    let (combined_controls, visible_ifaces, iface_right_start) =
+        controls_layout(app, chunks[1].width);
+    let mut control_line_spans = left_toggle_spans(app);
+    if combined_controls {
+        let gap = iface_right_start.saturating_sub(left_toggle_width(app));
+        control_line_spans.push(Span::raw(" ".repeat(gap as usize)));
+        control_line_spans.extend(interface_display_spans(
+            app,
+            visible_ifaces,
+            chunks[1].width,
+        ));
+    }
+    let mut filter_lines = filter_summary;
+    filter_lines.push(Line::from(control_line_spans));
+    if !combined_controls {
+        filter_lines.push(Line::from(interface_display_spans(
+            app,
+            visible_ifaces,
+            chunks[1].width,
+        )));
+    }
+    let filters = Paragraph::new(filter_lines);
+    frame.render_widget(filters, chunks[1]);
+
+    let selected = app.selected;
+    let table_width = chunks[2].width;
+    let row_layout = app.breakpoints.row_layout(table_width, app.redact);
+    let show_date_in_time_col = row_layout.show_date;
+    let show_gutter = app.show_minute_gutter;
+    let show_file = app.show_file_column();
+    let shown_columns = app.visible_columns();
+
+    // Only the rows inside the table viewport are built each frame; their cell
+    // text comes from the render cache.
+    let visible_rows = usize::from(chunks[2].height.saturating_sub(3));
+    app.retarget_selection_lock(&filtered_indices);
+    // The "older entries not loaded" line counts as one more row, so it
+    // scrolls into view below the last entry.
+    let older_line = usize::from(app.unloaded_older > 0);
+    let offset = if app.selection_lock.is_some() {
+        centered_window_offset(selected, visible_rows, filtered_indices.len())
+    } else {
+        let offset = visible_window_offset(
+            app.table_state.offset(),
+            app.table_state.selected(),
+            visible_rows,
+            filtered_indices.len() + older_line,
+        );
+        if app.table_state.selected() == filtered_indices.len().checked_sub(1) {
+            offset.max((filtered_indices.len() + older_line).saturating_sub(visible_rows))
+        } else {
+            offset
+        }
+    };
+    let (offset, row_count, row_lines) = fit_window(
+        offset,
+        app.table_state.selected(),
+        visible_rows,
+        filtered_indices.len(),
+        |pos| app.rotation_boundary(&filtered_indices, pos),
+    );
+    let window = &filtered_indices[offset..offset + row_count];
+    let separators: Vec<Option<String>> = (offset..offset + row_count)
+        .map(|pos| {
+            app.rotation_boundary(&filtered_indices, pos)
+                .then(|| app.rotation_separator(app.entries[filtered_indices[pos]].source))
+        })
+        .collect();
+    app.render_cache.prepare(row_layout);
+    for entry_idx in window {
+        app.render_cache.ensure(&app.entries[*entry_idx]);
+    }
+
+    let unexpected: Vec<bool> = window
+        .iter()
+        .map(|idx| app.is_unexpected_allow(&app.entries[*idx]))
+        .collect();
+    let bogon: Vec<bool> = window
+        .iter()
+        .map(|idx| app.bogon_source(&app.entries[*idx]).is_some())
+        .collect();
+    let listed: Vec<bool> = window
+        .iter()
+        .map(|idx| app.blocklisted(&app.entries[*idx]).is_some())
+        .collect();
+    let column_rects = table_column_rects(app, chunks[2]);
+    let [src_width, dst_width, service_width] = wide_column_indices(app).map(|column| {
+        column
+            .and_then(|column| column_rects.get(column))
+            .map_or(u16::MAX, |rect| rect.width)
+    });
+    let entries = &app.entries;
+    let render_cache = &app.render_cache;
+    let gutter_bars = if show_gutter {
+        minute_gutter_bars(entries, &filtered_indices)
+    } else {
+        Vec::new()
+    };
+    let rows = window.iter().enumerate().filter_map(|(pos, entry_idx)| {
+        let entry = &entries[*entry_idx];
+        let cached = render_cache.get(entry.id)?;
+        let pinned = pinned_extra == Some(*entry_idx);
+
+        let action_color = match entry.action.as_str() {
+            _ if pinned => Color::DarkGray,
+            "ALLOW" => Color::Green,
+            "BLOCK" | "REJECT" => Color::Red,
+            _ => Color::Yellow,
+        };
+        let direction = entry.direction();
+        let dir_color = match direction {
+            _ if pinned => Color::DarkGray,
+            "IN" => Color::LightBlue,
+            "OUT" => Color::LightMagenta,
+            "FWD" => Color::LightCyan,
+            _ => Color::DarkGray,
+        };
+        // Pinned rows no longer match the filters; render them dimmed.
+        let row_style = if pinned {
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::DIM | Modifier::ITALIC)
+        } else {
+            Style::default()
+        };
+
+        let group = groups.get(entry_idx);
+        let mut cells = Vec::with_capacity(13);
+        if show_gutter {
+            cells.push(
+                Cell::from(gutter_bars[offset + pos]).style(Style::default().fg(Color::LightBlue)),
+            );
+        }
+        if show_file {
+            let name = app.source_name(entry.source);
+            cells.push(
+                Cell::from(fit_cell(&name, FILE_COLUMN).into_owned())
+                    .style(Style::default().fg(Color::DarkGray)),
+            );
+        }
+        let group_time =
+            |idx: usize| format_timestamp_for_width(&entries[idx].timestamp, show_date_in_time_col);
+        if let Some(group) = group {
+            cells.extend([
+                Cell::from(format!("{:>7}", group_thousands(group.count)))
+                    .style(Style::default().fg(Color::Yellow)),
+                Cell::from(group_time(group.first_seen)),
+            ]);
+        }
+        cells.extend(shown_columns.iter().map(|column| {
+            match column {
+                Column::Time if let Some(burst) = bursts.get(entry_idx) => {
+                    Cell::from(format!("⊞ {}", group_thousands(burst.count))).style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                }
+                Column::Time => {
+                    Cell::from(group.map_or(Cow::Borrowed(cached.time.as_str()), |group| {
+                        Cow::Owned(group_time(group.last_seen))
+                    }))
+                }
+                Column::Action => {
+                    Cell::from(entry.action.as_str()).style(Style::default().fg(action_color))
+                }
+                Column::Direction => Cell::from(direction).style(Style::default().fg(dir_color)),
+                Column::Source if listed[pos] && !pinned => Cell::from(format!(
+                    "{}#",
+                    fit_cell(&cached.src, src_width.saturating_sub(1))
+                ))
+                .style(
+                    Style::default()
+                        .fg(Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Column::Source if bogon[pos] && !pinned => Cell::from(format!(
+                    "{}!",
+                    fit_cell(&cached.src, src_width.saturating_sub(1))
+                ))
+                .style(
+                    Style::default()
+                        .fg(Color::LightRed)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Column::Source => Cell::from(fit_cell(&cached.src, src_width)),
+                Column::Country => Cell::from(entry.country.as_deref().unwrap_or("-")),
+                Column::Dest => Cell::from(fit_cell(&cached.dst, dst_width)),
+                Column::Proto => Cell::from(entry.proto.as_deref().unwrap_or("-")),
+                Column::SrcPort => {
+                    Cell::from(if group.is_some_and(|group| group.mixed_src_ports) {
+                        "*"
+                    } else {
+                        cached.src_port.as_str()
+                    })
+                }
+                Column::DstPort if unexpected[pos] && !pinned => {
+                    Cell::from(format!("{}!", cached.dst_port)).style(
+                        Style::default()
+                            .fg(Color::LightMagenta)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                }
+                Column::DstPort => Cell::from(cached.dst_port.as_str()),
+                Column::Service => Cell::from(fit_cell(&cached.service, service_width)),
+                Column::InIface
+                | Column::OutIface
+                | Column::Ttl
+                | Column::Length
+                | Column::Flags => Cell::from(column_text(entry, *column, app.redact)),
+            }
+        }));
+        let separated = separators[pos].is_some();
+        Some(
+            Row::new(cells)
+                .style(row_style)
+                .top_margin(u16::from(separated)),
+        )
+    });
+
+    let columns = table_column_constraints(
+        show_gutter,
+        show_file,
+        app.aggregate,
+        &shown_columns,
+        table_width,
+        app.breakpoints,
+    );
+    let mut header_cells = Vec::with_capacity(13);
+    if show_gutter {
+        header_cells.push(String::new());
+    }
+    if show_file {
+        header_cells.push("File".to_string());
+    }
+    if app.aggregate {
+        header_cells.extend(["  Count".to_string(), "First seen".to_string()]);
+    }
+    header_cells.extend(shown_columns.iter().map(|column| {
+        let label = match column {
+            Column::Time if app.aggregate => "Last seen",
+            Column::Time if show_date_in_time_col => "Date/Time",
+            column => column.header(),
+        };
+        if SortColumn::for_column(*column) == Some(app.sort_column) {
+            format!("{} {}", label, sort_indicator(app.sort_descending))
+        } else {
+            label.to_string()
+        }
+    }));
+
+    let table = Table::new(rows, columns)
+        .header(Row::new(header_cells).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title("UFW Events"))
+        .row_highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut separator_lines = Vec::new();
+    let mut window_state =
+        TableState::default().with_selected(app.table_state.selected().map(|s| s - offset));
+    if app.show_stats {
+        let matching = if app.aggregate {
+            app.filtered_indices()
+        } else {
+            filtered_indices.clone()
+        };
+        let shown = matching
+            .iter()
+            .filter(|&&idx| pinned_extra != Some(idx))
+            .map(|&idx| &app.entries[idx]);
+        let limit = usize::from(chunks[2].height.saturating_sub(3));
+        let view_stats = stats::ViewStats::compute(shown, limit, app.ipv6_prefix);
+        let scope = app.view_context().describe();
+        stats::render_stats(frame, chunks[2], &view_stats, scope.as_deref(), app.redact);
+    } else {
+        frame.render_stateful_widget(table, chunks[2], &mut window_state);
+        let mut line = 0;
+        for separator in &separators {
+            if let Some(label) = separator {
+                let width = usize::from(chunks[2].width.saturating_sub(2));
+                let text = format!("── {} ", label);
+                let fill = width.saturating_sub(text.chars().count());
+                frame.render_widget(
+                    Paragraph::new(format!("{}{}", text, "─".repeat(fill)))
+                        .style(Style::default().fg(Color::DarkGray)),
+                    Rect {
+                        x: chunks[2].x + 1,
+                        y: chunks[2].y + 2 + line as u16,
+                        width: width as u16,
+                        height: 1,
+                    },
+                );
+                separator_lines.push(line);
+                line += 1;
+            }
+            line += 1;
+        }
+        let older_row = if offset + row_count == filtered_indices.len() {
+            row_lines
+        } else {
+            visible_rows
+        };
+        if older_line > 0 && older_row < visible_rows {
+            frame.render_widget(
+                Paragraph::new(format!(
+                    "… {} older entries not loaded (press L to load more)",
+                    group_thousands(app.unloaded_older)
+                ))
+                .style(
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::ITALIC),
+                ),
+                Rect {
+                    x: chunks[2].x + 1,
+                    y: chunks[2].y + 2 + older_row as u16,
+                    width: chunks[2].width.saturating_sub(2),
+                    height: 1,
+                },
+            );
+        }
+    }
+    *app.table_state.offset_mut() = offset;
+    app.separator_lines = separator_lines;
+
+    if chunks[6].width > 0 {
+        let talkers = app.top_talkers();
+        let cursor = app
+            .talkers
+            .as_ref()
+            .filter(|sidebar| sidebar.focused)
+            .map(|sidebar| sidebar.cursor.min(talkers.item_count().saturating_sub(1)));
+        let title = if cursor.is_some() {
+            "Top talkers (Enter, Esc)"
+        } else {
+            "Top talkers (| to focus)"
+        };
+        frame.render_widget(
+            Paragraph::new(talkers.lines(
+                usize::from(chunks[6].width.saturating_sub(2)),
+                cursor,
+                app.redact,
+            ))
+            .block(Block::default().borders(Borders::ALL).title(title)),
+            chunks[6],
+        );
+    }
+
+    let detail = if let Some(field) = app.input_mode {
+        format!(
+            "Edit {}: {} (Tab {}, Enter apply, Esc cancel)",
+            field.label(),
+            if app.input_buffer.is_empty() {
+                "_"
+            } else {
+                &app.input_buffer
+            },
+            app.completion
+                .as_ref()
+                .map_or_else(|| "complete".to_string(), Completion::position)
+        )
+    } else if let Some(prompt) = app.find_prompt.as_ref() {
+        format!(
+            "Find: {}_ ({} matching rows, Enter keep, Esc cancel)",
+            prompt.term, prompt.matches
+        )
+    } else if let Some(prompt) = app.export_prompt.as_ref() {
+        format!(
+            "Export {} rows as {} to: {}_ (Tab format, Enter write, Esc cancel)",
+            match_count,
+            prompt.format.label(),
+            prompt.path
+        )
+    } else if let Some(prompt) = app.source_prompt.as_ref() {
+        source_prompt_text(prompt)
+    } else if let Some(prompt) = app.block_prompt_text() {
+        prompt
+    } else if filtered_indices.is_empty() {
+        "No rows match current view (filters/interface/local+wan/flow/dir).".to_string()
+    } else {
+        app.detail_raw(filtered_indices[selected])
+    };
+    let detail_title = if app.input_mode.is_some() {
+        "Edit Filter".to_string()
+    } else if app.find_prompt.is_some() {
+        "Find in View".to_string()
+    } else if app.export_prompt.is_some() {
+        "Export View".to_string()
+    } else if app.source_prompt.is_some() {
+        "Open Source".to_string()
+    } else if let Some(action) = app.block_prompt.as_ref() {
+        match action.kind {
+            RuleKind::DenySource => "Block Source".to_string(),
+            RuleKind::AllowOut => "Allow Outbound".to_string(),
+        }
+    } else {
+        let current = filtered_indices.get(selected).map(|&idx| &app.entries[idx]);
+        let mut title = current
+            .and_then(|entry| app.source_display(entry.source))
+            .map_or_else(
+                || "Log Entry".to_string(),
+                |label| format!("Log Entry · {}", label),
+            );
+        let group = filtered_indices
+            .get(selected)
+            .and_then(|idx| groups.get(idx));
+        if let Some(group) = group.filter(|group| group.count > 1) {
+            title.push_str(&format!(
+                " · {}× from {} to {}",
+                group_thousands(group.count),
+                app.entries[group.first_seen].timestamp,
+                app.entries[group.last_seen].timestamp
+            ));
+        }
+        if let Some(burst) = filtered_indices
+            .get(selected)
+            .and_then(|idx| bursts.get(idx))
+        {
+            let time = |idx: usize| format_timestamp_for_width(&app.entries[idx].timestamp, false);
+            title.push_str(&format!(
+                " · ⊞ {} identical {}s {}–{} (+ expands)",
+                group_thousands(burst.count),
+                app.entries[burst.first_seen].action,
+                time(burst.first_seen),
+                time(burst.last_seen)
+            ));
+        }
+        let auth_stats = current
+            .and_then(|entry| entry.src_ip.as_deref())
+            .and_then(|ip| app.auth_log.as_ref()?.stats(ip));
+        if let Some(stats) = auth_stats.filter(|stats| stats.failed > 0) {
+            title.push_str(&format!(" · {} failed SSH logins", stats.failed));
+        }
+        if let Some(range) = current.and_then(|entry| app.bogon_source(entry)) {
+            title.push_str(&format!(" · bogon source ({})", range));
+        }
+        if let Some(list) = current.and_then(|entry| app.blocklisted(entry)) {
+            title.push_str(&format!(" · blocklisted ({})", list));
+        }
+        if let Some(hint) = current.and_then(|entry| fingerprint::os_hint(&entry.raw)) {
+            title.push_str(&format!(" · OS guess: {}", hint.label()));
+        }
+        if let Some(entry) = current.filter(|entry| app.is_unexpected_allow(entry)) {
+            title.push_str(&format!(
+                " · allowed port {} is not expected",
+                entry.dst_port.unwrap_or_default()
+            ));
+        }
+        for tag in current
+            .map(|entry| app.tagger.tags(entry))
+            .unwrap_or_default()
+        {
+            title.push_str(&format!(" · [{}]", tag));
+        }
+        title
+    };
+    let (capture_area, alerts_area) = if app.capture.is_some() && app.show_alerts {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[3]);
+        (halves[0], halves[1])
+    } else {
+        (chunks[3], chunks[3])
+    };
+    if let Some(capture) = app.capture.as_ref() {
+        let state = match capture.exit_status.as_deref() {
+            None => "running, T to stop".to_string(),
+            Some(status) => format!("{}, T to close", status),
+        };
+        let tee = capture
+            .tee_path
+            .as_ref()
+            .map(|path| format!(" → {}", path.display()))
+            .unwrap_or_default();
+        let shown = usize::from(capture_area.height.saturating_sub(2));
+        let skip = capture.lines.len().saturating_sub(shown);
+        let lines: Vec<Line> = capture
+            .lines
+            .iter()
+            .skip(skip)
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} ({}){}", capture.command, state, tee)),
+            ),
+            capture_area,
+        );
+    }
+    if app.show_alerts {
+        let alerts = app.scans.newest_first();
+        frame.render_widget(
+            Paragraph::new(portscan::alert_lines(&alerts, app.redact)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Alerts: {} port scans (^ to close)", alerts.len())),
+            ),
+            alerts_area,
+        );
+    }
+
+    let detail_content_width = chunks[4].width.saturating_sub(2);
+    let detail_max_scroll = max_horizontal_scroll(&detail, detail_content_width);
+    app.log_entry_scroll = min(app.log_entry_scroll, detail_max_scroll);
+    let shown_entry = filtered_indices
+        .get(selected)
+        .filter(|_| app.shows_log_entry())
+        .map(|&idx| &app.entries[idx]);
+    let detail_lines = match shown_entry {
+        Some(entry) if !app.detail_raw => {
+            let rows = usize::from(chunks[4].height.saturating_sub(2));
+            field_columns(&detail_fields(entry, app.redact), rows)
+                .into_iter()
+                .map(Line::from)
+                .collect()
+        }
+        _ => {
+            let packet = shown_entry
+                .map(|entry| packet_summary(entry, app.redact))
+                .unwrap_or_default();
+            let detail = match app.detail_drag.filter(|_| shown_entry.is_some()) {
+                Some((anchor, cursor)) if anchor != cursor => {
+                    highlight_chars(&detail, anchor.min(cursor), anchor.max(cursor))
+                }
+                _ if shown_entry.is_some() => highlight::raw_line(&detail),
+                _ => Line::from(detail),
+            };
+            vec![
+                detail,
+                Line::styled(packet, Style::default().fg(Color::DarkGray)),
+            ]
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(detail_lines)
+            .block(Block::default().borders(Borders::ALL).title(detail_title))
+            .scroll((0, app.log_entry_scroll)),
+        chunks[4],
+    );
+
+    let help = Paragraph::new(footer_help_lines(chunks[5].width)).wrap(Wrap { trim: true });
+    frame.render_widget(help, chunks[5]);
+
+    if let Some(report) = app.shown_report() {
+        render_report(frame, area, report);
+    } else if let Some(picker) = app.preset_picker.as_ref() {
+        render_report(frame, area, &picker.report(app.presets_path.as_deref()));
+    } else if let Some(picker) = app.column_picker.as_ref() {
+        render_report(frame, area, &picker.report(app.config_path.as_deref()));
+    } else if let Some(review) = app.egress_review.as_ref() {
+        let report = review.report(
+            |ip| display_ip(Some(ip), app.redact),
+            app.block_prompt_text(),
+            usize::from(area.height.saturating_sub(6)),
+        );
+        render_report(frame, area, &report);
+    } else if let Some(summary) = app.country_summary.as_ref() {
+        let report = summary.report(usize::from(area.height.saturating_sub(6)));
+        render_report(frame, area, &report);
+    }
+}
+
+fn resolve_default_log_path() -> PathBuf {
+    let preferred = PathBuf::from("/var/log/ufw-firewall.log");
+    if preferred.exists() {
+        return preferred;
+    }
+
+    let fallback = PathBuf::from("/var/log/ufw.log");
+    if fallback.exists() {
+        return fallback;
+    }
+
+    PathBuf::from("/var/log/kern.log")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RunOptions {
+    log_path: PathBuf,
+    // Further paths (or glob matches) after the first, merged by timestamp.
+    extra_logs: Vec<PathBuf>,
+    offline: bool,
+    watch_dirs: Vec<PathBuf>,
+    format: LogFormat,
+    sinks: Vec<SinkSpec>,
+    capture_dir: Option<PathBuf>,
+    abuse_dir: Option<PathBuf>,
+    abuse_top: Option<usize>,
+    ipv6_prefix: u8,
+    expected_ports: Option<HashSet<u16>>,
+    auth_log: Option<PathBuf>,
+    cgnat_local: bool,
+    dns_watch: bool,
+    pager: bool,
+    rotated: bool,
+    journal: bool,
+    // `--listen ADDR`: collect syslog over UDP and TCP instead of a file.
+    listen: Option<String>,
+    // `--http ADDR`: serve a read-only page of the view.
+    http: Option<String>,
+    low_memory: bool,
+    dry_run: bool,
+    tag_rules: Vec<TagRule>,
+    geoip: Option<PathBuf>,
+    blocklists: Vec<PathBuf>,
+    breakpoints: Option<Breakpoints>,
+    interface_globs: Vec<String>,
+    // `--interface NAME|all`: the interface chip to start on.
+    interface: Option<String>,
+    // Start with live updates on (`--follow`, the default) or paused.
+    follow: bool,
+    since: Option<i64>,
+    filters: Vec<(FilterField, String)>,
+    mouse: bool,
+    theme: Theme,
+    // From config.ini's `[columns]` and `[keys]`.
+    columns: Option<Vec<Column>>,
+    keys: Vec<(Command, Vec<KeyBinding>)>,
+}
+
+impl RunOptions {
+    fn new(log_path: PathBuf) -> Self {
+        Self {
+            log_path,
+            extra_logs: Vec::new(),
+            offline: false,
+            watch_dirs: Vec::new(),
+            format: LogFormat::default(),
+            sinks: Vec::new(),
+            capture_dir: None,
+            abuse_dir: None,
+            abuse_top: None,
+            ipv6_prefix: DEFAULT_IPV6_PREFIX,
+            expected_ports: None,
+            auth_log: None,
+            cgnat_local: false,
+            dns_watch: false,
+            pager: false,
+            rotated: false,
+            journal: false,
+            listen: None,
+            http: None,
+            low_memory: false,
+            dry_run: false,
+            tag_rules: Vec::new(),
+            geoip: None,
+            blocklists: Vec::new(),
+            breakpoints: None,
+            interface_globs: Vec::new(),
+            interface: None,
+            follow: true,
+            since: None,
+            filters: Vec::new(),
+            mouse: true,
+            theme: Theme::default(),
+            columns: None,
+            keys: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CliAction {
+    Run(RunOptions),
+    ClearCache,
+    Doctor(PathBuf),
+    Export(ExportOptions),
+    Trend(TrendOptions),
+    Print(PrintOptions),
+}
+
+// `--print`: the matching entries go to stdout instead of the TUI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PrintOptions {
+    run: RunOptions,
+    format: LineFormat,
+    // `--summary`: end the output with matched counts by action.
+    summary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrendOptions {
+    run: RunOptions,
+    weeks: u32,
+    format: TrendFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExportOptions {
+    run: RunOptions,
+    format: SiemFormat,
+    target: ExportTarget,
+}
+
+// Where `--export` writes records: stdout (default or "-"), a file, or a
+// remote syslog server given as udp://host[:port].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExportTarget {
+    Stdout,
+    File(PathBuf),
+    Syslog(String),
+}
+
+impl ExportTarget {
+    fn parse(value: &str) -> Self {
+        if value == "-" {
+            Self::Stdout
+        } else if let Some(host) = value.strip_prefix("udp://") {
+            Self::Syslog(host.trim_end_matches('/').to_string())
+        } else {
+            Self::File(PathBuf::from(value))
+        }
+    }
+}
+
+// What to do for a parsed command line, with `default_log_path` when no
+// LOG_PATH was given.
+fn cli_action(cli: cli::Cli, default_log_path: PathBuf) -> Result<CliAction, clap::Error> {
+    if cli.clear_cache {
+        return Ok(CliAction::ClearCache);
+    }
+    if let Some(cli::Tool::Doctor { log_path }) = cli.command {
+        return Ok(CliAction::Doctor(log_path.unwrap_or(default_log_path)));
+    }
+
+    let mut log_paths: Vec<PathBuf> = Vec::new();
+    for arg in &cli.logs {
+        add_log_paths(&mut log_paths, arg).map_err(cli::error)?;
+    }
+    let mut log_paths = log_paths.into_iter();
+    let mut options = RunOptions::new(log_paths.next().unwrap_or(default_log_path));
+    options.extra_logs = log_paths.collect();
+    options.offline = cli.offline;
+    options.watch_dirs = cli.watch_dir;
+    options.format = cli.format.unwrap_or_default();
+    options.sinks = cli
+        .forward
+        .into_iter()
+        .map(|target| SinkSpec {
+            kind: "syslog",
+            target,
+        })
+        .chain(cli.mqtt.into_iter().map(|target| SinkSpec {
+            kind: "mqtt",
+            target,
+        }))
+        .chain(cli.sink)
+        .collect();
+    options.capture_dir = cli.capture_dir;
+    options.abuse_dir = cli.abuse_dir;
+    options.abuse_top = cli.abuse_top;
+    options.ipv6_prefix = cli.ipv6_prefix.unwrap_or(DEFAULT_IPV6_PREFIX);
+    options.expected_ports = cli.expect_ports;
+    options.auth_log = cli.auth_log;
+    options.cgnat_local = cli.cgnat_local;
+    options.dns_watch = cli.dns_watch;
+    options.pager = cli.pager;
+    options.rotated = cli.rotated;
+    options.journal = cli.journal;
+    options.listen = cli.listen;
+    options.http = cli.http;
+    options.low_memory = cli.low_memory;
+    options.dry_run = cli.dry_run;
+    options.tag_rules = cli.tag;
+    options.geoip = cli.geoip;
+    options.blocklists = cli.blocklist;
+    options.breakpoints = cli.breakpoints;
+    options.interface_globs = cli
+        .iface
+        .iter()
+        .map(|glob| glob.trim())
+        .filter(|glob| !glob.is_empty())
+        .map(str::to_string)
+        .collect();
+    options.interface = cli.interface;
+    options.follow = !cli.no_follow;
+    options.since = cli.since;
+    options.filters = cli.filter;
+    options.mouse = !cli.no_mouse;
+    options.theme = cli.theme.unwrap_or_default();
+
+    if cli.print || cli.summary || cli.print_format.is_some() {
+        return Ok(CliAction::Print(PrintOptions {
+            run: options,
+            format: cli.print_format.unwrap_or(LineFormat::Raw),
+            summary: cli.summary,
+        }));
+    }
+    if let Some(weeks) = cli.trend {
+        return Ok(CliAction::Trend(TrendOptions {
+            run: options,
+            weeks,
+            format: cli.trend_format.unwrap_or(TrendFormat::Csv),
+        }));
+    }
+    Ok(match cli.export {
+        Some(format) => CliAction::Export(ExportOptions {
+            run: options,
+            format,
+            target: cli.output.unwrap_or(ExportTarget::Stdout),
+        }),
+        None => CliAction::Run(options),
+    })
+}
+
+// A log path argument, or a glob (`*`, `?`) in its file name that the shell
+// left unexpanded, e.g. '/var/log/ufw*.log'. Matches are added sorted;
+// paths already given are skipped.
+fn add_log_paths(paths: &mut Vec<PathBuf>, arg: &str) -> Result<(), String> {
+    let path = PathBuf::from(arg);
+    let pattern = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let found = if pattern.contains(['*', '?']) {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let read_dir = fs::read_dir(&dir)
+            .map_err(|err| format!("Failed to read {}: {}", dir.display(), err))?;
+        let mut found: Vec<PathBuf> = read_dir
+            .filter_map(|item| item.ok())
+            .filter(|item| item.path().is_file())
+            .filter(|item| {
+                item.file_name()
+                    .to_str()
+                    .is_some_and(|name| glob_match(pattern, name))
+            })
+            .map(|item| dir.join(item.file_name()))
+            .collect();
+        if found.is_empty() {
+            return Err(format!("No log files match {}", arg));
+        }
+        found.sort();
+        found
+    } else {
+        vec![path]
+    };
+    for path in found {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+// `--since`: a duration back from now (`2h`, `7d`), or the start of anything
+// F7 accepts (`today`, `20:00`, `2026-02-10 08:00`). Returns epoch seconds.
+fn parse_since(value: &str, now: i64) -> Result<i64, String> {
+    let offset = local_offset();
+    let range = TimeRange::parse(&format!("last {}", value), now, offset)
+        .or_else(|_| TimeRange::parse(value, now, offset))
+        .or_else(|_| TimeRange::parse(&format!("{}..", value), now, offset))
+        .map_err(|_| format!("Invalid --since time: {} (e.g. 2h, today, 20:00)", value))?;
+    match range {
+        TimeRange::Last(span) => now
+            .checked_sub(span)
+            .ok_or_else(|| format!("--since reaches too far back: {}", value)),
+        TimeRange::Between(Some(from), _) => Ok(from),
+        TimeRange::Between(None, _) => Err(format!("--since needs a start time: {}", value)),
+    }
+}
+
+// `arg` as one shell word: bare when it only has safe characters, else in
+// single quotes.
+fn shell_word(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=,@%+".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+// Epoch seconds as local `YYYY-MM-DD HH:MM:SS`, a form `--since` reads back.
+fn timestamp_text(secs: i64) -> String {
+    let secs = secs + local_offset();
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let clock = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        clock / 3_600,
+        clock % 3_600 / 60,
+        clock % 60
+    )
+}
+
+// `--filter FIELD=VALUE`, with the values the F1..F9 prompts take.
+fn parse_filter_arg(spec: &str) -> Result<(FilterField, String), String> {
+    let (name, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("--filter expects FIELD=VALUE, got {}", spec))?;
+    let field = FilterField::from_label(name.trim()).ok_or_else(|| {
+        format!(
+            "Unknown filter field: {} (expected service, port, ip, action, proto, text, time, country, flags)",
+            name
+        )
+    })?;
+    let value = value.trim();
+    check_filter_value(field, value)?;
+    Ok((field, value.to_string()))
+}
+
+// Time ranges and port expressions are rejected up front rather than
+// silently filtering nothing.
+fn check_filter_value(field: FilterField, value: &str) -> Result<(), String> {
+    let term = negated_filter(value).1.trim();
+    if term.is_empty() {
+        return Ok(());
+    }
+    match field {
+        FilterField::Time => TimeRange::parse(term, now_seconds(), local_offset()).map(|_| ()),
+        FilterField::Port => PortSet::parse(term).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+// Comma-separated ports or IANA service names, e.g. "22,80,https".
+fn parse_expected_ports(list: &str) -> Result<HashSet<u16>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse::<u16>()
+                .ok()
+                .or_else(|| port_from_service(item))
+                .ok_or_else(|| format!("Unknown port or service in --expect-ports: {}", item))
+        })
+        .collect()
+}
+
+fn clear_cache() -> Result<(), String> {
+    let dir = cache::cache_dir()
+        .ok_or("Could not determine cache directory (HOME is not set; try --cache-dir)")?;
+    let freed = cache::clear_cache(&dir)
+        .map_err(|err| format!("Failed to clear {}: {}", dir.display(), err))?;
+    println!("Cleared the cache in {} ({} bytes)", dir.display(), freed);
+    Ok(())
+}
+
+// Every entry of the main log, oldest first, for the modes without a UI.
+fn read_entries(options: &RunOptions) -> Result<Vec<LogEntry>, String> {
+    let mut entries = Vec::new();
+    let paths = std::iter::once(&options.log_path).chain(&options.extra_logs);
+    for (source, log_path) in paths.enumerate() {
+        let failed = |err: io::Error| format!("Failed to read {}: {}", log_path.display(), err);
+        for line in io::BufRead::lines(open_log(log_path).map_err(failed)?.lines) {
+            if let Some(entry) = parse_line(&line.map_err(failed)?, options.format) {
+                entries.push(LogEntry { source, ..entry });
+            }
+        }
+    }
+    if !options.extra_logs.is_empty() {
+        merge_chronologically(&mut entries);
+    }
+    Ok(entries)
+}
+
+// Converts the whole log to SIEM records without starting the UI.
+fn export_records(options: ExportOptions) -> Result<usize, String> {
+    offline::set_offline(options.run.offline);
+    let entries = read_entries(&options.run)?;
+    let mut tagger = Tagger::new(options.run.tag_rules.clone());
+    tagger.refresh(&entries);
+    let format = LineFormat::Siem(options.format);
+    let mut sink: Box<dyn OutputSink> = match &options.target {
+        ExportTarget::Syslog(host) => Box::new(
+            SyslogSink::connect(host, format)
+                .map_err(|err| format!("Failed to reach syslog {}: {}", host, err))?,
+        ),
+        ExportTarget::File(path) => Box::new(LineSink::new(
+            io::BufWriter::new(
+                fs::File::create(path)
+                    .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?,
+            ),
+            format,
+        )),
+        ExportTarget::Stdout => Box::new(LineSink::new(io::BufWriter::new(io::stdout()), format)),
+    };
+
+    let mut count = 0;
+    let result = entries
+        .iter()
+        .try_for_each(|entry| {
+            sink.write(entry, &tagger.tags(entry))?;
+            count += 1;
+            Ok(())
+        })
+        .and_then(|()| sink.finish());
+    match result {
+        // A closed pipe (e.g. `| head`) ends the export quietly.
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => match &options.target {
+            ExportTarget::Syslog(host) => Err(format!("Failed to send to {}: {}", host, err)),
+            _ => Err(err.to_string()),
+        },
+        _ => Ok(count),
+    }
+}
+
+// Headless query: entries matching `--filter`, `--since`, `--iface` and
+// `--interface`, oldest first, as raw lines or JSONL.
+fn print_matching(options: PrintOptions) -> Result<usize, String> {
+    let run = &options.run;
+    let mut filters = Filters::default();
+    for (field, value) in &run.filters {
+        filters.set(*field, value.clone());
+    }
+    let context = ViewContext {
+        filters: &filters,
+        interface: run
+            .interface
+            .as_deref()
+            .filter(|name| *name != ALL_INTERFACES),
+        window: None,
+    };
+    let now = now_seconds();
+    let mut tagger = Tagger::new(run.tag_rules.clone());
+    let entries = read_entries(run)?;
+    tagger.refresh(&entries);
+    let mut sink = LineSink::new(io::BufWriter::new(io::stdout()), options.format);
+    let mut count = 0;
+    let mut by_action: BTreeMap<&str, usize> = BTreeMap::new();
+    let result = entries
+        .iter()
+        .filter(|entry| {
+            run.since.is_none_or(|since| {
+                entry_epoch_seconds(&entry.timestamp, now).is_none_or(|secs| secs >= since)
+            })
+        })
+        .filter(|entry| {
+            run.interface_globs.is_empty()
+                || [&entry.in_iface, &entry.out_iface]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| interface_allowed(&run.interface_globs, name))
+        })
+        .filter(|entry| context.matches(entry))
+        .try_for_each(|entry| {
+            sink.write(entry, &tagger.tags(entry))?;
+            count += 1;
+            *by_action.entry(entry.action.as_str()).or_insert(0) += 1;
+            Ok(())
+        })
+        .and_then(|()| sink.finish())
+        .and_then(|()| {
+            if !options.summary {
+                return Ok(());
+            }
+            let trailer = summary_trailer(count, &by_action, options.format);
+            io::Write::write_all(&mut io::stdout(), trailer.as_bytes())
+        });
+    match result {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err.to_string()),
+        _ => Ok(count),
+    }
+}
+
+// The `--summary` line after the printed entries: a `#` comment for raw
+// lines, one more JSON object for JSON lines.
+fn summary_trailer(
+    matched: usize,
+    by_action: &BTreeMap<&str, usize>,
+    format: LineFormat,
+) -> String {
+    if format == LineFormat::Json {
+        let actions: Vec<String> = by_action
+            .iter()
+            .map(|(action, count)| format!("{}:{}", json::quote(action), count))
+            .collect();
+        return format!(
+            "{{\"summary\":{{\"matched\":{},\"actions\":{{{}}}}}}}\n",
+            matched,
+            actions.join(",")
+        );
+    }
+    let actions: Vec<String> = by_action
+        .iter()
+        .map(|(action, count)| format!("{} {}", action, count))
+        .collect();
+    if actions.is_empty() {
+        format!("# {} matched\n", matched)
+    } else {
+        format!("# {} matched: {}\n", matched, actions.join(", "))
+    }
+}
+
+fn run_app(options: RunOptions) -> Result<(), Box<dyn std::error::Error>> {
+    struct TerminalCleanup;
+    impl Drop for TerminalCleanup {
+        fn drop(&mut self) {
+            let _ = disable_raw_mode();
+            let mut stdout = io::stdout();
+            let _ = execute!(
+                stdout,
+                Show,
+                DisableMouseCapture,
+                terminal::LeaveAlternateScreen
+            );
+        }
+    }
+
+    offline::set_offline(options.offline);
+    let sinks = options
+        .sinks
+        .iter()
+        .map(SinkSpec::open)
+        .collect::<Result<Vec<_>, _>>()?;
+    let journal = if options.journal {
+        let mut journal = Journal::default();
+        journal
+            .poll()
+            .map_err(|err| format!("Failed to read the journal: {}", err))?;
+        Some(journal)
+    } else {
+        None
+    };
+    let listener = match options.listen.as_deref() {
+        Some(addr) => Some(
+            Listener::bind(addr).map_err(|err| format!("Failed to listen on {}: {}", addr, err))?,
+        ),
+        None => None,
+    };
+    let dashboard = match options.http.as_deref() {
+        Some(addr) => Some(
+            Dashboard::bind(addr)
+                .map_err(|err| format!("Failed to serve the dashboard on {}: {}", addr, err))?,
+        ),
+        None => None,
+    };
+    let mut geoip =
+        match options.geoip {
+            Some(path) => Some(GeoIp::open(&path).map_err(|err| {
+                format!("Failed to open GeoIP database {}: {}", path.display(), err)
+            })?),
+            None => None,
+        };
+    if let Some(geoip) = geoip.as_mut().filter(|_| options.low_memory) {
+        geoip.limit_cache(LOW_MEMORY_GEOIP_ADDRESSES);
+    }
+    let blocklists = if options.blocklists.is_empty() {
+        None
+    } else {
+        Some(
+            Blocklists::load(&options.blocklists)
+                .map_err(|err| format!("Failed to read blocklist {}", err))?,
+        )
+    };
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, terminal::EnterAlternateScreen)?;
+    // The pager has no clickable widgets; leave mouse events to the terminal.
+    if !options.pager && options.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+    let _cleanup = TerminalCleanup;
+    let backend = ratatui::backend::CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let draw: fn(&mut Frame, &mut App) = if options.pager { pager::render } else { ui };
+    let log_path = match (&journal, &listener) {
+        (Some(_), _) => PathBuf::from("journalctl -k"),
+        (None, Some(listener)) => PathBuf::from(format!("syslog {}", listener.addr)),
+        (None, None) => options.log_path,
+    };
+    let mut app = App::new(log_path);
+    if let Some(columns) = options.columns {
+        app.columns = columns;
+    }
+    app.keymap = Keymap::new(&options.keys);
+    if let Some(path) = app.interfaces_path.as_deref() {
+        match interfaces::load_pinned(path) {
+            Ok(pinned) => app.pinned_interfaces = pinned,
+            Err(err) => app
+                .status
+                .warn(format!("Reading pinned interfaces failed: {}", err)),
+        }
+    }
+    app.sinks = sinks;
+    app.geoip = geoip;
+    if let Some(lists) = blocklists {
+        app.status.info(format!("Blocklists: {}", lists.summary()));
+        app.blocklists = Some(lists);
+    }
+    app.dry_run = options.dry_run;
+    app.breakpoints = options.breakpoints.unwrap_or_default();
+    app.updates_paused = !options.follow;
+    app.open_view(
+        options.interface_globs,
+        options.interface,
+        options.filters,
+        options.since,
+    );
+    app.capture_dir = options.capture_dir;
+    if let Some(dir) = options.abuse_dir {
+        app.abuse_dir = dir;
+    }
+    app.abuse_top = options.abuse_top.unwrap_or(DEFAULT_ABUSE_TOP);
+    app.ipv6_prefix = options.ipv6_prefix;
+    app.expected_ports = options.expected_ports;
+    app.tagger = Tagger::new(options.tag_rules);
+    app.tagger.refresh(&app.entries);
+    app.cgnat_local = options.cgnat_local;
+    app.dns_watch = options.dns_watch;
+    app.auth_log = options.auth_log.map(AuthLog::new);
+    app.refresh_auth_log();
+    app.log_format = options.format;
+    app.extra_sources = options.extra_logs;
+    app.watch_dirs = options.watch_dirs;
+    app.include_rotated = options.rotated;
+    app.journal = journal;
+    app.listener = listener;
+    if let Some(dashboard) = dashboard.as_ref() {
+        app.status
+            .info(format!("Dashboard on http://{}/", dashboard.addr));
+    }
+    app.dashboard = dashboard;
+    if options.low_memory {
+        app.low_memory = true;
+        app.entry_cap = Some(LOW_MEMORY_MAX_ENTRIES);
+        app.render_cache = RenderCache::with_max_rows(LOW_MEMORY_CACHED_ROWS);
+    }
+    // App::new queued the main log with defaults; requeue once options apply.
+    if app.discover_watched_sources()
+        || !app.extra_sources.is_empty()
+        || app.log_format != LogFormat::default()
+        || app.include_rotated
+        || app.journal.is_some()
+        || app.listener.is_some()
+        || app.low_memory
+    {
+        app.reload();
+    }
+
+    loop {
+        app.maybe_reload();
+        app.ingest_tick();
+        app.apply_held_ingest();
+        app.publish_dashboard();
+        app.report_sink_errors();
+        if let Some(capture) = app.capture.as_mut() {
+            capture.poll();
+        }
+        terminal.draw(|frame| {
+            draw(frame, &mut app);
+            options.theme.apply(frame.buffer_mut());
+        })?;
+        // Pick up parsed batches promptly while the background reader is busy.
+        let poll_timeout = if app.ingest_pending() {
+            Duration::from_millis(50)
+        } else if app.capture.as_ref().is_some_and(CaptureSession::running) {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_millis(250)
+        };
+        if event::poll(poll_timeout)? && app.handle_event(&event::read()?) == Action::Quit {
+            break;
+        }
+    }
+
+    terminal.show_cursor()?;
+
+    Ok(())
+}
+
+// Applies config.ini; only the timestamp formats are global, the rest is
+// returned for the viewer.
+fn apply_config() -> Result<Config, String> {
+    let Some(path) = config::config_path() else {
+        return Ok(Config::default());
+    };
+    let mut config = config::load(&path)?;
+    timefmt::set_custom(std::mem::take(&mut config.timestamp_formats));
+    Ok(config)
+}
+
+fn main() {
+    timerange::detect_local_offset();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // Usage errors, `--help` and `--version` all end here, on their own stream
+    // and with their own exit code.
+    let action = cli::parse(&args)
+        .and_then(|cli| {
+            cache::set_overrides(cli.dir_overrides().or_env(|name| std::env::var_os(name)));
+            cli_action(cli, resolve_default_log_path())
+        })
+        .unwrap_or_else(|err| err.exit());
+    let config = if matches!(
+        action,
+        CliAction::Run(_) | CliAction::Print(_) | CliAction::Export(_) | CliAction::Trend(_)
+    ) {
+        apply_config().unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            std::process::exit(2);
+        })
+    } else {
+        Config::default()
+    };
+    match action {
+        CliAction::ClearCache => {
+            if let Err(err) = clear_cache() {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        CliAction::Doctor(log_path) => {
+            let (report, ok) = doctor::format_report(&doctor::run_checks(&log_path));
+            print!("{}", report);
+            if !ok {
+                std::process::exit(1);
+            }
+        }
+        CliAction::Export(options) => {
+            let to_stdout = options.target == ExportTarget::Stdout;
+            match export_records(options) {
+                Ok(count) if !to_stdout => eprintln!("Exported {} records", count),
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        CliAction::Trend(options) => {
+            let geoip = options.run.geoip.as_deref().map(|path| {
+                GeoIp::open(path).map_err(|err| {
+                    format!("Failed to open GeoIP database {}: {}", path.display(), err)
+                })
+            });
+            match geoip.transpose().and_then(|mut geoip| {
+                trend::trend_report(
+                    &options.run.log_path,
+                    options.run.format,
+                    options.weeks,
+                    options.format,
+                    geoip.as_mut(),
+                )
+            }) {
+                Ok(report) => print!("{}", report),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        // Like grep: 0 when entries matched, 1 when none did, 2 on errors.
+        CliAction::Print(options) => match print_matching(options) {
+            Ok(0) => std::process::exit(1),
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(2);
+            }
+        },
+        CliAction::Run(mut options) => {
+            options.columns = config.columns;
+            options.keys = config.keys;
+            if let Err(err) = run_app(options) {
+                eprintln!("Error: {}", err);
+            }
+        }
     }
 }
 
-fn ui(frame: &mut Frame, app: &mut App) {
-    app.refresh_status_lifetime();
-    let area = frame.area();
-    if area.width < MIN_UI_WIDTH || area.height < MIN_UI_HEIGHT {
-        let target_w = min(area.width.saturating_sub(2), 56).max(20);
-        let target_h = min(area.height.saturating_sub(2), 7).max(5);
-        let vpad = area.height.saturating_sub(target_h) / 2;
-        let hpad = area.width.saturating_sub(target_w) / 2;
-        let popup = Rect {
-            x: area.x.saturating_add(hpad),
-            y: area.y.saturating_add(vpad),
-            width: target_w,
-            height: target_h,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+    use std::io::Write;
+
+    #[test]
+    fn parse_ufw_line_extracts_core_fields() {
+        let line = "Feb 11 20:21:00 host kernel: [UFW BLOCK] IN=wlan0 OUT= MAC= SRC=10.0.0.5 DST=10.0.0.1 LEN=60 TOS=0x00 PREC=0x00 TTL=64 ID=12345 DF PROTO=TCP SPT=443 DPT=52910 WINDOW=64240 RES=0x00 ACK URGP=0";
+        let entry = parse_ufw_line(line).expect("line should parse");
+        assert_eq!(entry.action, "BLOCK");
+        assert_eq!(entry.src_ip.as_deref(), Some("10.0.0.5"));
+        assert_eq!(entry.dst_ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(entry.proto.as_deref(), Some("TCP"));
+        assert_eq!(entry.src_port, Some(443));
+        assert_eq!(entry.dst_port, Some(52910));
+        assert_eq!(entry.service.as_deref(), Some("https"));
+        assert_eq!(
+            (entry.length, entry.ttl, entry.tos, entry.window),
+            (Some(60), Some(64), Some(0), Some(64240))
+        );
+        assert_eq!(entry.mac, None);
+        assert_eq!(entry.tcp_flags.names(), ["ACK"]);
+    }
+
+    #[test]
+    fn filters_match_expected_fields() {
+        let entry = LogEntry {
+            id: 0,
+            source: 0,
+            raw_offset: None,
+            timestamp: "Feb 11 20:21:00".to_string(),
+            action: "ALLOW".to_string(),
+            in_iface: Some("eth0".to_string()),
+            out_iface: Some("".to_string()),
+            src_ip: Some("1.2.3.4".to_string()),
+            dst_ip: Some("10.0.0.1".to_string()),
+            src_port: Some(51234),
+            dst_port: Some(22),
+            proto: Some("TCP".to_string()),
+            service: Some("ssh".to_string()),
+            country: Some("LV".to_string()),
+            length: Some(60),
+            ttl: Some(52),
+            tos: Some(0),
+            mac: None,
+            window: Some(64240),
+            tcp_flags: TcpFlags::default(),
+            icmp_type: None,
+            icmp_code: None,
+            raw: "raw log line".to_string(),
         };
-        let msg = Paragraph::new(vec![
-            Line::from(format!(
-                "Terminal too small: {}x{}",
-                area.width, area.height
-            )),
-            Line::from(format!("Need at least {}x{}", MIN_UI_WIDTH, MIN_UI_HEIGHT)),
-            Line::from("Resize terminal to continue"),
-        ])
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title(APP_TITLE));
-        frame.render_widget(msg, popup);
-        return;
+
+        let mut filters = Filters {
+            service: "ssh".to_string(),
+            ip: "1.2.3".to_string(),
+            action: "allow".to_string(),
+            proto: "tcp".to_string(),
+            ..Default::default()
+        };
+        filters.set(FilterField::Port, "22".to_string());
+        assert!(filters.matches(&entry));
+
+        filters.set(FilterField::Port, "443".to_string());
+        assert!(!filters.matches(&entry));
+        filters.set(FilterField::Port, "80,443,20-25".to_string());
+        assert!(filters.matches(&entry));
+        // The ephemeral source port counts too.
+        filters.set(FilterField::Port, ">50000".to_string());
+        assert!(filters.matches(&entry));
+        filters.set(FilterField::Port, "<22".to_string());
+        assert!(!filters.matches(&entry));
+        assert!(check_filter_value(FilterField::Port, "!30-20").is_err());
+
+        filters.set(FilterField::Port, "!443".to_string());
+        assert!(filters.matches(&entry));
+        filters.ip = "!1.2.3.4".to_string();
+        assert!(!filters.matches(&entry));
+        filters.ip = "!".to_string();
+        assert!(filters.matches(&entry));
+
+        filters.country = "ee, lv".to_string();
+        assert!(filters.matches(&entry));
+        filters.country = "!LV".to_string();
+        assert!(!filters.matches(&entry));
+        assert_eq!(filter_assignment("F2 port", "!443"), "F2 port≠443");
+    }
+
+    #[test]
+    fn flags_filter_matches_set_and_unset_tcp_flags() {
+        let ack = parse_ufw_line(
+            "Feb 11 20:21:00 host kernel: [UFW BLOCK] IN=wlan0 OUT= SRC=10.0.0.5 DST=10.0.0.1 PROTO=TCP SPT=443 DPT=52910 ACK URGP=0",
+        )
+        .unwrap();
+        let mut filters = Filters::default();
+        filters.set(FilterField::Flags, "ack,!syn".to_string());
+        assert!(filters.matches(&ack));
+        filters.set(FilterField::Flags, "syn".to_string());
+        assert!(!filters.matches(&ack));
+        filters.set(FilterField::Flags, "!syn".to_string());
+        assert!(filters.matches(&ack));
+
+        // The last filter field has its own exclusion count.
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        app.apply_entries(vec![ack]);
+        app.set_filter_value(FilterField::Flags, "syn".to_string());
+        app.refresh_filter_exclusions();
+        assert_eq!(app.filter_exclusion(FilterField::Flags), Some(1));
+        assert_eq!(
+            parse_filter_arg("flags=syn"),
+            Ok((FilterField::Flags, "syn".to_string()))
+        );
+        assert!(
+            parse_filter_arg("flag=syn")
+                .unwrap_err()
+                .ends_with("country, flags)")
+        );
+    }
+
+    #[test]
+    fn fit_cell_marks_cut_values() {
+        assert_eq!(fit_cell("10.0.0.1", 8), "10.0.0.1");
+        assert_eq!(fit_cell("2001:db8::567:89ab", 8), "2001:db…");
+    }
+
+    #[test]
+    fn local_src_ip_detection_works_for_common_ranges() {
+        assert!(is_local_src_ip(Some("192.168.1.10")));
+        assert!(is_local_src_ip(Some("10.1.2.3")));
+        assert!(is_local_src_ip(Some("172.16.0.1")));
+        assert!(is_local_src_ip(Some("172.31.255.254")));
+        assert!(is_local_src_ip(Some("127.0.0.1")));
+        assert!(is_local_src_ip(Some("169.254.20.1")));
+        assert!(is_local_src_ip(Some("::1")));
+        assert!(is_local_src_ip(Some("fe80::1234")));
+        assert!(is_local_src_ip(Some("fd12::abcd")));
+
+        assert!(!is_local_src_ip(Some("8.8.8.8")));
+        assert!(!is_local_src_ip(Some("1.1.1.1")));
+        assert!(!is_local_src_ip(Some("2001:4860:4860::8888")));
+        assert!(!is_local_src_ip(None));
+        assert!(!is_local_src_ip(Some("")));
+    }
+
+    #[test]
+    fn broadcast_and_multicast_destinations_are_detected() {
+        assert!(is_broadcast_or_multicast_ip(Some("255.255.255.255")));
+        assert!(is_broadcast_or_multicast_ip(Some("192.168.1.255")));
+        assert!(is_broadcast_or_multicast_ip(Some("224.0.0.251")));
+        assert!(is_broadcast_or_multicast_ip(Some("239.255.255.250")));
+        assert!(is_broadcast_or_multicast_ip(Some("ff02::fb")));
+
+        assert!(!is_broadcast_or_multicast_ip(Some("192.168.1.10")));
+        assert!(!is_broadcast_or_multicast_ip(Some("2001:db8::ff")));
+        assert!(!is_broadcast_or_multicast_ip(Some("not-an-ip")));
+        assert!(!is_broadcast_or_multicast_ip(None));
+    }
+
+    #[test]
+    fn reverse_dns_names_use_arpa_zones() {
+        assert_eq!(
+            reverse_dns_name("1.2.3.4").as_deref(),
+            Some("4.3.2.1.in-addr.arpa")
+        );
+        assert_eq!(
+            reverse_dns_name("2001:db8::567:89ab").as_deref(),
+            Some("b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa")
+        );
+        assert_eq!(reverse_dns_name("not-an-ip"), None);
+    }
+
+    #[test]
+    fn bogon_sources_report_their_reserved_range() {
+        assert_eq!(bogon_range(Some("0.1.2.3"), false), Some("0.0.0.0/8"));
+        assert_eq!(
+            bogon_range(Some("100.100.1.1"), false),
+            Some("100.64.0.0/10")
+        );
+        assert_eq!(bogon_range(Some("100.100.1.1"), true), None);
+        assert_eq!(bogon_range(Some("192.0.2.7"), false), Some("192.0.2.0/24"));
+        assert_eq!(bogon_range(Some("250.1.1.1"), false), Some("240.0.0.0/4"));
+        assert_eq!(
+            bogon_range(Some("2001:db8::1"), false),
+            Some("2001:db8::/32")
+        );
+
+        assert_eq!(bogon_range(Some("100.128.0.1"), false), None);
+        assert_eq!(bogon_range(Some("8.8.8.8"), false), None);
+        assert_eq!(bogon_range(Some("192.168.1.10"), false), None);
+        assert_eq!(bogon_range(None, false), None);
+    }
+
+    #[test]
+    fn return_traffic_is_recognized_by_ports_and_flags() {
+        let entry = |proto: &str, spt: u16, dpt: u16, flags: &str| {
+            parse_ufw_line(&format!(
+                "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1 PROTO={proto} SPT={spt} DPT={dpt} {flags}"
+            ))
+            .unwrap()
+        };
+        assert!(is_return_traffic(&entry(
+            "TCP",
+            443,
+            51234,
+            "ACK FIN URGP=0"
+        )));
+        assert!(is_return_traffic(&entry(
+            "TCP",
+            80,
+            40000,
+            "ACK RST URGP=0"
+        )));
+        assert!(is_return_traffic(&entry("UDP", 53, 41000, "LEN=40")));
+
+        assert!(!is_return_traffic(&entry("TCP", 443, 51234, "SYN URGP=0")));
+        assert!(!is_return_traffic(&entry("TCP", 51234, 22, "ACK URGP=0")));
+        assert!(!is_return_traffic(&entry("TCP", 22, 80, "ACK URGP=0")));
+    }
+
+    #[test]
+    fn wan_src_ip_detection_works() {
+        assert!(is_wan_src_ip(Some("8.8.8.8")));
+        assert!(is_wan_src_ip(Some("1.1.1.1")));
+        assert!(is_wan_src_ip(Some("2001:4860:4860::8888")));
+
+        assert!(!is_wan_src_ip(Some("192.168.1.10")));
+        assert!(!is_wan_src_ip(Some("10.1.2.3")));
+        assert!(!is_wan_src_ip(Some("172.16.0.1")));
+        assert!(!is_wan_src_ip(Some("127.0.0.1")));
+        assert!(!is_wan_src_ip(Some("::1")));
+        assert!(!is_wan_src_ip(Some("fe80::1")));
+        assert!(!is_wan_src_ip(None));
+        assert!(!is_wan_src_ip(Some("")));
+        assert!(!is_wan_src_ip(Some("not-an-ip")));
+    }
+
+    #[test]
+    fn flow_filter_matches_local_to_local_and_local_to_external() {
+        let mut entry = LogEntry {
+            src_ip: Some("192.168.1.10".to_string()),
+            dst_ip: Some("192.168.1.20".to_string()),
+            ..Default::default()
+        };
+        assert!(matches_flow_filter(FlowFilter::All, &entry));
+        assert!(matches_flow_filter(FlowFilter::LocalToLocal, &entry));
+        assert!(!matches_flow_filter(FlowFilter::LocalToExternal, &entry));
+
+        entry.dst_ip = Some("8.8.8.8".to_string());
+        assert!(matches_flow_filter(FlowFilter::LocalToExternal, &entry));
+        assert!(!matches_flow_filter(FlowFilter::LocalToLocal, &entry));
+    }
+
+    #[test]
+    fn default_wan_interface_prefers_wan_like_names() {
+        let options = vec![
+            "docker0".to_string(),
+            "lo".to_string(),
+            "wlan0".to_string(),
+            "br-123".to_string(),
+        ];
+        assert_eq!(default_wan_interface(&options).as_deref(), Some("wlan0"));
+
+        let options = vec!["docker0".to_string(), "lo".to_string()];
+        assert_eq!(default_wan_interface(&options).as_deref(), Some("docker0"));
+    }
+
+    #[test]
+    fn iface_globs_restrict_interface_chips() {
+        let args = ["--iface", "eth*, ppp?"].map(String::from);
+        let Ok(CliAction::Run(options)) = parse_cli_action(&args, PathBuf::from("ufw.log")) else {
+            panic!("expected a run action");
+        };
+        assert_eq!(options.interface_globs, ["eth*", "ppp?"]);
+        let allowed = |name: &str| interface_allowed(&options.interface_globs, name);
+        assert!(allowed("eth0") && allowed("eth") && allowed("ppp0"));
+        assert!(!allowed("veth12ab") && !allowed("ppp10"));
+        assert!(interface_allowed(&["*a*b".to_string()], "xaxxb"));
+        assert!(interface_allowed(&[], "veth0"));
+
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        app.interface_globs = options.interface_globs;
+        app.apply_entries(
+            ["eth0", "veth1", "veth2"]
+                .iter()
+                .map(|iface| LogEntry {
+                    in_iface: Some(iface.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        assert_eq!(app.interface_options, ["eth0"]);
+    }
+
+    #[test]
+    fn selected_position_for_raw_finds_matching_row_in_filtered_view() {
+        let entries = vec![
+            LogEntry {
+                raw: "newest".to_string(),
+                ..Default::default()
+            },
+            LogEntry {
+                raw: "kept".to_string(),
+                ..Default::default()
+            },
+            LogEntry {
+                raw: "older".to_string(),
+                ..Default::default()
+            },
+        ];
+        let filtered = vec![0, 1, 2];
+        assert_eq!(
+            selected_position_for_raw(&entries, &filtered, "kept"),
+            Some(1)
+        );
+        assert_eq!(
+            selected_position_for_raw(&entries, &filtered, "missing"),
+            None
+        );
     }
 
-    let filters_height = filter_panel_height(app, area.width);
-    let chunks = main_chunks(area, filters_height);
+    #[test]
+    fn pinned_entry_stays_visible_at_its_chronological_position() {
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        app.entries = ["BLOCK", "ALLOW", "BLOCK"]
+            .iter()
+            .enumerate()
+            .map(|(idx, action)| LogEntry {
+                action: action.to_string(),
+                src_ip: Some("8.8.8.8".to_string()),
+                raw: format!("line {}", idx),
+                ..Default::default()
+            })
+            .collect();
+        app.selected = 1;
 
-    let filtered_indices = app.filtered_indices();
-    app.sync_selection_with_len(filtered_indices.len());
-    let count_text = format!("{}/{}", filtered_indices.len(), app.entries.len());
-    let live_style = if app.updates_paused {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-    } else {
-        Style::default()
-            .fg(Color::LightGreen)
-            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-    };
-    let pause_chip_width = text_cells("[live]").max(text_cells("[paused]"));
-    let left_width = text_cells(APP_TITLE)
-        .saturating_add(1)
-        .saturating_add(pause_chip_width);
-    let count_width = text_cells(&count_text);
-    let header_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Length(left_width),
-            Constraint::Min(1),
-            Constraint::Length(count_width),
-        ])
-        .split(chunks[0]);
+        app.set_filter_value(FilterField::Action, "block".to_string());
+        app.offer_pin();
+        assert_eq!(app.filtered_indices(), vec![0, 2]);
+        assert_eq!(app.pin_candidate.as_deref(), Some("line 1"));
 
-    let header_left = Paragraph::new(Line::from(vec![
-        Span::styled(APP_TITLE, Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" "),
-        Span::styled(pause_chip_label(app.updates_paused), live_style),
-    ]));
-    frame.render_widget(header_left, header_chunks[0]);
+        app.toggle_pin();
+        assert_eq!(app.view_indices_with_pin(), (vec![0, 1, 2], Some(1)));
+        assert_eq!(app.selected, 1);
 
-    let center_text = if app.status.is_empty() {
-        app.log_path.display().to_string()
-    } else {
-        app.status.clone()
-    };
-    let center_style = if app.status.to_ascii_lowercase().contains("failed") {
-        Style::default().fg(Color::LightRed)
-    } else {
-        Style::default().fg(Color::DarkGray)
-    };
-    let header_center = Paragraph::new(Line::from(Span::styled(center_text, center_style)))
-        .alignment(Alignment::Center);
-    frame.render_widget(header_center, header_chunks[1]);
+        app.toggle_pin();
+        assert_eq!(app.view_indices(), vec![0, 2]);
+    }
 
-    let header_count = Paragraph::new(Line::from(Span::styled(
-        count_text,
-        Style::default().fg(Color::Yellow),
-    )))
-    .alignment(Alignment::Right);
-    frame.render_widget(header_count, header_chunks[2]);
+    #[test]
+    fn minute_gutter_marks_first_row_of_each_minute() {
+        assert_eq!(
+            timestamp_minute_key("Feb 11 20:21:00 host kernel:"),
+            Some("Feb 11 20:21")
+        );
+        assert_eq!(
+            timestamp_minute_key("2026-02-11T23:00:39.987820+02:00 host"),
+            Some("2026-02-11T23:00")
+        );
+        // Cuts that would land inside a character are refused.
+        assert_eq!(timestamp_minute_key("Feb 11 20:2\u{e9}:00 host"), None);
+        assert_eq!(timestamp_minute_key("2026-02-11T23:0\u{e9} host"), None);
+        assert_eq!(timestamp_minute_key("garbage"), None);
 
-    let filter_summary = filter_summary_lines(app, chunks[1].width);
-    let (combined_controls, visible_ifaces, iface_right_start) =
-        controls_layout(app, chunks[1].width);
-    let mut control_line_spans = left_toggle_spans(app);
-    if combined_controls {
-        let gap = iface_right_start.saturating_sub(left_toggle_width(app));
-        control_line_spans.push(Span::raw(" ".repeat(gap as usize)));
-        control_line_spans.extend(interface_display_spans(
-            app,
-            visible_ifaces,
-            chunks[1].width,
-        ));
+        let entries: Vec<LogEntry> = ["Feb 11 20:22:05", "Feb 11 20:21:59", "Feb 11 20:21:01"]
+            .iter()
+            .map(|ts| LogEntry {
+                timestamp: ts.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(
+            minute_gutter_bars(&entries, &[0, 1, 2]),
+            vec!["▄", "█", " "]
+        );
     }
-    let mut filter_lines = filter_summary;
-    filter_lines.push(Line::from(control_line_spans));
-    if !combined_controls {
-        filter_lines.push(Line::from(interface_display_spans(
-            app,
-            visible_ifaces,
-            chunks[1].width,
-        )));
+
+    #[test]
+    fn handle_event_hit_tests_mouse_against_last_area() {
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: text_cells(APP_TITLE) + 1,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(app.handle_event(&click), Action::Continue);
+        assert!(!app.updates_paused);
+
+        app.handle_event(&Event::Resize(120, 30));
+        app.handle_event(&click);
+        assert!(app.updates_paused);
     }
-    let filters = Paragraph::new(filter_lines);
-    frame.render_widget(filters, chunks[1]);
 
-    let selected = app.selected;
-    let entries = &app.entries;
-    let table_width = chunks[2].width;
-    let show_date_in_time_col = chunks[2].width >= 120;
-    let show_service_description = table_width >= 150;
-    let rows = filtered_indices.iter().map(|entry_idx| {
-        let entry = &entries[*entry_idx];
+    #[test]
+    fn filters_label_masks_the_ip_filter_when_redacting() {
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        app.set_filter_value(FilterField::Ip, "!192.0.2.7".to_string());
+        app.set_filter_value(FilterField::Port, "22".to_string());
+        assert_eq!(app.filters_label(false), "port=22 ip≠192.0.2.7");
+        assert_eq!(app.filters_label(true), "port=22 ip≠192.0.2.x");
+    }
 
-        let action_color = match entry.action.as_str() {
-            "ALLOW" => Color::Green,
-            "BLOCK" => Color::Red,
-            _ => Color::Yellow,
+    #[test]
+    fn group_thousands_inserts_separators() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(1204), "1,204");
+        assert_eq!(group_thousands(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn macros_replay_recorded_keys() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        assert!(!app.show_local_src);
+        // The missing log is an error that stays until Esc dismisses it.
+        assert_eq!(app.status.level(), status::Level::Error);
+        app.handle_event(&Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(app.status.is_empty());
+
+        for c in ['Q', 'a', 'l', 'B', 'Q'] {
+            assert_eq!(app.handle_event(&Event::Key(key(c))), Action::Continue);
+        }
+        assert!(app.show_local_src);
+        assert!(!app.show_broadcast);
+
+        for c in ['@', 'a'] {
+            app.handle_event(&Event::Key(key(c)));
+        }
+        assert!(!app.show_local_src);
+        assert!(app.show_broadcast);
+        assert!(app.status.text().starts_with("Played macro a (2 keys)"));
+
+        app.handle_event(&Event::Key(key('@')));
+        app.handle_event(&Event::Key(key('b')));
+        assert_eq!(app.status.text(), "Macro b is empty");
+    }
+
+    #[test]
+    fn selection_lock_follows_entry_id_across_reloads() {
+        let path = std::env::temp_dir().join(format!("ufw-lock-{}.log", std::process::id()));
+        let line = |src: &str| {
+            format!(
+                "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+            )
         };
-        let direction = entry.direction();
-        let dir_color = match direction {
-            "IN" => Color::LightBlue,
-            "OUT" => Color::LightMagenta,
-            "FWD" => Color::LightCyan,
-            _ => Color::DarkGray,
+        fs::write(&path, line("1.1.1.1") + &line("2.2.2.2")).unwrap();
+        let mut app = App::new(path.clone());
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        app.selected_interface = None;
+        app.selected = 1;
+        app.toggle_selection_lock();
+        assert!(app.selection_lock.is_some());
+
+        let mut contents = line("1.1.1.1") + &line("2.2.2.2");
+        for n in 0..50 {
+            contents.push_str(&line(&format!("3.3.3.{}", n)));
+        }
+        fs::write(&path, contents).unwrap();
+        app.reload();
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        let filtered = app.view_indices();
+        assert_eq!(
+            app.entries[filtered[app.selected]].src_ip.as_deref(),
+            Some("1.1.1.1")
+        );
+        assert_eq!(centered_window_offset(app.selected, 10, filtered.len()), 42);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn scrolling_away_from_top_holds_new_entries() {
+        let path = std::env::temp_dir().join(format!("ufw-hold-{}.log", std::process::id()));
+        let line = |src: &str| {
+            format!(
+                "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+            )
         };
+        fs::write(&path, line("1.1.1.1") + &line("2.2.2.2")).unwrap();
+        let mut app = App::new(path.clone());
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        app.selected_interface = None;
+        app.selected = 1;
 
-        Row::new(vec![
-            Cell::from(format_timestamp_for_width(
-                &entry.timestamp,
-                show_date_in_time_col,
-            )),
-            Cell::from(entry.action.clone()).style(Style::default().fg(action_color)),
-            Cell::from(direction).style(Style::default().fg(dir_color)),
-            Cell::from(entry.src_ip.as_deref().unwrap_or("-").to_string()),
-            Cell::from(entry.dst_ip.as_deref().unwrap_or("-").to_string()),
-            Cell::from(entry.proto.as_deref().unwrap_or("-").to_string()),
-            Cell::from(
-                entry
-                    .src_port
-                    .map(|p| p.to_string())
-                    .unwrap_or_else(|| "-".to_string()),
-            ),
-            Cell::from(
-                entry
-                    .dst_port
-                    .map(|p| p.to_string())
-                    .unwrap_or_else(|| "-".to_string()),
-            ),
-            Cell::from(service_display_for_entry(entry, show_service_description)),
-        ])
-    });
+        fs::write(&path, line("1.1.1.1") + &line("2.2.2.2") + &line("3.3.3.3")).unwrap();
+        app.reload();
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        assert_eq!(app.entries.len(), 2);
+        assert_eq!(app.held_ingest.as_ref().map(|(_, new)| *new), Some(1));
+
+        app.selected = 0;
+        app.apply_held_ingest();
+        assert_eq!(app.entries.len(), 3);
+        assert!(app.held_ingest.is_none());
+        let _ = fs::remove_file(&path);
+    }
 
-    let time_col = if show_date_in_time_col { 16 } else { 8 };
-    let (action_col, dir_col, proto_col, port_col, src_weight, dst_weight, service_weight) =
-        if table_width >= 150 {
-            (8, 5, 6, 6, 5, 5, 3)
-        } else if table_width >= 120 {
-            (7, 4, 5, 5, 4, 4, 2)
-        } else {
-            (6, 3, 4, 5, 3, 3, 2)
+    #[test]
+    fn entry_key_values_lists_one_field_per_line() {
+        let line = "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1 PROTO=ICMP TYPE=8 CODE=0 ID=1 SEQ=1";
+        let entry = parse_ufw_line(line).unwrap();
+        assert_eq!(
+            entry_key_values(&entry),
+            "time=Feb 11 20:21:00 h\naction=BLOCK\ndirection=IN\nin=eth0\nout=-\nsrc=1.2.3.4\ndst=10.0.0.1\nproto=ICMP\nspt=-\ndpt=-\nservice=echo-request\n"
+        );
+    }
+
+    #[test]
+    fn visible_window_follows_selection() {
+        assert_eq!(visible_window_offset(0, Some(3), 10, 100), 0);
+        assert_eq!(visible_window_offset(0, Some(12), 10, 100), 3);
+        assert_eq!(visible_window_offset(5, Some(2), 10, 100), 2);
+        assert_eq!(visible_window_offset(50, Some(2), 10, 3), 2);
+        assert_eq!(visible_window_offset(7, None, 10, 0), 0);
+    }
+
+    #[test]
+    fn entry_ids_are_stable_and_distinguish_repeated_lines() {
+        let lines = [
+            "[UFW BLOCK] SRC=1.1.1.1",
+            "[UFW BLOCK] SRC=2.2.2.2",
+            "[UFW BLOCK] SRC=1.1.1.1",
+        ];
+        let parse = |lines: &[&str]| {
+            let mut entries: Vec<LogEntry> =
+                lines.iter().filter_map(|l| parse_ufw_line(l)).collect();
+            assign_entry_ids(&mut entries, &mut HashMap::new());
+            entries.iter().map(|entry| entry.id).collect::<Vec<_>>()
         };
-    let columns = [
-        Constraint::Length(time_col),
-        Constraint::Length(action_col),
-        Constraint::Length(dir_col),
-        Constraint::Fill(src_weight),
-        Constraint::Fill(dst_weight),
-        Constraint::Length(proto_col),
-        Constraint::Length(port_col),
-        Constraint::Length(port_col),
-        Constraint::Fill(service_weight),
-    ];
+        let first = parse(&lines);
+        assert_eq!(first.len(), 3);
+        assert_ne!(first[0], first[2]);
+        assert_eq!(parse(&lines[..2]), first[..2]);
+    }
 
-    let table = Table::new(rows, columns)
-        .header(
-            Row::new(vec![
-                if show_date_in_time_col {
-                    "Date/Time"
-                } else {
-                    "Time"
-                },
-                "Action",
-                "Dir",
-                "Source",
-                "Dest",
-                "Proto",
-                "SPT",
-                "DPT",
-                "Service",
-            ])
-            .style(Style::default().add_modifier(Modifier::BOLD)),
-        )
-        .block(Block::default().borders(Borders::ALL).title("UFW Events"))
-        .row_highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+    #[test]
+    fn sorting_orders_rows_and_header_click_switches_column() {
+        let mut app = App::new(PathBuf::from("/nonexistent/ufw.log"));
+        app.entries = ["9.9.9.10", "9.9.9.9", "2001:db8::1"]
+            .iter()
+            .map(|ip| LogEntry {
+                src_ip: Some(ip.to_string()),
+                raw: ip.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(app.filtered_indices(), vec![0, 1, 2]);
+
+        app.set_sort(SortColumn::Source, false);
+        assert_eq!(app.filtered_indices(), vec![1, 0, 2]);
+        app.reverse_sort();
+        assert_eq!(app.filtered_indices(), vec![2, 0, 1]);
+
+        let area = Rect::new(0, 0, 130, 30);
+        let chunks = main_chunks(
+            area,
+            filter_panel_height(&app, area.width),
+            0,
+            detail_pane_height(&app, area.height),
+            0,
         );
+        let (dpt_rect, _) = build_sort_header_hitboxes(&app, chunks[2])[7].clone();
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: dpt_rect.x,
+            row: dpt_rect.y,
+            modifiers: KeyModifiers::NONE,
+        };
+        handle_mouse_event(&mut app, click, area);
+        assert_eq!(app.sort_column, SortColumn::DstPort);
+        assert!(!app.sort_descending);
+        handle_mouse_event(&mut app, click, area);
+        assert!(app.sort_descending);
+    }
 
-    frame.render_stateful_widget(table, chunks[2], &mut app.table_state);
+    fn parse_cli_action(
+        args: &[String],
+        default_log_path: PathBuf,
+    ) -> Result<CliAction, clap::Error> {
+        cli_action(cli::parse(args)?, default_log_path)
+    }
 
-    let detail = if let Some(field) = app.input_mode {
-        format!(
-            "Edit {}: {} (Enter apply, Esc cancel)",
-            field.label(),
-            if app.input_buffer.is_empty() {
-                "_"
-            } else {
-                &app.input_buffer
+    #[test]
+    fn dir_overrides_apply_to_every_action() {
+        let args = [
+            "--cache-dir",
+            "/usb/cache",
+            "--clear-cache",
+            "--config=c.ini",
+        ]
+        .map(String::from);
+        let cli = cli::parse(&args).unwrap();
+        assert_eq!(
+            cli.dir_overrides(),
+            cache::DirOverrides {
+                config: Some(PathBuf::from("c.ini")),
+                state_dir: None,
+                cache_dir: Some(PathBuf::from("/usb/cache")),
             }
-        )
-    } else if filtered_indices.is_empty() {
-        "No rows match current view (filters/interface/local+wan/flow/dir).".to_string()
-    } else {
-        let current = &app.entries[filtered_indices[selected]];
-        current.raw.clone()
-    };
-    let detail_title = if app.input_mode.is_some() {
-        "Edit Filter"
-    } else {
-        "Log Entry"
-    };
-    let detail_content_width = chunks[3].width.saturating_sub(2);
-    let detail_max_scroll = max_horizontal_scroll(&detail, detail_content_width);
-    app.log_entry_scroll = min(app.log_entry_scroll, detail_max_scroll);
-    frame.render_widget(
-        Paragraph::new(detail)
-            .block(Block::default().borders(Borders::ALL).title(detail_title))
-            .scroll((0, app.log_entry_scroll)),
-        chunks[3],
-    );
-
-    let help = Paragraph::new(footer_help_lines(chunks[4].width)).wrap(Wrap { trim: true });
-    frame.render_widget(help, chunks[4]);
-}
-
-fn resolve_default_log_path() -> PathBuf {
-    let preferred = PathBuf::from("/var/log/ufw-firewall.log");
-    if preferred.exists() {
-        return preferred;
+        );
+        assert_eq!(
+            cli_action(cli, PathBuf::from("/tmp/a.log")).unwrap(),
+            CliAction::ClearCache
+        );
+        let args = ["doctor", "/var/log/ufw.log", "--state-dir", "/usb/state"].map(String::from);
+        let cli = cli::parse(&args).unwrap();
+        assert_eq!(
+            cli.dir_overrides().state_dir,
+            Some(PathBuf::from("/usb/state"))
+        );
+        assert!(cli::parse(&["--state-dir".to_string()]).is_err());
     }
 
-    let fallback = PathBuf::from("/var/log/ufw.log");
-    if fallback.exists() {
-        return fallback;
+    #[test]
+    fn parse_cli_action_handles_help_and_version() {
+        let default = PathBuf::from("/tmp/default.log");
+        let kind = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            parse_cli_action(&args, PathBuf::from("/tmp/default.log")).map_err(|err| err.kind())
+        };
+        assert_eq!(kind(&["--help"]), Err(clap::error::ErrorKind::DisplayHelp));
+        assert_eq!(
+            kind(&["--offline", "ufw.log", "--help"]),
+            Err(clap::error::ErrorKind::DisplayHelp)
+        );
+        assert_eq!(kind(&["-V"]), Err(clap::error::ErrorKind::DisplayVersion));
+        assert_eq!(
+            parse_cli_action(&["--clear-cache".to_string()], default.clone()).unwrap(),
+            CliAction::ClearCache
+        );
+        assert_eq!(
+            parse_cli_action(&["doctor".to_string()], default.clone()).unwrap(),
+            CliAction::Doctor(default.clone())
+        );
+        assert_eq!(
+            parse_cli_action(&["/var/log/ufw.log".to_string()], default.clone()).unwrap(),
+            CliAction::Run(RunOptions::new(PathBuf::from("/var/log/ufw.log")))
+        );
+        assert_eq!(
+            parse_cli_action(&Vec::new(), default.clone()).unwrap(),
+            CliAction::Run(RunOptions::new(PathBuf::from("/tmp/default.log")))
+        );
+        assert_eq!(
+            parse_cli_action(&["--offline".to_string()], default).unwrap(),
+            CliAction::Run(RunOptions {
+                offline: true,
+                ..RunOptions::new(PathBuf::from("/tmp/default.log"))
+            })
+        );
+        assert_eq!(
+            parse_cli_action(
+                &[
+                    "--watch-dir".to_string(),
+                    "/var/log/remote".to_string(),
+                    "--watch-dir".to_string(),
+                    "/srv/logs".to_string(),
+                ],
+                PathBuf::from("/tmp/default.log")
+            )
+            .unwrap(),
+            CliAction::Run(RunOptions {
+                watch_dirs: vec![PathBuf::from("/var/log/remote"), PathBuf::from("/srv/logs")],
+                ..RunOptions::new(PathBuf::from("/tmp/default.log"))
+            })
+        );
+        assert!(
+            parse_cli_action(&["--watch-dir".to_string()], PathBuf::from("/tmp/a.log")).is_err()
+        );
+        assert_eq!(
+            parse_cli_action(
+                &["--format".to_string(), "pfsense".to_string()],
+                PathBuf::from("/tmp/default.log")
+            )
+            .unwrap(),
+            CliAction::Run(RunOptions {
+                format: LogFormat::Pfsense,
+                ..RunOptions::new(PathBuf::from("/tmp/default.log"))
+            })
+        );
+        assert_eq!(
+            parse_cli_action(
+                &[
+                    "--export".to_string(),
+                    "leef".to_string(),
+                    "--output".to_string(),
+                    "udp://siem.local:5514".to_string(),
+                ],
+                PathBuf::from("/tmp/default.log")
+            )
+            .unwrap(),
+            CliAction::Export(ExportOptions {
+                run: RunOptions::new(PathBuf::from("/tmp/default.log")),
+                format: SiemFormat::Leef,
+                target: ExportTarget::Syslog("siem.local:5514".to_string()),
+            })
+        );
+        assert!(
+            parse_cli_action(
+                &["--output".to_string(), "out.cef".to_string()],
+                PathBuf::from("/tmp/a.log")
+            )
+            .is_err()
+        );
+        assert!(
+            parse_cli_action(
+                &["--format".to_string(), "cisco".to_string()],
+                PathBuf::from("/tmp/a.log")
+            )
+            .is_err()
+        );
     }
 
-    PathBuf::from("/var/log/kern.log")
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum CliAction {
-    Run(PathBuf),
-    PrintHelp,
-    PrintVersion,
-}
-
-fn parse_cli_action(args: &[String], default_log_path: PathBuf) -> Result<CliAction, String> {
-    if args.is_empty() {
-        return Ok(CliAction::Run(default_log_path));
+    #[test]
+    fn unexpected_allow_flags_inbound_ports_outside_expected_set() {
+        let mut app = App::new(PathBuf::from("/tmp/does-not-exist.log"));
+        app.expected_ports = Some(parse_expected_ports("22, https").unwrap());
+        let line = |action: &str, inbound: bool, dpt: u16| {
+            let (inf, out) = if inbound { ("eth0", "") } else { ("", "eth0") };
+            parse_ufw_line(&format!(
+                "Feb 11 20:21:00 h kernel: [UFW {action}] IN={inf} OUT={out} SRC=1.1.1.1 DST=10.0.0.1 PROTO=TCP SPT=1 DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        assert!(!app.is_unexpected_allow(&line("ALLOW", true, 443)));
+        assert!(app.is_unexpected_allow(&line("ALLOW", true, 4443)));
+        assert!(!app.is_unexpected_allow(&line("BLOCK", true, 4443)));
+        assert!(!app.is_unexpected_allow(&line("ALLOW", false, 4443)));
+        assert!(parse_expected_ports("22,not-a-service").is_err());
     }
 
-    let first = args[0].as_str();
-    match first {
-        "-h" | "--help" => {
-            if args.len() > 1 {
-                Err(format!("Unexpected argument after {}: {}", first, args[1]))
-            } else {
-                Ok(CliAction::PrintHelp)
-            }
+    #[test]
+    fn forwarder_relays_only_new_matching_entries() {
+        let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("ufw-forward-{}.log", std::process::id()));
+        let line = |src: &str, dpt: u16| {
+            format!(
+                "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT={dpt}\n"
+            )
+        };
+        fs::write(&path, line("1.1.1.1", 22)).unwrap();
+
+        let mut app = App::new(path.clone());
+        let spec = SinkSpec::parse(&format!("syslog:{}", receiver.local_addr().unwrap()));
+        app.sinks.push(spec.unwrap().open().unwrap());
+        app.selected_interface = None;
+        app.filters.set(FilterField::Port, "443".to_string());
+        while app.ingest_pending() {
+            app.ingest_tick();
         }
-        "-V" | "--version" => {
-            if args.len() > 1 {
-                Err(format!("Unexpected argument after {}: {}", first, args[1]))
-            } else {
-                Ok(CliAction::PrintVersion)
-            }
+        let appended = line("1.1.1.1", 22) + &line("2.2.2.2", 22) + &line("3.3.3.3", 443);
+        fs::write(&path, line("1.1.1.1", 22) + &appended).unwrap();
+        app.reload();
+        while app.ingest_pending() {
+            app.ingest_tick();
         }
-        _ if first.starts_with('-') => Err(format!("Unknown option: {}", first)),
-        _ => {
-            if args.len() > 1 {
-                Err(format!("Unexpected extra argument: {}", args[1]))
-            } else {
-                Ok(CliAction::Run(PathBuf::from(first)))
-            }
+
+        let mut buf = [0u8; 1024];
+        let len = receiver.recv(&mut buf).unwrap();
+        let datagram = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(datagram.starts_with("<134>ufw-log-viewer: "));
+        assert!(datagram.contains("SRC=3.3.3.3"));
+        // The worker counts the send once the write returns.
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while app.sinks[0].sent() == 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
         }
+        assert_eq!(app.sinks[0].sent(), 1);
+        let _ = fs::remove_file(&path);
     }
-}
-
-fn print_help() {
-    println!(
-        "{name} {version}\n\nUsage:\n  {name} [LOG_PATH]\n\nOptions:\n  -h, --help       Show this help\n  -V, --version    Show version\n\nIf LOG_PATH is not provided, the app checks:\n  1) /var/log/ufw-firewall.log\n  2) /var/log/ufw.log\n  3) /var/log/kern.log",
-        name = env!("CARGO_PKG_NAME"),
-        version = env!("CARGO_PKG_VERSION"),
-    );
-}
-
-fn print_version() {
-    println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
-}
 
-fn run_app(log_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    struct TerminalCleanup;
-    impl Drop for TerminalCleanup {
-        fn drop(&mut self) {
-            let _ = disable_raw_mode();
-            let mut stdout = io::stdout();
-            let _ = execute!(
-                stdout,
-                Show,
-                DisableMouseCapture,
-                terminal::LeaveAlternateScreen
-            );
+    #[test]
+    fn journal_contents_replace_the_main_log() {
+        let mut app = App::new(PathBuf::from("journalctl -k"));
+        let mut journal = Journal::default();
+        journal.contents.push_line(
+            "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1",
+        );
+        app.journal = Some(journal);
+        assert!(app.reload());
+        while app.ingest_pending() {
+            app.ingest_tick();
         }
+        assert_eq!(app.entries.len(), 1);
+        assert_eq!(app.entries[0].src_ip.as_deref(), Some("1.2.3.4"));
     }
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, terminal::EnterAlternateScreen, EnableMouseCapture)?;
-    let _cleanup = TerminalCleanup;
-    let backend = ratatui::backend::CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let mut app = App::new(log_path);
-
-    'mainloop: loop {
-        app.maybe_reload();
-        terminal.draw(|frame| ui(frame, &mut app))?;
-        if event::poll(Duration::from_millis(250))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    if key.kind != KeyEventKind::Press {
-                        continue;
-                    }
-
-                    if key.modifiers.contains(KeyModifiers::CONTROL)
-                        && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C'))
-                    {
-                        app.copy_selected_log_entry();
-                        continue;
-                    }
-
-                    if let Some(field) = app.input_mode {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.input_mode = None;
-                                app.input_buffer.clear();
-                            }
-                            KeyCode::Enter => {
-                                app.set_filter_value(field, app.input_buffer.clone());
-                                let current_value = app.get_filter_value(field).to_string();
-                                let matches = app.filtered_indices().len();
-                                app.status = if current_value.is_empty() {
-                                    format!(
-                                        "Removed {} filter. Matching rows: {}",
-                                        field.label(),
-                                        matches
-                                    )
-                                } else {
-                                    format!(
-                                        "Set {} filter='{}'. Matching rows: {}",
-                                        field.label(),
-                                        current_value,
-                                        matches
-                                    )
-                                };
-                                app.input_mode = None;
-                                app.input_buffer.clear();
-                            }
-                            KeyCode::Backspace => {
-                                app.input_buffer.pop();
-                            }
-                            KeyCode::Char(c) => {
-                                app.input_buffer.push(c);
-                            }
-                            _ => {}
-                        }
-                        continue;
-                    }
-
-                    if handle_modifier_shortcuts(&mut app, &key) {
-                        continue;
-                    }
-
-                    if (key.modifiers.contains(KeyModifiers::CONTROL)
-                        && matches!(key.code, KeyCode::Char('i') | KeyCode::Char('I')))
-                        || matches!(key.code, KeyCode::Tab)
-                    {
-                        app.copy_selected_src_ip();
-                        continue;
-                    }
-
-                    let filtered_len = app.filtered_indices().len();
-                    match key.code {
-                        KeyCode::Char('q') => break 'mainloop,
-                        KeyCode::Char('r') => {
-                            let _ = app.reload();
-                        }
-                        KeyCode::Char('a') | KeyCode::Char('A') => app.toggle_pause_updates(),
-                        KeyCode::Char('c') => app.clear_filters(),
-                        KeyCode::Char('l') | KeyCode::Char('L') => app.toggle_show_local_src(),
-                        KeyCode::Char('p') | KeyCode::Char('P') => app.toggle_show_wan_src(),
-                        KeyCode::Char('f') | KeyCode::Char('F') => app.cycle_flow_filter(),
-                        KeyCode::Char('d') | KeyCode::Char('D') => app.cycle_direction_filter(),
-                        KeyCode::Char(',') => app.cycle_interface(false),
-                        KeyCode::Char('.') => app.cycle_interface(true),
-                        KeyCode::Char('0') => app.select_all_interfaces(),
-                        KeyCode::Char('w') | KeyCode::Char('W') => {
-                            app.select_default_wan_interface()
-                        }
-                        KeyCode::Left => app.scroll_log_entry_left(),
-                        KeyCode::Right => app.scroll_log_entry_right(),
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if filtered_len > 0 {
-                                app.selected = app.selected.saturating_sub(1);
-                                app.sync_selection_with_len(filtered_len);
-                            }
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            if filtered_len > 0 {
-                                app.selected =
-                                    min(app.selected + 1, filtered_len.saturating_sub(1));
-                                app.sync_selection_with_len(filtered_len);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                Event::Mouse(mouse) => {
-                    let size = terminal.size()?;
-                    handle_mouse_event(
-                        &mut app,
-                        mouse,
-                        Rect {
-                            x: 0,
-                            y: 0,
-                            width: size.width,
-                            height: size.height,
-                        },
-                    );
-                }
-                _ => {
-                    continue;
-                }
+    #[test]
+    fn syslog_listener_collects_udp_and_tcp_messages() {
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .and_then(|socket| socket.local_addr())
+            .unwrap()
+            .port();
+        let addr = format!("127.0.0.1:{}", port);
+        let mut listener = Listener::bind(&addr).unwrap();
+        let line = |src: &str| {
+            format!(
+                "<4>Feb 11 20:21:00 web01 kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1\n"
+            )
+        };
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        udp.send_to(line("1.2.3.4").as_bytes(), &addr).unwrap();
+        udp.send_to(b"<30>Feb 11 20:21:00 web01 sshd[1]: noise", &addr)
+            .unwrap();
+        let mut tcp = std::net::TcpStream::connect(&addr).unwrap();
+        io::Write::write_all(&mut tcp, line("5.6.7.8").as_bytes()).unwrap();
+        let mut received = String::new();
+        for _ in 0..200 {
+            listener.poll();
+            received.push_str(&listener.contents.take_fresh().0);
+            if received.lines().count() == 2 {
+                break;
             }
+            std::thread::sleep(Duration::from_millis(10));
         }
-    }
-
-    terminal.show_cursor()?;
-
-    Ok(())
-}
 
-fn main() {
-    let args: Vec<String> = std::env::args().skip(1).collect();
-    match parse_cli_action(&args, resolve_default_log_path()) {
-        Ok(CliAction::PrintHelp) => print_help(),
-        Ok(CliAction::PrintVersion) => print_version(),
-        Ok(CliAction::Run(log_path)) => {
-            if let Err(err) = run_app(log_path) {
-                eprintln!("Error: {}", err);
-            }
+        let mut app = App::new(PathBuf::from(format!("syslog {}", addr)));
+        app.listener = Some(listener);
+        assert!(app.reload());
+        while app.ingest_pending() {
+            app.ingest_tick();
         }
-        Err(err) => {
-            eprintln!("Error: {}\n", err);
-            print_help();
-            std::process::exit(2);
+        let mut sources: Vec<&str> = app
+            .entries
+            .iter()
+            .filter_map(|entry| entry.src_ip.as_deref())
+            .collect();
+        sources.sort();
+        assert_eq!(sources, ["1.2.3.4", "5.6.7.8"]);
+
+        // A later message is tailed on its own instead of re-parsing the rest.
+        udp.send_to(line("9.9.9.9").as_bytes(), &addr).unwrap();
+        for _ in 0..200 {
+            app.last_watch_check = Instant::now() - Duration::from_secs(2);
+            app.maybe_reload();
+            if let Some(pending) = app.pending_ingest.as_ref() {
+                assert!(pending.append);
+            }
+            while app.ingest_pending() {
+                app.ingest_tick();
+            }
+            if app.entries.len() == 3 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
         }
+        assert_eq!(app.entries[0].src_ip.as_deref(), Some("9.9.9.9"));
+        assert_eq!(app.entries.len(), 3);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn parse_ufw_line_extracts_core_fields() {
-        let line = "Feb 11 20:21:00 host kernel: [UFW BLOCK] IN=wlan0 OUT= MAC= SRC=10.0.0.5 DST=10.0.0.1 LEN=60 TOS=0x00 PREC=0x00 TTL=64 ID=12345 DF PROTO=TCP SPT=443 DPT=52910 WINDOW=64240 RES=0x00 ACK URGP=0";
-        let entry = parse_ufw_line(line).expect("line should parse");
-        assert_eq!(entry.action, "BLOCK");
-        assert_eq!(entry.src_ip.as_deref(), Some("10.0.0.5"));
-        assert_eq!(entry.dst_ip.as_deref(), Some("10.0.0.1"));
-        assert_eq!(entry.proto.as_deref(), Some("TCP"));
-        assert_eq!(entry.src_port, Some(443));
-        assert_eq!(entry.dst_port, Some(52910));
-        assert_eq!(entry.service.as_deref(), Some("https"));
+    fn low_memory_cuts_raw_lines_and_rereads_them() {
+        let path = std::env::temp_dir().join(format!("ufw-lowmem-{}.log", std::process::id()));
+        let long = format!(
+            "Feb 11 20:00:01 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC=1.1.1.1 DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22 {}",
+            "X".repeat(400)
+        );
+        fs::write(
+            &path,
+            format!("Feb 11 20:00:00 h kernel: [UFW BLOCK] SRC=2.2.2.2\n{long}\n"),
+        )
+        .unwrap();
+
+        let mut app = App::new(path.clone());
+        app.low_memory = true;
+        app.reload();
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        assert_eq!(
+            app.entries[0].raw.len(),
+            LOW_MEMORY_RAW_BYTES + '…'.len_utf8()
+        );
+        assert_eq!(app.entries[0].raw_offset, Some(50));
+        assert_eq!(app.entries[1].raw_offset, None);
+        assert_eq!(app.full_raw(0), long);
+
+        // A source that changed underneath falls back to the cut line.
+        fs::write(&path, "rotated away\n").unwrap();
+        app.raw_reread = None;
+        assert!(app.full_raw(0).ends_with('…'));
+        fs::remove_file(&path).ok();
     }
 
     #[test]
-    fn filters_match_expected_fields() {
-        let entry = LogEntry {
-            timestamp: "Feb 11 20:21:00".to_string(),
-            action: "ALLOW".to_string(),
-            in_iface: Some("eth0".to_string()),
-            out_iface: Some("".to_string()),
-            src_ip: Some("1.2.3.4".to_string()),
-            dst_ip: Some("10.0.0.1".to_string()),
-            src_port: Some(51234),
-            dst_port: Some(22),
-            proto: Some("TCP".to_string()),
-            service: Some("ssh".to_string()),
-            raw: "raw log line".to_string(),
+    fn appended_lines_are_tailed_without_a_full_reload() {
+        let path = std::env::temp_dir().join(format!("ufw-tail-{}.log", std::process::id()));
+        let line = |sec: u8, src: &str| {
+            format!(
+                "Feb 11 20:00:{sec:02} h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+            )
         };
-
-        let mut filters = Filters {
-            service: "ssh".to_string(),
-            port: "22".to_string(),
-            ip: "1.2.3".to_string(),
-            action: "allow".to_string(),
-            proto: "tcp".to_string(),
-            ..Default::default()
+        fs::write(&path, line(0, "1.1.1.1") + &line(1, "1.1.1.1")).unwrap();
+        let mut app = App::new(path.clone());
+        // Back at the top, so new rows are not held for the scrolled-away view.
+        let ingest = |app: &mut App| {
+            while app.ingest_pending() {
+                app.ingest_tick();
+            }
+            app.selected = 0;
+            app.apply_held_ingest();
         };
-        assert!(filters.matches(&entry));
-
-        filters.port = "443".to_string();
-        assert!(!filters.matches(&entry));
+        ingest(&mut app);
+        let ids = |app: &App| app.entries.iter().map(|e| e.id).collect::<Vec<_>>();
+        let before = ids(&app);
+
+        // The last line is still being written; only complete lines are read.
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all((line(2, "1.1.1.1") + "Feb 11 20:00:03 h kern").as_bytes())
+            .unwrap();
+        assert!(app.tail_sources());
+        assert!(app.pending_ingest.as_ref().is_some_and(|p| p.append));
+        ingest(&mut app);
+        assert_eq!(app.entries.len(), 3);
+        assert_eq!(ids(&app)[1..], before);
+        file.write_all(b"el: [UFW ALLOW] SRC=2.2.2.2\n").unwrap();
+        assert!(app.tail_sources());
+        ingest(&mut app);
+        assert_eq!(app.entries[0].action, "ALLOW");
+
+        let tailed = ids(&app);
+        app.reload();
+        ingest(&mut app);
+        assert_eq!(ids(&app), tailed);
+
+        fs::write(&path, line(5, "3.3.3.3")).unwrap();
+        assert!(!app.tail_sources());
+        fs::remove_file(&path).ok();
     }
 
     #[test]
-    fn local_src_ip_detection_works_for_common_ranges() {
-        assert!(is_local_src_ip(Some("192.168.1.10")));
-        assert!(is_local_src_ip(Some("10.1.2.3")));
-        assert!(is_local_src_ip(Some("172.16.0.1")));
-        assert!(is_local_src_ip(Some("172.31.255.254")));
-        assert!(is_local_src_ip(Some("127.0.0.1")));
-        assert!(is_local_src_ip(Some("169.254.20.1")));
-        assert!(is_local_src_ip(Some("::1")));
-        assert!(is_local_src_ip(Some("fe80::1234")));
-        assert!(is_local_src_ip(Some("fd12::abcd")));
-
-        assert!(!is_local_src_ip(Some("8.8.8.8")));
-        assert!(!is_local_src_ip(Some("1.1.1.1")));
-        assert!(!is_local_src_ip(Some("2001:4860:4860::8888")));
-        assert!(!is_local_src_ip(None));
-        assert!(!is_local_src_ip(Some("")));
-    }
+    fn rotated_history_merges_compressed_rotations() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
 
-    #[test]
-    fn wan_src_ip_detection_works() {
-        assert!(is_wan_src_ip(Some("8.8.8.8")));
-        assert!(is_wan_src_ip(Some("1.1.1.1")));
-        assert!(is_wan_src_ip(Some("2001:4860:4860::8888")));
+        let dir = std::env::temp_dir().join(format!("ufw-rotated-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let line = |time: &str, src: &str| {
+            format!(
+                "Feb 11 {time} h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+            )
+        };
+        let log = dir.join("ufw.log");
+        fs::write(&log, line("20:00:00", "3.3.3.3")).unwrap();
+        fs::write(dir.join("ufw.log.1"), line("19:00:00", "2.2.2.2")).unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(line("18:00:00", "1.1.1.1").as_bytes())
+            .unwrap();
+        fs::write(dir.join("ufw.log.2.gz"), gz.finish().unwrap()).unwrap();
+
+        let mut app = App::new(log);
+        app.include_rotated = true;
+        app.reload();
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        let sources: Vec<&str> = app
+            .entries
+            .iter()
+            .map(|entry| entry.src_ip.as_deref().unwrap())
+            .collect();
+        assert_eq!(sources, ["3.3.3.3", "2.2.2.2", "1.1.1.1"]);
+        assert_eq!(app.source_display(app.entries[0].source), None);
+        assert_eq!(
+            app.source_display(app.entries[2].source).as_deref(),
+            Some("ufw.log.2.gz")
+        );
 
-        assert!(!is_wan_src_ip(Some("192.168.1.10")));
-        assert!(!is_wan_src_ip(Some("10.1.2.3")));
-        assert!(!is_wan_src_ip(Some("172.16.0.1")));
-        assert!(!is_wan_src_ip(Some("127.0.0.1")));
-        assert!(!is_wan_src_ip(Some("::1")));
-        assert!(!is_wan_src_ip(Some("fe80::1")));
-        assert!(!is_wan_src_ip(None));
-        assert!(!is_wan_src_ip(Some("")));
-        assert!(!is_wan_src_ip(Some("not-an-ip")));
+        app.toggle_rotated_history();
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        assert_eq!(app.entries.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn flow_filter_matches_local_to_local_and_local_to_external() {
-        let mut entry = LogEntry {
-            src_ip: Some("192.168.1.10".to_string()),
-            dst_ip: Some("192.168.1.20".to_string()),
-            ..Default::default()
+    fn watched_sources_merge_chronologically_with_labels() {
+        let dir = std::env::temp_dir().join(format!("ufw-watch-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("remote")).unwrap();
+        let line = |ts: &str, src: &str| {
+            format!(
+                "{ts} h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+            )
         };
-        assert!(matches_flow_filter(FlowFilter::All, &entry));
-        assert!(matches_flow_filter(FlowFilter::LocalToLocal, &entry));
-        assert!(!matches_flow_filter(FlowFilter::LocalToExternal, &entry));
+        let main_log = dir.join("ufw.log");
+        fs::write(
+            &main_log,
+            line("2026-02-11T10:00:00+00:00", "1.1.1.1")
+                + &line("2026-02-11T10:00:02+00:00", "1.1.1.3"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("remote").join("web01.log"),
+            line("2026-02-11T11:00:01+01:00", "1.1.1.2"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("remote").join("web01.log.1"),
+            line("2026-02-11T09:00:00+00:00", "9.9.9.9"),
+        )
+        .unwrap();
 
-        entry.dst_ip = Some("8.8.8.8".to_string());
-        assert!(matches_flow_filter(FlowFilter::LocalToExternal, &entry));
-        assert!(!matches_flow_filter(FlowFilter::LocalToLocal, &entry));
+        let mut app = App::new(main_log);
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        app.watch_dirs = vec![dir.join("remote")];
+        assert!(app.discover_watched_sources());
+        assert!(!app.discover_watched_sources());
+        assert_eq!(app.watched_sources.len(), 1);
+        app.reload();
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+
+        let sources: Vec<(&str, usize)> = app
+            .entries
+            .iter()
+            .map(|entry| (entry.src_ip.as_deref().unwrap(), entry.source))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![("1.1.1.3", 0), ("1.1.1.2", 1), ("1.1.1.1", 0)]
+        );
+        assert_eq!(source_label(&app.watched_sources[0]), "web01");
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn default_wan_interface_prefers_wan_like_names() {
-        let options = vec![
-            "docker0".to_string(),
-            "lo".to_string(),
-            "wlan0".to_string(),
-            "br-123".to_string(),
-        ];
-        assert_eq!(default_wan_interface(&options).as_deref(), Some("wlan0"));
-
-        let options = vec!["docker0".to_string(), "lo".to_string()];
-        assert_eq!(default_wan_interface(&options).as_deref(), Some("docker0"));
+    fn parse_cli_action_rejects_unknown_or_extra_args() {
+        let default = PathBuf::from("/tmp/default.log");
+        assert!(parse_cli_action(&["--bogus".to_string()], default.clone()).is_err());
+        assert!(
+            parse_cli_action(
+                &["--clear-cache".to_string(), "x".to_string()],
+                default.clone()
+            )
+            .is_err()
+        );
+        assert!(parse_cli_action(&["/tmp/no-such-dir/*.log".to_string()], default).is_err());
+        // Modes that each replace the TUI, or an option without its mode.
+        for bad in [
+            &["--print", "--trend", "4"][..],
+            &["--summary", "--export", "cef"],
+            &["--trend", "4", "--export=leef"],
+            &["--journal", "--listen", "0.0.0.0:5514"],
+            &["--trend-format", "json"],
+            &["--output=-"],
+        ] {
+            let args: Vec<String> = bad.iter().map(|arg| arg.to_string()).collect();
+            let err = parse_cli_action(&args, PathBuf::from("/tmp/a.log")).unwrap_err();
+            assert!(
+                matches!(
+                    err.kind(),
+                    clap::error::ErrorKind::ArgumentConflict
+                        | clap::error::ErrorKind::MissingRequiredArgument
+                ),
+                "{:?}: {}",
+                bad,
+                err
+            );
+        }
     }
 
     #[test]
-    fn selected_position_for_raw_finds_matching_row_in_filtered_view() {
-        let entries = vec![
-            LogEntry {
-                raw: "newest".to_string(),
-                ..Default::default()
-            },
-            LogEntry {
-                raw: "kept".to_string(),
-                ..Default::default()
-            },
-            LogEntry {
-                raw: "older".to_string(),
-                ..Default::default()
-            },
+    fn several_log_paths_merge_with_a_file_column() {
+        let dir = std::env::temp_dir().join(format!("ufw-files-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let line = |ts: &str, src: &str| {
+            format!(
+                "{ts} h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+            )
+        };
+        fs::write(
+            dir.join("ufw.log"),
+            line("2026-02-11T10:00:00+00:00", "1.1.1.1"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("kern.log"),
+            line("2026-02-11T10:00:01+00:00", "1.1.1.2"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("ufw-old.log"),
+            line("2026-02-11T09:00:00+00:00", "1.1.1.3"),
+        )
+        .unwrap();
+
+        let args = [
+            dir.join("ufw.log").display().to_string(),
+            dir.join("*.log").display().to_string(),
         ];
-        let filtered = vec![0, 1, 2];
+        let Ok(CliAction::Run(options)) = parse_cli_action(&args, PathBuf::from("/tmp/a.log"))
+        else {
+            panic!("expected a run action");
+        };
+        assert_eq!(options.log_path, dir.join("ufw.log"));
         assert_eq!(
-            selected_position_for_raw(&entries, &filtered, "kept"),
-            Some(1)
+            options.extra_logs,
+            [dir.join("kern.log"), dir.join("ufw-old.log")]
         );
+        let printed: Vec<(String, usize)> = read_entries(&options)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.src_ip.unwrap(), entry.source))
+            .collect();
         assert_eq!(
-            selected_position_for_raw(&entries, &filtered, "missing"),
-            None
+            printed,
+            [
+                ("1.1.1.3".to_string(), 2),
+                ("1.1.1.1".to_string(), 0),
+                ("1.1.1.2".to_string(), 1),
+            ]
         );
+
+        let mut app = App::new(options.log_path);
+        app.extra_sources = options.extra_logs;
+        assert!(app.show_file_column());
+        assert_eq!(app.source_name(0), "ufw");
+        assert_eq!(app.source_name(2), "ufw-old");
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn parse_cli_action_handles_help_and_version() {
-        let default = PathBuf::from("/tmp/default.log");
+    fn parse_cli_action_reads_scripting_options() {
+        let args = [
+            "--file=/var/log/ufw.log",
+            "/var/log/kern.log",
+            "--follow",
+            "--no-follow",
+            "--no-mouse",
+            "--theme=mono",
+            "--ipv6-prefix",
+            "/56",
+            "--filter",
+            "ip=10.0.0.5",
+            "--filter",
+            "proto=!udp",
+        ]
+        .map(String::from);
+        let Ok(CliAction::Run(options)) = parse_cli_action(&args, PathBuf::from("/tmp/a.log"))
+        else {
+            panic!("expected a run action");
+        };
+        assert_eq!(options.log_path, PathBuf::from("/var/log/ufw.log"));
+        assert_eq!(options.extra_logs, [PathBuf::from("/var/log/kern.log")]);
+        assert!(!options.follow && !options.mouse);
+        assert_eq!(options.theme, Theme::Mono);
+        assert_eq!(options.ipv6_prefix, 56);
+        assert_eq!(
+            options.filters,
+            [
+                (FilterField::Ip, "10.0.0.5".to_string()),
+                (FilterField::Proto, "!udp".to_string()),
+            ]
+        );
+
+        let now = 1_770_840_000;
+        assert_eq!(parse_since("2h", now), Ok(now - 7_200));
+        assert_eq!(parse_since("today", now), Ok(now / 86_400 * 86_400));
+        assert!(parse_since("soon", now).is_err());
+        let args = ["--query", "--print-format", "json", "--filter", "port=22"].map(String::from);
+        assert!(matches!(
+            parse_cli_action(&args, PathBuf::from("/tmp/a.log")),
+            Ok(CliAction::Print(PrintOptions {
+                format: LineFormat::Json,
+                ..
+            }))
+        ));
         assert_eq!(
-            parse_cli_action(&["--help".to_string()], default.clone()).unwrap(),
-            CliAction::PrintHelp
+            parse_cli_action(&["--summary".to_string()], PathBuf::from("/tmp/a.log")).unwrap(),
+            CliAction::Print(PrintOptions {
+                run: RunOptions::new(PathBuf::from("/tmp/a.log")),
+                format: LineFormat::Raw,
+                summary: true,
+            })
         );
+        let by_action = BTreeMap::from([("ALLOW", 2), ("BLOCK", 10)]);
         assert_eq!(
-            parse_cli_action(&["--version".to_string()], default.clone()).unwrap(),
-            CliAction::PrintVersion
+            summary_trailer(12, &by_action, LineFormat::Raw),
+            "# 12 matched: ALLOW 2, BLOCK 10\n"
         );
         assert_eq!(
-            parse_cli_action(&["/var/log/ufw.log".to_string()], default.clone()).unwrap(),
-            CliAction::Run(PathBuf::from("/var/log/ufw.log"))
+            summary_trailer(12, &by_action, LineFormat::Json),
+            "{\"summary\":{\"matched\":12,\"actions\":{\"ALLOW\":2,\"BLOCK\":10}}}\n"
         );
         assert_eq!(
-            parse_cli_action(&Vec::new(), default).unwrap(),
-            CliAction::Run(PathBuf::from("/tmp/default.log"))
+            summary_trailer(0, &BTreeMap::new(), LineFormat::Raw),
+            "# 0 matched\n"
         );
+        for bad in [
+            ["--filter", "colour=red"],
+            ["--filter", "time=never"],
+            ["--theme", "neon"],
+            ["--ipv6-prefix", "129"],
+            ["--print-format", "xml"],
+        ] {
+            assert!(parse_cli_action(&bad.map(String::from), PathBuf::from("/tmp/a.log")).is_err());
+        }
     }
 
     #[test]
-    fn parse_cli_action_rejects_unknown_or_extra_args() {
-        let default = PathBuf::from("/tmp/default.log");
-        assert!(parse_cli_action(&["--bogus".to_string()], default.clone()).is_err());
-        assert!(
-            parse_cli_action(&["--help".to_string(), "x".to_string()], default.clone()).is_err()
-        );
-        assert!(
-            parse_cli_action(
-                &["/tmp/a.log".to_string(), "/tmp/b.log".to_string()],
-                default
-            )
-            .is_err()
+    fn view_command_line_parses_back_into_the_same_view() {
+        let entries = || {
+            ["eth0", "eth1", "wg0"]
+                .iter()
+                .map(|iface| LogEntry {
+                    in_iface: Some(iface.to_string()),
+                    ..Default::default()
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut app = App::new(PathBuf::from("/var/log/ufw.log"));
+        app.interface_globs = vec!["eth*".to_string()];
+        app.apply_entries(entries());
+        app.set_filter_value(FilterField::Action, "BLOCK".to_string());
+        app.set_filter_value(FilterField::Port, "22".to_string());
+        app.set_filter_value(FilterField::Text, "it's".to_string());
+        app.selected_interface = Some("eth1".to_string());
+        app.load_since = Some(1_770_836_400);
+        assert_eq!(
+            app.view_command_line(),
+            "ufw-log-viewer /var/log/ufw.log --filter port=22 --filter action=BLOCK \
+             --filter 'text=it'\\''s' --iface 'eth*' --interface eth1 \
+             --since '2026-02-11 19:00:00'"
         );
+
+        // Neither the picked chip nor all interfaces is the default one.
+        for selected in [Some("eth1"), None] {
+            app.selected_interface = selected.map(str::to_string);
+            let args = app.view_args();
+            let Ok(CliAction::Run(options)) =
+                parse_cli_action(&args[1..], PathBuf::from("/tmp/a.log"))
+            else {
+                panic!("expected a run action");
+            };
+            let mut reopened = App::new(options.log_path);
+            reopened.open_view(
+                options.interface_globs,
+                options.interface,
+                options.filters,
+                options.since,
+            );
+            reopened.apply_entries(entries());
+            assert_eq!(reopened.interface_options, ["eth0", "eth1"]);
+            assert_eq!(reopened.selected_interface, app.selected_interface);
+            assert_eq!(reopened.view_command_line(), app.view_command_line());
+            // Later ingests keep the selection.
+            reopened.apply_entries(entries());
+            assert_eq!(reopened.selected_interface, app.selected_interface);
+        }
     }
 }
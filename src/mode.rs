@@ -0,0 +1,52 @@
+use crate::FilterField;
+
+// A layer of the UI that takes the keys. Layers stack in the order they were
+// opened (a confirmation from the egress review, a preset name from the
+// preset menu), keys go to the top one, and Esc closes just that one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    // The events table; keys go through the keymap.
+    Normal,
+    // `"` pressed; the next digit recalls a recent view.
+    RecentLeader,
+    // Typing a filter value after F1..F9.
+    FilterEdit(FilterField),
+    // `/` typing a search term.
+    Search,
+    // Typing a path: `o` switches the source, `e` exports the view.
+    SourcePrompt,
+    ExportPrompt,
+    // Drill-downs: the focused `|` sidebar and the `>` egress review, lists
+    // whose rows act on the view.
+    Talkers,
+    EgressReview,
+    // The `C` summary of blocked entries by country; Enter filters by one.
+    Countries,
+    // Menus: `;` columns and `'` presets, with naming a new preset or
+    // picking one to delete on top of the latter.
+    ColumnMenu,
+    PresetMenu,
+    PresetName,
+    PresetDelete,
+    // Asks before running ufw.
+    Confirm,
+    // A read-only report, and `?` help.
+    Popup,
+    Help,
+    // Views over the table: `s` statistics, `=` aggregated rows and the `^`
+    // alerts panel. Keys still go to the table; Esc turns the view off.
+    Stats,
+    Aggregate,
+    Alerts,
+}
+
+impl Mode {
+    // Whether keys go through the keymap, as in the table and the views
+    // over it.
+    pub fn table_keys(self) -> bool {
+        matches!(
+            self,
+            Mode::Normal | Mode::Stats | Mode::Aggregate | Mode::Alerts
+        )
+    }
+}
@@ -1,4 +1,5 @@
-use std::net::IpAddr;
+use std::borrow::Cow;
+use std::net::{IpAddr, Ipv6Addr};
 
 use crate::{DirectionFilter, FlowFilter, LogEntry};
 
@@ -31,6 +32,42 @@ pub fn matches_direction_filter(direction: DirectionFilter, entry: &LogEntry) ->
     }
 }
 
+// `--iface` patterns: the interface matches when any glob does. No patterns
+// allow every interface.
+pub fn interface_allowed(globs: &[String], name: &str) -> bool {
+    globs.is_empty() || globs.iter().any(|glob| glob_match(glob, name))
+}
+
+// Shell-style glob with `*` (any run) and `?` (one character).
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it resumed from.
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, n));
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 pub fn is_wan_candidate_interface(name: &str) -> bool {
     let lower = name.to_ascii_lowercase();
     if lower == "lo"
@@ -62,6 +99,184 @@ pub fn default_wan_interface(options: &[String]) -> Option<String> {
         .or_else(|| options.first().cloned())
 }
 
+// Heuristic for reply packets of connections the firewall already forgot
+// (conntrack timeouts): they come from a service port below 1024 to an
+// ephemeral port, and for TCP carry ACK/FIN/RST rather than a bare SYN.
+pub fn is_return_traffic(entry: &LogEntry) -> bool {
+    let (Some(src_port), Some(dst_port)) = (entry.src_port, entry.dst_port) else {
+        return false;
+    };
+    if src_port >= 1024 || dst_port < 1024 {
+        return false;
+    }
+    match entry.proto.as_deref() {
+        Some("TCP") => ["ACK", "FIN", "RST"]
+            .into_iter()
+            .any(|flag| entry.tcp_flags.contains(flag)),
+        Some("UDP") => true,
+        _ => false,
+    }
+}
+
+// Key that groups sources for offender counts. One IPv6 host rotates through
+// its whole /64, so IPv6 addresses are cut to `v6_prefix` bits, e.g.
+// "2001:db8:1:2::/64"; IPv4, a /128 and unparsable text pass through.
+pub fn source_key(ip: &str, v6_prefix: u8) -> Cow<'_, str> {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V6(addr)) if v6_prefix < 128 => {
+            let mask = u128::MAX
+                .checked_shl(u32::from(128 - v6_prefix))
+                .unwrap_or(0);
+            let network = Ipv6Addr::from(u128::from(addr) & mask);
+            Cow::Owned(format!("{}/{}", network, v6_prefix))
+        }
+        _ => Cow::Borrowed(ip),
+    }
+}
+
+// "a.b.c.d", "a.b.c.d/n" or the IPv6 equivalents as an address and prefix
+// length; a bare address is a single host.
+pub fn parse_network(text: &str) -> Option<(IpAddr, u8)> {
+    let (addr, len) = match text.split_once('/') {
+        Some((addr, len)) => (addr.parse::<IpAddr>().ok()?, len.parse::<u8>().ok()?),
+        None => {
+            let addr = text.parse::<IpAddr>().ok()?;
+            (addr, if addr.is_ipv4() { 32 } else { 128 })
+        }
+    };
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    (len <= max).then_some((addr, len))
+}
+
+// Binary prefix tree over IPv4 and IPv6 networks, each tagged with a label
+// (e.g. which blocklist it came from). IPv4 is stored IPv4-mapped, so a lookup
+// walks at most 128 nodes however many networks are loaded.
+pub struct PrefixTrie {
+    // Node 0 is the root; children index into the same vector.
+    nodes: Vec<TrieNode>,
+    networks: usize,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: [Option<u32>; 2],
+    label: Option<u32>,
+}
+
+impl Default for PrefixTrie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+            networks: 0,
+        }
+    }
+}
+
+impl PrefixTrie {
+    pub fn insert(&mut self, addr: IpAddr, len: u8, label: u32) {
+        let (bits, len) = trie_key(addr, len);
+        let mut node = 0;
+        for depth in 0..len {
+            let bit = trie_bit(bits, depth);
+            node = match self.nodes[node].children[bit] {
+                Some(child) => child as usize,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[bit] = Some(child as u32);
+                    child
+                }
+            };
+        }
+        if self.nodes[node].label.replace(label).is_none() {
+            self.networks += 1;
+        }
+    }
+
+    // Label of the most specific network containing `addr`.
+    pub fn lookup(&self, addr: IpAddr) -> Option<u32> {
+        let (bits, _) = trie_key(addr, 0);
+        let mut node = &self.nodes[0];
+        let mut found = node.label;
+        for depth in 0..128 {
+            let Some(child) = node.children[trie_bit(bits, depth)] else {
+                break;
+            };
+            node = &self.nodes[child as usize];
+            found = node.label.or(found);
+        }
+        found
+    }
+
+    pub fn networks(&self) -> usize {
+        self.networks
+    }
+}
+
+fn trie_key(addr: IpAddr, len: u8) -> (u128, u8) {
+    match addr {
+        IpAddr::V4(addr) => (u128::from(addr.to_ipv6_mapped()), len + 96),
+        IpAddr::V6(addr) => (u128::from(addr), len),
+    }
+}
+
+fn trie_bit(bits: u128, depth: u8) -> usize {
+    (bits >> (127 - depth) & 1) as usize
+}
+
+// Broadcast (x.x.x.255, 255.255.255.255) and multicast (224/4, ff00::/8)
+// destinations: mostly mDNS/SSDP/NetBIOS chatter on LANs.
+pub fn is_broadcast_or_multicast_ip(ip: Option<&str>) -> bool {
+    match ip.and_then(|ip| ip.trim().parse::<IpAddr>().ok()) {
+        Some(IpAddr::V4(addr)) => addr.is_multicast() || addr.octets()[3] == 255,
+        Some(IpAddr::V6(addr)) => addr.is_multicast(),
+        None => false,
+    }
+}
+
+// Reserved range a source address falls in, if any. Such sources should never
+// reach a WAN interface; 100.64.0.0/10 (carrier-grade NAT) is skipped when the
+// network uses it locally.
+pub fn bogon_range(ip: Option<&str>, cgnat_local: bool) -> Option<&'static str> {
+    match ip?.trim().parse::<IpAddr>().ok()? {
+        IpAddr::V4(addr) => match addr.octets() {
+            [0, ..] => Some("0.0.0.0/8"),
+            [100, b, ..] if !cgnat_local && (64..128).contains(&b) => Some("100.64.0.0/10"),
+            [192, 0, 0, _] => Some("192.0.0.0/24"),
+            [192, 0, 2, _] => Some("192.0.2.0/24"),
+            [198, 18 | 19, ..] => Some("198.18.0.0/15"),
+            [198, 51, 100, _] => Some("198.51.100.0/24"),
+            [203, 0, 113, _] => Some("203.0.113.0/24"),
+            [a, ..] if a >= 240 => Some("240.0.0.0/4"),
+            _ => None,
+        },
+        IpAddr::V6(addr) => match addr.segments() {
+            [0x2001, 0xdb8, ..] => Some("2001:db8::/32"),
+            _ if addr.is_unspecified() => Some("::/128"),
+            _ => None,
+        },
+    }
+}
+
+// Reverse-DNS zone name of an address: 4.3.2.1.in-addr.arpa for IPv4, one
+// label per nibble under ip6.arpa for IPv6.
+pub fn reverse_dns_name(ip: &str) -> Option<String> {
+    match ip.trim().parse::<IpAddr>().ok()? {
+        IpAddr::V4(addr) => {
+            let [a, b, c, d] = addr.octets();
+            Some(format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a))
+        }
+        IpAddr::V6(addr) => {
+            let mut name = String::with_capacity(72);
+            for byte in addr.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0xf, byte >> 4));
+            }
+            name.push_str("ip6.arpa");
+            Some(name)
+        }
+    }
+}
+
 fn is_local_ip(ip: Option<&str>) -> bool {
     let Some(ip) = ip else {
         return false;
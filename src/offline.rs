@@ -0,0 +1,13 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Global offline switch. Every feature that touches the network must check
+// `network_allowed()` before connecting, so `--offline` is enforced in one place.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn network_allowed() -> bool {
+    !OFFLINE.load(Ordering::Relaxed)
+}
@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+
+// What the `O` prompt switches to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceTarget {
+    File(PathBuf),
+    // Kernel messages from journald, as with `--journal`.
+    Journal,
+    // A directory of logs forwarded from other hosts, followed like
+    // `--watch-dir`.
+    Dir(PathBuf),
+}
+
+impl SourceTarget {
+    // "journal" (or "journalctl") reads journald; anything else is a path.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        match input {
+            "" => Err("Type a log path, a directory or 'journal'".to_string()),
+            "journal" | "journalctl" => Ok(Self::Journal),
+            _ if Path::new(input).is_dir() => Ok(Self::Dir(PathBuf::from(input))),
+            _ => Ok(Self::File(PathBuf::from(input))),
+        }
+    }
+}
+
+// The `O` prompt: the source being typed, and whether the entries loaded so
+// far stay merged with it.
+pub struct SourcePrompt {
+    pub input: String,
+    pub keep: bool,
+}
+
+impl SourcePrompt {
+    pub fn new(current: String) -> Self {
+        Self {
+            input: current,
+            keep: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_journal_directories_and_files() {
+        assert_eq!(SourceTarget::parse(" journal "), Ok(SourceTarget::Journal));
+        let dir = std::env::temp_dir();
+        assert_eq!(
+            SourceTarget::parse(&dir.display().to_string()),
+            Ok(SourceTarget::Dir(dir.clone()))
+        );
+        assert_eq!(
+            SourceTarget::parse("/var/log/kern.log"),
+            Ok(SourceTarget::File(PathBuf::from("/var/log/kern.log")))
+        );
+        assert!(SourceTarget::parse("  ").is_err());
+    }
+}
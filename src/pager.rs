@@ -0,0 +1,102 @@
+use std::cmp::min;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use crate::redact::redact_raw_line;
+use crate::report::render_report;
+use crate::{App, source_prompt_text, visible_window_offset};
+
+// less-like frontend for `--pager`: one raw line per entry, colored by action,
+// with a single prompt line at the bottom. Works down to a few columns, so it
+// suits serial consoles. Keys are the same as in the full UI.
+pub fn render(frame: &mut Frame, app: &mut App) {
+    app.status.expire();
+    let area = frame.area();
+    app.frame_area = area;
+    if area.height == 0 || area.width == 0 {
+        return;
+    }
+
+    let (filtered_indices, pinned_extra) = app.view_indices_with_pin();
+    app.sync_selection_with_len(filtered_indices.len());
+    let visible_rows = usize::from(area.height - 1);
+    app.retarget_selection_lock(&filtered_indices);
+    let offset = visible_window_offset(
+        app.table_state.offset(),
+        app.table_state.selected(),
+        visible_rows,
+        filtered_indices.len(),
+    );
+    *app.table_state.offset_mut() = offset;
+
+    let window = &filtered_indices[offset..min(offset + visible_rows, filtered_indices.len())];
+    let lines: Vec<Line> = window
+        .iter()
+        .enumerate()
+        .map(|(pos, &idx)| {
+            let entry = &app.entries[idx];
+            let raw = if app.redact {
+                redact_raw_line(&entry.raw)
+            } else {
+                entry.raw.clone()
+            };
+            let color = match entry.action.as_str() {
+                _ if pinned_extra == Some(idx) => Color::DarkGray,
+                "ALLOW" => Color::Green,
+                "BLOCK" | "REJECT" => Color::Red,
+                _ => Color::Yellow,
+            };
+            let style = if offset + pos == app.selected {
+                Style::default()
+                    .fg(color)
+                    .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            Line::from(Span::styled(raw, style))
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).scroll((0, app.log_entry_scroll)),
+        Rect {
+            height: area.height - 1,
+            ..area
+        },
+    );
+
+    let match_count = filtered_indices.len() - usize::from(pinned_extra.is_some());
+    let prompt = if let Some(field) = app.input_mode {
+        format!("{}: {}_", field.label(), app.input_buffer)
+    } else if let Some(prompt) = app.export_prompt.as_ref() {
+        format!("{} to: {}_", prompt.format.label(), prompt.path)
+    } else if let Some(prompt) = app.source_prompt.as_ref() {
+        source_prompt_text(prompt)
+    } else if let Some(prompt) = app.block_prompt_text() {
+        prompt
+    } else if !app.status.is_empty() {
+        app.status.text().to_string()
+    } else {
+        format!(
+            ":{}/{} ({} filters) q quit, j/k move, F1..F9 filter",
+            match_count,
+            app.entries.len(),
+            app.filters.active_count()
+        )
+    };
+    frame.render_widget(
+        Paragraph::new(prompt).style(Style::default().add_modifier(Modifier::REVERSED)),
+        Rect {
+            y: area.y + area.height - 1,
+            height: 1,
+            ..area
+        },
+    );
+
+    if let Some(report) = app.report.as_ref() {
+        render_report(frame, area, report);
+    }
+}
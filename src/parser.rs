@@ -1,5 +1,85 @@
+mod pfirewall;
+mod pfsense;
+
+use std::str::FromStr;
+
 use crate::LogEntry;
-use crate::services::service_from_port;
+use crate::services::{icmp_service, service_from_port};
+use crate::timefmt::custom_seconds;
+use crate::timerange::local_offset;
+
+pub use pfirewall::parse_pfirewall_line;
+pub use pfsense::parse_filterlog_line;
+
+// Which log dialect to parse. `Auto` tries every known format per line, so
+// mixed files (e.g. a syslog sink fed by several hosts) still load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Auto,
+    Ufw,
+    Pfsense,
+    Windows,
+}
+
+impl LogFormat {
+    pub const NAMES: &'static str = "auto, ufw, pfsense, windows";
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "ufw" => Some(Self::Ufw),
+            "pfsense" | "opnsense" | "filterlog" => Some(Self::Pfsense),
+            "windows" | "pfirewall" => Some(Self::Windows),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_line(line: &str, format: LogFormat) -> Option<LogEntry> {
+    match format {
+        LogFormat::Ufw => parse_ufw_line(line),
+        LogFormat::Pfsense => parse_filterlog_line(line),
+        LogFormat::Windows => parse_pfirewall_line(line),
+        LogFormat::Auto => parse_ufw_line(line)
+            .or_else(|| parse_filterlog_line(line))
+            .or_else(|| parse_pfirewall_line(line)),
+    }
+}
+
+// TCP flag names as the kernel logs them, in display order.
+pub const TCP_FLAGS: [&str; 8] = ["SYN", "ACK", "FIN", "RST", "PSH", "URG", "CWR", "ECE"];
+
+// The TCP flags set on a line, one bit per `TCP_FLAGS` name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcpFlags(u8);
+
+impl TcpFlags {
+    fn from_line(line: &str) -> Self {
+        Self(
+            line.split_whitespace()
+                .filter_map(|token| TCP_FLAGS.iter().position(|flag| *flag == token))
+                .fold(0, |bits, pos| bits | 1 << pos),
+        )
+    }
+
+    // Case-insensitive; unknown names are never set.
+    pub fn contains(self, name: &str) -> bool {
+        TCP_FLAGS
+            .iter()
+            .position(|flag| flag.eq_ignore_ascii_case(name))
+            .is_some_and(|pos| self.0 & 1 << pos != 0)
+    }
+
+    pub fn names(self) -> Vec<&'static str> {
+        TCP_FLAGS
+            .iter()
+            .enumerate()
+            .filter(|(pos, _)| self.0 & 1 << pos != 0)
+            .map(|(_, flag)| *flag)
+            .collect()
+    }
+}
 
 // Parses one kernel log line containing a UFW marker into a structured entry.
 pub fn parse_ufw_line(line: &str) -> Option<LogEntry> {
@@ -17,13 +97,20 @@ pub fn parse_ufw_line(line: &str) -> Option<LogEntry> {
     let proto = parse_field(line, "PROTO").map(|p| p.to_ascii_uppercase());
     let src_port = parse_field(line, "SPT").and_then(|v| v.parse::<u16>().ok());
     let dst_port = parse_field(line, "DPT").and_then(|v| v.parse::<u16>().ok());
+    let tos = parse_field(line, "TOS")
+        .and_then(|v| u8::from_str_radix(v.trim_start_matches("0x"), 16).ok());
 
-    let service = dst_port
-        .and_then(service_from_port)
-        .or_else(|| src_port.and_then(service_from_port))
-        .map(|name| name.to_string());
+    // ICMP carries a message type and code instead of ports.
+    let icmp_type = parse_number(line, "TYPE");
+    let icmp_code = parse_number(line, "CODE");
+    let service = service_for_ports(src_port, dst_port).or_else(|| {
+        icmp_service(proto.as_deref()?, icmp_type?, icmp_code.unwrap_or(0)).map(str::to_string)
+    });
 
     Some(LogEntry {
+        id: 0,
+        source: 0,
+        raw_offset: None,
         timestamp,
         action,
         in_iface,
@@ -34,10 +121,142 @@ pub fn parse_ufw_line(line: &str) -> Option<LogEntry> {
         dst_port,
         proto,
         service,
+        country: None,
+        length: parse_number(line, "LEN"),
+        ttl: parse_number(line, "TTL").or_else(|| parse_number(line, "HOPLIMIT")),
+        tos,
+        mac: parse_field(line, "MAC").filter(|mac| !mac.is_empty()),
+        window: parse_number(line, "WINDOW"),
+        tcp_flags: TcpFlags::from_line(line),
+        icmp_type,
+        icmp_code,
         raw: line.to_string(),
     })
 }
 
+fn parse_number<T: FromStr>(line: &str, name: &str) -> Option<T> {
+    parse_field(line, name).and_then(|value| value.parse().ok())
+}
+
+// Service name for a flow, preferring the destination port.
+fn service_for_ports(src_port: Option<u16>, dst_port: Option<u16>) -> Option<String> {
+    dst_port
+        .and_then(service_from_port)
+        .or_else(|| src_port.and_then(service_from_port))
+        .map(|name| name.to_string())
+}
+
+// Sortable seconds value for merging sources chronologically. ISO stamps are
+// normalized to UTC; syslog stamps carry no year, so they are placed in a fixed
+// year and only order correctly against other syslog stamps.
+pub fn timestamp_sort_key(timestamp: &str) -> Option<i64> {
+    timestamp_seconds(timestamp, 2000)
+}
+
+// Seconds since the Unix epoch. Syslog stamps have no year or zone and are
+// read as UTC in `syslog_year`, as are custom formats without them.
+pub fn timestamp_seconds(timestamp: &str, syslog_year: i64) -> Option<i64> {
+    timestamp_seconds_at(timestamp, syslog_year, 0)
+}
+
+// `timestamp_seconds` with stamps that carry no zone read as wall-clock time
+// `offset` seconds east of UTC.
+fn timestamp_seconds_at(timestamp: &str, syslog_year: i64, offset: i64) -> Option<i64> {
+    if let Some(secs) = custom_seconds(timestamp, syslog_year, offset) {
+        return Some(secs);
+    }
+    let token = timestamp.split_whitespace().next()?;
+    if let Some((date, time)) = token.split_once('T') {
+        let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+        let (Some(Some(year)), Some(Some(month)), Some(Some(day))) =
+            (date_parts.next(), date_parts.next(), date_parts.next())
+        else {
+            return None;
+        };
+        let clock_end = time.find(['.', '+', '-', 'Z']).unwrap_or(time.len());
+        let seconds = clock_seconds(&time[..clock_end])?;
+        let zone = &time[clock_end..];
+        let zone = zone.trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+        let offset = match zone.as_bytes().first() {
+            Some(sign @ (b'+' | b'-')) => {
+                let (hours, minutes) = zone[1..].split_once(':').unwrap_or((&zone[1..], "0"));
+                let value = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+                if *sign == b'+' { value } else { -value }
+            }
+            Some(b'Z') => 0,
+            _ => offset,
+        };
+        return Some(days_from_civil(year, month, day) * 86_400 + seconds - offset);
+    }
+
+    let mut parts = timestamp.split_whitespace();
+    let month = month_number(parts.next()?)?;
+    let day = parts.next()?.parse::<i64>().ok()?;
+    let seconds = clock_seconds(parts.next()?)?;
+    Some(days_from_civil(syslog_year, month, day) * 86_400 + seconds - offset)
+}
+
+fn clock_seconds(clock: &str) -> Option<i64> {
+    let mut parts = clock.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (Some(Some(h)), Some(Some(m)), Some(Some(s))) = (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some(h * 3600 + m * 60 + s)
+}
+
+pub fn month_number(name: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    MONTHS
+        .iter()
+        .position(|month| *month == name)
+        .map(|idx| idx as i64 + 1)
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date.
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// Inverse of `days_from_civil`.
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// Seconds since the Unix epoch for an entry, guessing the year of syslog
+// stamps: the current one, or the previous one when that would be in the future.
+// Syslog stamps are local time, so they are moved to UTC like `now`.
+pub fn entry_epoch_seconds(timestamp: &str, now: i64) -> Option<i64> {
+    entry_seconds_at(timestamp, now, local_offset())
+}
+
+// `entry_epoch_seconds` for a local clock `offset` seconds east of UTC.
+pub fn entry_seconds_at(timestamp: &str, now: i64, offset: i64) -> Option<i64> {
+    let (year, _, _) = civil_from_days((now + offset).div_euclid(86_400));
+    let secs = timestamp_seconds_at(timestamp, year, offset)?;
+    if secs > now + 86_400 {
+        return timestamp_seconds_at(timestamp, year - 1, offset);
+    }
+    Some(secs)
+}
+
 fn parse_action(line: &str) -> Option<String> {
     let marker = "[UFW ";
     let start = line.find(marker)? + marker.len();
@@ -46,7 +265,7 @@ fn parse_action(line: &str) -> Option<String> {
     Some(rest[..end].trim().to_string())
 }
 
-fn parse_field(line: &str, name: &str) -> Option<String> {
+pub fn parse_field(line: &str, name: &str) -> Option<String> {
     for token in line.split_whitespace() {
         if let Some((key, value)) = token.split_once('=')
             && key == name
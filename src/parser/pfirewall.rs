@@ -0,0 +1,92 @@
+use super::{TcpFlags, service_for_ports};
+use crate::LogEntry;
+
+// pfirewall.log records no interface; a placeholder keeps the direction and
+// interface filters meaningful.
+const HOST_IFACE: &str = "host";
+
+// Parses a Windows Firewall `pfirewall.log` line in the default W3C field
+// order: date time action protocol src-ip dst-ip src-port dst-port size
+// tcpflags tcpsyn tcpack tcpwin icmptype icmpcode info path [pid]. Header
+// lines starting with '#' are ignored.
+pub fn parse_pfirewall_line(line: &str) -> Option<LogEntry> {
+    if line.starts_with('#') {
+        return None;
+    }
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 17 {
+        return None;
+    }
+    let [date, time, action, proto, src, dst, spt, dpt] = fields[..8] else {
+        return None;
+    };
+    if date.len() != 10 || date.as_bytes()[4] != b'-' || !time.contains(':') {
+        return None;
+    }
+    let action = match action {
+        "ALLOW" => "ALLOW",
+        "DROP" => "BLOCK",
+        _ => return None,
+    };
+    let host = || Some(HOST_IFACE.to_string());
+    let (in_iface, out_iface) = match fields[16] {
+        "RECEIVE" => (host(), Some(String::new())),
+        "SEND" => (Some(String::new()), host()),
+        "FORWARD" => (host(), host()),
+        _ => (None, None),
+    };
+    let value = |v: &str| (v != "-").then(|| v.to_string());
+    let port = |v: &str| v.parse::<u16>().ok();
+    let src_port = port(spt);
+    let dst_port = port(dpt);
+
+    Some(LogEntry {
+        id: 0,
+        source: 0,
+        raw_offset: None,
+        timestamp: format!("{}T{}", date, time),
+        action: action.to_string(),
+        in_iface,
+        out_iface,
+        src_ip: value(src),
+        dst_ip: value(dst),
+        src_port,
+        dst_port,
+        proto: value(proto).map(|p| p.to_ascii_uppercase()),
+        service: service_for_ports(src_port, dst_port),
+        country: None,
+        length: None,
+        ttl: None,
+        tos: None,
+        mac: None,
+        window: None,
+        tcp_flags: TcpFlags::default(),
+        icmp_type: None,
+        icmp_code: None,
+        raw: line.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_drop_and_skips_headers() {
+        assert!(parse_pfirewall_line("#Fields: date time action protocol src-ip dst-ip").is_none());
+        let line = "2026-02-11 20:21:00 DROP TCP 203.0.113.5 192.168.1.10 51515 445 52 S 123456 0 8192 - - - RECEIVE 4";
+        let entry = parse_pfirewall_line(line).expect("line should parse");
+        assert_eq!(entry.timestamp, "2026-02-11T20:21:00");
+        assert_eq!(entry.action, "BLOCK");
+        assert_eq!(entry.direction(), "IN");
+        assert_eq!(entry.proto.as_deref(), Some("TCP"));
+        assert_eq!(entry.src_ip.as_deref(), Some("203.0.113.5"));
+        assert_eq!(entry.dst_port, Some(445));
+        assert_eq!(entry.service.as_deref(), Some("microsoft-ds"));
+
+        let icmp = "2026-02-11 20:21:05 ALLOW ICMP 192.168.1.10 8.8.8.8 - - 60 - - - - 8 0 - SEND";
+        let entry = parse_pfirewall_line(icmp).expect("line should parse");
+        assert_eq!(entry.direction(), "OUT");
+        assert_eq!(entry.src_port, None);
+    }
+}
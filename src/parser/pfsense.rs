@@ -0,0 +1,118 @@
+use super::{TcpFlags, service_for_ports};
+use crate::LogEntry;
+
+// Parses a pfSense/OPNsense `filterlog` line. The CSV payload follows the
+// syslog header; its layout after the common prefix depends on the IP version.
+pub fn parse_filterlog_line(line: &str) -> Option<LogEntry> {
+    let tag = line.find(" filterlog")?;
+    let timestamp = strip_rfc5424_priority(line[..tag].trim()).to_string();
+    let csv = line[tag..].split_whitespace().last()?;
+    let fields: Vec<&str> = csv.split(',').collect();
+
+    let interface = fields.get(4).filter(|v| !v.is_empty())?.to_string();
+    let action = match *fields.get(6)? {
+        "pass" => "ALLOW",
+        "block" => "BLOCK",
+        "reject" => "REJECT",
+        _ => return None,
+    };
+    let (in_iface, out_iface) = match *fields.get(7)? {
+        "in" => (Some(interface), Some(String::new())),
+        "out" => (Some(String::new()), Some(interface)),
+        _ => return None,
+    };
+    // Index of the protocol name and of the source address for each version.
+    let (proto_idx, src_idx) = match *fields.get(8)? {
+        "4" => (16, 18),
+        "6" => (12, 15),
+        _ => return None,
+    };
+    let proto = fields
+        .get(proto_idx)
+        .map(|p| p.to_ascii_uppercase())
+        .filter(|p| !p.is_empty());
+    let field = |idx: usize| {
+        fields
+            .get(idx)
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string())
+    };
+    let src_ip = field(src_idx);
+    let dst_ip = field(src_idx + 1);
+    let (src_port, dst_port) = match proto.as_deref() {
+        Some("TCP" | "UDP") => (
+            fields.get(src_idx + 2).and_then(|v| v.parse::<u16>().ok()),
+            fields.get(src_idx + 3).and_then(|v| v.parse::<u16>().ok()),
+        ),
+        _ => (None, None),
+    };
+
+    Some(LogEntry {
+        id: 0,
+        source: 0,
+        raw_offset: None,
+        timestamp,
+        action: action.to_string(),
+        in_iface,
+        out_iface,
+        src_ip,
+        dst_ip,
+        src_port,
+        dst_port,
+        proto,
+        service: service_for_ports(src_port, dst_port),
+        country: None,
+        length: None,
+        ttl: None,
+        tos: None,
+        mac: None,
+        window: None,
+        tcp_flags: TcpFlags::default(),
+        icmp_type: None,
+        icmp_code: None,
+        raw: line.to_string(),
+    })
+}
+
+// OPNsense can emit RFC 5424 headers ("<134>1 2024-..."); drop the priority
+// and version so the timestamp formats like any other.
+fn strip_rfc5424_priority(header: &str) -> &str {
+    match header
+        .strip_prefix('<')
+        .and_then(|rest| rest.split_once('>'))
+    {
+        Some((_, rest)) => rest.strip_prefix("1 ").unwrap_or(rest),
+        None => header,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ipv4_tcp_block() {
+        let line = "Feb 11 20:21:00 fw filterlog[12345]: 5,,,1000000103,igb0,match,block,in,4,0x0,,64,12345,0,DF,6,tcp,60,203.0.113.5,192.168.1.10,51515,22,0,S,123456,,64240,,mss;sackOK;TS;nop;wscale";
+        let entry = parse_filterlog_line(line).expect("line should parse");
+        assert_eq!(entry.timestamp, "Feb 11 20:21:00 fw");
+        assert_eq!(entry.action, "BLOCK");
+        assert_eq!(entry.in_iface.as_deref(), Some("igb0"));
+        assert_eq!(entry.proto.as_deref(), Some("TCP"));
+        assert_eq!(entry.src_ip.as_deref(), Some("203.0.113.5"));
+        assert_eq!(entry.dst_ip.as_deref(), Some("192.168.1.10"));
+        assert_eq!(entry.src_port, Some(51515));
+        assert_eq!(entry.dst_port, Some(22));
+    }
+
+    #[test]
+    fn parses_ipv6_udp_pass_with_rfc5424_header() {
+        let line = "<134>1 2026-02-11T20:21:00+00:00 opnsense filterlog 5812 - [meta sequenceId=\"7\"] 77,,,0,vtnet1,match,pass,out,6,0x00,0x00000,64,udp,17,76,2001:db8::1,2001:db8::53,40000,53,76";
+        let entry = parse_filterlog_line(line).expect("line should parse");
+        assert_eq!(entry.timestamp, "2026-02-11T20:21:00+00:00 opnsense");
+        assert_eq!(entry.action, "ALLOW");
+        assert_eq!(entry.out_iface.as_deref(), Some("vtnet1"));
+        assert_eq!(entry.proto.as_deref(), Some("UDP"));
+        assert_eq!(entry.dst_ip.as_deref(), Some("2001:db8::53"));
+        assert_eq!(entry.dst_port, Some(53));
+    }
+}
@@ -0,0 +1,76 @@
+// The F2 port expression: comma-separated ports, ranges and comparisons,
+// e.g. "22,80,443", "1000-2000" or ">1024", kept as inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortSet {
+    ranges: Vec<(u16, u16)>,
+}
+
+impl PortSet {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let ranges = text
+            .split(',')
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(parse_term)
+            .collect::<Result<Vec<_>, _>>()?;
+        if ranges.is_empty() {
+            return Err("Port filter is empty".to_string());
+        }
+        Ok(Self { ranges })
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(low, high)| (low..=high).contains(&port))
+    }
+
+    pub fn ranges(&self) -> &[(u16, u16)] {
+        &self.ranges
+    }
+}
+
+// `N`, `A-B`, `>N`, `>=N`, `<N` or `<=N`.
+fn parse_term(term: &str) -> Result<(u16, u16), String> {
+    let port = |text: &str| {
+        text.trim()
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid port '{}' in port filter", text.trim()))
+    };
+    let empty = || format!("Port filter '{}' matches no port", term);
+    if let Some(rest) = term.strip_prefix(">=") {
+        Ok((port(rest)?, u16::MAX))
+    } else if let Some(rest) = term.strip_prefix("<=") {
+        Ok((0, port(rest)?))
+    } else if let Some(rest) = term.strip_prefix('>') {
+        Ok((port(rest)?.checked_add(1).ok_or_else(empty)?, u16::MAX))
+    } else if let Some(rest) = term.strip_prefix('<') {
+        Ok((0, port(rest)?.checked_sub(1).ok_or_else(empty)?))
+    } else if let Some((low, high)) = term.split_once('-') {
+        let (low, high) = (port(low)?, port(high)?);
+        if low > high {
+            return Err(format!("Port range '{}' runs backwards", term));
+        }
+        Ok((low, high))
+    } else {
+        port(term).map(|port| (port, port))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lists_ranges_and_comparisons() {
+        let set = PortSet::parse("22, 1000-2000,>=60000").unwrap();
+        assert_eq!(set.ranges(), [(22, 22), (1000, 2000), (60000, 65535)]);
+        assert!(set.contains(1500) && set.contains(65535) && !set.contains(80));
+
+        assert_eq!(PortSet::parse("> 1024").unwrap().ranges(), [(1025, 65535)]);
+        assert_eq!(PortSet::parse("<1024").unwrap().ranges(), [(0, 1023)]);
+        for bad in ["", "ssh", "2000-1000", "<0", ">65535", "70000"] {
+            assert!(PortSet::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
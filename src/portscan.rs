@@ -0,0 +1,229 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+
+use crate::parser::{civil_from_days, entry_epoch_seconds};
+use crate::timerange::local_offset;
+use crate::{LogEntry, display_ip};
+
+// Ports listed per alert before the rest are elided.
+const SHOWN_PORTS: usize = 8;
+
+// A source that hits this many distinct destination ports within
+// SCAN_WINDOW seconds is scanning.
+const SCAN_PORTS: usize = 15;
+const SCAN_WINDOW: i64 = 60;
+
+// One scan: every port the source hit from the window that tripped the
+// threshold until it went quiet for a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanAlert {
+    pub source: String,
+    pub ports: BTreeSet<u16>,
+    pub first: i64,
+    pub last: i64,
+}
+
+// Port scans found so far. Entries are fed oldest first as they arrive, so
+// a live log only costs the new lines; a full reload starts over.
+#[derive(Default)]
+pub struct ScanDetector {
+    // Recent (time, port) hits per source, no older than SCAN_WINDOW.
+    hits: HashMap<String, VecDeque<(i64, u16)>>,
+    // Index into `alerts` of each source's latest scan.
+    open: HashMap<String, usize>,
+    pub alerts: Vec<ScanAlert>,
+}
+
+impl ScanDetector {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn feed<'a>(&mut self, entries: impl IntoIterator<Item = &'a LogEntry>, now: i64) {
+        let mut latest = None;
+        for entry in entries {
+            let (Some(source), Some(port), Some(secs)) = (
+                entry.src_ip.as_deref(),
+                entry.dst_port,
+                entry_epoch_seconds(&entry.timestamp, now),
+            ) else {
+                continue;
+            };
+            latest = latest.max(Some(secs));
+            self.hit(source, port, secs);
+        }
+        // Sources quiet for a whole window cannot continue a scan.
+        if let Some(latest) = latest {
+            self.hits.retain(|_, hits| {
+                hits.back()
+                    .is_some_and(|(secs, _)| *secs >= latest - SCAN_WINDOW)
+            });
+        }
+    }
+
+    fn hit(&mut self, source: &str, port: u16, secs: i64) {
+        if let Some(&idx) = self.open.get(source) {
+            let alert = &mut self.alerts[idx];
+            if secs >= alert.first && secs <= alert.last + SCAN_WINDOW {
+                alert.ports.insert(port);
+                alert.last = alert.last.max(secs);
+                return;
+            }
+        }
+        let hits = self.hits.entry(source.to_string()).or_default();
+        hits.push_back((secs, port));
+        while hits
+            .front()
+            .is_some_and(|(first, _)| *first < secs - SCAN_WINDOW)
+        {
+            hits.pop_front();
+        }
+        let ports: BTreeSet<u16> = hits.iter().map(|(_, port)| *port).collect();
+        if ports.len() < SCAN_PORTS {
+            return;
+        }
+        let first = hits.iter().map(|(secs, _)| *secs).min().unwrap_or(secs);
+        hits.clear();
+        self.open.insert(source.to_string(), self.alerts.len());
+        self.alerts.push(ScanAlert {
+            source: source.to_string(),
+            ports,
+            first,
+            last: secs,
+        });
+    }
+
+    // Alerts for the panel, the most recent scan first.
+    pub fn newest_first(&self) -> Vec<&ScanAlert> {
+        let mut alerts: Vec<&ScanAlert> = self.alerts.iter().collect();
+        alerts.sort_by_key(|alert| std::cmp::Reverse(alert.last));
+        alerts
+    }
+}
+
+// Rows of the Alerts panel: when the scan started, the source, how many
+// ports it hit over what span, and the first few ports. Sources are masked
+// with `redact`.
+pub fn alert_lines(alerts: &[&ScanAlert], redact: bool) -> Vec<Line<'static>> {
+    if alerts.is_empty() {
+        return vec![Line::from(Span::styled(
+            format!(
+                "No port scans yet ({}+ ports from one source within {}s)",
+                SCAN_PORTS, SCAN_WINDOW
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+    alerts
+        .iter()
+        .map(|alert| {
+            let mut ports: Vec<String> = alert
+                .ports
+                .iter()
+                .take(SHOWN_PORTS)
+                .map(u16::to_string)
+                .collect();
+            if alert.ports.len() > SHOWN_PORTS {
+                ports.push("…".to_string());
+            }
+            Line::from(vec![
+                Span::raw(format!("{}  ", clock(alert.first))),
+                Span::styled(
+                    format!("{:<15}", display_ip(Some(&alert.source), redact)),
+                    Style::default().fg(Color::LightCyan),
+                ),
+                Span::styled(
+                    format!("  {:>5} ports", alert.ports.len()),
+                    Style::default().fg(Color::LightRed),
+                ),
+                Span::raw(format!(
+                    " over {:<7}  {}",
+                    span(alert.last - alert.first),
+                    ports.join(",")
+                )),
+            ])
+        })
+        .collect()
+}
+
+// "2026-02-11 20:00:05" on the log's local clock.
+fn clock(secs: i64) -> String {
+    let secs = secs + local_offset();
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let time = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+// "45s", "3m10s", "2h05m".
+fn span(secs: i64) -> String {
+    match secs {
+        ..60 => format!("{}s", secs),
+        60..3_600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3_600, secs % 3_600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    fn entry(second: u32, src: &str, port: u16) -> LogEntry {
+        parse_ufw_line(&format!(
+            "2026-02-11T20:{:02}:{:02}+00:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={} DST=10.0.0.1 PROTO=TCP SPT=40000 DPT={}",
+            second / 60,
+            second % 60,
+            src,
+            port
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn flags_many_ports_in_a_short_window_and_extends_the_scan() {
+        let now = 1_770_840_000;
+        let mut detector = ScanDetector::default();
+        // A web client retrying the same ports is not a scan.
+        let browsing: Vec<LogEntry> = (0..40).map(|i| entry(i, "5.5.5.5", 443)).collect();
+        // Slow enough to spread fewer than SCAN_PORTS over any window.
+        let slow: Vec<LogEntry> = (0..20)
+            .map(|i| entry(i * 10, "6.6.6.6", 1000 + i as u16))
+            .collect();
+        let scan: Vec<LogEntry> = (0..20)
+            .map(|i| entry(i, "7.7.7.7", 20 + i as u16))
+            .collect();
+        detector.feed(browsing.iter().chain(&slow).chain(&scan), now);
+        assert_eq!(detector.alerts.len(), 1);
+        let alert = &detector.alerts[0];
+        assert_eq!(alert.source, "7.7.7.7");
+        assert_eq!(alert.ports.len(), 20);
+        assert_eq!(alert.last - alert.first, 19);
+
+        // New lines continue the open scan instead of raising another.
+        detector.feed(&[entry(50, "7.7.7.7", 8080)], now);
+        assert_eq!(detector.alerts.len(), 1);
+        assert_eq!(detector.alerts[0].ports.len(), 21);
+        assert_eq!(detector.alerts[0].last - detector.alerts[0].first, 50);
+
+        let text = alert_lines(&detector.newest_first(), false)[0].to_string();
+        assert!(text.starts_with("2026-02-11 20:00:00  7.7.7.7"), "{}", text);
+        let masked = alert_lines(&detector.newest_first(), true)[0].to_string();
+        assert!(
+            masked.starts_with("2026-02-11 20:00:00  7.7.7.x"),
+            "{}",
+            masked
+        );
+        assert!(text.contains("21 ports over 50s"), "{}", text);
+        assert!(text.ends_with("20,21,22,23,24,25,26,27,…"), "{}", text);
+    }
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ratatui::text::Line;
+
+use crate::cache::state_dir;
+use crate::ini;
+use crate::report::{Report, heading};
+
+const FILE_NAME: &str = "presets.ini";
+// Digits 1-9 pick a preset in the popup.
+pub const MAX_PRESETS: usize = 9;
+
+// A named set of view settings as `key=value` pairs (filters by field label,
+// plus iface, flow, dir and the row toggles). The app decides what the keys
+// mean; unknown keys are kept but ignored, so older builds can read newer files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preset {
+    pub name: String,
+    pub settings: Vec<(String, String)>,
+}
+
+impl Preset {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// State of the `'` popup: `1`-`9` apply, `s` names the current view, `d`
+// then a digit deletes.
+pub struct PresetPicker {
+    pub presets: Vec<Preset>,
+    pub naming: Option<String>,
+    pub deleting: bool,
+}
+
+impl PresetPicker {
+    pub fn new(presets: Vec<Preset>) -> Self {
+        Self {
+            presets,
+            naming: None,
+            deleting: false,
+        }
+    }
+
+    pub fn report(&self, path: Option<&Path>) -> Report {
+        let mut lines = vec![heading(format!("{:<3} {:<20} {}", "#", "NAME", "SETTINGS"))];
+        if self.presets.is_empty() {
+            lines.push(Line::from("    No presets saved yet."));
+        }
+        for (pos, preset) in self.presets.iter().enumerate() {
+            let settings: Vec<String> = preset
+                .settings
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            lines.push(Line::from(format!(
+                "{:<3} {:<20} {}",
+                pos + 1,
+                preset.name,
+                settings.join(" ")
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(match (&self.naming, self.deleting) {
+            (Some(name), _) => format!("Save current view as: {}_ (Enter save)", name),
+            (None, true) => "Delete which preset? Press its number".to_string(),
+            (None, false) => "1-9 apply · s save current view · d<n> delete".to_string(),
+        }));
+        if let Some(path) = path {
+            lines.push(Line::from(format!("File: {}", path.display())));
+        }
+        Report::new("Filter presets", lines)
+    }
+}
+
+pub fn presets_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+// A missing file is an empty list.
+pub fn load(path: &Path) -> io::Result<Vec<Preset>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(parse(&text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn save(path: &Path, presets: &[Preset]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serialize(presets))
+}
+
+// Adds `preset`, replacing one with the same name in place.
+pub fn upsert(presets: &mut Vec<Preset>, preset: Preset) {
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+}
+
+// One section per preset.
+fn parse(text: &str) -> Vec<Preset> {
+    ini::parse(text)
+        .into_iter()
+        .map(|(name, settings)| Preset { name, settings })
+        .collect()
+}
+
+fn serialize(presets: &[Preset]) -> String {
+    let sections: Vec<ini::Section> = presets
+        .iter()
+        .map(|preset| (preset.name.clone(), preset.settings.clone()))
+        .collect();
+    ini::serialize("Filter presets saved with ' in ufw-log-viewer", &sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_replaces_by_name() {
+        let preset = |name: &str, port: &str| Preset {
+            name: name.to_string(),
+            settings: vec![
+                ("port".to_string(), port.to_string()),
+                ("iface".to_string(), "eth0".to_string()),
+            ],
+        };
+        let mut presets = vec![preset("ssh", "22"), preset("web", "443")];
+        assert_eq!(parse(&serialize(&presets)), presets);
+
+        upsert(&mut presets, preset("ssh", "2222"));
+        assert_eq!(presets.len(), 2);
+        assert_eq!(presets[0].get("port"), Some("2222"));
+
+        let parsed = parse("stray=1\n[a]\n# note\nip = !10.0.0.5\n");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].get("ip"), Some("!10.0.0.5"));
+    }
+}
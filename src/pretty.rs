@@ -0,0 +1,187 @@
+use crate::LogEntry;
+use crate::parser::parse_field;
+use crate::redact::REDACTED_MAC;
+
+// A layer heading and its fields; `None` values are skipped.
+type FieldGroup<'a> = (&'a str, Vec<(&'a str, Option<String>)>);
+
+// Human-readable multi-line form of an entry: fields grouped by layer with
+// values aligned in one column. Fields missing from the line are left out.
+pub fn pretty_entry(entry: &LogEntry) -> String {
+    let groups = field_groups(entry);
+    let key_width = groups
+        .iter()
+        .flat_map(|(_, fields)| fields.iter().map(|(key, _)| key.len()))
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for (title, fields) in groups {
+        let present: Vec<(&str, String)> = fields
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect();
+        if present.is_empty() {
+            continue;
+        }
+        out.push_str(title);
+        out.push('\n');
+        for (key, value) in present {
+            out.push_str(&format!(
+                "  {:<width$}  {}\n",
+                key,
+                value,
+                width = key_width
+            ));
+        }
+    }
+    out
+}
+
+// Every field present on the entry, in `pretty_entry` order.
+pub fn present_fields(entry: &LogEntry) -> Vec<(&'static str, String)> {
+    field_groups(entry)
+        .into_iter()
+        .flat_map(|(_, fields)| fields)
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect()
+}
+
+// Lays `fields` out in columns of `rows` lines, filled top to bottom, each
+// as wide as its own longest key and value.
+pub fn field_columns(fields: &[(&str, String)], rows: usize) -> Vec<String> {
+    let mut lines = vec![String::new(); rows.min(fields.len())];
+    for (col, column) in fields.chunks(rows.max(1)).enumerate() {
+        let key_width = column.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+        let cells: Vec<String> = column
+            .iter()
+            .map(|(key, value)| format!("{:<key_width$}  {}", key, value))
+            .collect();
+        let width = cells
+            .iter()
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (line, cell) in lines.iter_mut().zip(cells) {
+            if col > 0 {
+                line.push_str(" │ ");
+            }
+            line.push_str(&format!("{:<width$}", cell));
+        }
+    }
+    lines
+        .into_iter()
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+fn field_groups(entry: &LogEntry) -> [FieldGroup<'static>; 4] {
+    let raw = |key: &str| parse_field(&entry.raw, key).filter(|value| !value.is_empty());
+    let port = |value: Option<u16>| value.map(|p| p.to_string());
+    let tokens: Vec<&str> = entry.raw.split_whitespace().collect();
+    let flags = entry.tcp_flags.names();
+
+    [
+        (
+            "Log",
+            vec![
+                ("time", Some(entry.timestamp.clone())),
+                ("action", Some(entry.action.clone())),
+                ("direction", Some(entry.direction().to_string())),
+            ],
+        ),
+        (
+            "Link",
+            vec![
+                ("in", entry.in_iface.clone().filter(|v| !v.is_empty())),
+                ("out", entry.out_iface.clone().filter(|v| !v.is_empty())),
+                ("mac", entry.mac.clone()),
+            ],
+        ),
+        (
+            "Network",
+            vec![
+                ("src", entry.src_ip.clone()),
+                ("country", entry.country.clone()),
+                ("dst", entry.dst_ip.clone()),
+                ("len", entry.length.map(|len| len.to_string())),
+                ("tos", entry.tos.map(format_tos)),
+                ("ttl", entry.ttl.map(|ttl| ttl.to_string())),
+                ("id", raw("ID")),
+                ("df", tokens.contains(&"DF").then(|| "yes".to_string())),
+            ],
+        ),
+        (
+            "Transport",
+            vec![
+                ("proto", entry.proto.clone()),
+                ("spt", port(entry.src_port)),
+                ("dpt", port(entry.dst_port)),
+                ("service", entry.service.clone()),
+                ("window", entry.window.map(|window| window.to_string())),
+                ("flags", (!flags.is_empty()).then(|| flags.join(" "))),
+                ("type", entry.icmp_type.map(|value| value.to_string())),
+                ("code", entry.icmp_code.map(|value| value.to_string())),
+            ],
+        ),
+    ]
+}
+
+// "len 60 · ttl 52 · tos 0x00 · window 29200 · flags SYN · mac …" for the
+// detail pane; empty when the line carries none of these.
+pub fn packet_summary(entry: &LogEntry, redact: bool) -> String {
+    let flags = entry.tcp_flags.names();
+    let mac = entry.mac.as_ref().map(|mac| {
+        if redact {
+            REDACTED_MAC.to_string()
+        } else {
+            mac.clone()
+        }
+    });
+    [
+        ("len", entry.length.map(|len| len.to_string())),
+        ("ttl", entry.ttl.map(|ttl| ttl.to_string())),
+        ("tos", entry.tos.map(format_tos)),
+        ("window", entry.window.map(|window| window.to_string())),
+        ("flags", (!flags.is_empty()).then(|| flags.join(" "))),
+        ("mac", mac),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some(format!("{} {}", key, value?)))
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+fn format_tos(tos: u8) -> String {
+    format!("{:#04x}", tos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn groups_fields_by_layer_and_aligns_values() {
+        let line = "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= MAC=aa:bb SRC=1.2.3.4 DST=10.0.0.1 LEN=60 TOS=0x00 PREC=0x00 TTL=52 ID=7 DF PROTO=TCP SPT=40000 DPT=22 WINDOW=29200 RES=0x00 SYN URGP=0";
+        let entry = parse_ufw_line(line).unwrap();
+        assert_eq!(
+            pretty_entry(&entry),
+            "Log\n  time       Feb 11 20:21:00 h\n  action     BLOCK\n  direction  IN\n\
+             Link\n  in         eth0\n  mac        aa:bb\n\
+             Network\n  src        1.2.3.4\n  dst        10.0.0.1\n  len        60\n  tos        0x00\n  ttl        52\n  id         7\n  df         yes\n\
+             Transport\n  proto      TCP\n  spt        40000\n  dpt        22\n  service    ssh\n  window     29200\n  flags      SYN\n"
+        );
+        let fields = present_fields(&entry);
+        assert_eq!(
+            field_columns(&fields[..5], 2),
+            [
+                "time    Feb 11 20:21:00 h │ direction  IN   │ mac  aa:bb",
+                "action  BLOCK             │ in         eth0",
+            ]
+        );
+        assert_eq!(
+            packet_summary(&entry, true),
+            "len 60 · ttl 52 · tos 0x00 · window 29200 · flags SYN · mac xx:xx:xx"
+        );
+    }
+}
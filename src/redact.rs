@@ -0,0 +1,71 @@
+use std::net::IpAddr;
+
+// Masks the host part of an address: IPv4 keeps the /24 (1.2.3.x), IPv6 keeps
+// the /64 prefix. Anything that is not an IP is masked entirely.
+pub fn redact_ip(ip: &str) -> String {
+    if let Some((addr, len)) = ip.split_once('/') {
+        return format!("{}/{}", redact_ip(addr), len);
+    }
+    match ip.trim().parse::<IpAddr>() {
+        Ok(IpAddr::V4(addr)) => {
+            let [a, b, c, _] = addr.octets();
+            format!("{}.{}.{}.x", a, b, c)
+        }
+        Ok(IpAddr::V6(addr)) => {
+            let segments = addr.segments();
+            format!(
+                "{:x}:{:x}:{:x}:{:x}:x",
+                segments[0], segments[1], segments[2], segments[3]
+            )
+        }
+        Err(_) if ip.is_empty() || ip == "-" => ip.to_string(),
+        Err(_) => "x".to_string(),
+    }
+}
+
+pub const REDACTED_MAC: &str = "xx:xx:xx";
+
+// Redacts a raw kernel line for display: SRC/DST addresses, MAC and the
+// syslog hostname in front of "kernel:". Spacing is preserved.
+pub fn redact_raw_line(line: &str) -> String {
+    let tokens: Vec<&str> = line.split(' ').collect();
+    let kernel_pos = tokens.iter().position(|token| *token == "kernel:");
+    tokens
+        .iter()
+        .enumerate()
+        .map(|(idx, token)| {
+            if kernel_pos.is_some_and(|pos| pos > 0 && idx == pos - 1) {
+                return "[host]".to_string();
+            }
+            match token.split_once('=') {
+                Some((key @ ("SRC" | "DST"), value)) => format!("{}={}", key, redact_ip(value)),
+                Some(("MAC", value)) if !value.is_empty() => format!("MAC={}", REDACTED_MAC),
+                _ => token.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_ip_host_parts() {
+        assert_eq!(redact_ip("1.2.3.4"), "1.2.3.x");
+        assert_eq!(redact_ip("2001:db8:1:2:3:4:5:6"), "2001:db8:1:2:x");
+        assert_eq!(redact_ip("2001:db8:1:2::/64"), "2001:db8:1:2:x/64");
+        assert_eq!(redact_ip("-"), "-");
+        assert_eq!(redact_ip("not-an-ip"), "x");
+    }
+
+    #[test]
+    fn redacts_raw_line_addresses_mac_and_host() {
+        let line = "Feb 11 20:21:00 myhost kernel: [UFW BLOCK] IN=eth0 OUT= MAC=aa:bb:cc:dd:ee:ff:00:11:22:33:44:55:08:00 SRC=8.8.8.8 DST=10.0.0.1 LEN=60";
+        assert_eq!(
+            redact_raw_line(line),
+            "Feb 11 20:21:00 [host] kernel: [UFW BLOCK] IN=eth0 OUT= MAC=xx:xx:xx SRC=8.8.8.x DST=10.0.0.x LEN=60"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::{LogEntry, display_ip, format_timestamp_for_width, service_display_for_entry};
+
+// Upper bound on cached rows; the cache is rebuilt from visible rows when hit.
+const MAX_CACHED_ROWS: usize = 4096;
+
+// Everything that changes how a row's cell text is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RowLayout {
+    pub show_date: bool,
+    pub show_description: bool,
+    pub redact: bool,
+}
+
+// Table widths at which the time column gains the date and the service column
+// its description (and the other columns widen); `--breakpoints` sets them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakpoints {
+    pub date: u16,
+    pub description: u16,
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Self {
+            date: 120,
+            description: 150,
+        }
+    }
+}
+
+impl Breakpoints {
+    // "DATE,DESC", e.g. "100,140".
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid breakpoints (expected DATE,DESC widths): {}", spec);
+        let (date, description) = spec.split_once(',').ok_or_else(invalid)?;
+        let width = |value: &str| value.trim().parse::<u16>().ok().filter(|w| *w > 0);
+        Ok(Self {
+            date: width(date).ok_or_else(invalid)?,
+            description: width(description).ok_or_else(invalid)?,
+        })
+    }
+
+    pub fn row_layout(self, table_width: u16, redact: bool) -> RowLayout {
+        RowLayout {
+            show_date: table_width >= self.date,
+            show_description: table_width >= self.description,
+            redact,
+        }
+    }
+}
+
+// Preformatted cell text for one entry under one layout.
+pub struct CachedRow {
+    pub time: String,
+    pub src: String,
+    pub dst: String,
+    pub src_port: String,
+    pub dst_port: String,
+    pub service: String,
+}
+
+impl CachedRow {
+    fn build(entry: &LogEntry, layout: RowLayout) -> Self {
+        let port = |port: Option<u16>| port.map_or_else(|| "-".to_string(), |p| p.to_string());
+        Self {
+            time: format_timestamp_for_width(&entry.timestamp, layout.show_date),
+            src: display_ip(entry.src_ip.as_deref(), layout.redact),
+            dst: display_ip(entry.dst_ip.as_deref(), layout.redact),
+            src_port: port(entry.src_port),
+            dst_port: port(entry.dst_port),
+            service: service_display_for_entry(entry, layout.show_description),
+        }
+    }
+}
+
+// Table cell text keyed by entry ID. Only rows that become visible (or whose
+// layout changed) allocate; everything else is reused across frames.
+pub struct RenderCache {
+    layout: RowLayout,
+    rows: HashMap<u64, CachedRow>,
+    max_rows: usize,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::with_max_rows(MAX_CACHED_ROWS)
+    }
+}
+
+impl RenderCache {
+    pub fn with_max_rows(max_rows: usize) -> Self {
+        Self {
+            layout: RowLayout::default(),
+            rows: HashMap::new(),
+            max_rows,
+        }
+    }
+
+    pub fn prepare(&mut self, layout: RowLayout) {
+        if self.layout != layout || self.rows.len() > self.max_rows {
+            self.layout = layout;
+            self.rows.clear();
+        }
+    }
+
+    // Drops every row, e.g. after a resize; they are rebuilt when drawn.
+    pub fn invalidate(&mut self) {
+        self.rows.clear();
+    }
+
+    pub fn ensure(&mut self, entry: &LogEntry) {
+        let layout = self.layout;
+        self.rows
+            .entry(entry.id)
+            .or_insert_with(|| CachedRow::build(entry, layout));
+    }
+
+    pub fn get(&self, id: u64) -> Option<&CachedRow> {
+        self.rows.get(&id)
+    }
+}
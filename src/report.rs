@@ -0,0 +1,60 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+// A read-only text report shown in a centered overlay on top of the table.
+// Esc or q closes it; Up/Down and PageUp/PageDown scroll.
+pub struct Report {
+    pub title: String,
+    pub lines: Vec<Line<'static>>,
+    pub scroll: u16,
+}
+
+impl Report {
+    pub fn new(title: impl Into<String>, lines: Vec<Line<'static>>) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+            scroll: 0,
+        }
+    }
+
+    pub fn scroll_by(&mut self, delta: i32) {
+        let max = self.lines.len().saturating_sub(1).min(u16::MAX as usize) as i32;
+        self.scroll = (i32::from(self.scroll) + delta).clamp(0, max) as u16;
+    }
+}
+
+// Bold column header line for tabular reports.
+pub fn heading(text: String) -> Line<'static> {
+    Line::from(Span::styled(
+        text,
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+pub fn render_report(frame: &mut Frame, area: Rect, report: &Report) {
+    let width = area.width.saturating_sub(4).clamp(20, 110);
+    let height = area.height.saturating_sub(4).max(5);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(report.lines.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} (Esc to close)", report.title)),
+            )
+            .scroll((report.scroll, 0)),
+        popup,
+    );
+}
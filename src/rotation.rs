@@ -0,0 +1,144 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use flate2::read::MultiGzDecoder;
+
+// The log plus its logrotate rotations (ufw.log.1, ufw.log.2.gz, ...), newest
+// first. Stops at the first missing number.
+pub fn rotated_files(log_path: &Path) -> Vec<PathBuf> {
+    let mut files = vec![log_path.to_path_buf()];
+    for n in 1.. {
+        let mut plain = log_path.as_os_str().to_owned();
+        plain.push(format!(".{}", n));
+        let plain = PathBuf::from(plain);
+        let mut gz = plain.clone().into_os_string();
+        gz.push(".gz");
+        let gz = PathBuf::from(gz);
+        if plain.is_file() {
+            files.push(plain);
+        } else if gz.is_file() {
+            files.push(gz);
+        } else {
+            break;
+        }
+    }
+    files
+}
+
+// When `path` was last written, in seconds since the epoch; for a rotation,
+// when logrotate moved it aside.
+pub fn modified_seconds(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64)
+}
+
+// Reads a log file, decompressing `.gz` files.
+pub fn read_log(path: &Path) -> io::Result<String> {
+    let mut contents = String::new();
+    open_log(path)?.lines.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+// A log opened for reading line by line.
+pub struct OpenLog {
+    // Decompressed as it is read for `.gz` files.
+    pub lines: Box<dyn BufRead + Send>,
+    // (device, inode) of the file actually opened, so one file reached under
+    // two names in a reload (rotated between reads) can be told.
+    pub id: Option<(u64, u64)>,
+}
+
+pub fn open_log(path: &Path) -> io::Result<OpenLog> {
+    let file = File::open(path)?;
+    let id = file_id(&file.metadata()?);
+    let lines: Box<dyn BufRead + Send> = if path.extension().is_none_or(|ext| ext != "gz") {
+        Box::new(BufReader::new(file))
+    } else {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    };
+    Ok(OpenLog { lines, id })
+}
+
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+// The line starting at byte `offset` of a log; compressed logs are
+// decompressed up to that point first.
+pub fn read_line_at(path: &Path, offset: usize) -> io::Result<String> {
+    let mut line = String::new();
+    if path.extension().is_none_or(|ext| ext != "gz") {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset as u64))?;
+        BufReader::new(file).read_line(&mut line)?;
+    } else {
+        let contents = read_log(path)?;
+        let rest = contents.get(offset..).unwrap_or_default();
+        line.push_str(rest.lines().next().unwrap_or_default());
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+// The complete lines appended to a plain log after byte `offset`, or None when
+// `offset` is not the start of a line (the last read ended mid-line). A
+// trailing partial line is left for the next read.
+pub fn read_appended(path: &Path, offset: u64) -> io::Result<Option<String>> {
+    let mut file = File::open(path)?;
+    let start = offset.saturating_sub(1);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    if offset > 0 {
+        if bytes.first() != Some(&b'\n') {
+            return Ok(None);
+        }
+        bytes.remove(0);
+    }
+    let complete = bytes
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |end| end + 1);
+    bytes.truncate(complete);
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    #[test]
+    fn finds_plain_and_compressed_rotations_in_order() {
+        let dir = std::env::temp_dir().join(format!("ufw-rotation-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("ufw.log");
+        fs::write(&log, "current\n").unwrap();
+        fs::write(dir.join("ufw.log.1"), "one\n").unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"two\n").unwrap();
+        fs::write(dir.join("ufw.log.2.gz"), gz.finish().unwrap()).unwrap();
+        fs::write(dir.join("ufw.log.4.gz"), b"after a gap").unwrap();
+
+        let files = rotated_files(&log);
+        assert_eq!(
+            files,
+            vec![log.clone(), dir.join("ufw.log.1"), dir.join("ufw.log.2.gz")]
+        );
+        assert_eq!(read_log(&files[1]).unwrap(), "one\n");
+        assert_eq!(read_log(&files[2]).unwrap(), "two\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
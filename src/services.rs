@@ -31,6 +31,55 @@ pub fn service_description_from_port(port: u16) -> Option<&'static str> {
         })
 }
 
+// Lowest port registered under `name` (case-insensitive).
+pub fn port_from_service(name: &str) -> Option<u16> {
+    PORT_SERVICES
+        .get_or_init(build_port_services)
+        .iter()
+        .filter(|(_, info)| info.name.eq_ignore_ascii_case(name))
+        .map(|(port, _)| *port)
+        .min()
+}
+
+// Name of an ICMP (`ICMP`) or ICMPv6 (`ICMPV6`) message as iptables spells
+// it. Destination-unreachable is named by its code where the code is common.
+pub fn icmp_service(proto: &str, icmp_type: u8, code: u8) -> Option<&'static str> {
+    let v6 = match proto {
+        "ICMP" => false,
+        "ICMPV6" | "IPV6-ICMP" => true,
+        _ => return None,
+    };
+    let name = match (v6, icmp_type, code) {
+        (false, 0, _) | (true, 129, _) => "echo-reply",
+        (false, 8, _) | (true, 128, _) => "echo-request",
+        (false, 3, 0) => "network-unreachable",
+        (false, 3, 1) => "host-unreachable",
+        (false, 3, 2) => "protocol-unreachable",
+        (false, 3, 3) | (true, 1, 4) => "port-unreachable",
+        (false, 3, 4) => "fragmentation-needed",
+        (false, 3, 9 | 10 | 13) | (true, 1, 1) => "communication-prohibited",
+        (true, 1, 0) => "no-route",
+        (true, 1, 3) => "address-unreachable",
+        (false, 3, _) | (true, 1, _) => "destination-unreachable",
+        (false, 4, _) => "source-quench",
+        (false, 5, _) | (true, 137, _) => "redirect",
+        (false, 9, _) | (true, 134, _) => "router-advertisement",
+        (false, 10, _) | (true, 133, _) => "router-solicitation",
+        (false, 11, _) | (true, 3, _) => "time-exceeded",
+        (false, 12, _) | (true, 4, _) => "parameter-problem",
+        (false, 13, _) => "timestamp-request",
+        (false, 14, _) => "timestamp-reply",
+        (true, 2, _) => "packet-too-big",
+        (true, 130, _) => "mld-listener-query",
+        (true, 131 | 143, _) => "mld-listener-report",
+        (true, 132, _) => "mld-listener-done",
+        (true, 135, _) => "neighbour-solicitation",
+        (true, 136, _) => "neighbour-advertisement",
+        _ => return None,
+    };
+    Some(name)
+}
+
 fn build_port_services() -> HashMap<u16, ServiceInfo> {
     let mut map = HashMap::new();
 
@@ -134,4 +183,17 @@ mod tests {
             Some("The Secure Shell (SSH) Protocol")
         );
     }
+
+    #[test]
+    fn names_icmp_messages() {
+        assert_eq!(icmp_service("ICMP", 8, 0), Some("echo-request"));
+        assert_eq!(icmp_service("ICMP", 3, 3), Some("port-unreachable"));
+        assert_eq!(icmp_service("ICMP", 3, 7), Some("destination-unreachable"));
+        assert_eq!(
+            icmp_service("ICMPV6", 135, 0),
+            Some("neighbour-solicitation")
+        );
+        assert_eq!(icmp_service("ICMPV6", 8, 0), None);
+        assert_eq!(icmp_service("TCP", 8, 0), None);
+    }
 }
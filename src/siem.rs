@@ -0,0 +1,196 @@
+use crate::LogEntry;
+use crate::parser::timestamp_sort_key;
+
+const VENDOR: &str = "ufw-log-viewer";
+const PRODUCT: &str = "ufw-log-viewer";
+
+// SIEM record layouts for `--export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiemFormat {
+    Cef,
+    Leef,
+}
+
+impl SiemFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "cef" => Some(Self::Cef),
+            "leef" => Some(Self::Leef),
+            _ => None,
+        }
+    }
+
+    pub fn record(self, entry: &LogEntry, tags: &[&str]) -> String {
+        match self {
+            Self::Cef => cef_record(entry, tags),
+            Self::Leef => leef_record(entry, tags),
+        }
+    }
+}
+
+// ArcSight CEF:0 with the standard src/dst/spt/dpt/proto extension keys.
+// Technique tags go into the cs1 custom string.
+pub fn cef_record(entry: &LogEntry, tags: &[&str]) -> String {
+    let severity = match entry.action.as_str() {
+        "BLOCK" | "REJECT" => 5,
+        "ALLOW" => 1,
+        _ => 3,
+    };
+    let mut ext: Vec<(&str, String)> = Vec::new();
+    match event_time(&entry.timestamp) {
+        Some(EventTime::EpochMillis(ms)) => ext.push(("rt", ms.to_string())),
+        Some(EventTime::Syslog(stamp)) => ext.push(("rt", stamp)),
+        None => {}
+    }
+    ext.push(("act", entry.action.clone()));
+    push_flow_fields(&mut ext, entry, ["src", "dst", "spt", "dpt", "proto"]);
+    if let Some(iface) = entry.in_iface.as_deref().filter(|v| !v.is_empty()) {
+        ext.push(("deviceInboundInterface", iface.to_string()));
+    }
+    if let Some(iface) = entry.out_iface.as_deref().filter(|v| !v.is_empty()) {
+        ext.push(("deviceOutboundInterface", iface.to_string()));
+    }
+    if let Some(service) = entry.service.as_deref() {
+        ext.push(("app", service.to_string()));
+    }
+    if !tags.is_empty() {
+        ext.push(("cs1Label", "technique".to_string()));
+        ext.push(("cs1", tags.join(", ")));
+    }
+
+    let extension: Vec<String> = ext
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, cef_escape_value(value)))
+        .collect();
+    format!(
+        "CEF:0|{}|{}|{}|{}|Firewall {}|{}|{}",
+        VENDOR,
+        PRODUCT,
+        env!("CARGO_PKG_VERSION"),
+        cef_escape_header(&entry.action),
+        cef_escape_header(&entry.action),
+        severity,
+        extension.join(" ")
+    )
+}
+
+// IBM QRadar LEEF:1.0, tab-delimited attributes.
+pub fn leef_record(entry: &LogEntry, tags: &[&str]) -> String {
+    let mut attrs: Vec<(&str, String)> = Vec::new();
+    match event_time(&entry.timestamp) {
+        Some(EventTime::EpochMillis(ms)) => attrs.push(("devTime", ms.to_string())),
+        Some(EventTime::Syslog(stamp)) => {
+            attrs.push(("devTime", stamp));
+            attrs.push(("devTimeFormat", "MMM dd HH:mm:ss".to_string()));
+        }
+        None => {}
+    }
+    attrs.push(("cat", entry.action.clone()));
+    push_flow_fields(
+        &mut attrs,
+        entry,
+        ["src", "dst", "srcPort", "dstPort", "proto"],
+    );
+    if let Some(iface) = entry.in_iface.as_deref().filter(|v| !v.is_empty()) {
+        attrs.push(("inInterface", iface.to_string()));
+    }
+    if let Some(iface) = entry.out_iface.as_deref().filter(|v| !v.is_empty()) {
+        attrs.push(("outInterface", iface.to_string()));
+    }
+    if !tags.is_empty() {
+        attrs.push(("technique", tags.join(", ")));
+    }
+
+    let body: Vec<String> = attrs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value.replace(['\t', '\n', '\r'], " ")))
+        .collect();
+    format!(
+        "LEEF:1.0|{}|{}|{}|{}|{}",
+        VENDOR,
+        PRODUCT,
+        env!("CARGO_PKG_VERSION"),
+        entry.action.replace('|', "/"),
+        body.join("\t")
+    )
+}
+
+enum EventTime {
+    EpochMillis(i64),
+    Syslog(String),
+}
+
+// ISO stamps carry a full date and zone, so they become epoch millis; syslog
+// stamps have no year and are passed through in their "MMM dd HH:mm:ss" form.
+fn event_time(timestamp: &str) -> Option<EventTime> {
+    let first = timestamp.split_whitespace().next()?;
+    if first.contains('T') {
+        return timestamp_sort_key(timestamp).map(|secs| EventTime::EpochMillis(secs * 1000));
+    }
+    timestamp_sort_key(timestamp)?;
+    let parts: Vec<&str> = timestamp.split_whitespace().take(3).collect();
+    Some(EventTime::Syslog(parts.join(" ")))
+}
+
+fn push_flow_fields<'a>(out: &mut Vec<(&'a str, String)>, entry: &LogEntry, keys: [&'a str; 5]) {
+    let [src, dst, spt, dpt, proto] = keys;
+    if let Some(ip) = entry.src_ip.as_deref() {
+        out.push((src, ip.to_string()));
+    }
+    if let Some(ip) = entry.dst_ip.as_deref() {
+        out.push((dst, ip.to_string()));
+    }
+    if let Some(port) = entry.src_port {
+        out.push((spt, port.to_string()));
+    }
+    if let Some(port) = entry.dst_port {
+        out.push((dpt, port.to_string()));
+    }
+    if let Some(p) = entry.proto.as_deref() {
+        out.push((proto, p.to_string()));
+    }
+}
+
+fn cef_escape_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn cef_escape_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    const LINE: &str = "2026-02-11T20:21:00+00:00 host kernel: [UFW BLOCK] IN=eth0 OUT= SRC=203.0.113.5 DST=10.0.0.1 PROTO=TCP SPT=51515 DPT=22";
+
+    #[test]
+    fn formats_cef_record() {
+        let entry = parse_ufw_line(LINE).unwrap();
+        assert_eq!(
+            cef_record(&entry, &["T1046 network scan"]),
+            format!(
+                "CEF:0|ufw-log-viewer|ufw-log-viewer|{}|BLOCK|Firewall BLOCK|5|rt=1770841260000 act=BLOCK src=203.0.113.5 dst=10.0.0.1 spt=51515 dpt=22 proto=TCP deviceInboundInterface=eth0 app=ssh cs1Label=technique cs1=T1046 network scan",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+        assert_eq!(cef_escape_value("a=b\\c"), "a\\=b\\\\c");
+    }
+
+    #[test]
+    fn formats_leef_record_with_syslog_time() {
+        let line = LINE.replace("2026-02-11T20:21:00+00:00", "Feb 11 20:21:00");
+        let entry = parse_ufw_line(&line).unwrap();
+        let record = leef_record(&entry, &[]);
+        assert!(record.starts_with("LEEF:1.0|ufw-log-viewer|ufw-log-viewer|"));
+        assert!(record.ends_with(
+            "|BLOCK|devTime=Feb 11 20:21:00\tdevTimeFormat=MMM dd HH:mm:ss\tcat=BLOCK\tsrc=203.0.113.5\tdst=10.0.0.1\tsrcPort=51515\tdstPort=22\tproto=TCP\tinInterface=eth0"
+        ));
+    }
+}
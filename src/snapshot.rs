@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{LogEntry, group_thousands};
+
+// Aggregate counts saved by `n`, so `N` can show what changed since, e.g.
+// whether a new block rule is holding during an attack.
+pub struct Snapshot {
+    pub name: String,
+    pub total: usize,
+    sources: HashSet<String>,
+    actions: HashMap<String, usize>,
+    // Blocked entries per service, or per port/protocol when unnamed.
+    blocks: HashMap<String, usize>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    pub before: usize,
+    pub after: usize,
+    pub new_sources: Vec<String>,
+    pub actions: Vec<(String, i64)>,
+    pub blocks: Vec<(String, i64)>,
+}
+
+impl Snapshot {
+    pub fn take<'a>(name: String, entries: impl IntoIterator<Item = &'a LogEntry>) -> Self {
+        let mut snapshot = Self {
+            name,
+            total: 0,
+            sources: HashSet::new(),
+            actions: HashMap::new(),
+            blocks: HashMap::new(),
+        };
+        for entry in entries {
+            snapshot.total += 1;
+            if let Some(ip) = entry.src_ip.as_deref() {
+                snapshot.sources.insert(ip.to_string());
+            }
+            *snapshot.actions.entry(entry.action.clone()).or_insert(0) += 1;
+            if entry.action != "ALLOW" && entry.action != "AUDIT" {
+                *snapshot.blocks.entry(block_key(entry)).or_insert(0) += 1;
+            }
+        }
+        snapshot
+    }
+
+    // Changes from `self` to `current`, largest first; unchanged keys are left out.
+    pub fn diff(&self, current: &Snapshot) -> SnapshotDiff {
+        let mut new_sources: Vec<String> =
+            current.sources.difference(&self.sources).cloned().collect();
+        new_sources.sort();
+        SnapshotDiff {
+            before: self.total,
+            after: current.total,
+            new_sources,
+            actions: count_deltas(&self.actions, &current.actions),
+            blocks: count_deltas(&self.blocks, &current.blocks),
+        }
+    }
+}
+
+impl SnapshotDiff {
+    // "+3 new source IPs, +212 ssh blocks": the headline for the popup title.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!(
+            "{} new source IPs",
+            signed(self.new_sources.len() as i64)
+        )];
+        parts.extend(
+            self.blocks
+                .first()
+                .map(|(key, delta)| format!("{} {} blocks", signed(*delta), key)),
+        );
+        parts.join(", ")
+    }
+}
+
+fn block_key(entry: &LogEntry) -> String {
+    match (&entry.service, entry.dst_port) {
+        (Some(service), _) => service.clone(),
+        (None, Some(port)) => format!("port {}", port),
+        (None, None) => entry.proto.clone().unwrap_or_else(|| "other".to_string()),
+    }
+}
+
+fn count_deltas(
+    before: &HashMap<String, usize>,
+    after: &HashMap<String, usize>,
+) -> Vec<(String, i64)> {
+    let keys: HashSet<&String> = before.keys().chain(after.keys()).collect();
+    let mut deltas: Vec<(String, i64)> = keys
+        .into_iter()
+        .map(|key| {
+            let count = |counts: &HashMap<String, usize>| counts.get(key).copied().unwrap_or(0);
+            (key.clone(), count(after) as i64 - count(before) as i64)
+        })
+        .filter(|(_, delta)| *delta != 0)
+        .collect();
+    deltas.sort_by(|a, b| b.1.abs().cmp(&a.1.abs()).then(a.0.cmp(&b.0)));
+    deltas
+}
+
+// "+1,204" / "-3".
+pub fn signed(value: i64) -> String {
+    let sign = if value < 0 { '-' } else { '+' };
+    format!("{}{}", sign, group_thousands(value.unsigned_abs() as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn diffs_sources_actions_and_blocks() {
+        let entry = |action: &str, src: &str, dpt: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 20:21:00 h kernel: [UFW {action}] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        let before = [
+            entry("BLOCK", "1.1.1.1", 22),
+            entry("ALLOW", "2.2.2.2", 443),
+        ];
+        let after = [
+            entry("BLOCK", "1.1.1.1", 22),
+            entry("ALLOW", "2.2.2.2", 443),
+            entry("BLOCK", "3.3.3.3", 22),
+            entry("BLOCK", "4.4.4.4", 22),
+            entry("BLOCK", "4.4.4.4", 23),
+        ];
+        let snapshot = Snapshot::take("#1".to_string(), &before);
+        let diff = snapshot.diff(&Snapshot::take(String::new(), &after));
+        assert_eq!(
+            diff,
+            SnapshotDiff {
+                before: 2,
+                after: 5,
+                new_sources: vec!["3.3.3.3".to_string(), "4.4.4.4".to_string()],
+                actions: vec![("BLOCK".to_string(), 3)],
+                blocks: vec![("ssh".to_string(), 2), ("telnet".to_string(), 1)],
+            }
+        );
+        assert_eq!(diff.summary(), "+2 new source IPs, +2 ssh blocks");
+        assert_eq!(signed(-1_204), "-1,204");
+    }
+}
@@ -0,0 +1,204 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::net::source_key;
+use crate::{LogEntry, display_ip, group_thousands};
+
+// Counts behind the `s` statistics view, computed from the filtered entries.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ViewStats {
+    pub total: usize,
+    pub sources: Vec<(String, usize)>,
+    pub ports: Vec<(u16, usize)>,
+    pub actions: Vec<(String, usize)>,
+    pub interfaces: Vec<(String, usize)>,
+}
+
+impl ViewStats {
+    // Top `limit` sources and destination ports; every action and interface.
+    // IPv6 sources are grouped by `v6_prefix`. An entry with both IN= and
+    // OUT= counts for both interfaces.
+    pub fn compute<'a>(
+        entries: impl IntoIterator<Item = &'a LogEntry>,
+        limit: usize,
+        v6_prefix: u8,
+    ) -> Self {
+        let mut total = 0;
+        let mut sources: HashMap<Cow<str>, usize> = HashMap::new();
+        let mut ports: HashMap<u16, usize> = HashMap::new();
+        let mut actions: HashMap<&str, usize> = HashMap::new();
+        let mut interfaces: HashMap<&str, usize> = HashMap::new();
+        for entry in entries {
+            total += 1;
+            if let Some(ip) = entry.src_ip.as_deref() {
+                *sources.entry(source_key(ip, v6_prefix)).or_insert(0) += 1;
+            }
+            if let Some(port) = entry.dst_port {
+                *ports.entry(port).or_insert(0) += 1;
+            }
+            *actions.entry(entry.action.as_str()).or_insert(0) += 1;
+            for iface in [entry.in_iface.as_deref(), entry.out_iface.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|iface| !iface.is_empty())
+            {
+                *interfaces.entry(iface).or_insert(0) += 1;
+            }
+        }
+        let owned = |counts: Vec<(&str, usize)>| {
+            counts
+                .into_iter()
+                .map(|(key, count)| (key.to_string(), count))
+                .collect()
+        };
+        Self {
+            total,
+            sources: ranked(sources, limit)
+                .into_iter()
+                .map(|(key, count)| (key.into_owned(), count))
+                .collect(),
+            ports: ranked(ports, limit),
+            actions: owned(ranked(actions, usize::MAX)),
+            interfaces: owned(ranked(interfaces, usize::MAX)),
+        }
+    }
+}
+
+// Busiest first, ties broken by key so the view does not flicker.
+fn ranked<K: Ord + Hash>(counts: HashMap<K, usize>, limit: usize) -> Vec<(K, usize)> {
+    let mut ranked: Vec<(K, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+    ranked
+}
+
+// Four columns in place of the table: top sources, top destination ports,
+// actions and interfaces, each with counts and a share of the view.
+pub fn render_stats(
+    frame: &mut Frame,
+    area: Rect,
+    stats: &ViewStats,
+    scope: Option<&str>,
+    redact: bool,
+) {
+    let outer = Block::default().borders(Borders::ALL).title(format!(
+        "Statistics · {} entries in view{} (s to return)",
+        group_thousands(stats.total),
+        scope
+            .map(|scope| format!(" · {}", scope))
+            .unwrap_or_default()
+    ));
+    let inner = outer.inner(area);
+    frame.render_widget(outer, area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(34),
+            Constraint::Percentage(22),
+            Constraint::Percentage(22),
+            Constraint::Percentage(22),
+        ])
+        .split(inner);
+    let sources: Vec<(String, usize)> = stats
+        .sources
+        .iter()
+        .map(|(ip, count)| (display_ip(Some(ip), redact), *count))
+        .collect();
+    let ports: Vec<(String, usize)> = stats
+        .ports
+        .iter()
+        .map(|(port, count)| (port.to_string(), *count))
+        .collect();
+    let panels = [
+        ("Top sources", &sources),
+        ("Top dst ports", &ports),
+        ("Actions", &stats.actions),
+        ("Interfaces", &stats.interfaces),
+    ];
+    for ((title, counts), column) in panels.into_iter().zip(columns.iter()) {
+        let mut lines = vec![Line::from(Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))];
+        for (label, count) in counts {
+            let share = count * 100 / stats.total.max(1);
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:>8} {:>3}% ", group_thousands(*count), share),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(label.clone()),
+            ]));
+        }
+        if counts.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "none",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines), *column);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn counts_sources_ports_actions_and_interfaces() {
+        let entry = |action: &str, ifaces: &str, src: &str, dpt: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 20:21:00 h kernel: [UFW {action}] {ifaces} SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        let entries = [
+            entry("BLOCK", "IN=eth0 OUT=", "1.1.1.1", 22),
+            entry("BLOCK", "IN=eth0 OUT=", "1.1.1.1", 443),
+            entry("ALLOW", "IN=eth0 OUT=docker0", "2.2.2.2", 22),
+            entry("BLOCK", "IN=eth0 OUT=", "3.3.3.3", 80),
+        ];
+        let stats = ViewStats::compute(&entries, 2, 64);
+        assert_eq!(stats.total, 4);
+        assert_eq!(
+            stats.sources,
+            [("1.1.1.1".to_string(), 2), ("2.2.2.2".to_string(), 1)]
+        );
+        assert_eq!(stats.ports, [(22, 2), (80, 1)]);
+        assert_eq!(
+            stats.actions,
+            [("BLOCK".to_string(), 3), ("ALLOW".to_string(), 1)]
+        );
+        assert_eq!(
+            stats.interfaces,
+            [("eth0".to_string(), 4), ("docker0".to_string(), 1)]
+        );
+
+        let rotating = [
+            entry("BLOCK", "IN=eth0 OUT=", "2001:db8:1:2::a", 22),
+            entry("BLOCK", "IN=eth0 OUT=", "2001:db8:1:2:ffff::1", 22),
+            entry("BLOCK", "IN=eth0 OUT=", "2001:db8:1:3::a", 22),
+        ];
+        let sources = |prefix| ViewStats::compute(&rotating, 5, prefix).sources;
+        assert_eq!(
+            sources(64),
+            [
+                ("2001:db8:1:2::/64".to_string(), 2),
+                ("2001:db8:1:3::/64".to_string(), 1)
+            ]
+        );
+        assert_eq!(sources(48), [("2001:db8:1::/48".to_string(), 3)]);
+        assert_eq!(sources(128).len(), 3);
+    }
+}
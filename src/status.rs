@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn color(self) -> Color {
+        match self {
+            Self::Info => Color::DarkGray,
+            Self::Warn => Color::Yellow,
+            Self::Error => Color::LightRed,
+        }
+    }
+
+    // Errors have no lifetime: they stay until acknowledged with Esc.
+    fn lifetime(self) -> Option<Duration> {
+        match self {
+            Self::Info => Some(Duration::from_secs(5)),
+            Self::Warn => Some(Duration::from_secs(10)),
+            Self::Error => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Message {
+    level: Level,
+    text: String,
+    since: Instant,
+}
+
+// The header's status message. An unacknowledged error is shown in place of
+// newer info and warnings, which take over once it is dismissed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusLine {
+    message: Option<Message>,
+    error: Option<Message>,
+}
+
+impl StatusLine {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.set(Level::Info, text.into());
+    }
+
+    pub fn warn(&mut self, text: impl Into<String>) {
+        self.set(Level::Warn, text.into());
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.set(Level::Error, text.into());
+    }
+
+    // Info on success, an error otherwise.
+    pub fn report(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(text) => self.info(text),
+            Err(text) => self.error(text),
+        }
+    }
+
+    fn set(&mut self, level: Level, text: String) {
+        let message = Some(Message {
+            level,
+            text,
+            since: Instant::now(),
+        });
+        if level == Level::Error {
+            self.error = message;
+        } else {
+            self.message = message;
+        }
+    }
+
+    // Adds a hint to the latest info or warning.
+    pub fn append(&mut self, suffix: &str) {
+        if let Some(message) = self.message.as_mut() {
+            message.text.push_str(suffix);
+        }
+    }
+
+    // Dismisses the pinned error; returns false when there was none.
+    pub fn acknowledge(&mut self) -> bool {
+        self.error.take().is_some()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    // Drops info and warnings that have outlived their level's lifetime.
+    pub fn expire(&mut self) {
+        if let Some(message) = self.message.as_ref()
+            && message
+                .level
+                .lifetime()
+                .is_some_and(|lifetime| message.since.elapsed() >= lifetime)
+        {
+            self.message = None;
+        }
+    }
+
+    fn shown(&self) -> Option<&Message> {
+        self.error.as_ref().or(self.message.as_ref())
+    }
+
+    pub fn text(&self) -> &str {
+        self.shown().map_or("", |message| message.text.as_str())
+    }
+
+    pub fn level(&self) -> Level {
+        self.shown().map_or(Level::Info, |message| message.level)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shown().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_stay_until_acknowledged() {
+        let mut status = StatusLine::default();
+        status.info("Sorted by Time");
+        assert_eq!(
+            (status.text(), status.level()),
+            ("Sorted by Time", Level::Info)
+        );
+
+        status.error("Export failed");
+        status.info("Copied");
+        assert_eq!(status.text(), "Export failed");
+        status.message.as_mut().unwrap().since -= Duration::from_secs(60);
+        status.error.as_mut().unwrap().since -= Duration::from_secs(60);
+        status.expire();
+        assert_eq!(status.level(), Level::Error);
+
+        status.info("Copied");
+        assert!(status.acknowledge());
+        assert_eq!(status.text(), "Copied");
+        assert!(!status.acknowledge());
+
+        status.warn("No source IP");
+        status.message.as_mut().unwrap().since -= Duration::from_secs(9);
+        status.expire();
+        assert_eq!(status.level(), Level::Warn);
+        status.message.as_mut().unwrap().since -= Duration::from_secs(1);
+        status.expire();
+        assert!(status.is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+// Text of a main source that is not a file (journald, the syslog listener).
+// It keeps the newest lines for full reloads, and remembers how far ingesting
+// got so each poll only parses what arrived since. Offsets count from the
+// start of the stream, including lines already trimmed away.
+#[derive(Debug, Default)]
+pub struct StreamLog {
+    text: String,
+    lines: usize,
+    // Bytes trimmed from the front of `text`.
+    dropped: usize,
+    // Stream offset up to which lines were handed to an ingest.
+    ingested: usize,
+}
+
+impl StreamLog {
+    pub fn push_line(&mut self, line: &str) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        self.lines += 1;
+    }
+
+    // Every kept line, for a full reload; they all count as ingested.
+    pub fn take_all(&mut self) -> String {
+        self.ingested = self.dropped + self.text.len();
+        self.text.clone()
+    }
+
+    // Lines that arrived since the last take, and the stream offset they
+    // start at.
+    pub fn take_fresh(&mut self) -> (String, usize) {
+        let start = self.ingested.max(self.dropped);
+        let fresh = self.text[start - self.dropped..].to_string();
+        self.ingested = self.dropped + self.text.len();
+        (fresh, start)
+    }
+
+    // Drops the oldest lines beyond `max`.
+    pub fn trim(&mut self, max: usize) {
+        let Some(excess) = self.lines.checked_sub(max).filter(|&excess| excess > 0) else {
+            return;
+        };
+        let cut = self
+            .text
+            .match_indices('\n')
+            .nth(excess - 1)
+            .map_or(self.text.len(), |(pos, _)| pos + 1);
+        self.text.drain(..cut);
+        self.dropped += cut;
+        self.lines = max;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hands_out_fresh_lines_and_trims_old_ones() {
+        let mut stream = StreamLog::default();
+        stream.push_line("a");
+        stream.push_line("b");
+        assert_eq!(stream.take_all(), "a\nb\n");
+        assert_eq!(stream.take_fresh(), (String::new(), 4));
+
+        stream.push_line("c");
+        stream.trim(2);
+        assert_eq!(stream.take_fresh(), ("c\n".to_string(), 4));
+        assert_eq!(stream.take_all(), "b\nc\n");
+
+        // Lines trimmed before they were taken are skipped.
+        stream.push_line("d");
+        stream.push_line("e");
+        stream.push_line("f");
+        stream.trim(2);
+        assert_eq!(stream.take_fresh(), ("e\nf\n".to_string(), 8));
+        assert_eq!(stream.take_all(), "e\nf\n");
+    }
+}
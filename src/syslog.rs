@@ -0,0 +1,49 @@
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use crate::offline;
+
+// local0.info
+const PRIORITY: u8 = 134;
+
+// Fire-and-forget RFC 3164 style sender over UDP.
+pub struct SyslogSender {
+    socket: UdpSocket,
+}
+
+impl SyslogSender {
+    // `target` is "host:port"; the port defaults to 514.
+    pub fn connect(target: &str) -> io::Result<Self> {
+        if !offline::network_allowed() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "syslog forwarding is disabled by --offline",
+            ));
+        }
+        let target = if target
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| port.parse::<u16>().is_ok())
+        {
+            target.to_string()
+        } else {
+            format!("{}:514", target)
+        };
+        let addr = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no address for target"))?;
+        let bind = if addr.is_ipv6() {
+            "[::]:0"
+        } else {
+            "0.0.0.0:0"
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.connect(addr)?;
+        Ok(Self { socket })
+    }
+
+    pub fn send(&self, message: &str) -> io::Result<()> {
+        let datagram = format!("<{}>{}: {}", PRIORITY, env!("CARGO_PKG_NAME"), message);
+        self.socket.send(datagram.as_bytes()).map(|_| ())
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use crate::fixed_port::fixed_source_ports;
+use crate::{FilterField, Filters, LogEntry};
+
+// Technique tag for sources the fixed source-port detector flags.
+const SCAN_TAG: &str = "T1046 network scan";
+
+// `--tag FIELD=VALUE:TAG`: entries matching the one-field filter get TAG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagRule {
+    filters: Filters,
+    tag: String,
+}
+
+impl TagRule {
+    // The tag follows the last ':', so IPv6 values need no escaping.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (rule, tag) = spec
+            .rsplit_once(':')
+            .filter(|(_, tag)| !tag.trim().is_empty())
+            .ok_or_else(|| format!("--tag expects FIELD=VALUE:TAG, got {}", spec))?;
+        let (name, value) = rule
+            .split_once('=')
+            .filter(|(_, value)| !value.trim().is_empty())
+            .ok_or_else(|| format!("--tag expects FIELD=VALUE:TAG, got {}", spec))?;
+        let name = name.trim().to_ascii_lowercase();
+        let field = FilterField::ALL
+            .into_iter()
+            .find(|field| {
+                field.label() == name || (name == "proto" && *field == FilterField::Proto)
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Unknown --tag field: {} (expected service, port, ip, action, proto, text)",
+                    name
+                )
+            })?;
+        let mut filters = Filters::default();
+        filters.set(field, value.trim().to_string());
+        Ok(Self {
+            filters,
+            tag: tag.trim().to_string(),
+        })
+    }
+}
+
+// Attaches technique tags to entries: user rules first, then built-in
+// detectors. Detector results are recomputed only when entries change.
+#[derive(Default)]
+pub struct Tagger {
+    rules: Vec<TagRule>,
+    scanners: HashSet<String>,
+}
+
+impl Tagger {
+    pub fn new(rules: Vec<TagRule>) -> Self {
+        Self {
+            rules,
+            scanners: HashSet::new(),
+        }
+    }
+
+    pub fn refresh(&mut self, entries: &[LogEntry]) {
+        self.scanners = fixed_source_ports(entries)
+            .into_iter()
+            .map(|fixed| fixed.source)
+            .collect();
+    }
+
+    pub fn tags(&self, entry: &LogEntry) -> Vec<&str> {
+        let mut tags: Vec<&str> = Vec::new();
+        for rule in self.rules.iter().filter(|rule| rule.filters.matches(entry)) {
+            if !tags.contains(&rule.tag.as_str()) {
+                tags.push(&rule.tag);
+            }
+        }
+        if entry
+            .src_ip
+            .as_deref()
+            .is_some_and(|ip| self.scanners.contains(ip))
+            && !tags.contains(&SCAN_TAG)
+        {
+            tags.push(SCAN_TAG);
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn rules_and_detectors_attach_tags() {
+        let entry = |src: &str, spt: u16, dpt: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 20:21:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT={spt} DPT={dpt}"
+            ))
+            .unwrap()
+        };
+        let mut entries: Vec<LogEntry> = (0..5).map(|n| entry("1.1.1.1", 61000, 20 + n)).collect();
+        entries.push(entry("2001:db8::7", 40000, 3389));
+
+        let mut tagger = Tagger::new(vec![
+            TagRule::parse("port=3389:T1021 remote services").unwrap(),
+            TagRule::parse("ip=2001:db8::7:T1595 scanning").unwrap(),
+        ]);
+        tagger.refresh(&entries);
+        assert_eq!(tagger.tags(&entries[0]), ["T1046 network scan"]);
+        assert_eq!(
+            tagger.tags(&entries[5]),
+            ["T1021 remote services", "T1595 scanning"]
+        );
+
+        assert!(TagRule::parse("port=22").is_err());
+        assert!(TagRule::parse("color=red:T1").is_err());
+        assert!(TagRule::parse("proto=udp:T1").is_ok());
+    }
+}
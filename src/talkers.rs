@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::{FilterField, LogEntry, display_ip};
+
+// Most rows either list gets, however tall the sidebar is.
+const MAX_ROWS: usize = 10;
+
+// The `|` sidebar. While focused it takes j/k and Enter; `cursor` runs over
+// the sources, then the ports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TalkerSidebar {
+    pub focused: bool,
+    pub cursor: usize,
+}
+
+// Busiest source IPs and destination ports of the filtered view, with their
+// entry counts, busiest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Talkers {
+    pub sources: Vec<(String, usize)>,
+    pub ports: Vec<(u16, usize)>,
+}
+
+impl Talkers {
+    // `rows` is the sidebar's inner height; both lists and their headings
+    // share it.
+    pub fn top<'a>(entries: impl IntoIterator<Item = &'a LogEntry>, rows: usize) -> Self {
+        let limit = (rows.saturating_sub(3) / 2).min(MAX_ROWS);
+        let mut sources: HashMap<&str, usize> = HashMap::new();
+        let mut ports: HashMap<u16, usize> = HashMap::new();
+        for entry in entries {
+            if let Some(ip) = entry.src_ip.as_deref() {
+                *sources.entry(ip).or_insert(0) += 1;
+            }
+            if let Some(port) = entry.dst_port {
+                *ports.entry(port).or_insert(0) += 1;
+            }
+        }
+        Self {
+            sources: busiest(sources, limit)
+                .into_iter()
+                .map(|(ip, count)| (ip.to_string(), count))
+                .collect(),
+            ports: busiest(ports, limit),
+        }
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.sources.len() + self.ports.len()
+    }
+
+    // The filter picking item `pos` applies: ip= for a source, port= for a port.
+    pub fn filter(&self, pos: usize) -> Option<(FilterField, String)> {
+        match self.sources.get(pos) {
+            Some((ip, _)) => Some((FilterField::Ip, ip.clone())),
+            None => self
+                .ports
+                .get(pos - self.sources.len())
+                .map(|(port, _)| (FilterField::Port, port.to_string())),
+        }
+    }
+
+    // The item on sidebar line `line`, counted as `lines` lays them out.
+    pub fn item_at_line(&self, line: usize) -> Option<usize> {
+        let sources = self.sources.len();
+        match line {
+            0 => None,
+            _ if line <= sources => Some(line - 1),
+            _ => line
+                .checked_sub(sources + 3)
+                .filter(|pos| *pos < self.ports.len())
+                .map(|pos| sources + pos),
+        }
+    }
+
+    // Sources are masked with `redact`, like the table's.
+    pub fn lines(&self, width: usize, cursor: Option<usize>, redact: bool) -> Vec<Line<'static>> {
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ))
+        };
+        let row = |pos: usize, label: String, count: usize| {
+            let count = count.to_string();
+            let room = width.saturating_sub(count.len() + 1);
+            let label = fit(&label, room);
+            let gap = width.saturating_sub(label.chars().count() + count.len());
+            let style = if cursor == Some(pos) {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(format!("{}{}", label, " ".repeat(gap)), style),
+                Span::styled(count, style.fg(Color::Yellow)),
+            ])
+        };
+        let mut lines = vec![heading("Top sources")];
+        for (pos, (ip, count)) in self.sources.iter().enumerate() {
+            lines.push(row(pos, display_ip(Some(ip), redact), *count));
+        }
+        lines.push(Line::default());
+        lines.push(heading("Top ports"));
+        for (pos, (port, count)) in self.ports.iter().enumerate() {
+            lines.push(row(self.sources.len() + pos, port.to_string(), *count));
+        }
+        lines
+    }
+}
+
+fn busiest<K: Ord + Copy>(counts: HashMap<K, usize>, limit: usize) -> Vec<(K, usize)> {
+    let mut counts: Vec<(K, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts.truncate(limit);
+    counts
+}
+
+// `text` cut to `width` cells, ending in "…" when shortened.
+fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(width.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn ranks_sources_and_ports_and_maps_lines_to_filters() {
+        let entry = |src: &str, port: u16| {
+            parse_ufw_line(&format!(
+                "Feb 11 20:00:00 h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={} DST=10.0.0.1 PROTO=TCP SPT=40000 DPT={}",
+                src, port
+            ))
+            .unwrap()
+        };
+        let entries = [
+            entry("1.1.1.1", 22),
+            entry("2.2.2.2", 22),
+            entry("2.2.2.2", 443),
+            entry("3.3.3.3", 80),
+        ];
+        // Room for two rows per list.
+        let talkers = Talkers::top(&entries, 7);
+        assert_eq!(
+            talkers.sources,
+            [("2.2.2.2".to_string(), 2), ("1.1.1.1".to_string(), 1)]
+        );
+        assert_eq!(talkers.ports, [(22, 2), (80, 1)]);
+
+        let lines = talkers.lines(12, Some(0), false);
+        let text: Vec<String> = lines.iter().map(ToString::to_string).collect();
+        assert_eq!(text[1], "2.2.2.2    2");
+        assert_eq!(talkers.lines(12, None, true)[1].to_string(), "2.2.2.x    2");
+        assert_eq!(text[4], "Top ports");
+        assert_eq!(text[5], "22         2");
+        assert_eq!(talkers.item_at_line(1), Some(0));
+        assert_eq!(talkers.item_at_line(3), None);
+        assert_eq!(talkers.item_at_line(5), Some(2));
+        assert_eq!(
+            talkers.filter(0),
+            Some((FilterField::Ip, "2.2.2.2".to_string()))
+        );
+        assert_eq!(
+            talkers.filter(3),
+            Some((FilterField::Port, "80".to_string()))
+        );
+        assert_eq!(talkers.filter(4), None);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Lines kept for the capture pane.
+const MAX_LINES: usize = 500;
+
+// A running `tcpdump` whose output is streamed into a pane inside the UI, so
+// the terminal stays in raw mode and Ctrl+C never reaches the child.
+pub struct CaptureSession {
+    child: Child,
+    rx: Receiver<String>,
+    pub command: String,
+    pub lines: VecDeque<String>,
+    pub tee_path: Option<PathBuf>,
+    pub exit_status: Option<String>,
+}
+
+impl CaptureSession {
+    // Starts `tcpdump -n -l -i IFACE EXPR`; stdout is also written to a new
+    // file in `tee_dir` when one is given.
+    pub fn start(iface: &str, expression: &str, tee_dir: Option<&Path>) -> io::Result<Self> {
+        let mut child = Command::new("tcpdump")
+            .args(["-n", "-l", "-i", iface, expression])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let tee_path = tee_dir.map(|dir| {
+            let stamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            dir.join(format!("tcpdump-{}.txt", stamp))
+        });
+        let tee = match tee_path.as_deref() {
+            Some(path) => Some(File::create(path)?),
+            None => None,
+        };
+
+        let (tx, rx) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            spawn_reader(stdout, tx.clone(), tee);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            spawn_reader(stderr, tx, None);
+        }
+        Ok(Self {
+            child,
+            rx,
+            command: format!("tcpdump -n -l -i {} '{}'", iface, expression),
+            lines: VecDeque::new(),
+            tee_path,
+            exit_status: None,
+        })
+    }
+
+    // Moves buffered output into `lines` and notices when tcpdump exits.
+    pub fn poll(&mut self) {
+        while let Ok(line) = self.rx.try_recv() {
+            if self.lines.len() == MAX_LINES {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line);
+        }
+        if self.exit_status.is_none()
+            && let Ok(Some(status)) = self.child.try_wait()
+        {
+            self.exit_status = Some(status.to_string());
+        }
+    }
+
+    pub fn running(&self) -> bool {
+        self.exit_status.is_none()
+    }
+
+    pub fn stop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for CaptureSession {
+    fn drop(&mut self) {
+        if self.running() {
+            self.stop();
+        }
+    }
+}
+
+fn spawn_reader<R: io::Read + Send + 'static>(
+    reader: R,
+    tx: Sender<String>,
+    mut tee: Option<File>,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Some(file) = tee.as_mut() {
+                let _ = writeln!(file, "{}", line);
+            }
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
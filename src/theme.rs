@@ -0,0 +1,84 @@
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+
+// `--theme`: the UI is drawn with its dark-terminal colors, then each frame's
+// cells are remapped, so widgets never need to know about themes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    // No colors; highlighted cells are shown reversed.
+    Mono,
+}
+
+impl Theme {
+    pub const NAMES: &str = "dark, light, mono";
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::Dark),
+            "light" => Some(Self::Light),
+            "mono" => Some(Self::Mono),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, buffer: &mut Buffer) {
+        match self {
+            Self::Dark => {}
+            Self::Light => {
+                for cell in &mut buffer.content {
+                    cell.fg = light_fg(cell.fg);
+                }
+            }
+            Self::Mono => {
+                for cell in &mut buffer.content {
+                    if cell.bg != Color::Reset {
+                        cell.modifier.insert(Modifier::REVERSED);
+                    }
+                    cell.fg = Color::Reset;
+                    cell.bg = Color::Reset;
+                }
+            }
+        }
+    }
+}
+
+// Darker stand-ins for the colors that wash out on a light background.
+fn light_fg(color: Color) -> Color {
+    match color {
+        Color::White => Color::Black,
+        Color::Gray => Color::DarkGray,
+        Color::Yellow | Color::LightYellow => Color::Rgb(140, 100, 0),
+        Color::LightRed => Color::Red,
+        Color::LightGreen => Color::Green,
+        Color::LightBlue => Color::Blue,
+        Color::LightCyan => Color::Cyan,
+        Color::LightMagenta => Color::Magenta,
+        color => color,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn mono_keeps_highlights_visible_and_light_darkens_pale_text() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 2, 1));
+        buffer[(0, 0)].set_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+        buffer[(1, 0)].set_style(Style::default().fg(Color::LightBlue));
+        let mut light = buffer.clone();
+
+        Theme::Mono.apply(&mut buffer);
+        assert!(buffer[(0, 0)].modifier.contains(Modifier::REVERSED));
+        assert!(!buffer[(1, 0)].modifier.contains(Modifier::REVERSED));
+        assert_eq!(buffer[(1, 0)].fg, Color::Reset);
+
+        Theme::Light.apply(&mut light);
+        assert_eq!(light[(1, 0)].fg, Color::Blue);
+    }
+}
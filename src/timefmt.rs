@@ -0,0 +1,197 @@
+use std::sync::OnceLock;
+
+use crate::parser::{days_from_civil, month_number};
+
+// Extra timestamp patterns from the `[timestamps]` config section, tried in
+// order before the built-in syslog and ISO 8601 forms.
+static CUSTOM: OnceLock<Vec<TimeFormat>> = OnceLock::new();
+
+pub fn set_custom(formats: Vec<TimeFormat>) {
+    let _ = CUSTOM.set(formats);
+}
+
+// Seconds since the Unix epoch from the first custom pattern that matches the
+// start of `timestamp`. Patterns without a year use `default_year`, and those
+// without %z `default_offset` (seconds east of UTC).
+pub fn custom_seconds(timestamp: &str, default_year: i64, default_offset: i64) -> Option<i64> {
+    CUSTOM
+        .get()?
+        .iter()
+        .find_map(|format| format.read(timestamp, default_year, default_offset))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Year,
+    ShortYear,
+    Month,
+    MonthName,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    // Optional `.123` fraction, ignored.
+    Fraction,
+    Zone,
+    Space,
+    Literal(char),
+}
+
+// A strftime-style pattern: %Y %y %m %b %d %e %H %M %S %f %z and %%, with
+// literal characters matched exactly and a space matching any run of spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeFormat {
+    tokens: Vec<Token>,
+}
+
+impl TimeFormat {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            tokens.push(match c {
+                '%' => match chars.next() {
+                    Some('Y') => Token::Year,
+                    Some('y') => Token::ShortYear,
+                    Some('m') => Token::Month,
+                    Some('b') => Token::MonthName,
+                    Some('d') | Some('e') => Token::Day,
+                    Some('H') => Token::Hour,
+                    Some('M') => Token::Minute,
+                    Some('S') => Token::Second,
+                    Some('f') => Token::Fraction,
+                    Some('z') => Token::Zone,
+                    Some('%') => Token::Literal('%'),
+                    Some(other) => {
+                        return Err(format!("unsupported %{} in {:?}", other, pattern));
+                    }
+                    None => return Err(format!("trailing % in {:?}", pattern)),
+                },
+                c if c.is_whitespace() => Token::Space,
+                c => Token::Literal(c),
+            });
+        }
+        let has = |token: Token| tokens.contains(&token);
+        if !(has(Token::Month) || has(Token::MonthName)) || !has(Token::Day) || !has(Token::Hour) {
+            return Err(format!(
+                "{:?} needs at least a month, a day and an hour",
+                pattern
+            ));
+        }
+        Ok(Self { tokens })
+    }
+
+    fn read(&self, text: &str, default_year: i64, default_offset: i64) -> Option<i64> {
+        let mut rest = text.trim_start();
+        let (mut year, mut month, mut day) = (default_year, 0, 0);
+        let (mut hour, mut minute, mut second, mut offset) = (0, 0, 0, default_offset);
+        for token in &self.tokens {
+            match token {
+                Token::Year => year = take_number(&mut rest, 4)?,
+                Token::ShortYear => year = 2000 + take_number(&mut rest, 2)?,
+                Token::Month => month = take_number(&mut rest, 2)?,
+                Token::MonthName => {
+                    month = month_number(rest.get(..3)?)?;
+                    rest = &rest[3..];
+                }
+                Token::Day => {
+                    rest = rest.trim_start_matches(' ');
+                    day = take_number(&mut rest, 2)?;
+                }
+                Token::Hour => hour = take_number(&mut rest, 2)?,
+                Token::Minute => minute = take_number(&mut rest, 2)?,
+                Token::Second => second = take_number(&mut rest, 2)?,
+                Token::Fraction => {
+                    if let Some(after) = rest.strip_prefix(['.', ',']) {
+                        rest = after.trim_start_matches(|c: char| c.is_ascii_digit());
+                    }
+                }
+                Token::Zone => offset = take_zone(&mut rest)?,
+                Token::Space => {
+                    let trimmed = rest.trim_start();
+                    if trimmed.len() == rest.len() {
+                        return None;
+                    }
+                    rest = trimmed;
+                }
+                Token::Literal(c) => rest = rest.strip_prefix(*c)?,
+            }
+        }
+        let valid = (1..=12).contains(&month)
+            && (1..=31).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 61;
+        valid.then(|| {
+            days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset
+        })
+    }
+}
+
+// Up to `max` digits.
+fn take_number(rest: &mut &str, max: usize) -> Option<i64> {
+    let len = rest
+        .bytes()
+        .take(max)
+        .take_while(u8::is_ascii_digit)
+        .count();
+    let value = rest[..len].parse().ok()?;
+    *rest = &rest[len..];
+    Some(value)
+}
+
+// `Z`, `+HH`, `+HHMM` or `+HH:MM`, as seconds east of UTC.
+fn take_zone(rest: &mut &str) -> Option<i64> {
+    if let Some(after) = rest.strip_prefix('Z') {
+        *rest = after;
+        return Some(0);
+    }
+    let sign = match rest.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    *rest = &rest[1..];
+    let hours = take_number(rest, 2)?;
+    if let Some(after) = rest.strip_prefix(':') {
+        *rest = after;
+    }
+    let minutes = take_number(rest, 2).unwrap_or(0);
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_custom_patterns() {
+        let secs =
+            |pattern: &str, text: &str| TimeFormat::parse(pattern).unwrap().read(text, 2026, 0);
+        let expected = days_from_civil(2026, 2, 11) * 86_400 + 20 * 3600 + 5;
+        assert_eq!(
+            secs("%d/%m/%Y %H:%M:%S", "11/02/2026 20:00:05 host"),
+            Some(expected)
+        );
+        assert_eq!(
+            secs("%Y-%m-%d %H:%M:%S%f", "2026-02-11  20:00:05,123 fw"),
+            Some(expected)
+        );
+        assert_eq!(
+            secs("%b %e %H:%M:%S %z", "Feb  11 22:00:05 +02:00"),
+            Some(expected)
+        );
+        assert_eq!(secs("%d/%m/%Y %H:%M:%S", "Feb 11 20:00:05 host"), None);
+        // Local stamps without %z take the default offset; %z wins over it.
+        let local =
+            |pattern: &str, text: &str| TimeFormat::parse(pattern).unwrap().read(text, 2026, 7_200);
+        assert_eq!(local("%b %e %H:%M:%S", "Feb 11 22:00:05"), Some(expected));
+        assert_eq!(
+            local("%b %e %H:%M:%S %z", "Feb 11 20:00:05 Z"),
+            Some(expected)
+        );
+
+        assert!(TimeFormat::parse("%H:%M").is_err());
+        assert!(TimeFormat::parse("%d.%m.%Y %H:%M:%Q").is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+
+use crate::LogEntry;
+use crate::parser::{civil_from_days, entry_epoch_seconds};
+use crate::timerange::local_offset;
+
+// Bucket sizes the drill-down chart picks from: the smallest that covers the
+// source's activity in at most MAX_BUCKETS rows.
+const STEPS: [i64; 8] = [
+    60,
+    300,
+    900,
+    3_600,
+    6 * 3_600,
+    86_400,
+    7 * 86_400,
+    30 * 86_400,
+];
+const MAX_BUCKETS: usize = 12;
+// Width of the longest bar, in cells.
+const BAR_WIDTH: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: i64,
+    pub allowed: usize,
+    pub blocked: usize,
+}
+
+impl Bucket {
+    fn mostly_blocked(&self) -> bool {
+        self.blocked > self.allowed
+    }
+}
+
+// ALLOW and BLOCK/REJECT counts per time bucket, oldest first. Empty buckets
+// between active ones are kept so gaps show; AUDIT lines are not counted.
+pub fn action_buckets<'a>(
+    entries: impl IntoIterator<Item = &'a LogEntry>,
+    now: i64,
+) -> Vec<Bucket> {
+    let mut stamped: Vec<(i64, bool)> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let allowed = match entry.action.as_str() {
+                "ALLOW" => true,
+                "BLOCK" | "REJECT" => false,
+                _ => return None,
+            };
+            Some((entry_epoch_seconds(&entry.timestamp, now)?, allowed))
+        })
+        .collect();
+    let (Some(first), Some(last)) = (
+        stamped.iter().map(|(secs, _)| *secs).min(),
+        stamped.iter().map(|(secs, _)| *secs).max(),
+    ) else {
+        return Vec::new();
+    };
+    let step = STEPS
+        .into_iter()
+        .find(|step| (last.div_euclid(*step) - first.div_euclid(*step)) < MAX_BUCKETS as i64)
+        .unwrap_or(STEPS[STEPS.len() - 1]);
+    let origin = first.div_euclid(step);
+    let count = (last.div_euclid(step) - origin + 1) as usize;
+    let mut buckets: Vec<Bucket> = (0..count)
+        .map(|pos| Bucket {
+            start: (origin + pos as i64) * step,
+            allowed: 0,
+            blocked: 0,
+        })
+        .collect();
+    stamped.sort_unstable();
+    for (secs, allowed) in stamped {
+        let bucket = &mut buckets[(secs.div_euclid(step) - origin) as usize];
+        if allowed {
+            bucket.allowed += 1;
+        } else {
+            bucket.blocked += 1;
+        }
+    }
+    buckets
+}
+
+// One row per bucket: green ALLOW and red BLOCK bars scaled to the busiest
+// bucket, and a note where the majority flips from one to the other.
+pub fn chart_lines(buckets: &[Bucket]) -> Vec<Line<'static>> {
+    let busiest = buckets
+        .iter()
+        .map(|bucket| bucket.allowed + bucket.blocked)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let cells = |count: usize| (count * BAR_WIDTH).div_ceil(busiest);
+    let mut majority = None;
+    buckets
+        .iter()
+        .map(|bucket| {
+            let (allow_cells, block_cells) = (cells(bucket.allowed), cells(bucket.blocked));
+            let mut spans = vec![
+                Span::raw(format!("  {}  ", label(bucket.start))),
+                Span::styled("█".repeat(allow_cells), Style::default().fg(Color::Green)),
+                Span::styled("█".repeat(block_cells), Style::default().fg(Color::Red)),
+                Span::raw(format!(
+                    "{} {} allow / {} block",
+                    " ".repeat((BAR_WIDTH + 1).saturating_sub(allow_cells + block_cells)),
+                    bucket.allowed,
+                    bucket.blocked
+                )),
+            ];
+            if bucket.allowed + bucket.blocked > 0 {
+                let blocked = bucket.mostly_blocked();
+                if majority.is_some_and(|was| was != blocked) {
+                    spans.push(Span::styled(
+                        if blocked {
+                            "  ← now mostly blocked"
+                        } else {
+                            "  ← now mostly allowed"
+                        },
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+                majority = Some(blocked);
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+// "2026-02-11 20:00" for the bucket start, on the log's local clock.
+fn label(secs: i64) -> String {
+    let secs = secs + local_offset();
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let clock = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        clock / 3_600,
+        clock % 3_600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_ufw_line;
+
+    #[test]
+    fn buckets_actions_and_marks_the_flip() {
+        let entry = |time: &str, action: &str| {
+            parse_ufw_line(&format!(
+                "2026-02-11T{time}+00:00 h kernel: [UFW {action}] IN=eth0 OUT= SRC=1.2.3.4 DST=10.0.0.1"
+            ))
+            .unwrap()
+        };
+        let entries = [
+            entry("20:00:10", "ALLOW"),
+            entry("20:00:50", "ALLOW"),
+            entry("20:03:00", "AUDIT"),
+            entry("20:05:00", "BLOCK"),
+            entry("20:05:30", "REJECT"),
+        ];
+        let buckets = action_buckets(&entries, 1_770_840_000);
+        assert_eq!(buckets.len(), 6);
+        assert_eq!((buckets[0].allowed, buckets[0].blocked), (2, 0));
+        assert_eq!((buckets[5].allowed, buckets[5].blocked), (0, 2));
+
+        let lines = chart_lines(&buckets);
+        let text: Vec<String> = lines.iter().map(ToString::to_string).collect();
+        assert!(text[0].starts_with("  2026-02-11 20:00  "));
+        assert!(text[5].ends_with("0 allow / 2 block  ← now mostly blocked"));
+        assert!(!text[0].contains('←'));
+    }
+}
@@ -0,0 +1,274 @@
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::parser::{civil_from_days, days_from_civil, month_number};
+
+// Seconds east of UTC of the local clock that syslog stamps and typed times
+// are in. Set once at startup; UTC until then, so tests do not depend on TZ.
+static LOCAL_OFFSET: OnceLock<i64> = OnceLock::new();
+
+const HELP: &str = "expected last 1h, today, yesterday or FROM..TO";
+
+// The F7 filter: a window relative to now, or fixed bounds (inclusive, in
+// epoch seconds) where either side may be open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeRange {
+    // The last N seconds; slides forward as new entries arrive.
+    Last(i64),
+    Between(Option<i64>, Option<i64>),
+}
+
+impl TimeRange {
+    // Accepts `last 30m` (s, m, h, d, w), `today`, `yesterday` and
+    // `FROM..TO` where each side is `HH:MM[:SS]` (today), `YYYY-MM-DD[ HH:MM[:SS]]`
+    // or `Mon DD[ HH:MM[:SS]]`. A date-only TO covers that whole day. Days and
+    // times are on the clock `offset` seconds east of UTC; bounds are UTC.
+    pub fn parse(text: &str, now: i64, offset: i64) -> Result<Self, String> {
+        let text = text.trim();
+        let lower = text.to_ascii_lowercase();
+        let midnight = (now + offset).div_euclid(86_400) * 86_400 - offset;
+        match lower.as_str() {
+            "today" => return Ok(Self::Between(Some(midnight), None)),
+            "yesterday" => return Ok(Self::Between(Some(midnight - 86_400), Some(midnight - 1))),
+            _ => {}
+        }
+        if let Some(span) = lower.strip_prefix("last") {
+            return parse_span(span.trim())
+                .map(Self::Last)
+                .ok_or_else(|| format!("Invalid duration in {:?} ({})", text, HELP));
+        }
+        let Some((from, to)) = text.split_once("..") else {
+            return Err(format!("Invalid time range {:?} ({})", text, HELP));
+        };
+        let bound = |side: &str, end: bool| -> Result<Option<i64>, String> {
+            let side = side.trim();
+            if side.is_empty() {
+                return Ok(None);
+            }
+            let (secs, has_clock) = parse_point(side, now + offset)
+                .ok_or_else(|| format!("Invalid time {:?} ({})", side, HELP))?;
+            let secs = secs - offset;
+            Ok(Some(if end && !has_clock {
+                secs + 86_399
+            } else {
+                secs
+            }))
+        };
+        match (bound(from, false)?, bound(to, true)?) {
+            (None, None) => Err(format!("Empty time range ({})", HELP)),
+            (Some(from), Some(to)) if from > to => {
+                Err(format!("Time range {:?} ends before it starts", text))
+            }
+            (from, to) => Ok(Self::Between(from, to)),
+        }
+    }
+
+    pub fn contains(self, secs: i64, now: i64) -> bool {
+        match self {
+            Self::Last(span) => now.checked_sub(span).is_none_or(|start| secs >= start),
+            Self::Between(from, to) => {
+                from.is_none_or(|from| secs >= from) && to.is_none_or(|to| secs <= to)
+            }
+        }
+    }
+}
+
+pub fn now_seconds() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+pub fn local_offset() -> i64 {
+    LOCAL_OFFSET.get().copied().unwrap_or(0)
+}
+
+// Reads the local offset from `date +%z`, which follows TZ and
+// /etc/localtime; UTC when that fails.
+pub fn detect_local_offset() {
+    let offset = Command::new("date")
+        .arg("+%z")
+        .output()
+        .ok()
+        .and_then(|output| parse_offset(String::from_utf8_lossy(&output.stdout).trim()));
+    let _ = LOCAL_OFFSET.set(offset.unwrap_or(0));
+}
+
+// "+0200" or "-0530" as seconds east of UTC.
+fn parse_offset(text: &str) -> Option<i64> {
+    let sign = match text.as_bytes().first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = &text[1..];
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours = digits[..2].parse::<i64>().ok()?;
+    let minutes = digits[2..].parse::<i64>().ok()?;
+    Some(sign * (hours * 3_600 + minutes * 60))
+}
+
+// "90s", "30m", "1h", "2d", "1w"; a bare number is minutes.
+fn parse_span(span: &str) -> Option<i64> {
+    let split = span
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(span.len());
+    let value = span[..split].parse::<i64>().ok()?;
+    let unit = match span[split..].trim() {
+        "s" => 1,
+        "" | "m" | "min" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return None,
+    };
+    // Spans past what epoch seconds can hold are rejected rather than wrapped.
+    value.checked_mul(unit)
+}
+
+// Seconds for one side of a range, and whether it named a time of day.
+fn parse_point(text: &str, now: i64) -> Option<(i64, bool)> {
+    let today = now.div_euclid(86_400);
+    let (current_year, _, _) = civil_from_days(today);
+    let mut parts = text.split([' ', 'T']).filter(|p| !p.is_empty());
+    let first = parts.next()?;
+    let day = if first.contains(':') {
+        return Some((today * 86_400 + clock(first)?, true));
+    } else if let Some(month) = month_number(first) {
+        let day = parts.next()?.parse::<i64>().ok()?;
+        // Like syslog stamps: the current year unless that is in the future.
+        let day_this_year = days_from_civil(current_year, month, day);
+        if day_this_year > today + 1 {
+            days_from_civil(current_year - 1, month, day)
+        } else {
+            day_this_year
+        }
+    } else {
+        let mut date = first.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+        let (Some(Some(year)), Some(Some(month)), Some(Some(day))) =
+            (date.next(), date.next(), date.next())
+        else {
+            return None;
+        };
+        days_from_civil(year, month, day)
+    };
+    match parts.next() {
+        Some(time) if parts.next().is_none() => Some((day * 86_400 + clock(time)?, true)),
+        Some(_) => None,
+        None => Some((day * 86_400, false)),
+    }
+}
+
+// "HH:MM" or "HH:MM:SS" as seconds after midnight.
+fn clock(text: &str) -> Option<i64> {
+    let mut parts = text.split(':').map(|p| p.parse::<i64>().ok());
+    let hours = parts.next()??;
+    let minutes = parts.next()??;
+    let seconds = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() || hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+    Some(hours * 3_600 + minutes * 60 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_relative_and_absolute_ranges() {
+        // 2026-02-11 20:21:00 UTC
+        let now = days_from_civil(2026, 2, 11) * 86_400 + 20 * 3_600 + 21 * 60;
+        let midnight = days_from_civil(2026, 2, 11) * 86_400;
+
+        assert_eq!(
+            TimeRange::parse("last 1h", now, 0),
+            Ok(TimeRange::Last(3_600))
+        );
+        assert_eq!(
+            TimeRange::parse("LAST 90s", now, 0),
+            Ok(TimeRange::Last(90))
+        );
+        assert_eq!(
+            TimeRange::parse("today", now, 0),
+            Ok(TimeRange::Between(Some(midnight), None))
+        );
+        assert_eq!(
+            TimeRange::parse("20:00..20:15", now, 0),
+            Ok(TimeRange::Between(
+                Some(midnight + 72_000),
+                Some(midnight + 72_900)
+            ))
+        );
+        assert_eq!(
+            TimeRange::parse("2026-02-10..2026-02-10", now, 0),
+            Ok(TimeRange::Between(
+                Some(midnight - 86_400),
+                Some(midnight - 1)
+            ))
+        );
+        assert_eq!(
+            TimeRange::parse("Feb 11 20:00..", now, 0),
+            Ok(TimeRange::Between(Some(midnight + 72_000), None))
+        );
+        // A December date in February belongs to last year.
+        assert_eq!(
+            TimeRange::parse("Dec 31..", now, 0),
+            Ok(TimeRange::Between(
+                Some(days_from_civil(2025, 12, 31) * 86_400),
+                None
+            ))
+        );
+
+        assert!(TimeRange::parse("last 1y", now, 0).is_err());
+        assert!(TimeRange::parse("..", now, 0).is_err());
+        assert!(TimeRange::parse("21:00..20:00", now, 0).is_err());
+        assert!(TimeRange::parse("soon", now, 0).is_err());
+        assert!(TimeRange::parse("last 9223372036854775807w", now, 0).is_err());
+
+        assert!(TimeRange::Last(3_600).contains(now - 3_600, now));
+        assert!(!TimeRange::Last(3_600).contains(now - 3_601, now));
+        assert!(TimeRange::Last(i64::MAX).contains(0, -1));
+    }
+
+    #[test]
+    fn compares_local_stamps_and_days_in_utc() {
+        use crate::parser::entry_seconds_at;
+
+        // 2026-02-11 23:30 UTC is already 01:30 on the 12th at UTC+2.
+        let offset = 7_200;
+        let now = days_from_civil(2026, 2, 11) * 86_400 + 23 * 3_600 + 30 * 60;
+        let local_midnight = days_from_civil(2026, 2, 12) * 86_400 - offset;
+
+        assert_eq!(
+            TimeRange::parse("today", now, offset),
+            Ok(TimeRange::Between(Some(local_midnight), None))
+        );
+        assert_eq!(
+            TimeRange::parse("01:00..", now, offset),
+            Ok(TimeRange::Between(Some(local_midnight + 3_600), None))
+        );
+
+        // A syslog stamp written 20 minutes ago, and one from the local yesterday.
+        let recent = entry_seconds_at("Feb 12 01:10:00", now, offset).unwrap();
+        assert_eq!(recent, now - 20 * 60);
+        assert!(TimeRange::Last(3_600).contains(recent, now));
+        let today = TimeRange::parse("today", now, offset).unwrap();
+        assert!(today.contains(recent, now));
+        let late = entry_seconds_at("Feb 11 23:59:00", now, offset).unwrap();
+        assert!(!today.contains(late, now));
+        // Stamps with their own zone keep it.
+        assert_eq!(
+            entry_seconds_at("2026-02-11T23:00:00+00:00", now, offset),
+            Some(now - 30 * 60)
+        );
+
+        assert_eq!(parse_offset("+0200"), Some(7_200));
+        assert_eq!(parse_offset("-0530"), Some(-19_800));
+        assert_eq!(parse_offset("UTC"), None);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::LogEntry;
+use crate::geoip::GeoIp;
+use crate::json::quote;
+use crate::parser::{LogFormat, civil_from_days, days_from_civil, parse_line, timestamp_seconds};
+use crate::rotation::{read_log, rotated_files};
+
+// Ports, sources and countries listed per week; the rest only count towards the total.
+const TOP_KEYS: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Default)]
+struct WeekCounts {
+    total: usize,
+    actions: HashMap<String, usize>,
+    ports: HashMap<u16, usize>,
+    sources: HashMap<String, usize>,
+    countries: HashMap<String, usize>,
+}
+
+// Per-ISO-week counts by action, destination port, source IP and, with a
+// GeoIP database, source country for the last `weeks` weeks, rendered as CSV
+// or JSON.
+pub fn trend_report(
+    log_path: &Path,
+    format: LogFormat,
+    weeks: u32,
+    output: TrendFormat,
+    mut geoip: Option<&mut GeoIp>,
+) -> Result<String, String> {
+    let today = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64 / 86_400)
+        .unwrap_or_default();
+    let oldest_day = week_monday(today) - 7 * (i64::from(weeks.max(1)) - 1);
+    let (year, _, _) = civil_from_days(today);
+
+    let mut by_week: BTreeMap<String, WeekCounts> = BTreeMap::new();
+    for path in rotated_files(log_path) {
+        let contents =
+            read_log(&path).map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        for entry in contents.lines().filter_map(|line| parse_line(line, format)) {
+            let Some(day) = entry_day(&entry, year, today) else {
+                continue;
+            };
+            if day < oldest_day {
+                continue;
+            }
+            let counts = by_week.entry(iso_week_label(day)).or_default();
+            counts.total += 1;
+            *counts.actions.entry(entry.action.clone()).or_insert(0) += 1;
+            if let Some(port) = entry.dst_port {
+                *counts.ports.entry(port).or_insert(0) += 1;
+            }
+            if let Some(src) = entry.src_ip.as_deref() {
+                *counts.sources.entry(src.to_string()).or_insert(0) += 1;
+                if let Some(country) = geoip.as_mut().and_then(|geoip| geoip.country(src)) {
+                    *counts.countries.entry(country).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    Ok(match output {
+        TrendFormat::Csv => trend_csv(&by_week),
+        TrendFormat::Json => trend_json(&by_week),
+    })
+}
+
+// Syslog stamps carry no year: assume the current one, or the previous one
+// when that would put the entry in the future.
+fn entry_day(entry: &LogEntry, current_year: i64, today: i64) -> Option<i64> {
+    let day = timestamp_seconds(&entry.timestamp, current_year)?.div_euclid(86_400);
+    if day > today + 1 {
+        return Some(timestamp_seconds(&entry.timestamp, current_year - 1)?.div_euclid(86_400));
+    }
+    Some(day)
+}
+
+fn top<K: Ord + Clone>(counts: &HashMap<K, usize>) -> Vec<(K, usize)> {
+    let mut rows: Vec<(K, usize)> = counts.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    rows.truncate(TOP_KEYS);
+    rows
+}
+
+fn trend_csv(by_week: &BTreeMap<String, WeekCounts>) -> String {
+    let mut out = String::from("week,dimension,key,count\n");
+    for (week, counts) in by_week {
+        out.push_str(&format!("{},total,,{}\n", week, counts.total));
+        for (action, count) in top(&counts.actions) {
+            out.push_str(&format!("{},action,{},{}\n", week, action, count));
+        }
+        for (port, count) in top(&counts.ports) {
+            out.push_str(&format!("{},port,{},{}\n", week, port, count));
+        }
+        for (src, count) in top(&counts.sources) {
+            out.push_str(&format!("{},source,{},{}\n", week, src, count));
+        }
+        for (country, count) in top(&counts.countries) {
+            out.push_str(&format!("{},country,{},{}\n", week, country, count));
+        }
+    }
+    out
+}
+
+fn trend_json(by_week: &BTreeMap<String, WeekCounts>) -> String {
+    let object = |rows: Vec<(String, usize)>| {
+        let fields: Vec<String> = rows
+            .iter()
+            .map(|(key, count)| format!("{}:{}", quote(key), count))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    };
+    let weeks: Vec<String> = by_week
+        .iter()
+        .map(|(week, counts)| {
+            let ports = top(&counts.ports)
+                .into_iter()
+                .map(|(port, count)| (port.to_string(), count))
+                .collect();
+            format!(
+                "{{\"week\":{},\"total\":{},\"actions\":{},\"ports\":{},\"sources\":{},\"countries\":{}}}",
+                quote(week),
+                counts.total,
+                object(top(&counts.actions)),
+                object(ports),
+                object(top(&counts.sources)),
+                object(top(&counts.countries)),
+            )
+        })
+        .collect();
+    format!("[{}]\n", weeks.join(",\n"))
+}
+
+fn week_monday(day: i64) -> i64 {
+    // 1970-01-01 was a Thursday.
+    day - (day + 3).rem_euclid(7)
+}
+
+// "2026-W07": the ISO week-year is the year of the week's Thursday.
+fn iso_week_label(day: i64) -> String {
+    let thursday = week_monday(day) + 3;
+    let (year, _, _) = civil_from_days(thursday);
+    let week = (thursday - days_from_civil(year, 1, 1)) / 7 + 1;
+    format!("{}-W{:02}", year, week)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_iso_weeks() {
+        assert_eq!(civil_from_days(days_from_civil(2026, 2, 11)), (2026, 2, 11));
+        assert_eq!(iso_week_label(days_from_civil(2026, 2, 11)), "2026-W07");
+        // 2027-01-01 is a Friday and still belongs to 2026-W53.
+        assert_eq!(iso_week_label(days_from_civil(2027, 1, 1)), "2026-W53");
+        assert_eq!(iso_week_label(days_from_civil(2024, 12, 30)), "2025-W01");
+    }
+
+    #[test]
+    fn renders_csv_counts() {
+        let mut by_week = BTreeMap::new();
+        let mut counts = WeekCounts {
+            total: 2,
+            ..Default::default()
+        };
+        counts.actions.insert("BLOCK".to_string(), 2);
+        counts.ports.insert(22, 2);
+        counts.sources.insert("1.2.3.4".to_string(), 2);
+        counts.countries.insert("LV".to_string(), 2);
+        by_week.insert("2026-W07".to_string(), counts);
+        assert_eq!(
+            trend_csv(&by_week),
+            "week,dimension,key,count\n2026-W07,total,,2\n2026-W07,action,BLOCK,2\n2026-W07,port,22,2\n2026-W07,source,1.2.3.4,2\n2026-W07,country,LV,2\n"
+        );
+        assert!(
+            trend_json(&by_week)
+                .ends_with(",\"sources\":{\"1.2.3.4\":2},\"countries\":{\"LV\":2}}]\n")
+        );
+    }
+}
@@ -0,0 +1,858 @@
+// End-to-end tests: the full App driven by scripted key and mouse events,
+// rendered into ratatui's TestBackend and asserted on the screen text.
+
+use std::path::PathBuf;
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+
+use crate::countries::CountrySummary;
+use crate::keymap::{self, Keymap};
+use crate::mode::Mode;
+use crate::render_cache::Breakpoints;
+use crate::{Action, App, FilterField, help, pager, ui};
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 30;
+
+struct Harness {
+    app: App,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    fn new(fixture: &str) -> Self {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(fixture);
+        Self::with_app(App::new(path))
+    }
+
+    fn with_app(mut app: App) -> Self {
+        while app.ingest_pending() {
+            app.ingest_tick();
+        }
+        let terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        let mut harness = Self { app, terminal };
+        harness.screen();
+        harness
+    }
+
+    // Sends an event and redraws, as one turn of the event loop would.
+    fn send(&mut self, event: Event) -> Action {
+        let action = self.app.handle_event(&event);
+        self.screen();
+        action
+    }
+
+    fn press(&mut self, code: KeyCode) -> Action {
+        self.send(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c));
+        }
+    }
+
+    fn mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) {
+        let mouse = MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        self.send(Event::Mouse(mouse));
+    }
+
+    fn click(&mut self, column: u16, row: u16) {
+        self.mouse(MouseEventKind::Down(MouseButton::Left), column, row);
+    }
+
+    fn screen(&mut self) -> Vec<String> {
+        self.terminal
+            .draw(|frame| ui(frame, &mut self.app))
+            .unwrap();
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn find(&mut self, text: &str) -> Option<(u16, u16)> {
+        self.screen().iter().enumerate().find_map(|(y, line)| {
+            let byte = line.find(text)?;
+            Some((line[..byte].chars().count() as u16, y as u16))
+        })
+    }
+
+    fn shows(&mut self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+}
+
+#[test]
+fn renders_wan_rows_newest_first() {
+    let mut harness = Harness::new("ufw.log");
+    let screen = harness.screen();
+    assert!(screen[0].ends_with("3/5"));
+    let rows: Vec<&String> = screen
+        .iter()
+        .filter(|line| line.contains("Feb 11"))
+        .collect();
+    assert!(rows[0].contains("1.1.1.1"));
+    assert!(rows[1].contains("8.8.8.8"));
+    assert!(rows[2].contains("45.33.2.1"));
+    // Local sources and other interfaces are hidden by default.
+    assert!(!harness.shows("192.168.1.10"));
+    assert!(!harness.shows("9.9.9.9"));
+}
+
+#[test]
+fn separators_mark_where_rotated_files_begin() {
+    let dir = std::env::temp_dir().join(format!("ufw-separators-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let line = |time: &str, src: &str| {
+        format!(
+            "Feb 11 {time} h kernel: [UFW BLOCK] IN=eth0 OUT= SRC={src} DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n"
+        )
+    };
+    let log = dir.join("ufw.log");
+    std::fs::write(&log, line("20:00:00", "3.3.3.3")).unwrap();
+    std::fs::write(dir.join("ufw.log.1"), line("19:00:00", "2.2.2.2")).unwrap();
+    let mut app = App::new(log);
+    app.include_rotated = true;
+    app.reload();
+    let mut harness = Harness::with_app(app);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let (_, separator) = harness.find("── ufw.log.1 · rotated 20").unwrap();
+    let (x, older) = harness.find("2.2.2.2").unwrap();
+    assert_eq!(older, separator + 1);
+    assert_eq!(harness.find("3.3.3.3").unwrap().1 + 1, separator);
+    // The separator line is not a row; the one below it is.
+    harness.click(x, separator);
+    assert_eq!(harness.app.selected, 0);
+    harness.click(x, older);
+    assert_eq!(harness.app.selected, 1);
+
+    // Oldest first, the current log's block gets the separator.
+    harness.press(KeyCode::Char('v'));
+    assert!(harness.shows("── ufw.log · current log"));
+}
+
+#[test]
+fn selection_moves_and_detail_pane_follows() {
+    let mut harness = Harness::new("ufw.log");
+    // Parsed fields by default, in columns filled top to bottom.
+    let (x, y) = harness.find("time       Feb 11 20:00:10 host").unwrap();
+    let screen = harness.screen();
+    let below: String = screen[y as usize + 1].chars().skip(x as usize).collect();
+    assert!(below.starts_with("action     ALLOW"));
+    assert!(harness.shows("│ src  1.1.1.1"));
+    assert!(harness.shows("flags    SYN"));
+
+    // `J` shows the raw line; kernel uptimes identify the rows.
+    harness.press(KeyCode::Char('J'));
+    assert!(harness.shows("[12347.678] [UFW ALLOW]"));
+    assert!(harness.shows("len 60 · ttl 57 · tos 0x00 · window 64240 · flags SYN · mac"));
+    harness.press(KeyCode::Down);
+    harness.press(KeyCode::Char('j'));
+    assert!(harness.shows("[12345.678] [UFW BLOCK]"));
+    harness.press(KeyCode::Up);
+    assert!(harness.shows("[12346.678] [UFW BLOCK]"));
+}
+
+#[test]
+fn slash_finds_rows_without_hiding_the_others() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('/'));
+    harness.type_text("45.33");
+    assert!(harness.shows("Find: 45.33_ (1 matching rows"));
+    assert_eq!(harness.app.selected, 2);
+    harness.press(KeyCode::Esc);
+    assert_eq!(harness.app.selected, 0);
+
+    harness.press(KeyCode::Char('/'));
+    harness.type_text("UFW BLOCK");
+    harness.press(KeyCode::Enter);
+    assert!(harness.shows("Found 'UFW BLOCK' in 2 rows"));
+    assert!(harness.shows("1.1.1.1"));
+    assert_eq!(harness.app.selected, 1);
+    harness.press(KeyCode::Char('n'));
+    assert_eq!(harness.app.selected, 2);
+    harness.press(KeyCode::Char('n'));
+    assert_eq!(harness.app.selected, 1);
+    assert!(harness.shows("(wrapped)"));
+    harness.press(KeyCode::Char('N'));
+    assert_eq!(harness.app.selected, 2);
+    // Esc ends the search; `n` snapshots again.
+    harness.press(KeyCode::Esc);
+    harness.press(KeyCode::Char('n'));
+    assert_eq!(harness.app.selected, 2);
+    assert!(harness.app.find_term.is_none());
+}
+
+#[test]
+fn dragging_over_the_detail_line_copies_the_span() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('J'));
+    for _ in 0..10 {
+        harness.press(KeyCode::Right);
+    }
+    let (x, y) = harness.find("ID=1234 DF").unwrap();
+    harness.click(x, y);
+    harness.mouse(MouseEventKind::Drag(MouseButton::Left), x + 6, y);
+    assert_eq!(
+        harness.app.detail_drag,
+        Some((80 + x as usize - 1, 80 + x as usize + 5))
+    );
+    harness.mouse(MouseEventKind::Up(MouseButton::Left), x + 6, y);
+    assert_eq!(harness.app.status.text(), "Copied \"ID=1234\" (OSC52)");
+    assert_eq!(harness.app.detail_drag, None);
+}
+
+#[test]
+fn editing_a_filter_narrows_the_table() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::F(2));
+    harness.type_text("443");
+    assert!(harness.shows("Edit port: 443"));
+    harness.press(KeyCode::Enter);
+    assert!(harness.shows("F2 port=443 (−4)"));
+    assert!(harness.shows("(1 active)"));
+    assert!(harness.shows("1/5"));
+    assert!(!harness.shows("45.33.2.1"));
+
+    harness.press(KeyCode::Char('c'));
+    assert!(harness.shows("3/5"));
+}
+
+#[test]
+fn tab_completes_filter_values_from_loaded_entries() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::F(3));
+    harness.type_text("1");
+    harness.press(KeyCode::Tab);
+    // The destination shared by most rows comes first.
+    assert!(harness.shows("Edit ip: 10.0.0.1 (Tab 1/4,"));
+    harness.press(KeyCode::Tab);
+    assert_eq!(harness.app.input_buffer, "1.1.1.1");
+    harness.press(KeyCode::BackTab);
+    harness.press(KeyCode::BackTab);
+    assert_eq!(harness.app.input_buffer, "192.168.1.10");
+
+    harness.press(KeyCode::Esc);
+    harness.press(KeyCode::F(4));
+    harness.type_text("a");
+    harness.press(KeyCode::Tab);
+    harness.press(KeyCode::Enter);
+    assert_eq!(harness.app.filters.action, "ALLOW");
+}
+
+#[test]
+fn clicking_chips_toggles_local_rows_and_interfaces() {
+    let mut harness = Harness::new("ufw.log");
+    let (x, y) = harness.find("[local]").unwrap();
+    harness.click(x + 1, y);
+    assert!(harness.shows("192.168.1.10"));
+
+    assert!(harness.shows("[eth0(4)] docker0(1)"));
+    let (x, y) = harness.find("docker0").unwrap();
+    harness.click(x + 1, y);
+    assert!(harness.shows("9.9.9.9"));
+    assert!(!harness.shows("45.33.2.1"));
+}
+
+#[test]
+fn star_pins_the_selected_interface_first() {
+    let path = std::env::temp_dir().join(format!("ufw-interfaces-{}.ini", std::process::id()));
+    let mut harness = Harness::new("ufw.log");
+    harness.app.interfaces_path = Some(path.clone());
+    harness.press(KeyCode::Char('.'));
+    assert!(harness.shows("eth0(3) [docker0(1)]"));
+    harness.press(KeyCode::Char('*'));
+    assert!(harness.shows("[*docker0(1)] eth0(3)"));
+    // Cycling follows the chip order, so `.` moves on to eth0.
+    harness.press(KeyCode::Char('.'));
+    assert!(harness.shows("*docker0(1) [eth0(3)]"));
+    assert_eq!(crate::interfaces::load_pinned(&path).unwrap(), ["docker0"]);
+
+    harness.press(KeyCode::Char(','));
+    harness.press(KeyCode::Char('*'));
+    assert!(harness.shows("eth0(3) [docker0(1)]"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn capital_o_switches_source_and_can_keep_the_old_entries() {
+    let path = std::env::temp_dir().join(format!("ufw-open-{}.log", std::process::id()));
+    std::fs::write(
+        &path,
+        "Feb 11 20:01:00 host kernel: [UFW BLOCK] IN=eth0 OUT= SRC=203.0.113.9 DST=10.0.0.1 PROTO=TCP SPT=1 DPT=22\n",
+    )
+    .unwrap();
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::F(2));
+    harness.type_text("22");
+    harness.press(KeyCode::Enter);
+
+    harness.press(KeyCode::Char('O'));
+    assert!(harness.shows("Open Source"));
+    let typed = harness.app.source_prompt.as_ref().unwrap().input.len();
+    for _ in 0..typed {
+        harness.press(KeyCode::Backspace);
+    }
+    harness.type_text(&path.display().to_string());
+    harness.press(KeyCode::Tab);
+    assert!(harness.shows("keep current entries: yes"));
+    harness.press(KeyCode::Enter);
+    while harness.app.ingest_pending() {
+        harness.app.ingest_tick();
+    }
+    harness.screen();
+    // The port filter still applies across both files.
+    assert!(harness.shows("File"));
+    assert!(harness.shows("203.0.113.9"));
+    assert!(harness.shows("45.33.2.1"));
+    assert!(!harness.shows("8.8.8.8"));
+
+    harness.press(KeyCode::Char('O'));
+    harness.press(KeyCode::Enter);
+    while harness.app.ingest_pending() {
+        harness.app.ingest_tick();
+    }
+    harness.screen();
+    assert!(!harness.shows("45.33.2.1"));
+    assert!(!harness.shows("File"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn hash_shows_only_blocklisted_sources() {
+    let path = std::env::temp_dir().join(format!("ufw-drop-{}.txt", std::process::id()));
+    std::fs::write(&path, "; DROP list\n45.33.0.0/16 ; SBL1\n").unwrap();
+    let mut harness = Harness::new("ufw.log");
+    harness.app.blocklists =
+        Some(crate::blocklist::Blocklists::load(std::slice::from_ref(&path)).unwrap());
+    harness.screen();
+    assert!(harness.shows("[1 listed]"));
+    assert!(harness.shows("45.33.2.1#"));
+
+    harness.press(KeyCode::Char('#'));
+    assert!(harness.shows("[listed only: 1]"));
+    assert!(harness.shows("blocklisted (ufw-drop-"));
+    assert!(!harness.shows("8.8.8.8"));
+    harness.press(KeyCode::Char('#'));
+    assert!(harness.shows("8.8.8.8"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rebound_keys_drive_the_table() {
+    let mut harness = Harness::new("ufw.log");
+    harness.app.keymap = Keymap::new(&[
+        keymap::parse_binding("up", "j").unwrap(),
+        keymap::parse_binding("down", "k").unwrap(),
+        keymap::parse_binding("stats", "ctrl+s").unwrap(),
+    ]);
+    harness.press(KeyCode::Char('k'));
+    harness.press(KeyCode::Char('k'));
+    assert_eq!(harness.app.selected, 2);
+    harness.press(KeyCode::Char('j'));
+    assert_eq!(harness.app.selected, 1);
+    harness.press(KeyCode::Char('s'));
+    assert!(harness.shows("UFW Events"));
+    harness.send(Event::Key(KeyEvent::new(
+        KeyCode::Char('s'),
+        KeyModifiers::CONTROL,
+    )));
+    assert!(harness.shows("Statistics"));
+}
+
+#[test]
+fn question_mark_lists_keys_and_filter_syntax() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('?'));
+    assert!(harness.shows("Help (Esc to close)"));
+    assert!(harness.shows("TABLE KEYS"));
+    assert!(harness.shows("Reload the log"));
+    assert!(!harness.shows("syn,!ack"));
+    for _ in 0..8 {
+        harness.press(KeyCode::PageDown);
+    }
+    assert!(harness.shows("syn,!ack"));
+    harness.press(KeyCode::Esc);
+    assert!(!harness.shows("Help (Esc to close)"));
+}
+
+#[test]
+fn esc_closes_one_layer_at_a_time() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('\''));
+    harness.press(KeyCode::Char('s'));
+    harness.type_text("qx");
+    assert_eq!(harness.app.mode_stack, [Mode::PresetMenu, Mode::PresetName]);
+    harness.press(KeyCode::Esc);
+    assert_eq!(harness.app.mode(), Mode::PresetMenu);
+    harness.press(KeyCode::Char('?'));
+    assert_eq!(harness.app.mode(), Mode::PresetMenu);
+    harness.press(KeyCode::Esc);
+    assert_eq!(harness.app.mode(), Mode::Normal);
+
+    // Text layers keep q and ; as text; Esc drops the edit.
+    harness.press(KeyCode::F(3));
+    harness.type_text("q;");
+    assert_eq!(harness.app.mode(), Mode::FilterEdit(FilterField::Ip));
+    assert_eq!(harness.app.input_buffer, "q;");
+    harness.press(KeyCode::Esc);
+    assert_eq!(harness.app.mode(), Mode::Normal);
+    assert_eq!(harness.app.filters.ip, "");
+    harness.press(KeyCode::Char('?'));
+    assert_eq!(harness.app.mode(), Mode::Help);
+    harness.press(KeyCode::Char('q'));
+    assert_eq!(harness.app.mode(), Mode::Normal);
+
+    // Views stack too, and Esc turns off the one opened last.
+    harness.press(KeyCode::Char('s'));
+    harness.press(KeyCode::Char('='));
+    assert_eq!(harness.app.mode_stack, [Mode::Stats, Mode::Aggregate]);
+    harness.press(KeyCode::Char('/'));
+    assert_eq!(harness.app.mode(), Mode::Search);
+    harness.press(KeyCode::Esc);
+    assert_eq!(harness.app.mode(), Mode::Aggregate);
+    harness.press(KeyCode::Esc);
+    assert!(!harness.app.aggregate && harness.app.show_stats);
+    harness.press(KeyCode::Esc);
+    assert!(!harness.app.show_stats);
+    assert_eq!(harness.app.mode(), Mode::Normal);
+}
+
+#[test]
+fn macros_and_row_copy_work_over_the_views() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('s'));
+    assert_eq!(harness.app.mode(), Mode::Stats);
+    harness.type_text("QalQ");
+    assert!(harness.app.show_local_src);
+    assert!(harness.shows("Recorded macro a (1 keys)"));
+    harness.type_text("@a");
+    assert!(!harness.app.show_local_src);
+    assert!(harness.shows("Played macro a (1 keys)"));
+
+    harness.send(Event::Key(KeyEvent::new(
+        KeyCode::Char('c'),
+        KeyModifiers::CONTROL,
+    )));
+    assert!(harness.shows("Copied selected log entry (OSC52)"));
+    assert_eq!(harness.app.mode(), Mode::Stats);
+
+    // Both are keymap commands, listed in the help under their keys.
+    harness.app.keymap = Keymap::new(&[keymap::parse_binding("record_macro", "ctrl+q").unwrap()]);
+    let help: Vec<String> = help::report(&harness.app.keymap)
+        .lines
+        .iter()
+        .map(|line| line.to_string().trim().to_string())
+        .collect();
+    assert!(
+        help.iter()
+            .any(|line| line.starts_with("Ctrl+Q         Record the keys"))
+    );
+    assert!(
+        help.iter()
+            .any(|line| line.starts_with("Ctrl+C         Copy the selected"))
+    );
+    assert!(
+        !help
+            .iter()
+            .any(|line| line.starts_with("Q") || line.starts_with("@<reg>"))
+    );
+    assert_eq!(
+        help.iter().filter(|line| line.ends_with(" Quit")).count(),
+        1
+    );
+}
+
+#[test]
+fn plus_unfolds_and_folds_a_flood_of_identical_rows() {
+    let path = std::env::temp_dir().join(format!("ufw-flood-{}.log", std::process::id()));
+    let line = |time: &str, src: &str, spt: u32| {
+        format!(
+            "Feb 11 {} host kernel: [UFW BLOCK] IN=eth0 OUT= SRC={} DST=10.0.0.1 PROTO=TCP SPT={} DPT=22\n",
+            time, src, spt
+        )
+    };
+    let mut log = line("20:00:00", "192.0.2.1", 1000);
+    for second in 0..30 {
+        log.push_str(&line(
+            &format!("20:02:{:02}", second),
+            "203.0.113.9",
+            40000 + second,
+        ));
+    }
+    log.push_str(&line("20:03:00", "192.0.2.1", 1001));
+    std::fs::write(&path, log).unwrap();
+    let mut harness = Harness::new(&path.display().to_string());
+    assert!(harness.shows("32/32"));
+    assert!(harness.shows("⊞ 30"));
+    harness.press(KeyCode::Char('j'));
+    assert!(harness.shows("⊞ 30 identical BLOCKs 20:02:00–20:02:29 (+ expands)"));
+
+    harness.press(KeyCode::Char('+'));
+    assert!(harness.shows("Expanded 30 identical rows"));
+    assert!(!harness.shows("⊞ 30"));
+    assert!(harness.shows("20:02:29"));
+    harness.press(KeyCode::Char('+'));
+    assert!(harness.shows("Folded 30 identical rows"));
+    assert!(harness.shows("⊞ 30"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn caret_shows_port_scans_found_as_lines_arrive() {
+    let path = std::env::temp_dir().join(format!("ufw-scan-{}.log", std::process::id()));
+    let probes = |ports: std::ops::Range<u16>| -> String {
+        ports
+            .map(|port| {
+                format!(
+                    "Feb 11 20:00:{:02} host kernel: [UFW BLOCK] IN=eth0 OUT= SRC=198.51.100.7 DST=10.0.0.1 PROTO=TCP SPT=40000 DPT={}\n",
+                    port % 60,
+                    port
+                )
+            })
+            .collect()
+    };
+    std::fs::write(&path, probes(20..30)).unwrap();
+    let mut harness = Harness::new(&path.display().to_string());
+    harness.press(KeyCode::Char('^'));
+    assert!(harness.shows("Alerts: 0 port scans"));
+    assert!(harness.shows("No port scans yet"));
+
+    let mut log = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    std::io::Write::write_all(&mut log, probes(30..40).as_bytes()).unwrap();
+    assert!(harness.app.tail_sources());
+    while harness.app.ingest_pending() {
+        harness.app.ingest_tick();
+    }
+    harness.screen();
+    assert!(harness.shows("[1 scans]"));
+    assert!(harness.shows("Alerts: 1 port scans"));
+    assert!(harness.shows("198.51.100.7"));
+    assert!(harness.shows("20 ports over 19s"));
+    harness.press(KeyCode::Char('^'));
+    assert!(!harness.shows("(^ to close)"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn pipe_sidebar_filters_by_a_top_source_or_port() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('|'));
+    assert!(harness.shows("Top talkers (Enter, Esc)"));
+    assert!(harness.shows("Top sources"));
+    let second = harness.app.top_talkers().sources[1].0.clone();
+    harness.press(KeyCode::Char('j'));
+    harness.press(KeyCode::Enter);
+    assert!(harness.shows(&format!("Set ip filter='{}'", second)));
+    assert!(harness.shows("Top talkers (| to focus)"));
+    assert_eq!(harness.app.filtered_indices().len(), 1);
+
+    // A click works without focusing the sidebar.
+    harness.app.clear_filter(crate::FilterField::Ip);
+    let port = harness.app.top_talkers().ports[0].0;
+    let (x, y) = harness.find("Top ports").unwrap();
+    harness.click(x, y + 1);
+    assert!(harness.shows(&format!("Set port filter='{}'", port)));
+    harness.press(KeyCode::Char('|'));
+    harness.press(KeyCode::Char('|'));
+    assert!(!harness.shows("Top talkers"));
+}
+
+#[test]
+fn semicolon_picker_reorders_and_adds_columns() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char(';'));
+    assert!(harness.shows("[x] service"));
+    assert!(harness.shows("[ ] ttl"));
+    // Move Service (after time..dpt) to the front.
+    harness.type_text(&"j".repeat(9));
+    harness.type_text(&"K".repeat(9));
+    // TTL is the third hidden column.
+    harness.type_text(&"j".repeat(12));
+    harness.press(KeyCode::Char(' '));
+    harness.press(KeyCode::Esc);
+    assert!(!harness.shows("[x] service"));
+
+    let screen = harness.screen();
+    let header = screen
+        .iter()
+        .find(|line| line.contains("Action") && line.contains("Dir"))
+        .unwrap();
+    let at = |label: &str| header.find(label).unwrap();
+    assert!(at("Service") < at("Time"), "{}", header);
+    assert!(at("DPT") < at("TTL"), "{}", header);
+}
+
+#[test]
+fn stats_view_replaces_the_table() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('s'));
+    assert!(harness.shows("Statistics · 3 entries in view"));
+    assert!(harness.shows("Top sources"));
+    assert!(!harness.shows("UFW Events"));
+    harness.press(KeyCode::Char('s'));
+    assert!(harness.shows("UFW Events"));
+}
+
+#[test]
+fn equals_toggles_the_aggregated_view() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('='));
+    assert!(harness.shows("3 groups · 3/5"));
+    assert!(harness.shows("Count"));
+    assert!(harness.shows("Last seen"));
+    harness.press(KeyCode::Char('='));
+    assert!(harness.shows("Back to raw per-line rows"));
+    assert!(!harness.shows("Count"));
+}
+
+#[test]
+fn time_lock_scopes_reports_and_stats() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('t'));
+    assert!(harness.shows("[±15m]"));
+    assert!(harness.shows("3/5"));
+    harness.press(KeyCode::Enter);
+    assert!(harness.shows("eth0 · ±15m around Feb 11 20:00:10"));
+    harness.press(KeyCode::Esc);
+    harness.press(KeyCode::Char('s'));
+    assert!(harness.shows("3 entries in view · eth0 · ±15m"));
+    harness.press(KeyCode::Char('t'));
+    assert!(!harness.shows("[±15m]"));
+}
+
+#[test]
+fn e_exports_the_filtered_rows() {
+    let path = std::env::temp_dir().join(format!("ufw-view-{}.json", std::process::id()));
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('e'));
+    assert!(harness.shows("Export 3 rows as CSV to: ufw-export.csv_"));
+    harness.press(KeyCode::Tab);
+    for _ in 0.."ufw-export.json".len() {
+        harness.press(KeyCode::Backspace);
+    }
+    harness.type_text(path.to_str().unwrap());
+    harness.press(KeyCode::Enter);
+    assert!(harness.shows("Exported 3 rows as JSON"));
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(written.matches("\"id\":").count(), 3);
+}
+
+#[test]
+fn apostrophe_saves_and_recalls_presets() {
+    let path = std::env::temp_dir().join(format!("ufw-presets-{}.ini", std::process::id()));
+    let mut harness = Harness::new("ufw.log");
+    harness.app.presets_path = Some(path.clone());
+    harness.press(KeyCode::F(2));
+    harness.type_text("443");
+    harness.press(KeyCode::Enter);
+    harness.press(KeyCode::Char('l'));
+
+    harness.press(KeyCode::Char('\''));
+    assert!(harness.shows("No presets saved yet."));
+    harness.press(KeyCode::Char('s'));
+    harness.type_text("https");
+    assert!(harness.shows("Save current view as: https_"));
+    harness.press(KeyCode::Enter);
+    assert!(harness.shows("1   https                port=443 iface=eth0 flow=all"));
+    // q closes the popup rather than quitting.
+    assert_eq!(harness.press(KeyCode::Char('q')), Action::Continue);
+
+    harness.press(KeyCode::Char('c'));
+    assert!(harness.shows("3/5"));
+    harness.press(KeyCode::Char('\''));
+    harness.press(KeyCode::Char('1'));
+    assert!(harness.shows("Applied preset https. Matching rows: 1"));
+    assert!(harness.shows("F2 port=443"));
+    assert!(harness.app.show_local_src);
+
+    harness.press(KeyCode::Char('\''));
+    harness.press(KeyCode::Char('d'));
+    harness.press(KeyCode::Char('1'));
+    assert!(harness.shows("No presets saved yet."));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn quote_digit_flips_between_recent_filters() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::F(2));
+    harness.type_text("22");
+    harness.press(KeyCode::Enter);
+    harness.press(KeyCode::F(3));
+    harness.type_text("45.33");
+    harness.press(KeyCode::Enter);
+
+    harness.press(KeyCode::Char('"'));
+    assert!(harness.shows("Recent filters: 1 port=22 ip=45.33 · 2 port=22 · 3 no filters"));
+    harness.press(KeyCode::Char('3'));
+    assert_eq!(harness.app.filters.active_count(), 0);
+    harness.press(KeyCode::Char('"'));
+    harness.press(KeyCode::Char('2'));
+    assert_eq!(
+        (
+            harness.app.filters.port.as_str(),
+            harness.app.filters.ip.as_str()
+        ),
+        ("22", "45.33")
+    );
+    assert!(harness.shows("Recalled port=22 ip=45.33. Matching rows: 1"));
+
+    // Anything but a listed digit ends the leader and acts as usual.
+    harness.press(KeyCode::Char('"'));
+    harness.press(KeyCode::Char('s'));
+    assert!(harness.app.show_stats);
+}
+
+#[test]
+fn capped_loads_mark_older_entries_until_l_loads_them() {
+    let mut harness = Harness::new("ufw.log");
+    harness.app.entry_cap = Some(2);
+    harness.app.reload();
+    while harness.app.ingest_pending() {
+        harness.app.ingest_tick();
+    }
+    assert!(harness.shows("… 3 older entries not loaded (press L to load more)"));
+
+    harness.press(KeyCode::Char('L'));
+    while harness.app.ingest_pending() {
+        harness.app.ingest_tick();
+    }
+    assert!(harness.shows("Loading older entries (3 not loaded yet)"));
+    assert!(!harness.shows("older entries not loaded"));
+    harness.press(KeyCode::Char('L'));
+    assert!(harness.shows("All entries are loaded"));
+}
+
+#[test]
+fn resizing_across_breakpoints_relayouts_columns() {
+    let mut harness = Harness::new("ufw.log");
+    assert!(harness.shows("Date/Time"));
+    harness.terminal.backend_mut().resize(100, HEIGHT);
+    harness.send(Event::Resize(100, HEIGHT));
+    assert!(!harness.shows("Date/Time"));
+    assert!(harness.shows("Time"));
+
+    harness.press(KeyCode::Char('I'));
+    assert!(harness.shows("Nothing in the selected row is truncated"));
+
+    harness.app.breakpoints = Breakpoints::parse("90,200").unwrap();
+    harness.send(Event::Resize(100, HEIGHT));
+    assert!(harness.shows("Date/Time"));
+    assert!(Breakpoints::parse("90").is_err());
+}
+
+#[test]
+fn b_confirms_before_blocking_the_source() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('b'));
+    assert!(harness.shows("Block Source"));
+    assert!(harness.shows("ufw deny from 1.1.1.1`? (Enter run, c copy only, Esc cancel)"));
+    // Keys other than the prompt's own are swallowed while it is open.
+    assert_eq!(harness.press(KeyCode::Char('q')), Action::Continue);
+    harness.press(KeyCode::Esc);
+    assert!(harness.shows("Block cancelled"));
+
+    harness.app.dry_run = true;
+    harness.press(KeyCode::Char('b'));
+    assert!(harness.shows("Copy `"));
+}
+
+#[test]
+fn greater_than_reviews_blocked_outbound_traffic() {
+    let mut harness = Harness::new("ufw.log");
+    harness.app.egress_path = None;
+    harness.press(KeyCode::Char('>'));
+    // The fixture only has inbound traffic.
+    assert!(harness.shows("Egress review"));
+    assert!(harness.shows("No blocked outbound traffic left to review."));
+    harness.press(KeyCode::Char('>'));
+    assert!(!harness.shows("Egress review"));
+}
+
+#[test]
+fn capital_c_summarizes_blocked_countries_and_filters_by_one() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Char('C'));
+    assert!(harness.shows("The country summary needs a GeoIP database (--geoip)"));
+
+    // As `--geoip` would have placed them.
+    for entry in &mut harness.app.entries {
+        entry.country = entry.src_ip.as_deref().map(|ip| match ip {
+            "8.8.8.8" => "US".to_string(),
+            _ => "DE".to_string(),
+        });
+    }
+    harness.app.country_summary = Some(CountrySummary::new(harness.app.scoped_entries()));
+    harness.app.push_mode(Mode::Countries);
+    harness.screen();
+    assert!(harness.shows("Blocked by country"));
+    assert!(harness.shows("> DE "));
+    harness.press(KeyCode::Char('j'));
+    assert!(harness.shows("> US "));
+    harness.press(KeyCode::Enter);
+    assert_eq!(harness.app.mode(), Mode::Normal);
+    assert_eq!(harness.app.filters.value(FilterField::Country), "US");
+    assert!(harness.shows("8.8.8.8"));
+    assert!(!harness.shows("45.33.2.1"));
+}
+
+#[test]
+fn pager_frontend_lists_raw_lines_on_narrow_terminals() {
+    let mut harness = Harness::new("ufw.log");
+    harness.terminal = Terminal::new(TestBackend::new(40, 6)).unwrap();
+    let draw = |harness: &mut Harness| {
+        harness
+            .terminal
+            .draw(|frame| pager::render(frame, &mut harness.app))
+            .unwrap();
+        let buffer = harness.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..40).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect::<Vec<String>>()
+    };
+    let screen = draw(&mut harness);
+    assert!(screen[0].starts_with("Feb 11 20:00:10 host kernel"));
+    assert_eq!(screen[3].trim(), "");
+    assert!(screen[5].starts_with(":3/5 (0 filters)"));
+
+    harness.app.handle_event(&Event::Key(KeyEvent::new(
+        KeyCode::F(3),
+        KeyModifiers::NONE,
+    )));
+    let screen = draw(&mut harness);
+    assert!(screen[5].starts_with("ip: _"));
+}
+
+#[test]
+fn q_quits_unless_a_popup_is_open() {
+    let mut harness = Harness::new("ufw.log");
+    harness.press(KeyCode::Enter);
+    assert!(harness.app.report.is_some());
+    assert_eq!(harness.press(KeyCode::Char('q')), Action::Continue);
+    assert!(harness.app.report.is_none());
+    assert_eq!(harness.press(KeyCode::Char('q')), Action::Quit);
+}